@@ -0,0 +1,132 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage backend of the Frontier client.
+//!
+//! The RPC reads the Ethereum hash mappings and, when available, a log index
+//! through [`Backend`], so that the storage behind them can be swapped without
+//! touching the RPC.
+
+use codec::{Encode, Decode, Input};
+use sp_core::{H160, H256};
+use sp_runtime::traits::Block as BlockT;
+
+/// Where an Ethereum transaction was included.
+#[derive(Clone, PartialEq, Eq, Debug, Encode)]
+pub struct TransactionMetadata<Hash> {
+	/// Substrate block hash.
+	pub block_hash: Hash,
+	/// Ethereum block hash.
+	pub ethereum_block_hash: H256,
+	/// Index of the transaction in the Ethereum block, which is also the
+	/// number of receipts before its own.
+	pub ethereum_index: u32,
+	/// Index in the Ethereum block of the first log of the transaction, that
+	/// is the number of logs of the receipts before its own. Unknown for
+	/// blocks whose consensus log does not count logs.
+	pub log_index: Option<u32>,
+}
+
+// Metadata written before `log_index` existed ends after `ethereum_index`.
+impl<Hash: Decode> Decode for TransactionMetadata<Hash> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let block_hash = Hash::decode(input)?;
+		let ethereum_block_hash = H256::decode(input)?;
+		let ethereum_index = u32::decode(input)?;
+		let log_index = match input.remaining_len()? {
+			Some(0) => None,
+			_ => Option::<u32>::decode(input)?,
+		};
+		Ok(TransactionMetadata { block_hash, ethereum_block_hash, ethereum_index, log_index })
+	}
+}
+
+/// Log found by a [`LogIndexerBackend`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FilteredLog<Hash> {
+	/// Substrate block hash.
+	pub substrate_block_hash: Hash,
+	/// Ethereum block hash.
+	pub ethereum_block_hash: H256,
+	/// Ethereum block number.
+	pub block_number: u32,
+	/// Index of the transaction in the Ethereum block.
+	pub transaction_index: u32,
+	/// Index of the log in the Ethereum block.
+	pub log_index: u32,
+}
+
+/// Frontier storage backend.
+pub trait Backend<Block: BlockT>: Send + Sync {
+	/// Substrate block containing the Ethereum block `ethereum_block_hash`.
+	fn block_hash(
+		&self,
+		ethereum_block_hash: &H256,
+	) -> Result<Option<Block::Hash>, String>;
+
+	/// Inclusion of the Ethereum transaction `ethereum_transaction_hash`.
+	fn transaction_metadata(
+		&self,
+		ethereum_transaction_hash: &H256,
+	) -> Result<Option<TransactionMetadata<Block::Hash>>, String>;
+
+	/// Number of the first block whose transactions are indexed, if the
+	/// transactions of older blocks were pruned.
+	fn transactions_indexed_from(&self) -> Result<Option<u32>, String>;
+
+	/// Index of the logs of the Ethereum blocks.
+	fn log_indexer(&self) -> &dyn LogIndexerBackend<Block>;
+}
+
+/// Index of the logs of the Ethereum blocks.
+pub trait LogIndexerBackend<Block: BlockT>: Send + Sync {
+	/// Whether logs are indexed. When they are not, logs can only be found by
+	/// reading the blocks.
+	fn is_indexed(&self) -> bool;
+
+	/// Logs of the blocks `from_block..=to_block` emitted by one of
+	/// `addresses`, or by any address if empty, and matching `topics`.
+	///
+	/// Topics are matched by position: a log matches if, for each position, its
+	/// topic is one of the given ones. An empty position is a wildcard.
+	/// Logs are returned in block and log order.
+	fn filter_logs(
+		&self,
+		from_block: u32,
+		to_block: u32,
+		addresses: &[H160],
+		topics: &[Vec<H256>],
+	) -> Result<Vec<FilteredLog<Block::Hash>>, String>;
+}
+
+/// Log indexer of a backend that does not index logs.
+pub struct NoLogIndexer;
+
+impl<Block: BlockT> LogIndexerBackend<Block> for NoLogIndexer {
+	fn is_indexed(&self) -> bool {
+		false
+	}
+
+	fn filter_logs(
+		&self,
+		_from_block: u32,
+		_to_block: u32,
+		_addresses: &[H160],
+		_topics: &[Vec<H256>],
+	) -> Result<Vec<FilteredLog<Block::Hash>>, String> {
+		Err("logs are not indexed".into())
+	}
+}
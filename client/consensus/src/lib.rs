@@ -0,0 +1,111 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block import checking that each block carries the Frontier consensus log,
+//! and recording the Ethereum hash mapping of the block atomically with its
+//! import.
+//!
+//! The log itself is deposited by the runtime, and block execution already
+//! checks that the digest of an imported header matches the one computed from
+//! the executed storage. So a block whose log does not describe its Ethereum
+//! block is rejected by the inner import, before it can become best.
+
+use std::{collections::HashMap, marker::PhantomData};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_consensus::{
+	BlockCheckParams, BlockImport, BlockImportParams, Error as ConsensusError, ImportResult,
+};
+use sp_consensus::import_queue::CacheKeyId;
+use fp_consensus::FindLogError;
+
+#[derive(derive_more::Display, Debug)]
+pub enum Error {
+	#[display(fmt = "Multiple Frontier consensus logs in block")]
+	MultipleLogs,
+	#[display(fmt = "Frontier consensus log not found in block")]
+	NoLog,
+}
+
+impl From<FindLogError> for Error {
+	fn from(error: FindLogError) -> Error {
+		match error {
+			FindLogError::NotFound => Error::NoLog,
+			FindLogError::MultipleLogs => Error::MultipleLogs,
+		}
+	}
+}
+
+impl From<Error> for ConsensusError {
+	fn from(error: Error) -> ConsensusError {
+		ConsensusError::ClientImport(error.to_string())
+	}
+}
+
+/// Block import wrapping `inner`, which requires each block to carry exactly
+/// one Frontier consensus log and writes its Ethereum hash mapping to `fc-db`.
+pub struct FrontierBlockImport<B: BlockT, I> {
+	inner: I,
+	enabled: bool,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, I: Clone> Clone for FrontierBlockImport<B, I> {
+	fn clone(&self) -> Self {
+		FrontierBlockImport {
+			inner: self.inner.clone(),
+			enabled: self.enabled,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B: BlockT, I> FrontierBlockImport<B, I> {
+	/// Wrap `inner`. When not `enabled`, blocks are passed through unchecked,
+	/// for chains whose early blocks predate the consensus log.
+	pub fn new(inner: I, enabled: bool) -> Self {
+		Self { inner, enabled, _marker: PhantomData }
+	}
+}
+
+impl<B, I> BlockImport<B> for FrontierBlockImport<B, I> where
+	B: BlockT,
+	I: BlockImport<B>,
+	I::Error: Into<ConsensusError>,
+{
+	type Error = ConsensusError;
+	type Transaction = I::Transaction;
+
+	fn check_block(
+		&mut self,
+		block: BlockCheckParams<B>,
+	) -> Result<ImportResult, Self::Error> {
+		self.inner.check_block(block).map_err(Into::into)
+	}
+
+	fn import_block(
+		&mut self,
+		mut block: BlockImportParams<B, Self::Transaction>,
+		new_cache: HashMap<CacheKeyId, Vec<u8>>,
+	) -> Result<ImportResult, Self::Error> {
+		if self.enabled {
+			let log = fp_consensus::find_log(block.header.digest()).map_err(Error::from)?;
+			let hash = block.post_hash();
+			block.auxiliary.extend(fc_db::mapping_commitment::<B>(hash, &log));
+		}
+
+		self.inner.import_block(block, new_cache).map_err(Into::into)
+	}
+}
@@ -0,0 +1,319 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mapping from Ethereum block and transaction hashes to the Substrate blocks
+//! that contain them.
+//!
+//! The mapping lives in the client auxiliary storage, so that it can be written
+//! as part of a block import and committed atomically with it. When the same
+//! Ethereum hash appears in blocks of several forks, the last imported wins.
+//! [`Backend`] serves it to the RPC as a `fc-api` backend.
+//!
+//! Non-archive nodes may keep the transaction mappings of the last blocks
+//! only, with [`prune_transactions`]. Block mappings are always kept.
+//!
+//! A corrupted mapping is rebuilt from the block digests of the canonical
+//! chain with [`rebuild_mapping`], without resyncing.
+
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+use codec::{Encode, Decode};
+use futures::StreamExt;
+use sp_core::H256;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto}};
+use sp_blockchain::HeaderBackend;
+use sc_client_api::{BlockchainEvents, backend::AuxStore};
+use fp_consensus::{ConsensusLog, FindLogError};
+
+pub use fc_api::TransactionMetadata;
+
+const BLOCK_MAPPING_PREFIX: &[u8] = b"frontier/block_mapping/";
+const TRANSACTION_MAPPING_PREFIX: &[u8] = b"frontier/transaction_mapping/";
+const BLOCK_TRANSACTIONS_PREFIX: &[u8] = b"frontier/block_transactions/";
+const TRANSACTIONS_PRUNED_KEY: &[u8] = b"frontier/transactions_pruned";
+const REBUILD_CHECKPOINT_KEY: &[u8] = b"frontier/rebuild_checkpoint";
+
+/// Number of blocks whose transaction mappings are pruned at most per imported
+/// block, so that enabling pruning on an existing database catches up
+/// gradually.
+const MAX_PRUNED_BLOCKS: u32 = 256;
+
+/// Number of blocks whose mappings are rebuilt per write, along with the
+/// checkpoint a rebuild resumes from.
+const REBUILD_BATCH: u32 = 256;
+
+fn key(prefix: &[u8], hash: &[u8]) -> Vec<u8> {
+	let mut key = prefix.to_vec();
+	key.extend_from_slice(hash);
+	key
+}
+
+/// Auxiliary storage writes recording the mapping of the Ethereum block
+/// described by `log`, built in the Substrate block `block_hash`. These are meant
+/// for `BlockImportParams::auxiliary`.
+pub fn mapping_commitment<Block: BlockT>(
+	block_hash: Block::Hash,
+	log: &ConsensusLog,
+) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+	let ethereum_block_hash = log.block_hash();
+	let transaction_hashes = log.transaction_hashes();
+	let mut commitment = Vec::with_capacity(transaction_hashes.len() + 2);
+	commitment.push((
+		key(BLOCK_MAPPING_PREFIX, ethereum_block_hash.as_bytes()),
+		Some(block_hash.encode()),
+	));
+	commitment.push((
+		key(BLOCK_TRANSACTIONS_PREFIX, block_hash.as_ref()),
+		Some(transaction_hashes.encode()),
+	));
+	// Logs of the transactions before the current one.
+	let mut log_index = log.log_counts().map(|_| 0u32);
+	for (index, transaction_hash) in transaction_hashes.iter().enumerate() {
+		let metadata = TransactionMetadata {
+			block_hash,
+			ethereum_block_hash,
+			ethereum_index: index as u32,
+			log_index,
+		};
+		commitment.push((
+			key(TRANSACTION_MAPPING_PREFIX, transaction_hash.as_bytes()),
+			Some(metadata.encode()),
+		));
+		let log_count = log.log_counts().and_then(|log_counts| log_counts.get(index));
+		log_index = log_index.and_then(|log_index| log_count.map(|count| log_index.saturating_add(*count)));
+	}
+	commitment
+}
+
+fn load_decode<C: AuxStore, T: Decode>(client: &C, key: &[u8]) -> sp_blockchain::Result<Option<T>> {
+	match client.get_aux(key)? {
+		Some(value) => T::decode(&mut &value[..])
+			.map(Some)
+			.map_err(|e| sp_blockchain::Error::Backend(format!("frontier mapping decode failed: {:?}", e))),
+		None => Ok(None),
+	}
+}
+
+/// Substrate block containing the Ethereum block `ethereum_block_hash`.
+pub fn load_block_hash<Block: BlockT, C: AuxStore>(
+	client: &C,
+	ethereum_block_hash: H256,
+) -> sp_blockchain::Result<Option<Block::Hash>> {
+	load_decode(client, &key(BLOCK_MAPPING_PREFIX, ethereum_block_hash.as_bytes()))
+}
+
+/// Inclusion of the Ethereum transaction `ethereum_transaction_hash`.
+pub fn load_transaction_metadata<Block: BlockT, C: AuxStore>(
+	client: &C,
+	ethereum_transaction_hash: H256,
+) -> sp_blockchain::Result<Option<TransactionMetadata<Block::Hash>>> {
+	load_decode(client, &key(TRANSACTION_MAPPING_PREFIX, ethereum_transaction_hash.as_bytes()))
+}
+
+/// Number of the last block whose transaction mappings were pruned.
+pub fn load_transactions_pruned<C: AuxStore>(client: &C) -> sp_blockchain::Result<Option<u32>> {
+	load_decode(client, TRANSACTIONS_PRUNED_KEY)
+}
+
+/// Ethereum transactions of the Substrate block `block_hash`, as recorded with
+/// its mapping. Mappings written before these were recorded fall back to the
+/// Frontier consensus log of the block.
+fn block_transaction_hashes<Block: BlockT, C: AuxStore + HeaderBackend<Block>>(
+	client: &C,
+	block_hash: Block::Hash,
+) -> sp_blockchain::Result<Vec<H256>> {
+	if let Some(transaction_hashes) = load_decode(client, &key(BLOCK_TRANSACTIONS_PREFIX, block_hash.as_ref()))? {
+		return Ok(transaction_hashes);
+	}
+	Ok(match client.header(BlockId::Hash(block_hash))? {
+		Some(header) => fp_consensus::find_log(header.digest())
+			.map(|log| log.transaction_hashes().to_vec())
+			.unwrap_or_default(),
+		None => Vec::new(),
+	})
+}
+
+/// Remove the transaction mappings of the canonical blocks up to `number`,
+/// from the block after the last pruned one. Mappings that a later block
+/// overwrote are kept.
+pub fn prune_transactions_until<Block: BlockT, C: AuxStore + HeaderBackend<Block>>(
+	client: &C,
+	number: u32,
+) -> sp_blockchain::Result<()> {
+	let from = load_transactions_pruned(client)?.map_or(0, |pruned| pruned + 1);
+	let to = number.min(from.saturating_add(MAX_PRUNED_BLOCKS - 1));
+	for number in from..=to {
+		let mut deleted = Vec::new();
+		if let Some(block_hash) = client.hash(number.into())? {
+			for transaction_hash in block_transaction_hashes::<Block, _>(client, block_hash)? {
+				let metadata = load_transaction_metadata::<Block, _>(client, transaction_hash)?;
+				if metadata.map_or(false, |metadata| metadata.block_hash == block_hash) {
+					deleted.push(key(TRANSACTION_MAPPING_PREFIX, transaction_hash.as_bytes()));
+				}
+			}
+			deleted.push(key(BLOCK_TRANSACTIONS_PREFIX, block_hash.as_ref()));
+		}
+		let pruned = number.encode();
+		client.insert_aux(
+			&[(TRANSACTIONS_PRUNED_KEY, &pruned[..])],
+			&deleted.iter().map(|key| &key[..]).collect::<Vec<_>>(),
+		)?;
+	}
+	Ok(())
+}
+
+/// Block from which an interrupted rebuild resumes.
+pub fn load_rebuild_checkpoint<C: AuxStore>(client: &C) -> sp_blockchain::Result<Option<u32>> {
+	load_decode(client, REBUILD_CHECKPOINT_KEY)
+}
+
+/// Rewrite the mapping of every canonical block up to the best one from its
+/// Frontier consensus log, resuming an interrupted rebuild unless `restart` is
+/// set. `progress` is called with the last rebuilt block and the best block
+/// after each batch. Returns the number of rebuilt blocks.
+///
+/// The transaction mappings a canonical block listed before are removed if
+/// they point to it or cannot be decoded, and all transaction mappings are
+/// kept again until pruned. Mappings of blocks of abandoned forks are not
+/// reachable from the canonical chain, and stay as they are.
+pub fn rebuild_mapping<Block, C, P>(
+	client: &C,
+	restart: bool,
+	mut progress: P,
+) -> sp_blockchain::Result<u32> where
+	Block: BlockT,
+	C: AuxStore + HeaderBackend<Block>,
+	P: FnMut(u32, u32),
+{
+	let best = UniqueSaturatedInto::<u32>::unique_saturated_into(client.info().best_number);
+	let mut batch_from = if restart {
+		0
+	} else {
+		load_rebuild_checkpoint(client)?.unwrap_or(0)
+	};
+	let mut rebuilt = 0;
+	while batch_from <= best {
+		let batch_to = best.min(batch_from.saturating_add(REBUILD_BATCH - 1));
+		let mut inserted = Vec::new();
+		let mut deleted = vec![TRANSACTIONS_PRUNED_KEY.to_vec()];
+		for number in batch_from..=batch_to {
+			let header = match client.header(BlockId::Number(number.into()))? {
+				Some(header) => header,
+				None => continue,
+			};
+			let block_hash = header.hash();
+			let block_transactions = key(BLOCK_TRANSACTIONS_PREFIX, block_hash.as_ref());
+			let transaction_hashes: Vec<H256> = load_decode(client, &block_transactions)
+				.unwrap_or_default()
+				.unwrap_or_default();
+			for transaction_hash in transaction_hashes {
+				let stale = load_transaction_metadata::<Block, _>(client, transaction_hash)
+					.map_or(true, |metadata| metadata.map_or(false, |metadata| metadata.block_hash == block_hash));
+				if stale {
+					deleted.push(key(TRANSACTION_MAPPING_PREFIX, transaction_hash.as_bytes()));
+				}
+			}
+			deleted.push(block_transactions);
+			match fp_consensus::find_log(header.digest()) {
+				Ok(log) => inserted.extend(mapping_commitment::<Block>(block_hash, &log)),
+				// Blocks without an Ethereum block, such as genesis.
+				Err(FindLogError::NotFound) => {},
+				Err(e) => log::warn!(
+					target: "frontier", "Skipped block #{} with an invalid Frontier log: {:?}", number, e,
+				),
+			}
+			rebuilt += 1;
+		}
+		// Deletions are applied after insertions, so written keys must not be
+		// deleted.
+		let written: HashSet<_> = inserted.iter().map(|(key, _)| key.clone()).collect();
+		deleted.retain(|key| !written.contains(key));
+		let checkpoint = batch_to.saturating_add(1).encode();
+		inserted.push((REBUILD_CHECKPOINT_KEY.to_vec(), Some(checkpoint)));
+		client.insert_aux(
+			&inserted.iter()
+				.filter_map(|(key, value)| value.as_ref().map(|value| (&key[..], &value[..])))
+				.collect::<Vec<_>>(),
+			&deleted.iter().map(|key| &key[..]).collect::<Vec<_>>(),
+		)?;
+		progress(batch_to, best);
+		batch_from = batch_to + 1;
+	}
+	client.insert_aux(&[], &[REBUILD_CHECKPOINT_KEY])?;
+	Ok(rebuilt)
+}
+
+/// Keep the transaction mappings of the last `retention` blocks only, pruning
+/// older ones as new best blocks get imported. Mappings of blocks of abandoned
+/// forks are not pruned.
+pub async fn prune_transactions<Block, C>(client: Arc<C>, retention: u32) where
+	Block: BlockT,
+	C: AuxStore + HeaderBackend<Block> + BlockchainEvents<Block>,
+{
+	let mut imports = client.import_notification_stream();
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+		let best = UniqueSaturatedInto::<u32>::unique_saturated_into(*notification.header.number());
+		if best < retention {
+			continue
+		}
+		if let Err(e) = prune_transactions_until(&*client, best - retention) {
+			log::warn!(target: "frontier", "Pruning transaction mappings failed: {:?}", e);
+		}
+	}
+}
+
+/// Frontier backend reading the mapping from the client auxiliary storage.
+/// Logs are not indexed.
+pub struct Backend<Block, C> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Block, C> Backend<Block, C> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: PhantomData }
+	}
+}
+
+impl<Block: BlockT, C: AuxStore + Send + Sync> fc_api::Backend<Block> for Backend<Block, C> {
+	fn block_hash(
+		&self,
+		ethereum_block_hash: &H256,
+	) -> Result<Option<Block::Hash>, String> {
+		load_block_hash::<Block, _>(&*self.client, *ethereum_block_hash)
+			.map_err(|e| format!("{:?}", e))
+	}
+
+	fn transaction_metadata(
+		&self,
+		ethereum_transaction_hash: &H256,
+	) -> Result<Option<TransactionMetadata<Block::Hash>>, String> {
+		load_transaction_metadata::<Block, _>(&*self.client, *ethereum_transaction_hash)
+			.map_err(|e| format!("{:?}", e))
+	}
+
+	fn transactions_indexed_from(&self) -> Result<Option<u32>, String> {
+		load_transactions_pruned(&*self.client)
+			.map(|pruned| pruned.map(|pruned| pruned + 1))
+			.map_err(|e| format!("{:?}", e))
+	}
+
+	fn log_indexer(&self) -> &dyn fc_api::LogIndexerBackend<Block> {
+		&fc_api::NoLogIndexer
+	}
+}
@@ -0,0 +1,130 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Base fee pallet
+//!
+//! Stores the base fee per gas charged to EVM transactions, and the elasticity
+//! of the fee market, and lets governance update both without a runtime
+//! upgrade.
+//!
+//! At the end of each block the base fee follows the demand for block space,
+//! as in EIP-1559: it rises when the normal extrinsics used more than half of
+//! the weight available to them, and falls when they used less, by at most
+//! the elasticity for a full or an empty block.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{decl_module, decl_storage, decl_event, weights::Weight, traits::Get};
+use frame_system::{self as system, ensure_root};
+use sp_core::U256;
+use sp_runtime::{Permill, PerThing};
+use pallet_evm::FeeCalculator;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// Base fee per gas used at genesis.
+	type DefaultBaseFeePerGas: Get<U256>;
+	/// Elasticity used at genesis.
+	type DefaultElasticity: Get<Permill>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as BaseFee {
+		/// Base fee per gas charged to EVM transactions.
+		pub BaseFeePerGas get(fn base_fee_per_gas): U256 = T::DefaultBaseFeePerGas::get();
+		/// Maximum relative change of the base fee from one block to the next.
+		/// Zero keeps the base fee fixed.
+		pub Elasticity get(fn elasticity): Permill = T::DefaultElasticity::get();
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// The base fee per gas was set. [base_fee_per_gas]
+		NewBaseFeePerGas(U256),
+		/// The elasticity was set. [elasticity]
+		NewElasticity(Permill),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Set the base fee per gas.
+		#[weight = T::DbWeight::get().writes(1) as Weight]
+		fn set_base_fee_per_gas(origin, fee: U256) {
+			ensure_root(origin)?;
+			BaseFeePerGas::put(fee);
+			Self::deposit_event(Event::NewBaseFeePerGas(fee));
+		}
+
+		/// Set the elasticity.
+		#[weight = T::DbWeight::get().writes(1) as Weight]
+		fn set_elasticity(origin, elasticity: Permill) {
+			ensure_root(origin)?;
+			Elasticity::put(elasticity);
+			Self::deposit_event(Event::NewElasticity(elasticity));
+		}
+
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			// Reserve the weight of adjusting the base fee in `on_finalize`.
+			T::DbWeight::get().reads_writes(3, 1)
+		}
+
+		fn on_finalize(_n: T::BlockNumber) {
+			let available = T::AvailableBlockRatio::get() * T::MaximumBlockWeight::get();
+			let used = system::Module::<T>::all_extrinsics_weight().min(available);
+			BaseFeePerGas::mutate(|fee| *fee = Self::adjust(*fee, used, available / 2));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Base fee following `fee` after a block that used `used` weight, for a
+	/// `target` of half the available weight.
+	fn adjust(fee: U256, used: Weight, target: Weight) -> U256 {
+		let elasticity = Elasticity::get();
+		if target == 0 || elasticity.is_zero() {
+			return fee
+		}
+
+		// `fee * elasticity * |used - target| / target`, at most `fee * elasticity`.
+		let change = |difference: Weight| {
+			fee.saturating_mul(U256::from(elasticity.deconstruct()))
+				.saturating_mul(U256::from(difference))
+				/ U256::from(Permill::ACCURACY)
+				/ U256::from(target)
+		};
+		if used > target {
+			// Rise by at least one, so that a base fee of zero does not stay stuck.
+			fee.saturating_add(change(used - target).max(U256::one()))
+		} else {
+			fee.saturating_sub(change(target - used))
+		}
+	}
+}
+
+impl<T: Trait> FeeCalculator for Module<T> {
+	fn min_gas_price() -> U256 {
+		BaseFeePerGas::get()
+	}
+}
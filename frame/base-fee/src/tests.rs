@@ -0,0 +1,189 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types,
+	traits::OnFinalize,
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod base_fee {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		base_fee,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub DefaultBaseFeePerGas: U256 = U256::from(1_000_000_000);
+	pub DefaultElasticity: Permill = Permill::from_parts(125_000);
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
+	type DefaultElasticity = DefaultElasticity;
+}
+
+type System = frame_system::Module<Test>;
+type BaseFee = Module<Test>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	// Events are not stored in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+#[test]
+fn defaults_should_be_used_until_set() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(BaseFee::min_gas_price(), U256::from(1_000_000_000));
+		assert_eq!(BaseFee::elasticity(), Permill::from_parts(125_000));
+	});
+}
+
+#[test]
+fn root_should_set_base_fee_and_elasticity() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(BaseFee::set_base_fee_per_gas(Origin::root(), U256::from(42)));
+		assert_ok!(BaseFee::set_elasticity(Origin::root(), Permill::from_percent(50)));
+
+		assert_eq!(BaseFee::min_gas_price(), U256::from(42));
+		assert_eq!(BaseFee::elasticity(), Permill::from_percent(50));
+
+		let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+		assert_eq!(events, vec![
+			TestEvent::base_fee(Event::NewBaseFeePerGas(U256::from(42))),
+			TestEvent::base_fee(Event::NewElasticity(Permill::from_percent(50))),
+		]);
+	});
+}
+
+#[test]
+fn signed_origin_should_not_set_base_fee_or_elasticity() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			BaseFee::set_base_fee_per_gas(Origin::signed(1), U256::from(42)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			BaseFee::set_elasticity(Origin::signed(1), Permill::zero()),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+/// Base fee after a block whose normal extrinsics used `used` weight, out of
+/// the 768 available in the mock.
+fn base_fee_after_block(used: Weight) -> U256 {
+	System::register_extra_weight_unchecked(used);
+	BaseFee::on_finalize(1);
+	BaseFee::min_gas_price()
+}
+
+#[test]
+fn base_fee_should_follow_block_fullness() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(base_fee_after_block(768), U256::from(1_125_000_000));
+	});
+	new_test_ext().execute_with(|| {
+		assert_eq!(base_fee_after_block(576), U256::from(1_062_500_000));
+	});
+	new_test_ext().execute_with(|| {
+		assert_eq!(base_fee_after_block(384), U256::from(1_000_000_000));
+	});
+	new_test_ext().execute_with(|| {
+		assert_eq!(base_fee_after_block(0), U256::from(875_000_000));
+	});
+}
+
+#[test]
+fn base_fee_should_change_by_at_most_elasticity() {
+	new_test_ext().execute_with(|| {
+		// Operational extrinsics can go past the weight available to normal ones.
+		assert_eq!(base_fee_after_block(1024), U256::from(1_125_000_000));
+	});
+}
+
+#[test]
+fn base_fee_should_rise_from_zero() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(BaseFee::set_base_fee_per_gas(Origin::root(), U256::zero()));
+		assert_eq!(base_fee_after_block(768), U256::one());
+	});
+}
+
+#[test]
+fn zero_elasticity_should_keep_base_fee() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(BaseFee::set_elasticity(Origin::root(), Permill::zero()));
+		assert_eq!(base_fee_after_block(768), U256::from(1_000_000_000));
+		assert_eq!(base_fee_after_block(0), U256::from(1_000_000_000));
+	});
+}
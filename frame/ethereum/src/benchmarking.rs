@@ -0,0 +1,96 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for pallet-ethereum.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::traits::OnFinalize;
+use frame_system::RawOrigin;
+use ethereum::{TransactionAction, TransactionSignature};
+
+/// Largest input benchmarked for `transact`.
+const MAX_INPUT_LEN: u32 = 64 * 1024;
+/// Largest number of transactions benchmarked for `on_finalize`.
+const MAX_TRANSACTIONS: u32 = 100;
+
+/// A call to an account without code, signed by a fixed benchmark account.
+fn signed_call(nonce: u32, input: Vec<u8>) -> ethereum::Transaction {
+	let secret_key = secp256k1::SecretKey::parse(&[1u8; 32])
+		.expect("benchmark secret key is valid");
+	let mut transaction = ethereum::Transaction {
+		nonce: U256::from(nonce),
+		gas_price: U256::zero(),
+		gas_limit: U256::from(0x100000),
+		action: TransactionAction::Call(H160::repeat_byte(0x11)),
+		value: U256::zero(),
+		input,
+		signature: TransactionSignature::new(27, H256::from_low_u64_be(1), H256::from_low_u64_be(1))
+			.expect("placeholder signature is valid"),
+	};
+
+	let chain_id = sp_io::misc::chain_id();
+	let message = secp256k1::Message::parse(
+		transaction.message_hash(Some(chain_id)).as_fixed_bytes()
+	);
+	let (signature, recovery_id) = secp256k1::sign(&message, &secret_key);
+	let signature = signature.serialize();
+	transaction.signature = TransactionSignature::new(
+		chain_id * 2 + 35 + recovery_id.serialize() as u64,
+		H256::from_slice(&signature[0..32]),
+		H256::from_slice(&signature[32..64]),
+	).expect("secp256k1 signature is valid");
+
+	transaction
+}
+
+benchmarks! {
+	where_clause { where <T as frame_system::Trait>::Event: TryInto<pallet_evm::Event<T>> }
+
+	_ { }
+
+	transact {
+		let s in 0 .. MAX_INPUT_LEN;
+		let transaction = signed_call(0, vec![0x42; s as usize]);
+	}: _(RawOrigin::None, transaction)
+
+	on_finalize {
+		let t in 0 .. MAX_TRANSACTIONS;
+		for nonce in 0..t {
+			Module::<T>::transact(RawOrigin::None.into(), signed_call(nonce, vec![]))
+				.map_err(|error| error.error)?;
+		}
+	}: {
+		Module::<T>::on_finalize(frame_system::Module::<T>::block_number());
+	}
+}
+
+#[cfg(all(feature = "std", test))]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext(0).1.execute_with(|| {
+			assert_ok!(test_benchmark_transact::<Test>());
+			assert_ok!(test_benchmark_on_finalize::<Test>());
+		});
+	}
+}
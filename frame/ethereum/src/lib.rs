@@ -22,19 +22,42 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{decl_module, decl_storage, decl_event, weights::Weight};
-use sp_std::prelude::*;
+use frame_support::{
+	decl_module, decl_storage, decl_event, ensure, weights::Weight, traits::{Get, Filter, FindAuthor, EnsureOrigin},
+	dispatch::DispatchResultWithPostInfo,
+	storage::migration::{put_storage_value, remove_storage_prefix, StorageIterator},
+	Blake2_128Concat, StorageHasher,
+};
+use codec::{Encode, Decode};
+use sp_std::{prelude::*, collections::btree_map::BTreeMap};
 use frame_system::{self as system, ensure_none};
-use ethereum_types::{H160, H64, H256, U256, Bloom};
+use ethereum_types::{H160, H64, H256, U256, Bloom, BloomInput};
+use sp_std::convert::TryInto;
 use sp_runtime::{
-	traits::UniqueSaturatedInto,
-	transaction_validity::{TransactionValidity, TransactionSource, ValidTransaction}
+	ConsensusEngineId,
+	generic::DigestItem,
+	traits::{UniqueSaturatedInto, UniqueSaturatedFrom},
+	transaction_validity::{
+		TransactionValidity, TransactionSource, ValidTransaction, InvalidTransaction,
+		TransactionPriority, TransactionValidityError,
+	},
 };
+use pallet_evm::FeeCalculator;
+use fp_consensus::{FRONTIER_ENGINE_ID, ConsensusLog};
 use rlp;
 use sha3::{Digest, Keccak256};
 
-pub use frontier_rpc_primitives::TransactionStatus;
+pub use frontier_rpc_primitives::{
+	TransactionStatus, AccountOverride, BlockOverride, DryRunCall, DryRunOutcome, SimulatedBlock,
+	SimulatedOutcome, ValidationConstants, ExecutionError, EthereumStorageSchema,
+	PendingChanges, AccountRange, AccountRangeEntry, PALLET_ETHEREUM_SCHEMA,
+};
 pub use ethereum::{Transaction, Log, Block, Receipt};
+pub use weights::WeightInfo;
+
+mod benchmarking;
+pub mod trie;
+pub mod weights;
 
 #[cfg(all(feature = "std", test))]
 mod tests;
@@ -53,6 +76,204 @@ pub type BalanceOf<T> = <T as pallet_balances::Trait>::Balance;
 pub trait Trait: frame_system::Trait<Hash=H256> + pallet_balances::Trait + pallet_timestamp::Trait + pallet_evm::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The gas limit of emulated Ethereum blocks.
+	type BlockGasLimit: Get<U256>;
+	/// Weight information for the extrinsics of this pallet.
+	type WeightInfo: WeightInfo;
+	/// Weight charged for one unit of gas, the one `BlockGasLimitFromWeight`
+	/// should be given.
+	type WeightPerGas: Get<Weight>;
+	/// State root of emulated Ethereum headers: `ZeroStateRoot`,
+	/// `IntermediateStateRoot` or `EthereumStateRoot`.
+	type StateRoot: Get<H256>;
+	/// Addresses allowed to deploy contracts with CREATE transactions.
+	type DeploymentFilter: Filter<H160>;
+	/// Whether transactions without EIP-155 replay protection are accepted.
+	/// Protected transactions must always be signed for the chain id.
+	type AllowUnprotectedTxs: Get<bool>;
+	/// Author of the block, reported as the beneficiary of Ethereum headers.
+	type FindAuthor: FindAuthor<H160>;
+	/// Origin allowed to pause and resume Ethereum transactions.
+	type PauseOrigin: EnsureOrigin<Self::Origin>;
+	/// Maximum length of the RLP encoding of an Ethereum transaction. Being
+	/// unsigned extrinsics, they skip the length check of `CheckWeight`.
+	type MaxTransactionSize: Get<u32>;
+	/// `extra_data` of emulated Ethereum headers, such as a client identifier
+	/// built with `extra_data` for explorers to display. Zero with `()`.
+	type ExtraData: Get<H256>;
+	/// `mix_hash` of emulated Ethereum headers, a placeholder of the proof of
+	/// work seal. Zero with `()`.
+	type MixHash: Get<H256>;
+	/// `nonce` of emulated Ethereum headers, a placeholder of the proof of
+	/// work seal. Zero with `()`.
+	type HeaderNonce: Get<H64>;
+	/// Where the base fee of a `BlockOverride` is put for dry runs, such as the
+	/// base fee of pallet-base-fee. `()` ignores it.
+	type BaseFeeOverride: SetBaseFee;
+}
+
+/// Sets the base fee per gas that gas prices are checked against.
+pub trait SetBaseFee {
+	fn set_base_fee(base_fee: U256);
+}
+
+impl SetBaseFee for () {
+	fn set_base_fee(_base_fee: U256) {}
+}
+
+/// `extra_data` of Ethereum headers holding `bytes`, zero-padded on the
+/// right and truncated to the 32 bytes of the field.
+pub fn extra_data(bytes: &[u8]) -> H256 {
+	let mut extra_data = H256::zero();
+	let len = bytes.len().min(extra_data.as_bytes().len());
+	extra_data.as_bytes_mut()[..len].copy_from_slice(&bytes[..len]);
+	extra_data
+}
+
+/// Custom validity error of a CREATE transaction from a sender that may not
+/// deploy contracts.
+pub const DEPLOYMENT_NOT_ALLOWED: u8 = 1;
+/// Custom validity error of a transaction signed for another chain.
+pub const INVALID_CHAIN_ID: u8 = 2;
+/// Custom validity error of a transaction without EIP-155 replay protection,
+/// when `AllowUnprotectedTxs` is not set.
+pub const UNPROTECTED_TRANSACTION: u8 = 3;
+/// Custom validity error of a transaction submitted while Ethereum
+/// transactions are paused.
+pub const EVM_PAUSED: u8 = 4;
+/// Custom validity error of a transaction whose encoding is longer than
+/// `MaxTransactionSize`.
+pub const TRANSACTION_TOO_LARGE: u8 = 5;
+
+/// Upper bound of the length of the RLP encoding of a transaction, besides its
+/// input: the list and input headers (9 bytes each), four `U256` (33 bytes
+/// each), the action (21 bytes), `v` (9 bytes), `r` and `s` (33 bytes each).
+const MAX_TRANSACTION_RLP_OVERHEAD: usize = 9 + 9 + 4 * 33 + 21 + 9 + 2 * 33;
+
+/// Zero state root, for chains whose tools do not look at the state root.
+pub struct ZeroStateRoot;
+
+impl Get<H256> for ZeroStateRoot {
+	fn get() -> H256 {
+		H256::zero()
+	}
+}
+
+/// Substrate state root of the block, as it is when the Ethereum block is
+/// built. It changes with the state but cannot back Ethereum state proofs.
+pub struct IntermediateStateRoot;
+
+impl Get<H256> for IntermediateStateRoot {
+	fn get() -> H256 {
+		H256::decode(&mut &sp_io::storage::root()[..])
+			.expect("Substrate state roots are 32 bytes in Frontier runtimes; qed")
+	}
+}
+
+/// Root of an Ethereum state trie of the EVM accounts, as Ethereum clients
+/// compute it. This reads the whole EVM state, so its cost grows with it.
+pub struct EthereumStateRoot<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: pallet_evm::Trait> Get<H256> for EthereumStateRoot<T> {
+	fn get() -> H256 {
+		const EVM: &[u8] = b"EVM";
+		// Keys are suffixed with the blake2_128_concat hashes of the address
+		// and storage index.
+		let mut storages = BTreeMap::<H160, Vec<(H256, H256)>>::new();
+		for (key, value) in StorageIterator::<H256>::new(EVM, b"AccountStorages") {
+			if key.len() == 16 + 20 + 16 + 32 {
+				storages.entry(H160::from_slice(&key[16..36]))
+					.or_default()
+					.push((H256::from_slice(&key[52..84]), value));
+			}
+		}
+
+		let accounts = StorageIterator::<pallet_evm::Account>::new(EVM, b"Accounts")
+			.filter(|(key, _)| key.len() == 16 + 20)
+			.map(|(key, account)| {
+				let address = H160::from_slice(&key[16..36]);
+				let code_hash = H256::from_slice(
+					Keccak256::digest(&pallet_evm::Module::<T>::account_codes(address)).as_slice()
+				);
+				let storage_root = trie::storage_root(storages.remove(&address).unwrap_or_default());
+				(address, trie::encode_account(account.nonce, account.balance, storage_root, code_hash))
+			})
+			.collect::<Vec<_>>();
+
+		trie::sec_trie_root(accounts)
+	}
+}
+
+/// Ethereum address of the account of the block author found by `F`, mapped
+/// like any other account by the `ConvertAccountId` of pallet-evm. `F` finds
+/// the account in the pre-runtime digests of the consensus engine, such as
+/// `pallet_session::FindAccountFromAuthorIndex<T, Babe>` on chains with
+/// sessions.
+pub struct FindAuthorAddress<T, F>(sp_std::marker::PhantomData<(T, F)>);
+
+impl<T: pallet_evm::Trait, F: FindAuthor<T::AccountId>> FindAuthor<H160> for FindAuthorAddress<T, F> {
+	fn find_author<'a, I>(digests: I) -> Option<H160> where
+		I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>
+	{
+		use pallet_evm::ConvertAccountId;
+
+		let account = F::find_author(digests)?;
+		Some(T::ConvertAccountId::convert_account_id(&account))
+	}
+}
+
+/// Gas limit given to pallet-evm, which takes a `u32`, for `gas_limit`. Larger
+/// limits saturate rather than wrap around to a smaller one.
+pub fn saturated_gas_limit(gas_limit: U256) -> u32 {
+	gas_limit.min(U256::from(u32::max_value())).low_u32()
+}
+
+/// Weight of `transact` for a transaction with `input_len` bytes of input and
+/// `gas`: the benchmarked weight of the extrinsic and of its share of
+/// `on_finalize`, and `WeightPerGas` for each unit of gas.
+fn transact_weight<T: Trait>(input_len: usize, gas: U256) -> Weight {
+	T::WeightInfo::transact(input_len as u32)
+		.saturating_add(T::WeightInfo::on_finalize(1).saturating_sub(T::WeightInfo::on_finalize(0)))
+		.saturating_add(T::WeightPerGas::get().saturating_mul(saturated_gas_limit(gas) as Weight))
+}
+
+/// Why an execution exiting with `reason` did not succeed, if it did not.
+pub fn execution_error(reason: &pallet_evm::ExitReason) -> Option<ExecutionError> {
+	use pallet_evm::{ExitReason, ExitError};
+
+	Some(match reason {
+		ExitReason::Succeed(_) => return None,
+		ExitReason::Revert(_) => ExecutionError::Reverted,
+		ExitReason::Fatal(_) => ExecutionError::Fatal,
+		ExitReason::Error(error) => match error {
+			ExitError::OutOfGas => ExecutionError::OutOfGas,
+			ExitError::OutOfFund => ExecutionError::OutOfFund,
+			ExitError::StackOverflow => ExecutionError::StackOverflow,
+			ExitError::StackUnderflow => ExecutionError::StackUnderflow,
+			ExitError::CallTooDeep => ExecutionError::CallTooDeep,
+			ExitError::CreateCollision => ExecutionError::CreateCollision,
+			ExitError::CreateContractLimit => ExecutionError::CreateContractLimit,
+			ExitError::DesignatedInvalid | ExitError::InvalidJump | ExitError::InvalidRange =>
+				ExecutionError::InvalidCode,
+			ExitError::OutOfOffset => ExecutionError::OutOfOffset,
+			_ => ExecutionError::Other,
+		},
+	})
+}
+
+/// Block gas limit derived from the weight available to normal extrinsics in a
+/// block, so that both limits stay consistent when the block weights change.
+/// `WeightPerGas` is the weight charged for one unit of gas.
+pub struct BlockGasLimitFromWeight<T, WeightPerGas>(sp_std::marker::PhantomData<(T, WeightPerGas)>);
+
+impl<T, WeightPerGas> Get<U256> for BlockGasLimitFromWeight<T, WeightPerGas> where
+	T: frame_system::Trait,
+	WeightPerGas: Get<Weight>,
+{
+	fn get() -> U256 {
+		let normal_weight = T::AvailableBlockRatio::get() * T::MaximumBlockWeight::get();
+		U256::from(normal_weight / WeightPerGas::get().max(1))
+	}
 }
 
 decl_storage! {
@@ -69,6 +290,22 @@ decl_storage! {
 		PendingTransactionsAndReceipts: Vec<(ethereum::Transaction, ethereum::Receipt)>;
 		TransactionStatuses: map hasher(blake2_128_concat) H256 => Option<TransactionStatus>;
 		Transactions: map hasher(blake2_128_concat) H256 => Option<(H256, u32)>;
+		/// Hashes of the transactions of an Ethereum block, in order, so that
+		/// lookups by position do not encode the transactions to hash them.
+		BlockTransactionHashes: map hasher(blake2_128_concat) H256 => Vec<H256>;
+		/// Hash of the Ethereum transaction that created a contract. Only
+		/// contracts created by a CREATE transaction are recorded, not the ones
+		/// created by CREATE or CREATE2 from inside another contract.
+		ContractCreations get(fn contract_creation): map hasher(blake2_128_concat) H160 => Option<H256>;
+		/// Whether Ethereum transactions are suspended, see `set_paused`.
+		Paused get(fn paused): bool;
+		/// Gas used by the Ethereum transactions of the current block.
+		GasUsed get(fn gas_used): U256;
+	}
+	add_extra_genesis {
+		build(|_config: &GenesisConfig| {
+			frame_support::storage::unhashed::put(PALLET_ETHEREUM_SCHEMA, &EthereumStorageSchema::V1);
+		});
 	}
 }
 
@@ -80,6 +317,10 @@ decl_event!(
 		// Just a normal `enum`, here's a dummy event to ensure it compiles.
 		/// Dummy event, just here so there's a generic type that's used.
 		Dummy(B),
+		/// Ethereum transactions were paused or resumed. [paused]
+		PausedSet(bool),
+		/// An Ethereum transaction did not succeed. [transaction_hash, error]
+		ExecutionFailed(H256, ExecutionError),
 	}
 );
 
@@ -119,40 +360,65 @@ decl_event!(
 // `ensure_root` and `ensure_none`.
 decl_module! {
 	// Simple declaration of the `Module` type. Lets the macro know what its working on.
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+	pub struct Module<T: Trait> for enum Call where
+		origin: T::Origin,
+		<T as frame_system::Trait>::Event: TryInto<pallet_evm::Event<T>>
+	{
 		/// Deposit one of this pallet's events by using the default implementation.
 		/// It is also possible to provide a custom implementation.
 		/// For non-generic events, the generic parameter just needs to be dropped, so that it
 		/// looks like: `fn deposit_event() = default;`.
 		fn deposit_event() = default;
 
-		/// Transact an Ethereum transaction.
-		#[weight = 0]
-		fn transact(origin, transaction: ethereum::Transaction) {
+		/// Transact an Ethereum transaction. Its whole gas limit is charged
+		/// upfront and the gas it did not use is refunded.
+		#[weight = transact_weight::<T>(transaction.input.len(), transaction.gas_limit)]
+		fn transact(origin, transaction: ethereum::Transaction) -> DispatchResultWithPostInfo {
 			ensure_none(origin)?;
 
-			let mut sig = [0u8; 65];
-			let mut msg = [0u8; 32];
-			sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
-			sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
-			sig[64] = transaction.signature.standard_v();
-			msg.copy_from_slice(&transaction.message_hash(Some(sp_io::misc::chain_id()))[..]);
+			ensure!(!Paused::get(), "Ethereum transactions are paused");
+			// Encoded once, for both the size check and the hash.
+			let encoded = rlp::encode(&transaction);
+			ensure!(encoded.len() <= T::MaxTransactionSize::get() as usize, "Transaction too large");
+			ensure!(Self::fits_remaining_gas(&transaction), "Exceeds block gas limit");
+			Self::check_replay_protection(&transaction).map_err(|(_, message)| message)?;
+			let source = Self::recover_signer(&transaction).ok_or("Recover public key failed")?;
+			ensure!(Self::may_deploy(source, &transaction), "Deployment not allowed");
+
+			let transaction_hash = H256::from_slice(Keccak256::digest(&encoded).as_slice());
+			let input_len = transaction.input.len();
+			let used_gas = Self::execute_with_hash(source, transaction, transaction_hash);
+
+			Ok(Some(transact_weight::<T>(input_len, used_gas)).into())
+		}
+
+		/// Pause or resume Ethereum transactions. While paused, they are neither
+		/// accepted in the pool nor executed, other extrinsics are unaffected.
+		#[weight = T::DbWeight::get().writes(1)]
+		fn set_paused(origin, paused: bool) {
+			T::PauseOrigin::ensure_origin(origin)?;
 
-			let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg)
-				.map_err(|_| "Recover public key failed")?;
-			let source = H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice()));
+			Paused::put(paused);
+			Self::deposit_event(RawEvent::PausedSet(paused));
+		}
 
-			Self::execute(source, transaction);
+		/// Record the storage schema on chains started before it was, so that
+		/// the blocks stored from now on are decoded as `V1` by clients.
+		fn on_runtime_upgrade() -> Weight {
+			if frame_support::storage::unhashed::exists(PALLET_ETHEREUM_SCHEMA) {
+				return T::DbWeight::get().reads(1)
+			}
+			frame_support::storage::unhashed::put(PALLET_ETHEREUM_SCHEMA, &EthereumStorageSchema::V1);
+			T::DbWeight::get().reads_writes(1, 1)
 		}
 
 		// The signature could also look like: `fn on_initialize()`.
 		// This function could also very well have a weight annotation, similar to any other. The
 		// only difference is that it mut be returned, not annotated.
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
-			// Anything that needs to be done at the start of the block.
-			// We don't do anything here.
-
-			0
+			// Reserve the base cost of building the Ethereum block in `on_finalize`,
+			// the per-transaction part is included in the weight of `transact`.
+			T::WeightInfo::on_finalize(0)
 		}
 
 		// The signature could also look like: `fn on_finalize()`
@@ -160,59 +426,74 @@ decl_module! {
 			let transactions_and_receipts = PendingTransactionsAndReceipts::take();
 			let (transactions, receipts): (Vec<_>, Vec<_>) =
 				transactions_and_receipts.into_iter().unzip();
+			// Encoded once, for both the transactions root and their hashes.
+			let encoded_transactions: Vec<_> = transactions.iter().map(rlp::encode).collect();
 			let ommers = Vec::<ethereum::Header>::new();
 
+			let mut logs_bloom = Bloom::default();
+			for receipt in &receipts {
+				logs_bloom.accrue_bloom(&receipt.logs_bloom);
+			}
+
 			let header = ethereum::Header {
 				parent_hash: frame_system::Module::<T>::parent_hash(),
 				ommers_hash: H256::from_slice(
 					Keccak256::digest(&rlp::encode_list(&ommers)[..]).as_slice(),
 				), // TODO: check ommers hash.
-				beneficiary: H160::default(),
-				state_root: H256::default(), // TODO: figure out if there's better way to get a sort-of-valid state root.
-				transactions_root: H256::from_slice(
-					Keccak256::digest(&rlp::encode_list(&transactions)[..]).as_slice(),
-				), // TODO: check transactions hash.
-				receipts_root: H256::from_slice(
-					Keccak256::digest(&rlp::encode_list(&receipts)[..]).as_slice(),
-				), // TODO: check receipts hash.
-				logs_bloom: Bloom::default(), // TODO: gather the logs bloom from receipts.
+				beneficiary: Self::find_author(),
+				state_root: T::StateRoot::get(),
+				transactions_root: trie::ordered_trie_root(&encoded_transactions),
+				receipts_root: trie::receipts_root(&receipts),
+				logs_bloom,
 				difficulty: U256::zero(),
 				number: U256::from(
 					UniqueSaturatedInto::<u128>::unique_saturated_into(
 						frame_system::Module::<T>::block_number()
 					)
 				),
-				gas_limit: U256::zero(), // TODO: set this using Ethereum's gas limit change algorithm.
-				gas_used: U256::zero(), // TODO: get this from receipts.
+				gas_limit: T::BlockGasLimit::get(),
+				gas_used: GasUsed::take(),
 				timestamp: UniqueSaturatedInto::<u64>::unique_saturated_into(
 					pallet_timestamp::Module::<T>::get()
 				),
-				extra_data: H256::default(),
-				mix_hash: H256::default(),
-				nonce: H64::default(),
+				extra_data: T::ExtraData::get(),
+				mix_hash: T::MixHash::get(),
+				nonce: T::HeaderNonce::get(),
 			};
 			let hash = H256::from_slice(Keccak256::digest(&rlp::encode(&header)).as_slice());
 
 			let block = ethereum::Block {
 				header,
-				transactions: transactions.clone(),
+				transactions,
 				ommers,
 			};
 
-			for t in &transactions {
-				let transaction_hash = H256::from_slice(
-					Keccak256::digest(&rlp::encode(t)).as_slice()
-				);
+			let log_counts = receipts.iter().map(|receipt| receipt.logs.len() as u32).collect();
+			let mut transaction_hashes = Vec::with_capacity(encoded_transactions.len());
+			for encoded in &encoded_transactions {
+				let transaction_hash = H256::from_slice(Keccak256::digest(encoded).as_slice());
 				if let Some(status) = TransactionStatuses::get(transaction_hash) {
 					Transactions::insert(
 						transaction_hash,
 						(hash, status.transaction_index)
 					);
 				}
+				transaction_hashes.push(transaction_hash);
 			}
 
 			BlocksAndReceipts::insert(hash, (block, receipts));
 			BlockNumbers::<T>::insert(n, hash);
+			BlockTransactionHashes::insert(hash, &transaction_hashes);
+
+			let log: DigestItem<T::Hash> = DigestItem::Consensus(
+				FRONTIER_ENGINE_ID,
+				ConsensusLog::EndBlockWithLogs {
+					block_hash: hash,
+					transaction_hashes,
+					log_counts,
+				}.encode(),
+			);
+			frame_system::Module::<T>::deposit_log(log.into());
 		}
 
 		// A runtime code run after every block and have access to extended set of APIs.
@@ -226,13 +507,50 @@ decl_module! {
 	}
 }
 
-impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> where
+	<T as frame_system::Trait>::Event: TryInto<pallet_evm::Event<T>>
+{
 	type Call = Call<T>;
 
 	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-		ValidTransaction::with_tag_prefix("Ethereum")
-			.and_provides(call)
-			.build()
+		if let Call::transact(transaction) = call {
+			if Paused::get() {
+				return Err(InvalidTransaction::Custom(EVM_PAUSED).into());
+			}
+			if !Self::fits_size_limit(transaction) {
+				return Err(InvalidTransaction::Custom(TRANSACTION_TOO_LARGE).into());
+			}
+			// It could never be included in a block.
+			if !Self::fits_block_gas_limit(transaction) {
+				return Err(InvalidTransaction::ExhaustsResources.into());
+			}
+			if let Err((code, _)) = Self::check_replay_protection(transaction) {
+				return Err(InvalidTransaction::Custom(code).into());
+			}
+			if let Some(source) = Self::recover_signer(transaction) {
+				if !Self::may_deploy(source, transaction) {
+					return Err(InvalidTransaction::Custom(DEPLOYMENT_NOT_ALLOWED).into());
+				}
+			}
+
+			ValidTransaction::with_tag_prefix("Ethereum")
+				.and_provides(call)
+				.priority(Self::transaction_priority(transaction))
+				.build()
+		} else {
+			Err(InvalidTransaction::Call.into())
+		}
+	}
+
+	fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+		if let Call::transact(transaction) = call {
+			// The transactions before it in the block may have used the gas
+			// it could take.
+			if !Self::fits_remaining_gas(transaction) {
+				return Err(InvalidTransaction::ExhaustsResources.into());
+			}
+		}
+		Self::validate_unsigned(TransactionSource::InBlock, call).map(|_| ())
 	}
 }
 
@@ -241,7 +559,43 @@ impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 // - Public interface. These are functions that are `pub` and generally fall into inspector
 // functions that do not write to storage and operation functions that do.
 // - Private functions. These are your usual private utilities unavailable to other pallets.
-impl<T: Trait> Module<T> {
+impl<T: Trait> Module<T> where
+	<T as frame_system::Trait>::Event: TryInto<pallet_evm::Event<T>>
+{
+	/// Pool priority of an Ethereum transaction: the tip it pays over the minimum
+	/// gas price for its whole gas limit. Like the fee-based priority of Substrate
+	/// transactions, this lets both kinds be ordered by what they pay, and lets a
+	/// gas price bump speed up inclusion.
+	pub fn transaction_priority(transaction: &ethereum::Transaction) -> TransactionPriority {
+		let tip_per_gas = transaction.gas_price
+			.saturating_sub(T::FeeCalculator::min_gas_price());
+		tip_per_gas
+			.saturating_mul(transaction.gas_limit)
+			.min(U256::from(TransactionPriority::max_value()))
+			.low_u64()
+	}
+
+	/// Whether the RLP encoding of `transaction` fits in `MaxTransactionSize`.
+	/// Only transactions whose input is close to the limit are encoded.
+	pub fn fits_size_limit(transaction: &ethereum::Transaction) -> bool {
+		let max_size = T::MaxTransactionSize::get() as usize;
+		if transaction.input.len().saturating_add(MAX_TRANSACTION_RLP_OVERHEAD) <= max_size {
+			return true
+		}
+		rlp::encode(transaction).len() <= max_size
+	}
+
+	/// Whether the gas limit of `transaction` is within the block gas limit.
+	pub fn fits_block_gas_limit(transaction: &ethereum::Transaction) -> bool {
+		transaction.gas_limit <= T::BlockGasLimit::get()
+	}
+
+	/// Whether the gas limit of `transaction` is within the gas the
+	/// transactions of the current block left of the block gas limit.
+	pub fn fits_remaining_gas(transaction: &ethereum::Transaction) -> bool {
+		transaction.gas_limit <= T::BlockGasLimit::get().saturating_sub(GasUsed::get())
+	}
+
 	pub fn transaction_status(hash: H256) -> Option<TransactionStatus> {
 		TransactionStatuses::get(hash)
 	}
@@ -259,6 +613,29 @@ impl<T: Trait> Module<T> {
 		Some((transaction.clone(), block, transaction_status, receipts[transaction_index as usize].clone()))
 	}
 
+	/// Transaction at `index` in the Ethereum block `hash`, with its block,
+	/// status and receipt, and the gas used in the block before it, read with a
+	/// single decoding of the block.
+	pub fn transaction_receipt(hash: H256, index: u32) -> Option<(
+		ethereum::Transaction,
+		ethereum::Block,
+		TransactionStatus,
+		ethereum::Receipt,
+		U256
+	)> {
+		let (block, mut receipts) = BlocksAndReceipts::get(hash)?;
+		let index = index as usize;
+		if index >= receipts.len() || index >= block.transactions.len() {
+			return None
+		}
+		let transaction = block.transactions[index].clone();
+		let status = TransactionStatuses::get(Self::transaction_hash_at(hash, &block, index))?;
+		let previous_gas_used = index.checked_sub(1)
+			.map(|previous_index| receipts[previous_index].used_gas)
+			.unwrap_or_default();
+		Some((transaction, block, status, receipts.swap_remove(index), previous_gas_used))
+	}
+
 	pub fn transaction_by_block_hash_and_index(
 		hash: H256,
 		index: u32
@@ -270,9 +647,7 @@ impl<T: Trait> Module<T> {
 		let (block,_receipt) = BlocksAndReceipts::get(hash)?;
 		if index < block.transactions.len() as u32 {
 			let transaction = &block.transactions[index as usize];
-			let transaction_hash = H256::from_slice(
-				Keccak256::digest(&rlp::encode(transaction)).as_slice()
-			);
+			let transaction_hash = Self::transaction_hash_at(hash, &block, index as usize);
 			let transaction_status = TransactionStatuses::get(transaction_hash)?;
 			Some((transaction.clone(), block, transaction_status))
 		} else {
@@ -305,6 +680,26 @@ impl<T: Trait> Module<T> {
 		None
 	}
 
+	/// Header of the Ethereum block `number`. It comes first in the encoding of
+	/// the stored block and receipts, so that only it is decoded.
+	pub fn block_header_by_number(number: T::BlockNumber) -> Option<ethereum::Header> {
+		if <BlockNumbers<T>>::contains_key(number) {
+			let hash = <BlockNumbers<T>>::get(number);
+			let raw = frame_support::storage::unhashed::get_raw(&BlocksAndReceipts::hashed_key_for(hash))?;
+			return ethereum::Header::decode(&mut &raw[..]).ok()
+		}
+		None
+	}
+
+	/// Receipts of the Ethereum block `number`, in transaction order.
+	pub fn block_receipts_by_number(number: T::BlockNumber) -> Option<Vec<ethereum::Receipt>> {
+		if <BlockNumbers<T>>::contains_key(number) {
+			let hash = <BlockNumbers<T>>::get(number);
+			return BlocksAndReceipts::get(hash).map(|(_block, receipts)| receipts)
+		}
+		None
+	}
+
 	pub fn block_by_hash(hash: H256) -> Option<ethereum::Block> {
 		if let Some((block, _receipt)) = BlocksAndReceipts::get(hash) {
 			return Some(block)
@@ -315,6 +710,11 @@ impl<T: Trait> Module<T> {
 	pub fn block_transaction_statuses(
 		block: &Block
 	) -> Vec<Option<TransactionStatus>> {
+		let hash = H256::from_slice(Keccak256::digest(&rlp::encode(&block.header)).as_slice());
+		let hashes = BlockTransactionHashes::get(hash);
+		if hashes.len() == block.transactions.len() {
+			return hashes.into_iter().map(<Module<T>>::transaction_status).collect()
+		}
 		block.transactions.iter().map(|transaction|{
 			let transaction_hash = H256::from_slice(
 				Keccak256::digest(&rlp::encode(transaction)).as_slice()
@@ -323,68 +723,388 @@ impl<T: Trait> Module<T> {
 		}).collect()
 	}
 
-	/// Execute an Ethereum transaction, ignoring transaction signatures.
+	/// Hash of the transaction at `index` in the Ethereum block `hash`, read
+	/// from `BlockTransactionHashes`, or computed for the blocks stored before
+	/// it was.
+	fn transaction_hash_at(hash: H256, block: &Block, index: usize) -> H256 {
+		BlockTransactionHashes::get(hash).get(index).copied().unwrap_or_else(|| {
+			H256::from_slice(Keccak256::digest(&rlp::encode(&block.transactions[index])).as_slice())
+		})
+	}
+
+	/// Root of the Ethereum storage trie of `address`, as committed to by its
+	/// account in `EthereumStateRoot`.
+	pub fn account_storage_root(address: H160) -> H256 {
+		let suffix = Blake2_128Concat::hash(&address.encode());
+		// Keys are left with the blake2_128_concat hash of the storage index.
+		let slots = StorageIterator::<H256>::with_suffix(b"EVM", b"AccountStorages", &suffix)
+			.filter(|(key, _)| key.len() == 16 + 32)
+			.map(|(key, value)| (H256::from_slice(&key[16..48]), value))
+			.collect();
+		trie::storage_root(slots)
+	}
+
+	/// Up to `max_results` EVM accounts, in the order of their keys in the
+	/// pallet-evm account map, from the key `start` on. Keys are the
+	/// blake2_128_concat hashes of the addresses, so that pages are stable
+	/// whatever accounts are listed.
+	pub fn account_range(start: Vec<u8>, max_results: u32) -> AccountRange {
+		let prefix = [
+			sp_io::hashing::twox_128(b"EVM"),
+			sp_io::hashing::twox_128(b"Accounts"),
+		].concat();
+		let first = [&prefix[..], &start[..]].concat();
+		let mut next = if sp_io::storage::get(&first).is_some() {
+			Some(first)
+		} else {
+			sp_io::storage::next_key(&first)
+		};
+
+		let mut accounts = Vec::new();
+		while let Some(key) = next.take().filter(|key| key.starts_with(&prefix)) {
+			let suffix = key[prefix.len()..].to_vec();
+			if accounts.len() as u32 >= max_results {
+				return AccountRange { accounts, next: Some(suffix) };
+			}
+			next = sp_io::storage::next_key(&key);
+			if suffix.len() != 16 + 20 {
+				continue;
+			}
+
+			let address = H160::from_slice(&suffix[16..36]);
+			let code_hash = H256::from_slice(
+				Keccak256::digest(&pallet_evm::Module::<T>::account_codes(address)).as_slice()
+			);
+			accounts.push(AccountRangeEntry {
+				key: suffix,
+				address,
+				basic: pallet_evm::Module::<T>::accounts(address),
+				code_hash,
+			});
+		}
+		AccountRange { accounts, next: None }
+	}
+
+	/// Recover the sender of an Ethereum transaction from its signature.
+	pub fn recover_signer(transaction: &ethereum::Transaction) -> Option<H160> {
+		let mut sig = [0u8; 65];
+		let mut msg = [0u8; 32];
+		sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
+		sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
+		sig[64] = transaction.signature.standard_v();
+		msg.copy_from_slice(&transaction.message_hash(transaction.signature.chain_id())[..]);
+
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).ok()?;
+		Some(H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice())))
+	}
+
+	/// Address of the author of the current block, zero if unknown.
+	fn find_author() -> H160 {
+		let digest = frame_system::Module::<T>::digest();
+		let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+		T::FindAuthor::find_author(pre_runtime_digests).unwrap_or_default()
+	}
+
+	/// Check `transaction` against the EIP-155 rules of the chain, returning
+	/// the custom validity error and dispatch error of a violation.
+	fn check_replay_protection(transaction: &ethereum::Transaction) -> Result<(), (u8, &'static str)> {
+		match transaction.signature.chain_id() {
+			Some(chain_id) if chain_id != sp_io::misc::chain_id() =>
+				Err((INVALID_CHAIN_ID, "invalid chain id")),
+			None if !T::AllowUnprotectedTxs::get() =>
+				Err((UNPROTECTED_TRANSACTION, "only replay-protected transactions allowed")),
+			_ => Ok(()),
+		}
+	}
+
+	/// Whether `source` may send `transaction`, checking CREATE transactions
+	/// against `T::DeploymentFilter`.
+	fn may_deploy(source: H160, transaction: &ethereum::Transaction) -> bool {
+		match transaction.action {
+			ethereum::TransactionAction::Create => T::DeploymentFilter::filter(&source),
+			ethereum::TransactionAction::Call(_) => true,
+		}
+	}
+
+	/// Execute an Ethereum transaction, ignoring transaction signatures. Callers
+	/// that already have its hash use `execute_with_hash`.
 	pub fn execute(source: H160, transaction: ethereum::Transaction) {
 		let transaction_hash = H256::from_slice(
 			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
 		);
-		let transaction_index = PendingTransactionsAndReceipts::get().len() as u32;
+		Self::execute_with_hash(source, transaction, transaction_hash);
+	}
 
-		let status = match transaction.action {
+	/// Execute an Ethereum transaction whose hash is already known, ignoring
+	/// transaction signatures. Returns the gas it used.
+	pub fn execute_with_hash(source: H160, transaction: ethereum::Transaction, transaction_hash: H256) -> U256 {
+		let pending = PendingTransactionsAndReceipts::get();
+		let transaction_index = pending.len() as u32;
+		let previous_gas_used = pending.last()
+			.map(|(_, receipt)| receipt.used_gas)
+			.unwrap_or_default();
+		let events_before = frame_system::Module::<T>::events().len();
+
+		let (to, contract_address, reason, used_gas) = match transaction.action {
 			ethereum::TransactionAction::Call(target) => {
-				pallet_evm::Module::<T>::execute_call(
+				let (reason, _, used_gas) = pallet_evm::Module::<T>::execute_call(
 					source,
 					target,
 					transaction.input.clone(),
 					transaction.value,
-					transaction.gas_limit.low_u32(),
+					saturated_gas_limit(transaction.gas_limit),
 					transaction.gas_price,
 					Some(transaction.nonce),
 					true,
 				).unwrap(); // TODO: handle error
 
-				TransactionStatus {
-					transaction_hash,
-					transaction_index,
-					from: source,
-					to: Some(target),
-					contract_address: None,
-					logs: Vec::new(), // TODO: feed in logs.
-					logs_bloom: Bloom::default(), // TODO: feed in bloom.
-				}
+				(Some(target), None, reason, used_gas)
 			},
 			ethereum::TransactionAction::Create => {
-				let contract_address = pallet_evm::Module::<T>::execute_create(
+				let (reason, contract_address, used_gas) = pallet_evm::Module::<T>::execute_create(
 					source,
 					transaction.input.clone(),
 					transaction.value,
-					transaction.gas_limit.low_u32(),
+					saturated_gas_limit(transaction.gas_limit),
 					transaction.gas_price,
 					Some(transaction.nonce),
 					true,
-				).unwrap().1; // TODO: handle error
-
-				TransactionStatus {
-					transaction_hash,
-					transaction_index,
-					from: source,
-					to: None,
-					contract_address: Some(contract_address),
-					logs: Vec::new(), // TODO: feed in logs.
-					logs_bloom: Bloom::default(), // TODO: feed in bloom.
-				}
+				).unwrap(); // TODO: handle error
+
+				(None, Some(contract_address), reason, used_gas)
 			},
 		};
 
+		let logs = Self::evm_logs_since(events_before);
+		let logs_bloom = Self::logs_bloom(&logs);
+		let status = TransactionStatus {
+			transaction_hash,
+			transaction_index,
+			from: source,
+			to,
+			contract_address,
+			logs: logs.clone(),
+			logs_bloom,
+		};
+
+		if let (Some(address), true) = (contract_address, reason.is_succeed()) {
+			ContractCreations::insert(address, transaction_hash);
+		}
 		TransactionStatuses::insert(transaction_hash, status);
+		if let Some(error) = execution_error(&reason) {
+			Self::deposit_event(RawEvent::ExecutionFailed(transaction_hash, error));
+		}
 
 		let receipt = ethereum::Receipt {
-			state_root: H256::default(), // TODO: should be okay / error status.
-			used_gas: U256::default(), // TODO: set this.
-			logs_bloom: Bloom::default(), // TODO: set this.
-			logs: Vec::new(), // TODO: set this.
+			state_root: trie::receipt_status_word(reason.is_succeed()),
+			used_gas: previous_gas_used.saturating_add(used_gas),
+			logs_bloom,
+			logs,
 		};
 
 		PendingTransactionsAndReceipts::append((transaction, receipt));
+		GasUsed::mutate(|gas_used| *gas_used = gas_used.saturating_add(used_gas));
+		used_gas
+	}
+
+	/// Override the EVM state of `address`.
+	///
+	/// This writes pallet-evm storage directly and is only meant for dry runs, such
+	/// as `eth_call`, whose state changes are discarded afterwards.
+	pub fn apply_account_override(address: H160, account_override: AccountOverride) {
+		const EVM: &[u8] = b"EVM";
+		let hashed_address = Blake2_128Concat::hash(&address.encode());
+
+		if account_override.balance.is_some() || account_override.nonce.is_some() {
+			let mut account = pallet_evm::Module::<T>::accounts(address);
+			if let Some(balance) = account_override.balance {
+				account.balance = balance;
+			}
+			if let Some(nonce) = account_override.nonce {
+				account.nonce = nonce;
+			}
+			put_storage_value(EVM, b"Accounts", &hashed_address, account);
+		}
+
+		if let Some(code) = account_override.code {
+			put_storage_value(EVM, b"AccountCodes", &hashed_address, code);
+		}
+
+		let slots = match (account_override.state, account_override.state_diff) {
+			(Some(state), _) => {
+				remove_storage_prefix(EVM, b"AccountStorages", &hashed_address);
+				state
+			},
+			(None, Some(state_diff)) => state_diff,
+			(None, None) => Vec::new(),
+		};
+		for (index, value) in slots {
+			let key = [&hashed_address[..], &Blake2_128Concat::hash(&index.encode())[..]].concat();
+			put_storage_value(EVM, b"AccountStorages", &key, value);
+		}
+	}
+
+	/// Override the block context seen by the EVM. Like `apply_account_override`,
+	/// this is only meant for dry runs.
+	pub fn apply_block_override(block_override: BlockOverride) {
+		if let Some(number) = block_override.number {
+			frame_system::Module::<T>::set_block_number(number.into());
+		}
+		if let Some(timestamp) = block_override.timestamp {
+			pallet_timestamp::Now::<T>::put(
+				T::Moment::unique_saturated_from(timestamp.saturating_mul(1000))
+			);
+		}
+		if let Some(base_fee) = block_override.base_fee {
+			T::BaseFeeOverride::set_base_fee(base_fee);
+		}
+	}
+
+	/// Execute the `pending` transactions from their senders, in order, to build
+	/// the pending state on top of the current one. Transactions that cannot be
+	/// executed anymore are skipped, and so are those whose gas limit does not
+	/// fit in what remains of the block gas limit, which bounds the work to that
+	/// of one block. Like `apply_account_override`, this is only meant for dry
+	/// runs.
+	pub fn apply_pending(pending: Vec<(H160, ethereum::Transaction)>) {
+		let mut remaining_gas = T::BlockGasLimit::get();
+		for (source, transaction) in pending {
+			if transaction.gas_limit > remaining_gas {
+				continue
+			}
+			remaining_gas -= transaction.gas_limit;
+			let gas_limit = saturated_gas_limit(transaction.gas_limit);
+
+			let _ = match transaction.action {
+				ethereum::TransactionAction::Call(target) => pallet_evm::Module::<T>::execute_call(
+					source,
+					target,
+					transaction.input,
+					transaction.value,
+					gas_limit,
+					transaction.gas_price,
+					Some(transaction.nonce),
+					true,
+				).map(|_| ()),
+				ethereum::TransactionAction::Create => pallet_evm::Module::<T>::execute_create(
+					source,
+					transaction.input,
+					transaction.value,
+					gas_limit,
+					transaction.gas_price,
+					Some(transaction.nonce),
+					true,
+				).map(|_| ()),
+			};
+		}
+	}
+
+	/// Write back the storage `changes` of `apply_pending`, as collected by the
+	/// client, to execute a dry run on the pending state without executing the
+	/// pending transactions again.
+	pub fn apply_pending_changes(changes: PendingChanges) {
+		for (key, value) in changes {
+			match value {
+				Some(value) => sp_io::storage::set(&key, &value),
+				None => sp_io::storage::clear(&key),
+			}
+		}
+	}
+
+	/// Execute `blocks` of calls in sequence, each block numbered after the
+	/// previous one unless overridden. Like `apply_account_override`, this is
+	/// only meant for dry runs.
+	pub fn simulate(blocks: Vec<SimulatedBlock>) -> Vec<Vec<Option<SimulatedOutcome>>> {
+		blocks.into_iter().map(|block| {
+			for (address, account_override) in block.state_overrides {
+				Self::apply_account_override(address, account_override);
+			}
+			let number = UniqueSaturatedInto::<u32>::unique_saturated_into(
+				frame_system::Module::<T>::block_number()
+			);
+			Self::apply_block_override(BlockOverride {
+				number: Some(block.block_override.number.unwrap_or(number.saturating_add(1))),
+				..block.block_override
+			});
+
+			block.calls.into_iter().map(|call| {
+				let events_before = frame_system::Module::<T>::events().len();
+				Self::dry_run(call).map(|outcome| SimulatedOutcome {
+					succeeded: outcome.succeeded,
+					error: outcome.error,
+					output: outcome.output,
+					used_gas: outcome.used_gas,
+					logs: Self::evm_logs_since(events_before),
+				})
+			}).collect()
+		}).collect()
+	}
+
+	/// Execute `call`, applying its state changes, and creating a contract if
+	/// it has no recipient. `None` if it could not be executed at all.
+	pub fn dry_run(call: DryRunCall) -> Option<DryRunOutcome> {
+		let gas_limit = saturated_gas_limit(call.gas_limit);
+		let (reason, output, used_gas, created) = match call.to {
+			Some(to) => {
+				let (reason, output, used_gas) = pallet_evm::Module::<T>::execute_call(
+					call.from,
+					to,
+					call.data,
+					call.value,
+					gas_limit,
+					call.gas_price,
+					call.nonce,
+					true,
+				).ok()?;
+				(reason, output, used_gas, None)
+			},
+			None => {
+				let (reason, address, used_gas) = pallet_evm::Module::<T>::execute_create(
+					call.from,
+					call.data,
+					call.value,
+					gas_limit,
+					call.gas_price,
+					call.nonce,
+					true,
+				).ok()?;
+				(reason, pallet_evm::Module::<T>::account_codes(address), used_gas, Some(address))
+			},
+		};
+		Some(DryRunOutcome {
+			succeeded: reason.is_succeed(),
+			error: execution_error(&reason),
+			output,
+			used_gas,
+			created,
+		})
+	}
+
+	/// Collect the logs the EVM deposited as events since the event at index `from`.
+	fn evm_logs_since(from: usize) -> Vec<Log> {
+		frame_system::Module::<T>::events()
+			.into_iter()
+			.skip(from)
+			.filter_map(|record| match record.event.try_into() {
+				Ok(pallet_evm::RawEvent::Log(log)) => Some(Log {
+					address: log.address,
+					topics: log.topics,
+					data: log.data,
+				}),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Compute the 2048-bit bloom of the given logs' addresses and topics.
+	pub fn logs_bloom(logs: &[Log]) -> Bloom {
+		let mut bloom = Bloom::default();
+		for log in logs {
+			bloom.accrue(BloomInput::Raw(&log.address[..]));
+			for topic in &log.topics {
+				bloom.accrue(BloomInput::Raw(&topic[..]));
+			}
+		}
+		bloom
 	}
 }
@@ -19,20 +19,35 @@
 use super::*;
 use crate::{Module, Trait};
 use ethereum::{TransactionAction, TransactionSignature};
-use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_support::{
+	impl_outer_origin, impl_outer_event, parameter_types, weights::Weight, traits::FindAuthor,
+};
 use pallet_evm::{FeeCalculator, HashTruncateConvertAccountId};
 use rlp::*;
 use sp_core::{H160, H256, U256};
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
-	ModuleId, Perbill,
+	ConsensusEngineId, ModuleId, Perbill,
 };
 
 impl_outer_origin! {
 	pub enum Origin for Test where system = frame_system {}
 }
 
+mod pallet_ethereum {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_balances<T>,
+		pallet_evm<T>,
+		pallet_ethereum<T>,
+	}
+}
+
 // For testing the pallet, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of pallets we want to use.
@@ -55,7 +70,7 @@ impl frame_system::Trait for Test {
 	type AccountId = H160;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -77,7 +92,7 @@ parameter_types! {
 
 impl pallet_balances::Trait for Test {
 	type Balance = u64;
-	type Event = ();
+	type Event = TestEvent;
 	type DustRemoval = ();
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
@@ -93,10 +108,19 @@ impl pallet_timestamp::Trait for Test {
 	type MinimumPeriod = MinimumPeriod;
 }
 
-pub struct FixedGasPrice;
-impl FeeCalculator for FixedGasPrice {
+/// Storage key of the gas price of the mock, kept outside any pallet.
+const GAS_PRICE_KEY: &[u8] = b":mock:gas_price";
+
+/// Gas price of 1, unless a block override set another base fee.
+pub struct MockGasPrice;
+impl FeeCalculator for MockGasPrice {
 	fn min_gas_price() -> U256 {
-		1.into()
+		frame_support::storage::unhashed::get_or(GAS_PRICE_KEY, 1.into())
+	}
+}
+impl SetBaseFee for MockGasPrice {
+	fn set_base_fee(base_fee: U256) {
+		frame_support::storage::unhashed::put(GAS_PRICE_KEY, &base_fee);
 	}
 }
 
@@ -107,15 +131,49 @@ parameter_types! {
 
 impl pallet_evm::Trait for Test {
 	type ModuleId = EVMModuleId;
-	type FeeCalculator = FixedGasPrice;
+	type FeeCalculator = MockGasPrice;
 	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
 	type Currency = Balances;
-	type Event = ();
+	type Event = TestEvent;
 	type Precompiles = ();
 }
 
+/// Author of every block.
+pub const AUTHOR: H160 = H160::repeat_byte(0xaa);
+
+pub struct FixedAuthor;
+impl FindAuthor<H160> for FixedAuthor {
+	fn find_author<'a, I>(_digests: I) -> Option<H160> where
+		I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>
+	{
+		Some(AUTHOR)
+	}
+}
+
+parameter_types! {
+	pub BlockGasLimit: U256 = U256::from(u32::max_value());
+	pub const WeightPerGas: Weight = 1;
+	pub const AllowUnprotectedTxs: bool = false;
+	pub const MaxTransactionSize: u32 = 16 * 1024;
+	pub ExtraData: H256 = extra_data(b"frontier-test");
+	pub HeaderNonce: H64 = H64::repeat_byte(0x42);
+}
+
 impl Trait for Test {
-	type Event = ();
+	type Event = TestEvent;
+	type BlockGasLimit = BlockGasLimit;
+	type WeightInfo = ();
+	type WeightPerGas = WeightPerGas;
+	type StateRoot = EthereumStateRoot<Test>;
+	type DeploymentFilter = DenySecondAccount;
+	type AllowUnprotectedTxs = AllowUnprotectedTxs;
+	type FindAuthor = FixedAuthor;
+	type PauseOrigin = frame_system::EnsureRoot<H160>;
+	type MaxTransactionSize = MaxTransactionSize;
+	type ExtraData = ExtraData;
+	type MixHash = ();
+	type HeaderNonce = HeaderNonce;
+	type BaseFeeOverride = MockGasPrice;
 }
 
 pub type System = frame_system::Module<Test>;
@@ -136,24 +194,34 @@ pub fn new_test_ext(accounts_len: usize) -> (Vec<AccountInfo>, sp_io::TestExtern
 		.unwrap()
 		.into();
 
-	let pairs = (0..accounts_len)
-		.map(|i| {
-			let private_key = H256::from_slice(&[(i + 1) as u8; 32]); //H256::from_low_u64_be((i + 1) as u64);
-			let secret_key = secp256k1::SecretKey::parse_slice(&private_key[..]).unwrap();
-			let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
-			let address = H160::from(H256::from_slice(
-				&Keccak256::digest(&public_key.serialize()[1..])[..],
-			));
-			AccountInfo {
-				private_key: private_key,
-				address: address,
-			}
-		})
-		.collect::<Vec<_>>();
+	let pairs = (0..accounts_len).map(account).collect::<Vec<_>>();
 
 	(pairs, ext)
 }
 
+/// The `i`-th test account.
+pub fn account(i: usize) -> AccountInfo {
+	let private_key = H256::from_slice(&[(i + 1) as u8; 32]); //H256::from_low_u64_be((i + 1) as u64);
+	let secret_key = secp256k1::SecretKey::parse_slice(&private_key[..]).unwrap();
+	let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
+	let address = H160::from(H256::from_slice(
+		&Keccak256::digest(&public_key.serialize()[1..])[..],
+	));
+	AccountInfo {
+		private_key: private_key,
+		address: address,
+	}
+}
+
+/// Lets every test account deploy contracts, except the second one.
+pub struct DenySecondAccount;
+
+impl Filter<H160> for DenySecondAccount {
+	fn filter(deployer: &H160) -> bool {
+		*deployer != account(1).address
+	}
+}
+
 pub fn contract_address(sender: H160, nonce: u64) -> H160 {
 	let mut rlp = RlpStream::new_list(2);
 	rlp.append(&sender);
@@ -178,27 +246,35 @@ pub struct UnsignedTransaction {
 }
 
 impl UnsignedTransaction {
-	fn signing_rlp_append(&self, s: &mut RlpStream) {
-		s.begin_list(9);
+	fn signing_rlp_append(&self, s: &mut RlpStream, chain_id: Option<u64>) {
+		s.begin_list(if chain_id.is_some() { 9 } else { 6 });
 		s.append(&self.nonce);
 		s.append(&self.gas_price);
 		s.append(&self.gas_limit);
 		s.append(&self.action);
 		s.append(&self.value);
 		s.append(&self.input);
-		s.append(&42u8); // TODO: move this chain id into the frame ethereum configuration
-		s.append(&0u8);
-		s.append(&0u8);
+		if let Some(chain_id) = chain_id {
+			s.append(&chain_id);
+			s.append(&0u8);
+			s.append(&0u8);
+		}
 	}
 
-	fn signing_hash(&self) -> H256 {
+	fn signing_hash(&self, chain_id: Option<u64>) -> H256 {
 		let mut stream = RlpStream::new();
-		self.signing_rlp_append(&mut stream);
+		self.signing_rlp_append(&mut stream, chain_id);
 		H256::from_slice(&Keccak256::digest(&stream.drain()).as_slice())
 	}
 
+	/// Sign for the chain id of test externalities.
 	pub fn sign(self, key: &H256) -> Transaction {
-		let hash = self.signing_hash();
+		self.sign_with_chain_id(key, Some(42))
+	}
+
+	/// Sign for `chain_id`, or without replay protection.
+	pub fn sign_with_chain_id(self, key: &H256, chain_id: Option<u64>) -> Transaction {
+		let hash = self.signing_hash(chain_id);
 		let msg = {
 			let mut a = [0u8; 32];
 			for i in 0..32 {
@@ -206,11 +282,15 @@ impl UnsignedTransaction {
 			}
 			secp256k1::Message::parse(&a)
 		};
-		let s = secp256k1::sign(&msg, &secp256k1::SecretKey::parse_slice(&key[..]).unwrap());
-		let sig = s.0.serialize();
+		let (s, recovery_id) = secp256k1::sign(&msg, &secp256k1::SecretKey::parse_slice(&key[..]).unwrap());
+		let sig = s.serialize();
+		let v = match chain_id {
+			Some(chain_id) => recovery_id.serialize() as u64 + chain_id * 2 + 35,
+			None => recovery_id.serialize() as u64 + 27,
+		};
 
 		let sig = TransactionSignature::new(
-			0x78,
+			v,
 			H256::from_slice(&sig[0..32]),
 			H256::from_slice(&sig[32..64]),
 		)
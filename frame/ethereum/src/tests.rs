@@ -22,7 +22,11 @@ use mock::*;
 use rustc_hex::FromHex;
 use std::str::FromStr;
 use ethereum::TransactionSignature;
-use frame_support::assert_noop;
+use ethereum_types::BloomInput;
+use frame_support::{
+	assert_ok, assert_noop, traits::OnFinalize, unsigned::ValidateUnsigned,
+	weights::GetDispatchInfo,
+};
 
 // This ERC-20 contract mints the maximum amount of tokens to the contract creator.
 // pragma solidity ^0.5.0;
@@ -32,7 +36,7 @@ use frame_support::assert_noop;
 // }
 const ERC20_CONTRACT_BYTECODE: &str = "608060405234801561001057600080fd5b50610041337fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff61004660201b60201c565b610291565b600073ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff1614156100e9576040517f08c379a000000000000000000000000000000000000000000000000000000000815260040180806020018281038252601f8152602001807f45524332303a206d696e7420746f20746865207a65726f20616464726573730081525060200191505060405180910390fd5b6101028160025461020960201b610c7c1790919060201c565b60028190555061015d816000808573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020016000205461020960201b610c7c1790919060201c565b6000808473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff168152602001908152602001600020819055508173ffffffffffffffffffffffffffffffffffffffff16600073ffffffffffffffffffffffffffffffffffffffff167fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef836040518082815260200191505060405180910390a35050565b600080828401905083811015610287576040517f08c379a000000000000000000000000000000000000000000000000000000000815260040180806020018281038252601b8152602001807f536166654d6174683a206164646974696f6e206f766572666c6f77000000000081525060200191505060405180910390fd5b8091505092915050565b610e3a806102a06000396000f3fe608060405234801561001057600080fd5b50600436106100885760003560e01c806370a082311161005b57806370a08231146101fd578063a457c2d714610255578063a9059cbb146102bb578063dd62ed3e1461032157610088565b8063095ea7b31461008d57806318160ddd146100f357806323b872dd146101115780633950935114610197575b600080fd5b6100d9600480360360408110156100a357600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff16906020019092919080359060200190929190505050610399565b604051808215151515815260200191505060405180910390f35b6100fb6103b7565b6040518082815260200191505060405180910390f35b61017d6004803603606081101561012757600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff169060200190929190803573ffffffffffffffffffffffffffffffffffffffff169060200190929190803590602001909291905050506103c1565b604051808215151515815260200191505060405180910390f35b6101e3600480360360408110156101ad57600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff1690602001909291908035906020019092919050505061049a565b604051808215151515815260200191505060405180910390f35b61023f6004803603602081101561021357600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff16906020019092919050505061054d565b6040518082815260200191505060405180910390f35b6102a16004803603604081101561026b57600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff16906020019092919080359060200190929190505050610595565b604051808215151515815260200191505060405180910390f35b610307600480360360408110156102d157600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff16906020019092919080359060200190929190505050610662565b604051808215151515815260200191505060405180910390f35b6103836004803603604081101561033757600080fd5b81019080803573ffffffffffffffffffffffffffffffffffffffff169060200190929190803573ffffffffffffffffffffffffffffffffffffffff169060200190929190505050610680565b6040518082815260200191505060405180910390f35b60006103ad6103a6610707565b848461070f565b6001905092915050565b6000600254905090565b60006103ce848484610906565b61048f846103da610707565b61048a85604051806060016040528060288152602001610d7060289139600160008b73ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff1681526020019081526020016000206000610440610707565b73ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054610bbc9092919063ffffffff16565b61070f565b600190509392505050565b60006105436104a7610707565b8461053e85600160006104b8610707565b73ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002060008973ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054610c7c90919063ffffffff16565b61070f565b6001905092915050565b60008060008373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff168152602001908152602001600020549050919050565b60006106586105a2610707565b8461065385604051806060016040528060258152602001610de160259139600160006105cc610707565b73ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002060008a73ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054610bbc9092919063ffffffff16565b61070f565b6001905092915050565b600061067661066f610707565b8484610906565b6001905092915050565b6000600160008473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002060008373ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054905092915050565b600033905090565b600073ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff161415610795576040517f08c379a0000000000000000000000000000000000000000000000000000000008152600401808060200182810382526024815260200180610dbd6024913960400191505060405180910390fd5b600073ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff16141561081b576040517f08c379a0000000000000000000000000000000000000000000000000000000008152600401808060200182810382526022815260200180610d286022913960400191505060405180910390fd5b80600160008573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002060008473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff168152602001908152602001600020819055508173ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff167f8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925836040518082815260200191505060405180910390a3505050565b600073ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff16141561098c576040517f08c379a0000000000000000000000000000000000000000000000000000000008152600401808060200182810382526025815260200180610d986025913960400191505060405180910390fd5b600073ffffffffffffffffffffffffffffffffffffffff168273ffffffffffffffffffffffffffffffffffffffff161415610a12576040517f08c379a0000000000000000000000000000000000000000000000000000000008152600401808060200182810382526023815260200180610d056023913960400191505060405180910390fd5b610a7d81604051806060016040528060268152602001610d4a602691396000808773ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054610bbc9092919063ffffffff16565b6000808573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002081905550610b10816000808573ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff16815260200190815260200160002054610c7c90919063ffffffff16565b6000808473ffffffffffffffffffffffffffffffffffffffff1673ffffffffffffffffffffffffffffffffffffffff168152602001908152602001600020819055508173ffffffffffffffffffffffffffffffffffffffff168373ffffffffffffffffffffffffffffffffffffffff167fddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef836040518082815260200191505060405180910390a3505050565b6000838311158290610c69576040517f08c379a00000000000000000000000000000000000000000000000000000000081526004018080602001828103825283818151815260200191508051906020019080838360005b83811015610c2e578082015181840152602081019050610c13565b50505050905090810190601f168015610c5b5780820380516001836020036101000a031916815260200191505b509250505060405180910390fd5b5060008385039050809150509392505050565b600080828401905083811015610cfa576040517f08c379a000000000000000000000000000000000000000000000000000000000815260040180806020018281038252601b8152602001807f536166654d6174683a206164646974696f6e206f766572666c6f77000000000081525060200191505060405180910390fd5b809150509291505056fe45524332303a207472616e7366657220746f20746865207a65726f206164647265737345524332303a20617070726f766520746f20746865207a65726f206164647265737345524332303a207472616e7366657220616d6f756e7420657863656564732062616c616e636545524332303a207472616e7366657220616d6f756e74206578636565647320616c6c6f77616e636545524332303a207472616e736665722066726f6d20746865207a65726f206164647265737345524332303a20617070726f76652066726f6d20746865207a65726f206164647265737345524332303a2064656372656173656420616c6c6f77616e63652062656c6f77207a65726fa265627a7a72315820c7a5ffabf642bda14700b2de42f8c57b36621af020441df825de45fd2b3e1c5c64736f6c63430005100032";
 
-fn default_erc20_creation_transaction(account: &AccountInfo) -> Transaction {
+fn default_erc20_creation_unsigned_transaction() -> UnsignedTransaction {
 	UnsignedTransaction {
 		nonce: U256::zero(),
 		gas_price: U256::from(0),
@@ -40,7 +44,11 @@ fn default_erc20_creation_transaction(account: &AccountInfo) -> Transaction {
 		action: ethereum::TransactionAction::Create,
 		value: U256::zero(),
 		input: FromHex::from_hex(ERC20_CONTRACT_BYTECODE).unwrap(),
-	}.sign(&account.private_key)
+	}
+}
+
+fn default_erc20_creation_transaction(account: &AccountInfo) -> Transaction {
+	default_erc20_creation_unsigned_transaction().sign(&account.private_key)
 }
 
 #[test]
@@ -74,6 +82,24 @@ fn transaction_should_be_added_to_pending() {
 	});
 }
 
+#[test]
+fn contract_creation_should_be_recorded() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		let transaction_hash = H256::from_slice(
+			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
+		);
+		let erc20_address = contract_address(alice.address, 0);
+		assert_eq!(Ethereum::contract_creation(erc20_address), None);
+
+		Ethereum::execute(alice.address, transaction);
+		assert_eq!(Ethereum::contract_creation(erc20_address), Some(transaction_hash));
+	});
+}
+
 
 #[test]
 #[should_panic(expected = "called `Result::unwrap()` on an `Err` value: BalanceLow")]
@@ -92,6 +118,60 @@ fn transaction_without_enough_gas_should_not_work() {
 	});
 }
 
+#[test]
+fn pending_transactions_should_be_applied_skipping_failing_ones() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+	let erc20_address = contract_address(alice.address, 0);
+
+	ext.execute_with(|| {
+		let mut unaffordable = default_erc20_creation_transaction(alice);
+		unaffordable.gas_price = U256::from(1);
+
+		Ethereum::apply_pending(vec![
+			(alice.address, unaffordable),
+			(alice.address, default_erc20_creation_transaction(alice)),
+		]);
+		assert_eq!(Evm::accounts(alice.address).nonce, U256::from(1));
+		assert!(!Evm::account_codes(erc20_address).is_empty());
+		assert_eq!(PendingTransactionsAndReceipts::get().len(), 0);
+	});
+}
+
+#[test]
+fn pending_transactions_should_fit_in_the_block_gas_limit() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+	let erc20_address = contract_address(alice.address, 0);
+
+	ext.execute_with(|| {
+		let mut too_large = default_erc20_creation_unsigned_transaction();
+		too_large.gas_limit = BlockGasLimit::get() + 1;
+
+		Ethereum::apply_pending(vec![
+			(alice.address, too_large.sign(&alice.private_key)),
+			(alice.address, default_erc20_creation_transaction(alice)),
+		]);
+		assert_eq!(Evm::accounts(alice.address).nonce, U256::from(1));
+		assert!(!Evm::account_codes(erc20_address).is_empty());
+	});
+}
+
+#[test]
+fn pending_changes_should_be_written_back() {
+	let (_, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		sp_io::storage::set(b"pending:cleared", &[2]);
+		Ethereum::apply_pending_changes(vec![
+			(b"pending:set".to_vec(), Some(vec![1])),
+			(b"pending:cleared".to_vec(), None),
+		]);
+		assert_eq!(sp_io::storage::get(b"pending:set"), Some(vec![1]));
+		assert_eq!(sp_io::storage::get(b"pending:cleared"), None);
+	});
+}
+
 #[test]
 fn contract_constructor_should_get_executed() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -149,6 +229,214 @@ fn invalid_signature_should_be_ignored() {
 }
 
 
+#[test]
+fn filtered_deployer_should_not_create_contracts() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(bob);
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::Custom(DEPLOYMENT_NOT_ALLOWED).into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"Deployment not allowed"
+		);
+	});
+}
+
+#[test]
+fn paused_ethereum_should_reject_transactions() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		assert_noop!(
+			Ethereum::set_paused(Origin::none(), true),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Ethereum::set_paused(Origin::root(), true));
+		assert!(Ethereum::paused());
+
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::Custom(EVM_PAUSED).into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction.clone()),
+			"Ethereum transactions are paused"
+		);
+
+		assert_ok!(Ethereum::set_paused(Origin::root(), false));
+		assert_ok!(Ethereum::transact(Origin::none(), transaction));
+	});
+}
+
+#[test]
+fn oversized_transaction_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let mut transaction = default_erc20_creation_unsigned_transaction();
+		transaction.input = vec![0; MaxTransactionSize::get() as usize];
+		let transaction = transaction.sign(&alice.private_key);
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::Custom(TRANSACTION_TOO_LARGE).into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"Transaction too large"
+		);
+	});
+}
+
+#[test]
+fn transaction_above_block_gas_limit_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let mut transaction = default_erc20_creation_unsigned_transaction();
+		transaction.gas_limit = BlockGasLimit::get() + 1;
+		let transaction = transaction.sign(&alice.private_key);
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::ExhaustsResources.into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"Exceeds block gas limit"
+		);
+	});
+}
+
+#[test]
+fn transaction_above_remaining_block_gas_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		GasUsed::put(BlockGasLimit::get() - transaction.gas_limit + 1);
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::pre_dispatch(&Call::transact(transaction.clone())),
+			Err(InvalidTransaction::ExhaustsResources.into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"Exceeds block gas limit"
+		);
+	});
+}
+
+#[test]
+fn transaction_should_refund_the_weight_of_unused_gas() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		let call = Call::<Test>::transact(transaction.clone());
+		let charged = call.get_dispatch_info().weight;
+		let post_info = Ethereum::transact(Origin::none(), transaction).unwrap();
+
+		let used_gas = Ethereum::gas_used();
+		assert!(!used_gas.is_zero());
+		assert_eq!(
+			post_info.actual_weight,
+			Some(charged - (U256::from(0x100000) - used_gas).low_u64() * WeightPerGas::get()),
+		);
+
+		Ethereum::on_finalize(1);
+		assert!(Ethereum::gas_used().is_zero());
+		assert_eq!(Ethereum::block_by_number(1).unwrap().header.gas_used, used_gas);
+	});
+}
+
+#[test]
+fn failed_transaction_should_deposit_its_execution_error() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		// Events are not stored in the genesis block.
+		System::set_block_number(1);
+		let mut transaction = default_erc20_creation_unsigned_transaction();
+		// INVALID
+		transaction.input = vec![0xfe];
+		let transaction = transaction.sign(&alice.private_key);
+		let hash = H256::from_slice(Keccak256::digest(&rlp::encode(&transaction)).as_slice());
+
+		assert_ok!(Ethereum::transact(Origin::none(), transaction));
+		assert!(System::events().into_iter().any(|record| record.event ==
+			TestEvent::pallet_ethereum(RawEvent::ExecutionFailed(hash, ExecutionError::InvalidCode))
+		));
+	});
+}
+
+#[test]
+fn transaction_for_other_chain_should_be_rejected() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_unsigned_transaction()
+			.sign_with_chain_id(&alice.private_key, Some(43));
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::Custom(INVALID_CHAIN_ID).into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"invalid chain id"
+		);
+	});
+}
+
+#[test]
+fn unprotected_transaction_should_be_rejected_unless_allowed() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_unsigned_transaction()
+			.sign_with_chain_id(&alice.private_key, None);
+		assert_eq!(transaction.signature.chain_id(), None);
+		assert_eq!(Ethereum::recover_signer(&transaction), Some(alice.address));
+		assert_eq!(
+			<Ethereum as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact(transaction.clone()),
+			),
+			Err(InvalidTransaction::Custom(UNPROTECTED_TRANSACTION).into()),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::none(), transaction),
+			"only replay-protected transactions allowed"
+		);
+	});
+}
+
 #[test]
 fn contract_should_be_created_at_given_address() {
 	let (pairs, mut ext) = new_test_ext(1);
@@ -164,3 +452,495 @@ fn contract_should_be_created_at_given_address() {
 		assert_ne!(Evm::account_codes(erc20_address).len(), 0);
 	});
 }
+
+#[test]
+fn receipt_and_block_should_contain_logs_bloom() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let erc20_address = contract_address(alice.address, 0);
+
+	ext.execute_with(|| {
+		// Events, and therefore EVM logs, are not recorded at genesis.
+		System::set_block_number(1);
+		Ethereum::execute(
+			alice.address,
+			default_erc20_creation_transaction(alice),
+		);
+
+		let (_, receipt) = PendingTransactionsAndReceipts::get()[0].clone();
+		assert_eq!(receipt.logs.len(), 1);
+		assert_eq!(receipt.logs[0].address, erc20_address);
+		assert!(receipt.logs_bloom.contains_input(BloomInput::Raw(&erc20_address[..])));
+
+		Ethereum::on_finalize(1);
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(block.header.logs_bloom, receipt.logs_bloom);
+	});
+}
+
+#[test]
+fn block_header_should_be_read_without_the_block() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Ethereum::execute(alice.address, default_erc20_creation_transaction(alice));
+		Ethereum::on_finalize(1);
+
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(Ethereum::block_header_by_number(1), Some(block.header));
+		assert_eq!(Ethereum::block_header_by_number(2), None);
+	});
+}
+
+#[test]
+fn block_should_name_its_author_as_beneficiary() {
+	let (_, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Ethereum::on_finalize(1);
+		assert_eq!(Ethereum::block_by_number(1).unwrap().header.beneficiary, AUTHOR);
+	});
+}
+
+#[test]
+fn account_override_should_replace_evm_state() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let erc20_address = contract_address(alice.address, 0);
+	let alice_storage_address = storage_address(alice.address, H256::zero());
+
+	ext.execute_with(|| {
+		Ethereum::execute(
+			alice.address,
+			default_erc20_creation_transaction(alice),
+		);
+
+		Ethereum::apply_account_override(alice.address, AccountOverride {
+			balance: Some(U256::from(1000)),
+			nonce: Some(U256::from(7)),
+			..Default::default()
+		});
+		assert_eq!(Evm::accounts(alice.address).balance, U256::from(1000));
+		assert_eq!(Evm::accounts(alice.address).nonce, U256::from(7));
+
+		let slot = H256::from_low_u64_be(1);
+		Ethereum::apply_account_override(erc20_address, AccountOverride {
+			code: Some(vec![0x00]),
+			state_diff: Some(vec![(slot, H256::repeat_byte(0x11))]),
+			..Default::default()
+		});
+		assert_eq!(Evm::account_codes(erc20_address), vec![0x00]);
+		assert_eq!(Evm::account_storages(erc20_address, slot), H256::repeat_byte(0x11));
+		assert_ne!(Evm::account_storages(erc20_address, alice_storage_address), H256::zero());
+
+		Ethereum::apply_account_override(erc20_address, AccountOverride {
+			state: Some(vec![]),
+			..Default::default()
+		});
+		assert_eq!(Evm::account_storages(erc20_address, slot), H256::zero());
+		assert_eq!(Evm::account_storages(erc20_address, alice_storage_address), H256::zero());
+	});
+}
+
+#[test]
+fn account_range_should_page_through_accounts() {
+	let (pairs, mut ext) = new_test_ext(3);
+
+	ext.execute_with(|| {
+		for (i, pair) in pairs.iter().enumerate() {
+			Ethereum::apply_account_override(pair.address, AccountOverride {
+				balance: Some(U256::from(i + 1)),
+				..Default::default()
+			});
+		}
+
+		let first = Ethereum::account_range(vec![], 2);
+		assert_eq!(first.accounts.len(), 2);
+		let start = first.next.clone().unwrap();
+		assert!(first.accounts[0].key < first.accounts[1].key);
+		assert!(first.accounts[1].key < start);
+
+		let second = Ethereum::account_range(start.clone(), 2);
+		assert_eq!(second.accounts.len(), 1);
+		assert_eq!(second.accounts[0].key, start);
+		assert_eq!(second.next, None);
+
+		let mut listed = first.accounts.into_iter().chain(second.accounts)
+			.map(|entry| (entry.address, entry.basic.balance, entry.code_hash))
+			.collect::<Vec<_>>();
+		listed.sort();
+		let empty_code_hash = H256::from_slice(Keccak256::digest(&[]).as_slice());
+		let mut expected = pairs.iter().enumerate()
+			.map(|(i, pair)| (pair.address, U256::from(i + 1), empty_code_hash))
+			.collect::<Vec<_>>();
+		expected.sort();
+		assert_eq!(listed, expected);
+	});
+}
+
+#[test]
+fn block_override_should_replace_block_context() {
+	let (_, mut ext) = new_test_ext(0);
+
+	ext.execute_with(|| {
+		Ethereum::apply_block_override(BlockOverride {
+			number: Some(42),
+			timestamp: Some(1_600_000_000),
+			base_fee: Some(7.into()),
+		});
+		assert_eq!(System::block_number(), 42);
+		assert_eq!(pallet_timestamp::Module::<Test>::get(), 1_600_000_000_000);
+		assert_eq!(<Test as pallet_evm::Trait>::FeeCalculator::min_gas_price(), U256::from(7));
+	});
+}
+
+#[test]
+fn simulated_blocks_should_see_previous_blocks() {
+	let (pairs, mut ext) = new_test_ext(2);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	let erc20_address = contract_address(alice.address, 0);
+	let call = |data: String| DryRunCall {
+		from: alice.address,
+		to: Some(erc20_address),
+		data: FromHex::from_hex(data.as_str()).unwrap(),
+		gas_limit: U256::from(0x100000),
+		gas_price: U256::one(),
+		..Default::default()
+	};
+	let bob_word = format!("{:0>64}", format!("{:x}", bob.address));
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Ethereum::execute(
+			alice.address,
+			default_erc20_creation_transaction(alice),
+		);
+
+		let outcomes = Ethereum::simulate(vec![
+			SimulatedBlock {
+				calls: vec![call(format!("a9059cbb{}{:0>64}", bob_word, "1"))],
+				..Default::default()
+			},
+			SimulatedBlock {
+				calls: vec![call(format!("70a08231{}", bob_word))],
+				..Default::default()
+			},
+		]);
+
+		let transfer = outcomes[0][0].as_ref().unwrap();
+		assert!(transfer.succeeded);
+		assert_eq!(transfer.logs.len(), 1);
+		let balance = outcomes[1][0].as_ref().unwrap();
+		assert_eq!(U256::from_big_endian(&balance.output), U256::one());
+		assert_eq!(System::block_number(), 3);
+	});
+}
+
+#[test]
+fn dry_run_without_recipient_should_create_contract() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let outcome = Ethereum::dry_run(DryRunCall {
+			from: alice.address,
+			to: None,
+			data: FromHex::from_hex(ERC20_CONTRACT_BYTECODE).unwrap(),
+			gas_limit: U256::from(0x100000),
+			gas_price: U256::one(),
+			..Default::default()
+		}).unwrap();
+
+		let created = contract_address(alice.address, 0);
+		assert!(outcome.succeeded);
+		assert_eq!(outcome.created, Some(created));
+		assert_eq!(outcome.output, pallet_evm::Module::<Test>::account_codes(created));
+		assert!(!outcome.output.is_empty());
+	});
+}
+
+#[test]
+fn transaction_priority_should_follow_tip() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let mut transaction = default_erc20_creation_transaction(alice);
+		assert_eq!(Ethereum::transaction_priority(&transaction), 0);
+
+		transaction.gas_price = U256::from(11);
+		assert_eq!(Ethereum::transaction_priority(&transaction), 10 * 0x100000);
+
+		let validity = <Ethereum as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&Call::transact(transaction),
+		).unwrap();
+		assert_eq!(validity.priority, 10 * 0x100000);
+	});
+}
+
+#[test]
+fn block_gas_limit_should_follow_block_weights() {
+	frame_support::parameter_types! {
+		pub const WeightPerGas: Weight = 2;
+	}
+
+	// 75% of the maximum block weight of 1024, at a weight of 2 per gas.
+	assert_eq!(BlockGasLimitFromWeight::<Test, WeightPerGas>::get(), U256::from(384));
+}
+
+#[test]
+fn block_should_deposit_consensus_log() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		let transaction_hash = H256::from_slice(
+			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
+		);
+		Ethereum::execute(alice.address, transaction);
+		Ethereum::on_finalize(1);
+
+		let block_hash = BlockNumbers::<Test>::get(1);
+		let (_, receipts) = BlocksAndReceipts::get(block_hash).unwrap();
+		assert_eq!(
+			fp_consensus::find_log(&System::digest()),
+			Ok(ConsensusLog::EndBlockWithLogs {
+				block_hash,
+				transaction_hashes: vec![transaction_hash],
+				log_counts: vec![receipts[0].logs.len() as u32],
+			})
+		);
+	});
+}
+
+#[test]
+fn transaction_receipt_should_be_found_by_index() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		Ethereum::execute(alice.address, default_erc20_creation_transaction(alice));
+		Ethereum::on_finalize(1);
+
+		let block_hash = BlockNumbers::<Test>::get(1);
+		let (block, receipts) = BlocksAndReceipts::get(block_hash).unwrap();
+		let transaction_hash = H256::from_slice(
+			Keccak256::digest(&rlp::encode(&block.transactions[0])).as_slice()
+		);
+		assert_eq!(
+			Ethereum::transaction_receipt(block_hash, 0),
+			Some((
+				block.transactions[0].clone(),
+				block.clone(),
+				TransactionStatuses::get(transaction_hash).unwrap(),
+				receipts[0].clone(),
+				U256::zero(),
+			))
+		);
+		assert_eq!(Ethereum::transaction_receipt(block_hash, 1), None);
+	});
+}
+
+#[test]
+fn block_statuses_should_be_found_with_and_without_stored_hashes() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		let transaction_hash = H256::from_slice(
+			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
+		);
+		Ethereum::execute(alice.address, transaction);
+		Ethereum::on_finalize(1);
+
+		let block_hash = BlockNumbers::<Test>::get(1);
+		let (block, _) = BlocksAndReceipts::get(block_hash).unwrap();
+		let status = TransactionStatuses::get(transaction_hash);
+		assert_eq!(BlockTransactionHashes::get(block_hash), vec![transaction_hash]);
+		assert_eq!(Ethereum::block_transaction_statuses(&block), vec![status.clone()]);
+
+		// Blocks stored before the hashes were have them computed.
+		BlockTransactionHashes::remove(block_hash);
+		assert_eq!(Ethereum::block_transaction_statuses(&block), vec![status]);
+		assert!(Ethereum::transaction_by_block_hash_and_index(block_hash, 0).is_some());
+	});
+}
+
+#[test]
+fn receipts_root_should_commit_to_eip658_receipts() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		Ethereum::execute(alice.address, default_erc20_creation_transaction(alice));
+
+		let (_, receipt) = PendingTransactionsAndReceipts::get()[0].clone();
+		assert_eq!(trie::receipt_status(&receipt), 1);
+		assert!(receipt.used_gas > U256::zero());
+		assert!(!trie::is_legacy_receipt(&receipt));
+
+		Ethereum::on_finalize(1);
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(block.header.gas_used, receipt.used_gas);
+		assert_eq!(
+			block.header.receipts_root,
+			trie::ordered_trie_root(vec![trie::encode_receipt(&receipt)])
+		);
+		assert_eq!(rlp::Rlp::new(&trie::encode_receipt(&receipt)).at(0).unwrap().as_raw(), &[1u8]);
+	});
+}
+
+#[test]
+fn empty_block_should_have_empty_receipts_root() {
+	let (_, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		Ethereum::on_finalize(1);
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(
+			block.header.receipts_root,
+			H256::from_str("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421").unwrap()
+		);
+	});
+}
+
+#[test]
+fn transactions_root_should_commit_to_transactions() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		Ethereum::execute(alice.address, transaction.clone());
+		Ethereum::on_finalize(1);
+
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(
+			block.header.transactions_root,
+			trie::ordered_trie_root(vec![rlp::encode(&transaction)])
+		);
+		assert_ne!(block.header.transactions_root, trie::transactions_root(&[]));
+	});
+}
+
+#[test]
+fn ethereum_state_root_should_follow_evm_state() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let before = EthereumStateRoot::<Test>::get();
+		Ethereum::execute(alice.address, default_erc20_creation_transaction(alice));
+		let after = EthereumStateRoot::<Test>::get();
+		assert_ne!(before, after);
+
+		Ethereum::on_finalize(1);
+		let block = Ethereum::block_by_number(1).unwrap();
+		assert_eq!(block.header.state_root, after);
+		assert_eq!(ZeroStateRoot::get(), H256::zero());
+	});
+}
+
+#[test]
+fn account_storage_root_should_commit_to_account_storage() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let erc20_address = contract_address(alice.address, 0);
+		assert_eq!(Ethereum::account_storage_root(erc20_address), trie::storage_root(vec![]));
+
+		Ethereum::execute(alice.address, default_erc20_creation_transaction(alice));
+		let root = Ethereum::account_storage_root(erc20_address);
+		assert_ne!(root, trie::storage_root(vec![]));
+		assert_eq!(Ethereum::account_storage_root(alice.address), trie::storage_root(vec![]));
+	});
+}
+
+#[test]
+fn runtime_upgrade_should_record_the_storage_schema() {
+	let (_pairs, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade};
+
+		assert_eq!(unhashed::get::<EthereumStorageSchema>(PALLET_ETHEREUM_SCHEMA), None);
+		Ethereum::on_runtime_upgrade();
+		assert_eq!(
+			unhashed::get::<EthereumStorageSchema>(PALLET_ETHEREUM_SCHEMA),
+			Some(EthereumStorageSchema::V1),
+		);
+	});
+}
+
+#[test]
+fn genesis_should_record_the_storage_schema() {
+	let storage = GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(
+			frame_support::storage::unhashed::get::<EthereumStorageSchema>(PALLET_ETHEREUM_SCHEMA),
+			Some(EthereumStorageSchema::V1),
+		);
+	});
+}
+
+#[test]
+fn header_should_hold_the_configured_extra_data_and_seal() {
+	let (_pairs, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		Ethereum::on_finalize(1);
+		let header = Ethereum::block_by_number(1).unwrap().header;
+		assert_eq!(&header.extra_data[..13], b"frontier-test");
+		assert_eq!(header.extra_data, extra_data(b"frontier-test"));
+		assert_eq!(header.mix_hash, H256::zero());
+		assert_eq!(header.nonce, H64::repeat_byte(0x42));
+	});
+}
+
+#[test]
+fn extra_data_should_be_truncated_to_the_field() {
+	assert_eq!(extra_data(&[0xff; 40]), H256::repeat_byte(0xff));
+	assert_eq!(extra_data(b""), H256::zero());
+}
+
+#[test]
+fn gas_limit_should_saturate_to_u32() {
+	assert_eq!(saturated_gas_limit(U256::from(21000)), 21000);
+	assert_eq!(saturated_gas_limit(U256::from(u32::max_value()) + 1), u32::max_value());
+	assert_eq!(saturated_gas_limit(U256::max_value()), u32::max_value());
+}
+
+#[test]
+fn find_author_address_should_map_the_author_account() {
+	use frame_support::traits::FindAuthor;
+	use pallet_evm::ConvertAccountId;
+
+	struct FixedAccount;
+	impl FindAuthor<H160> for FixedAccount {
+		fn find_author<'a, I>(_digests: I) -> Option<H160> where
+			I: 'a + IntoIterator<Item=(sp_runtime::ConsensusEngineId, &'a [u8])>
+		{
+			Some(H160::repeat_byte(0xbb))
+		}
+	}
+
+	assert_eq!(
+		FindAuthorAddress::<Test, FixedAccount>::find_author(Vec::new()),
+		Some(pallet_evm::HashTruncateConvertAccountId::<sp_runtime::traits::BlakeTwo256>::convert_account_id(
+			&H160::repeat_byte(0xbb)
+		)),
+	);
+	assert_eq!(FindAuthorAddress::<Test, ()>::find_author(Vec::new()), None);
+}
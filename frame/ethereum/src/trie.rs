@@ -0,0 +1,116 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum encodings and Merkle-Patricia trie roots of block contents, as
+//! committed to by the roots of Ethereum headers.
+
+use sp_std::prelude::*;
+use ethereum_types::{H256, U256};
+use hash_db::Hasher;
+use sha3::{Digest, Keccak256};
+
+/// Keccak-256 hasher of Ethereum tries.
+pub struct KeccakHasher;
+
+impl Hasher for KeccakHasher {
+	type Out = H256;
+	type StdHasher = plain_hasher::PlainHasher;
+	const LENGTH: usize = 32;
+
+	fn hash(x: &[u8]) -> H256 {
+		H256::from_slice(Keccak256::digest(x).as_slice())
+	}
+}
+
+/// Root of the trie mapping the RLP-encoded index of each item to the item.
+pub fn ordered_trie_root<I, V>(input: I) -> H256 where
+	I: IntoIterator<Item=V>,
+	V: AsRef<[u8]>,
+{
+	triehash::ordered_trie_root::<KeccakHasher, I>(input)
+}
+
+/// Root of the trie mapping the Keccak-256 hash of each key to its value.
+pub fn sec_trie_root<I, A, B>(input: I) -> H256 where
+	I: IntoIterator<Item=(A, B)>,
+	A: AsRef<[u8]> + Ord,
+	B: AsRef<[u8]>,
+{
+	triehash::sec_trie_root::<KeccakHasher, I, A, B>(input)
+}
+
+/// Root of the storage trie of an account, given its storage slots.
+pub fn storage_root(slots: Vec<(H256, H256)>) -> H256 {
+	sec_trie_root(
+		slots.into_iter()
+			.filter(|(_, value)| !value.is_zero())
+			.map(|(index, value)| (index, rlp::encode(&U256::from_big_endian(value.as_bytes()))))
+	)
+}
+
+/// Encoding of an account in the state trie.
+pub fn encode_account(nonce: U256, balance: U256, storage_root: H256, code_hash: H256) -> Vec<u8> {
+	let mut stream = rlp::RlpStream::new_list(4);
+	stream.append(&nonce);
+	stream.append(&balance);
+	stream.append(&storage_root);
+	stream.append(&code_hash);
+	stream.out()
+}
+
+/// Root of the transactions trie of a block.
+pub fn transactions_root(transactions: &[ethereum::Transaction]) -> H256 {
+	ordered_trie_root(transactions.iter().map(|transaction| rlp::encode(transaction)))
+}
+
+/// EIP-658 status of a receipt: 1 if the transaction succeeded, 0 otherwise.
+///
+/// `ethereum::Receipt` predates EIP-658, so the status is stored as a word in
+/// its `state_root` field.
+pub fn receipt_status(receipt: &ethereum::Receipt) -> u64 {
+	receipt.state_root.to_low_u64_be()
+}
+
+/// Whether `receipt` was recorded before receipts held their EIP-658 status,
+/// when neither the status nor the gas used were set.
+pub fn is_legacy_receipt(receipt: &ethereum::Receipt) -> bool {
+	receipt.used_gas.is_zero()
+}
+
+/// `state_root` field of a receipt holding the EIP-658 status `succeeded`.
+pub fn receipt_status_word(succeeded: bool) -> H256 {
+	H256::from_low_u64_be(succeeded as u64)
+}
+
+/// EIP-658 encoding of a receipt: its status, the cumulative gas used in the
+/// block, its logs bloom and its logs.
+pub fn encode_receipt(receipt: &ethereum::Receipt) -> Vec<u8> {
+	let mut stream = rlp::RlpStream::new_list(4);
+	if receipt_status(receipt) == 1 {
+		stream.append(&1u8);
+	} else {
+		stream.append_empty_data();
+	}
+	stream.append::<U256>(&receipt.used_gas);
+	stream.append(&receipt.logs_bloom);
+	stream.append_list(&receipt.logs);
+	stream.out()
+}
+
+/// Root of the receipts trie of a block.
+pub fn receipts_root(receipts: &[ethereum::Receipt]) -> H256 {
+	ordered_trie_root(receipts.iter().map(encode_receipt))
+}
@@ -0,0 +1,89 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet-ethereum.
+//!
+//! Nothing here was measured. The `transact` and `on_finalize` benchmarks of
+//! `benchmarking.rs` have not been run, and every figure below, base weights,
+//! per-byte and per-transaction slopes and database accesses alike, is a
+//! placeholder. The gas of a transaction is charged apart at `WeightPerGas`,
+//! so the `transact` figures only stand in for the signature recovery and the
+//! bookkeeping around the EVM execution, and the `on_finalize` ones for
+//! building the Ethereum block, its receipts and its transaction root.
+//!
+//! A chain should replace them with the output of the benchmarks on its
+//! reference hardware, from a node built with `--features runtime-benchmarks`:
+//!
+//! ```sh
+//! frontier-template-node benchmark --chain dev \
+//!     --execution wasm --wasm-execution compiled \
+//!     --pallet pallet-ethereum --extrinsic '*' --steps 50 --repeat 20
+//! ```
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+use frame_support::traits::Get;
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by pallet-ethereum.
+pub trait WeightInfo {
+	/// `transact` of a transaction with `s` bytes of input, apart from its
+	/// gas, which is charged at `WeightPerGas`.
+	fn transact(s: u32) -> Weight;
+	/// `on_finalize` of a block with `t` transactions.
+	fn on_finalize(t: u32) -> Weight;
+}
+
+/// Placeholder weights of the pallet, for runtimes on RocksDb.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn transact(s: u32) -> Weight {
+		// Unmeasured placeholders.
+		(200_000_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+
+	fn on_finalize(t: u32) -> Weight {
+		// Unmeasured placeholders.
+		(50_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(t as Weight))
+	}
+}
+
+/// The placeholders of `SubstrateWeight` with `RocksDbWeight`, for mock runtimes.
+impl WeightInfo for () {
+	fn transact(s: u32) -> Weight {
+		// Unmeasured placeholders.
+		(200_000_000 as Weight)
+			.saturating_add((10_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+
+	fn on_finalize(t: u32) -> Weight {
+		// Unmeasured placeholders.
+		(50_000_000 as Weight)
+			.saturating_add((5_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+			.saturating_add(DbWeight::get().writes(t as Weight))
+	}
+}
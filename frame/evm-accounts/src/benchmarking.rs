@@ -0,0 +1,87 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for pallet-evm-accounts.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, account};
+use frame_support::{storage::migration::put_storage_value, Blake2_128Concat, StorageHasher};
+use frame_system::RawOrigin;
+
+/// Fixed key of the address claimed in the benchmarks.
+fn secret_key() -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&[1u8; 32]).expect("benchmark secret key is valid")
+}
+
+/// Address of `secret_key`.
+fn address() -> H160 {
+	let public_key = secp256k1::PublicKey::from_secret_key(&secret_key());
+	H160::from_slice(&keccak_256(&public_key.serialize()[1..])[12..])
+}
+
+/// Claim of `who` signed by `secret_key`.
+fn sign_claim<T: Trait>(who: &T::AccountId) -> ecdsa::Signature {
+	let message = secp256k1::Message::parse(&Module::<T>::claim_hash(who));
+	let (signature, recovery_id) = secp256k1::sign(&message, &secret_key());
+
+	let mut raw = [0u8; 65];
+	raw[..64].copy_from_slice(&signature.serialize()[..]);
+	raw[64] = recovery_id.serialize();
+	ecdsa::Signature::from_raw(raw)
+}
+
+benchmarks! {
+	_ { }
+
+	claim_account {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let signature = sign_claim::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), address(), signature)
+	verify {
+		assert_eq!(Module::<T>::evm_address(caller), Some(address()));
+	}
+
+	transfer {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let source = T::ConvertAccountId::convert_account_id(&caller);
+		let target = H160::repeat_byte(0x22);
+		let key = Blake2_128Concat::hash(&source.encode());
+		put_storage_value(b"EVM", b"Accounts", &key, pallet_evm::Account {
+			nonce: U256::zero(),
+			balance: U256::from(u64::max_value()),
+		});
+	}: _(RawOrigin::Signed(caller), source, target, U256::from(1_000))
+	verify {
+		assert_eq!(pallet_evm::Module::<T>::accounts(target).balance, U256::from(1_000));
+	}
+}
+
+#[cfg(all(feature = "std", test))]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn test_benchmarks() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_claim_account::<Test>());
+			assert_ok!(test_benchmark_transfer::<Test>());
+		});
+	}
+}
@@ -0,0 +1,196 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # EVM accounts pallet
+//!
+//! Lets a Substrate account claim an EVM address, by submitting an EIP-712
+//! signature of its account id made with the address' key. Claims are
+//! recorded both ways: `EvmAddressMapping` uses them to give a linked account
+//! its claimed address in the EVM, and precompiles can look up the Substrate
+//! account behind an address with `Module::account_id`.
+//!
+//! Funds held by the EVM under the address an account had before its claim
+//! are not moved; they should be withdrawn before claiming.
+//!
+//! `transfer` moves EVM balance from the address of the sender, charging the
+//! gas of a plain value transfer rather than the pricing of `pallet_evm::call`.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure};
+use frame_system::{self as system, ensure_signed};
+use sp_core::{H160, U256, ecdsa};
+use sp_io::hashing::keccak_256;
+use sp_std::{marker::PhantomData, prelude::*};
+use pallet_evm::{ConvertAccountId, FeeCalculator};
+
+pub use weights::WeightInfo;
+
+mod benchmarking;
+pub mod weights;
+
+#[cfg(test)]
+mod tests;
+
+/// Name of the EIP-712 signing domain of claims.
+pub const DOMAIN_NAME: &[u8] = b"Frontier";
+/// Version of the EIP-712 signing domain of claims.
+pub const DOMAIN_VERSION: &[u8] = b"1";
+
+/// Gas limit of `transfer`, the intrinsic gas of an Ethereum transaction.
+pub const TRANSFER_GAS: u32 = 21_000;
+
+pub trait Trait: frame_system::Trait + pallet_evm::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// Weight information for the extrinsics of this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as EvmAccounts {
+		/// Substrate account that claimed an EVM address.
+		pub Accounts get(fn account_id): map hasher(blake2_128_concat) H160 => Option<T::AccountId>;
+		/// EVM address claimed by a Substrate account.
+		pub EvmAddresses get(fn evm_address): map hasher(blake2_128_concat) T::AccountId => Option<H160>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as frame_system::Trait>::AccountId {
+		/// An account claimed an EVM address. [who, address]
+		ClaimAccount(AccountId, H160),
+		/// EVM balance was transferred. [source, target, value]
+		Transferred(H160, H160, U256),
+		/// An EVM balance transfer failed, its gas being charged. [source, target]
+		TransferFailed(H160, H160),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account already claimed an EVM address.
+		AccountAlreadyLinked,
+		/// The EVM address was already claimed.
+		AddressAlreadyLinked,
+		/// The signature was not made by the claimed address.
+		BadSignature,
+		/// The source address is not the EVM address of the sender.
+		NotAllowed,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Claim `address` for the sender. `signature` is the EIP-712 signature
+		/// of the sender's account id by `address`, see `claim_hash`.
+		#[weight = T::WeightInfo::claim_account()]
+		fn claim_account(origin, address: H160, signature: ecdsa::Signature) {
+			let who = ensure_signed(origin)?;
+
+			ensure!(!EvmAddresses::<T>::contains_key(&who), Error::<T>::AccountAlreadyLinked);
+			ensure!(!Accounts::<T>::contains_key(address), Error::<T>::AddressAlreadyLinked);
+			ensure!(
+				Self::recover_signer(&who, &signature) == Some(address),
+				Error::<T>::BadSignature
+			);
+
+			Accounts::<T>::insert(address, &who);
+			EvmAddresses::<T>::insert(&who, address);
+
+			Self::deposit_event(RawEvent::ClaimAccount(who, address));
+		}
+
+		/// Transfer `value` from `source`, the EVM address of the sender, to
+		/// `target`, paying `TRANSFER_GAS` at the minimum gas price. The call
+		/// runs with no data and no more gas, so transfers to contracts doing
+		/// any work on receipt fail.
+		#[weight = T::WeightInfo::transfer()]
+		fn transfer(origin, source: H160, target: H160, value: U256) {
+			let who = ensure_signed(origin)?;
+			ensure!(T::ConvertAccountId::convert_account_id(&who) == source, Error::<T>::NotAllowed);
+
+			let (reason, _, _) = pallet_evm::Module::<T>::execute_call(
+				source,
+				target,
+				Vec::new(),
+				value,
+				TRANSFER_GAS,
+				T::FeeCalculator::min_gas_price(),
+				None,
+				true,
+			)?;
+
+			if reason.is_succeed() {
+				Self::deposit_event(RawEvent::Transferred(source, target, value));
+			} else {
+				Self::deposit_event(RawEvent::TransferFailed(source, target));
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// EIP-712 hash signed to claim an address for `who`, of the typed data
+	/// `Claim(bytes substrateAddress)` where `substrateAddress` is the encoded
+	/// account id, in the domain `(name, version, chainId)`.
+	pub fn claim_hash(who: &T::AccountId) -> [u8; 32] {
+		let mut chain_id = [0u8; 32];
+		U256::from(sp_io::misc::chain_id()).to_big_endian(&mut chain_id);
+
+		let domain_separator = keccak_256(&[
+			&keccak_256(b"EIP712Domain(string name,string version,uint256 chainId)")[..],
+			&keccak_256(DOMAIN_NAME)[..],
+			&keccak_256(DOMAIN_VERSION)[..],
+			&chain_id[..],
+		].concat());
+		let struct_hash = keccak_256(&[
+			&keccak_256(b"Claim(bytes substrateAddress)")[..],
+			&keccak_256(&who.encode())[..],
+		].concat());
+
+		keccak_256(&[&b"\x19\x01"[..], &domain_separator[..], &struct_hash[..]].concat())
+	}
+
+	/// Address whose key signed the claim of `who`.
+	fn recover_signer(who: &T::AccountId, signature: &ecdsa::Signature) -> Option<H160> {
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(
+			signature.as_ref(),
+			&Self::claim_hash(who),
+		).ok()?;
+		Some(H160::from_slice(&keccak_256(&pubkey)[12..]))
+	}
+}
+
+/// Converts an account to the EVM address it claimed, or with `Fallback` if it
+/// did not claim any.
+pub struct EvmAddressMapping<T, Fallback>(PhantomData<(T, Fallback)>);
+
+impl<T, Fallback> ConvertAccountId<T::AccountId> for EvmAddressMapping<T, Fallback> where
+	T: Trait,
+	Fallback: ConvertAccountId<T::AccountId>,
+{
+	fn convert_account_id(account_id: &T::AccountId) -> H160 {
+		Module::<T>::evm_address(account_id)
+			.unwrap_or_else(|| Fallback::convert_account_id(account_id))
+	}
+}
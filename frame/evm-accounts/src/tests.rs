@@ -0,0 +1,290 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types, weights::Weight,
+};
+use std::collections::BTreeMap;
+use pallet_evm::GenesisAccount;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	ModuleId, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod evm_accounts {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_balances<T>,
+		pallet_evm<T>,
+		evm_accounts<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		1.into()
+	}
+}
+
+parameter_types! {
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = FixedGasPrice;
+	type ConvertAccountId = EvmAddressMapping<Test, ZeroConvertAccountId>;
+	type Currency = Balances;
+	type Event = TestEvent;
+	type Precompiles = ();
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type WeightInfo = ();
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Evm = pallet_evm::Module<Test>;
+type EvmAccounts = Module<Test>;
+
+/// Converts every account to the zero address.
+pub struct ZeroConvertAccountId;
+
+impl ConvertAccountId<u64> for ZeroConvertAccountId {
+	fn convert_account_id(_account_id: &u64) -> H160 {
+		H160::zero()
+	}
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_accounts(BTreeMap::new())
+}
+
+fn new_test_ext_with_accounts(accounts: BTreeMap<H160, GenesisAccount>) -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_evm::GenesisConfig { accounts }
+		.assimilate_storage::<Test>(&mut storage)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	// Events are not stored in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn genesis_account(balance: u64, code: Vec<u8>) -> GenesisAccount {
+	GenesisAccount {
+		nonce: U256::zero(),
+		balance: balance.into(),
+		storage: BTreeMap::new(),
+		code,
+	}
+}
+
+fn secret_key(seed: u8) -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&[seed; 32]).unwrap()
+}
+
+fn address(secret_key: &secp256k1::SecretKey) -> H160 {
+	let public_key = secp256k1::PublicKey::from_secret_key(secret_key);
+	H160::from_slice(&keccak_256(&public_key.serialize()[1..])[12..])
+}
+
+fn sign_claim(secret_key: &secp256k1::SecretKey, who: u64) -> ecdsa::Signature {
+	let message = secp256k1::Message::parse(&EvmAccounts::claim_hash(&who));
+	let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
+
+	let mut raw = [0u8; 65];
+	raw[..64].copy_from_slice(&signature.serialize()[..]);
+	raw[64] = recovery_id.serialize();
+	ecdsa::Signature::from_raw(raw)
+}
+
+#[test]
+fn claim_account_should_link_both_ways() {
+	new_test_ext().execute_with(|| {
+		let key = secret_key(1);
+		let address = address(&key);
+
+		assert_ok!(EvmAccounts::claim_account(Origin::signed(7), address, sign_claim(&key, 7)));
+
+		assert_eq!(EvmAccounts::account_id(address), Some(7));
+		assert_eq!(EvmAccounts::evm_address(7), Some(address));
+		assert_eq!(
+			EvmAddressMapping::<Test, ZeroConvertAccountId>::convert_account_id(&7),
+			address
+		);
+		assert_eq!(
+			EvmAddressMapping::<Test, ZeroConvertAccountId>::convert_account_id(&8),
+			H160::zero()
+		);
+
+		let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+		assert_eq!(events, vec![TestEvent::evm_accounts(RawEvent::ClaimAccount(7, address))]);
+	});
+}
+
+#[test]
+fn claim_account_should_reject_signature_for_another_account() {
+	new_test_ext().execute_with(|| {
+		let key = secret_key(1);
+
+		assert_noop!(
+			EvmAccounts::claim_account(Origin::signed(7), address(&key), sign_claim(&key, 8)),
+			Error::<Test>::BadSignature
+		);
+		assert_noop!(
+			EvmAccounts::claim_account(Origin::signed(7), address(&secret_key(2)), sign_claim(&key, 7)),
+			Error::<Test>::BadSignature
+		);
+	});
+}
+
+#[test]
+fn claim_account_should_reject_linked_account_or_address() {
+	new_test_ext().execute_with(|| {
+		let key = secret_key(1);
+		let other_key = secret_key(2);
+		assert_ok!(EvmAccounts::claim_account(Origin::signed(7), address(&key), sign_claim(&key, 7)));
+
+		assert_noop!(
+			EvmAccounts::claim_account(Origin::signed(7), address(&other_key), sign_claim(&other_key, 7)),
+			Error::<Test>::AccountAlreadyLinked
+		);
+		assert_noop!(
+			EvmAccounts::claim_account(Origin::signed(8), address(&key), sign_claim(&key, 8)),
+			Error::<Test>::AddressAlreadyLinked
+		);
+	});
+}
+
+#[test]
+fn transfer_should_move_evm_balance_for_the_intrinsic_gas() {
+	let key = secret_key(1);
+	let source = address(&key);
+	let target = H160::repeat_byte(2);
+	let mut accounts = BTreeMap::new();
+	accounts.insert(source, genesis_account(100_000, Vec::new()));
+
+	new_test_ext_with_accounts(accounts).execute_with(|| {
+		assert_ok!(EvmAccounts::claim_account(Origin::signed(7), source, sign_claim(&key, 7)));
+		assert_ok!(EvmAccounts::transfer(Origin::signed(7), source, target, 1_000.into()));
+
+		assert_eq!(Evm::accounts(target).balance, U256::from(1_000));
+		assert_eq!(Evm::accounts(source).balance, U256::from(100_000 - 1_000 - TRANSFER_GAS as u64));
+		assert_eq!(
+			System::events().pop().map(|record| record.event),
+			Some(TestEvent::evm_accounts(RawEvent::Transferred(source, target, 1_000.into())))
+		);
+	});
+}
+
+#[test]
+fn transfer_should_reject_the_address_of_another_account() {
+	let source = address(&secret_key(1));
+
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EvmAccounts::transfer(Origin::signed(7), source, H160::repeat_byte(2), 1_000.into()),
+			Error::<Test>::NotAllowed
+		);
+	});
+}
+
+#[test]
+fn transfer_to_a_contract_doing_work_should_fail() {
+	let key = secret_key(1);
+	let source = address(&key);
+	let target = H160::repeat_byte(2);
+	let mut accounts = BTreeMap::new();
+	accounts.insert(source, genesis_account(100_000, Vec::new()));
+	// PUSH1 1 PUSH1 0 SSTORE, which needs more gas than the transfer has left.
+	accounts.insert(target, genesis_account(0, vec![0x60, 0x01, 0x60, 0x00, 0x55]));
+
+	new_test_ext_with_accounts(accounts).execute_with(|| {
+		assert_ok!(EvmAccounts::claim_account(Origin::signed(7), source, sign_claim(&key, 7)));
+		assert_ok!(EvmAccounts::transfer(Origin::signed(7), source, target, 1_000.into()));
+
+		assert_eq!(Evm::accounts(target).balance, U256::zero());
+		assert_eq!(
+			System::events().pop().map(|record| record.event),
+			Some(TestEvent::evm_accounts(RawEvent::TransferFailed(source, target)))
+		);
+	});
+}
@@ -0,0 +1,80 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet-evm-accounts.
+//!
+//! The base weights were picked by hand, not benchmarked: 50_000_000 for the
+//! secp256k1 recovery of `claim_account`, and 40_000_000 for the bare EVM
+//! call of `transfer`. The two lookups and two inserts of `claim_account` are
+//! counted from its code, while the accesses of `transfer`, which go through
+//! pallet-evm, are estimates.
+//!
+//! The `claim_account` and `transfer` benchmarks of `benchmarking.rs` measure
+//! them, from a node built with `--features runtime-benchmarks`:
+//!
+//! ```sh
+//! frontier-template-node benchmark --chain dev \
+//!     --execution wasm --wasm-execution compiled \
+//!     --pallet pallet-evm-accounts --extrinsic '*' --steps 50 --repeat 20
+//! ```
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+use frame_support::traits::Get;
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by pallet-evm-accounts.
+pub trait WeightInfo {
+	/// `claim_account`, recovering the signer of the claim.
+	fn claim_account() -> Weight;
+	/// `transfer` of EVM balance to an account without code.
+	fn transfer() -> Weight;
+}
+
+/// Hand-picked weights of the pallet, for runtimes on RocksDb.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn claim_account() -> Weight {
+		// Not benchmarked.
+		(50_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+
+	fn transfer() -> Weight {
+		// Not benchmarked.
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
+
+/// The weights of `SubstrateWeight` with `RocksDbWeight`, for mock runtimes.
+impl WeightInfo for () {
+	fn claim_account() -> Weight {
+		// Not benchmarked.
+		(50_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+
+	fn transfer() -> Weight {
+		// Not benchmarked.
+		(40_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+}
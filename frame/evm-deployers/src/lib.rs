@@ -0,0 +1,125 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # EVM deployers pallet
+//!
+//! Keeps an allow-list of addresses that may deploy contracts, managed by
+//! governance at runtime. The list is only consulted while it is enforced, so
+//! chains can seed it before switching enforcement on.
+//!
+//! The pallet implements `Filter<H160>`, for use as the `DeploymentFilter` of
+//! pallet-ethereum. Runtimes exposing the `create` calls of pallet-evm check
+//! them against it in their base call filter.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	decl_module, decl_storage, decl_event, decl_error, ensure, weights::Weight,
+	traits::{Get, EnsureOrigin, Filter},
+};
+use sp_core::H160;
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// Origin allowed to manage the allow-list.
+	type ManagerOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as EvmDeployers {
+		/// Whether only allow-listed addresses may deploy contracts.
+		pub Enforced get(fn enforced) config(): bool;
+		/// Addresses allowed to deploy contracts.
+		pub Deployers get(fn is_deployer): map hasher(blake2_128_concat) H160 => bool;
+	}
+	add_extra_genesis {
+		config(deployers): Vec<H160>;
+		build(|config: &GenesisConfig| {
+			for deployer in &config.deployers {
+				Deployers::insert(deployer, true);
+			}
+		});
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// An address was allowed to deploy contracts. [deployer]
+		DeployerAdded(H160),
+		/// An address was no longer allowed to deploy contracts. [deployer]
+		DeployerRemoved(H160),
+		/// The allow-list was switched on or off. [enforced]
+		EnforcementSet(bool),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The address is already allowed to deploy contracts.
+		AlreadyDeployer,
+		/// The address is not allowed to deploy contracts.
+		NotDeployer,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Allow `deployer` to deploy contracts.
+		#[weight = T::DbWeight::get().reads_writes(1, 1) as Weight]
+		fn add_deployer(origin, deployer: H160) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(!Deployers::contains_key(deployer), Error::<T>::AlreadyDeployer);
+
+			Deployers::insert(deployer, true);
+			Self::deposit_event(Event::DeployerAdded(deployer));
+		}
+
+		/// Stop allowing `deployer` to deploy contracts.
+		#[weight = T::DbWeight::get().reads_writes(1, 1) as Weight]
+		fn remove_deployer(origin, deployer: H160) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(Deployers::contains_key(deployer), Error::<T>::NotDeployer);
+
+			Deployers::remove(deployer);
+			Self::deposit_event(Event::DeployerRemoved(deployer));
+		}
+
+		/// Switch enforcement of the allow-list on or off.
+		#[weight = T::DbWeight::get().writes(1) as Weight]
+		fn set_enforced(origin, enforced: bool) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+
+			Enforced::put(enforced);
+			Self::deposit_event(Event::EnforcementSet(enforced));
+		}
+	}
+}
+
+impl<T: Trait> Filter<H160> for Module<T> {
+	fn filter(deployer: &H160) -> bool {
+		!Self::enforced() || Self::is_deployer(deployer)
+	}
+}
@@ -0,0 +1,160 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types,
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod evm_deployers {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		evm_deployers,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type ManagerOrigin = EnsureRoot<u64>;
+}
+
+type System = frame_system::Module<Test>;
+type EvmDeployers = Module<Test>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	GenesisConfig {
+		enforced: false,
+		deployers: vec![H160::repeat_byte(1)],
+	}.assimilate_storage::<Test>(&mut storage).unwrap();
+
+	let mut ext: sp_io::TestExternalities = storage.into();
+	// Events are not stored in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+#[test]
+fn anyone_may_deploy_until_enforced() {
+	new_test_ext().execute_with(|| {
+		assert!(EvmDeployers::filter(&H160::repeat_byte(1)));
+		assert!(EvmDeployers::filter(&H160::repeat_byte(2)));
+
+		assert_ok!(EvmDeployers::set_enforced(Origin::root(), true));
+
+		assert!(EvmDeployers::filter(&H160::repeat_byte(1)));
+		assert!(!EvmDeployers::filter(&H160::repeat_byte(2)));
+	});
+}
+
+#[test]
+fn manager_should_add_and_remove_deployers() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(EvmDeployers::set_enforced(Origin::root(), true));
+		assert_ok!(EvmDeployers::add_deployer(Origin::root(), H160::repeat_byte(2)));
+		assert!(EvmDeployers::filter(&H160::repeat_byte(2)));
+
+		assert_ok!(EvmDeployers::remove_deployer(Origin::root(), H160::repeat_byte(1)));
+		assert!(!EvmDeployers::filter(&H160::repeat_byte(1)));
+
+		let events = System::events().into_iter().map(|record| record.event).collect::<Vec<_>>();
+		assert_eq!(events, vec![
+			TestEvent::evm_deployers(Event::EnforcementSet(true)),
+			TestEvent::evm_deployers(Event::DeployerAdded(H160::repeat_byte(2))),
+			TestEvent::evm_deployers(Event::DeployerRemoved(H160::repeat_byte(1))),
+		]);
+	});
+}
+
+#[test]
+fn duplicate_or_missing_deployers_should_fail() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EvmDeployers::add_deployer(Origin::root(), H160::repeat_byte(1)),
+			Error::<Test>::AlreadyDeployer
+		);
+		assert_noop!(
+			EvmDeployers::remove_deployer(Origin::root(), H160::repeat_byte(2)),
+			Error::<Test>::NotDeployer
+		);
+	});
+}
+
+#[test]
+fn signed_origin_should_not_manage_deployers() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EvmDeployers::add_deployer(Origin::signed(1), H160::repeat_byte(2)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			EvmDeployers::set_enforced(Origin::signed(1), true),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,207 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # EVM hotfix pallet
+//!
+//! Lets a configured origin repair EVM account state left inconsistent by
+//! past bugs, without manual storage surgery:
+//!
+//! - `set_nonces` resets the nonces of stuck accounts;
+//! - `clear_orphaned_storage` removes storage left behind at an address with no
+//!   code;
+//! - `set_code` and `remove_code` overwrite or delete the code of an exploited
+//!   or bricked contract; new code is bounded by the EIP-170 `MAX_CODE_SIZE`.
+//!
+//! Storage left behind by contracts whose code is gone can also be reclaimed by
+//! anyone with `clear_suicided_storage`, which pays `CleanupRewardPerSlot` for
+//! each removed slot out of the `CleanupRewardPot` account.
+//!
+//! Each call touches a bounded number of accounts or storage slots.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Encode;
+use frame_support::{
+	decl_module, decl_event, decl_error, ensure, weights::Weight,
+	traits::{Get, EnsureOrigin, Currency, ExistenceRequirement},
+	storage::migration::{put_storage_value, take_storage_value, StorageIterator},
+	Blake2_128Concat, StorageHasher,
+};
+use frame_system::ensure_signed;
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Storage prefix of pallet-evm.
+const EVM: &[u8] = b"EVM";
+
+/// Largest code `set_code` writes, the EIP-170 limit of deployed contracts.
+pub const MAX_CODE_SIZE: usize = 0x6000;
+
+type BalanceOf<T> = <<T as pallet_evm::Trait>::Currency as Currency<
+	<T as frame_system::Trait>::AccountId
+>>::Balance;
+
+pub trait Trait: frame_system::Trait + pallet_evm::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// Origin allowed to repair EVM state.
+	type HotfixOrigin: EnsureOrigin<Self::Origin>;
+	/// Maximum number of accounts a single call repairs.
+	type MaxAccountsPerCall: Get<u32>;
+	/// Maximum number of storage slots a single call removes.
+	type MaxSlotsPerCall: Get<u32>;
+	/// Amount paid to the caller of `clear_suicided_storage` for each removed
+	/// storage slot.
+	type CleanupRewardPerSlot: Get<BalanceOf<Self>>;
+	/// Account the cleanup rewards are paid from. Nothing is minted: once the
+	/// account cannot cover a reward, the reward is skipped.
+	type CleanupRewardPot: Get<Self::AccountId>;
+}
+
+decl_event!(
+	pub enum Event {
+		/// The nonce of an account was set. [address, nonce]
+		NonceSet(H160, U256),
+		/// Orphaned storage slots of an address were removed. [address, count]
+		StorageCleared(H160, u32),
+		/// The code at an address was overwritten. [address]
+		CodeSet(H160),
+		/// The code at an address was removed. [address]
+		CodeRemoved(H160),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The call repairs more items than allowed.
+		TooManyItems,
+		/// The address has code, so its storage is not orphaned.
+		AccountHasCode,
+		/// The address has no code to remove.
+		NoCode,
+		/// The code is larger than `MAX_CODE_SIZE`.
+		CodeTooLarge,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Set the nonces of stuck accounts.
+		#[weight = T::DbWeight::get().reads_writes(nonces.len() as Weight, nonces.len() as Weight)]
+		fn set_nonces(origin, nonces: Vec<(H160, U256)>) {
+			T::HotfixOrigin::ensure_origin(origin)?;
+			ensure!(nonces.len() <= T::MaxAccountsPerCall::get() as usize, Error::<T>::TooManyItems);
+
+			for (address, nonce) in nonces {
+				let mut account = pallet_evm::Module::<T>::accounts(address);
+				account.nonce = nonce;
+				put_storage_value(EVM, b"Accounts", &Blake2_128Concat::hash(&address.encode()), account);
+				Self::deposit_event(Event::NonceSet(address, nonce));
+			}
+		}
+
+		/// Remove up to `limit` storage slots of `address`, which must have no code.
+		#[weight = T::DbWeight::get().reads_writes(1 + *limit as Weight, *limit as Weight)]
+		fn clear_orphaned_storage(origin, address: H160, limit: u32) {
+			T::HotfixOrigin::ensure_origin(origin)?;
+			ensure!(limit <= T::MaxSlotsPerCall::get(), Error::<T>::TooManyItems);
+			ensure!(
+				pallet_evm::Module::<T>::account_codes(address).is_empty(),
+				Error::<T>::AccountHasCode
+			);
+
+			let removed = StorageIterator::<H256>::with_suffix(
+				EVM,
+				b"AccountStorages",
+				&Blake2_128Concat::hash(&address.encode()),
+			).drain().take(limit as usize).count();
+
+			Self::deposit_event(Event::StorageCleared(address, removed as u32));
+		}
+
+		/// Remove up to `limit` storage slots in total of the `addresses` that
+		/// have no code, like self-destructed contracts. Addresses with code are
+		/// skipped. Anyone can call it, and is rewarded for each removed slot.
+		#[weight = T::DbWeight::get().reads_writes(
+			addresses.len() as Weight + *limit as Weight,
+			1 + *limit as Weight,
+		)]
+		fn clear_suicided_storage(origin, addresses: Vec<H160>, limit: u32) {
+			let who = ensure_signed(origin)?;
+			ensure!(addresses.len() <= T::MaxAccountsPerCall::get() as usize, Error::<T>::TooManyItems);
+			ensure!(limit <= T::MaxSlotsPerCall::get(), Error::<T>::TooManyItems);
+
+			let mut remaining = limit as usize;
+			for address in addresses {
+				if remaining == 0 {
+					break
+				}
+				if !pallet_evm::Module::<T>::account_codes(address).is_empty() {
+					continue
+				}
+
+				let removed = StorageIterator::<H256>::with_suffix(
+					EVM,
+					b"AccountStorages",
+					&Blake2_128Concat::hash(&address.encode()),
+				).drain().take(remaining).count();
+				if removed > 0 {
+					remaining -= removed;
+					Self::deposit_event(Event::StorageCleared(address, removed as u32));
+				}
+			}
+
+			let removed = limit as usize - remaining;
+			let reward = T::CleanupRewardPerSlot::get().saturating_mul((removed as u32).into());
+			let _ = T::Currency::transfer(
+				&T::CleanupRewardPot::get(),
+				&who,
+				reward,
+				ExistenceRequirement::KeepAlive,
+			);
+		}
+
+		/// Overwrite the code at `address`, with code of at most `MAX_CODE_SIZE`
+		/// bytes.
+		#[weight = T::DbWeight::get().writes(1)]
+		fn set_code(origin, address: H160, code: Vec<u8>) {
+			T::HotfixOrigin::ensure_origin(origin)?;
+			ensure!(code.len() <= MAX_CODE_SIZE, Error::<T>::CodeTooLarge);
+
+			put_storage_value(EVM, b"AccountCodes", &Blake2_128Concat::hash(&address.encode()), code);
+			Self::deposit_event(Event::CodeSet(address));
+		}
+
+		/// Remove the code at `address`, leaving its balance, nonce and storage.
+		#[weight = T::DbWeight::get().reads_writes(1, 1)]
+		fn remove_code(origin, address: H160) {
+			T::HotfixOrigin::ensure_origin(origin)?;
+
+			take_storage_value::<Vec<u8>>(EVM, b"AccountCodes", &Blake2_128Concat::hash(&address.encode()))
+				.ok_or(Error::<T>::NoCode)?;
+			Self::deposit_event(Event::CodeRemoved(address));
+		}
+	}
+}
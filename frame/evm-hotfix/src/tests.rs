@@ -0,0 +1,350 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{
+	assert_ok, assert_noop, impl_outer_origin, impl_outer_event, parameter_types,
+	storage::migration::get_storage_value,
+};
+use frame_system::EnsureRoot;
+use pallet_evm::{FeeCalculator, HashTruncateConvertAccountId};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup, BadOrigin},
+	ModuleId, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod evm_hotfix {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_balances<T>,
+		pallet_evm<T>,
+		evm_hotfix,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 500;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		1.into()
+	}
+}
+
+parameter_types! {
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = FixedGasPrice;
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = TestEvent;
+	type Precompiles = ();
+}
+
+parameter_types! {
+	pub const MaxAccountsPerCall: u32 = 2;
+	pub const MaxSlotsPerCall: u32 = 2;
+	pub const CleanupRewardPerSlot: u64 = 250;
+	pub const CleanupRewardPot: u64 = 100;
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type HotfixOrigin = EnsureRoot<u64>;
+	type MaxAccountsPerCall = MaxAccountsPerCall;
+	type MaxSlotsPerCall = MaxSlotsPerCall;
+	type CleanupRewardPerSlot = CleanupRewardPerSlot;
+	type CleanupRewardPot = CleanupRewardPot;
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Evm = pallet_evm::Module<Test>;
+type EvmHotfix = Module<Test>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(CleanupRewardPot::get(), 1_000)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	// Events are not stored in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn hashed(address: H160) -> Vec<u8> {
+	Blake2_128Concat::hash(&address.encode())
+}
+
+fn put_slot(address: H160, index: u8) {
+	let mut key = hashed(address);
+	key.extend(Blake2_128Concat::hash(&H256::repeat_byte(index).encode()));
+	put_storage_value(EVM, b"AccountStorages", &key, H256::repeat_byte(0xff));
+}
+
+fn slot(address: H160, index: u8) -> Option<H256> {
+	let mut key = hashed(address);
+	key.extend(Blake2_128Concat::hash(&H256::repeat_byte(index).encode()));
+	get_storage_value(EVM, b"AccountStorages", &key)
+}
+
+#[test]
+fn set_nonces_requires_hotfix_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			EvmHotfix::set_nonces(Origin::signed(1), vec![(H160::repeat_byte(1), 5.into())]),
+			BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn set_nonces_works() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		assert_ok!(EvmHotfix::set_nonces(Origin::root(), vec![(address, 5.into())]));
+
+		assert_eq!(Evm::accounts(address).nonce, U256::from(5));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			TestEvent::evm_hotfix(Event::NonceSet(address, 5.into())),
+		);
+	});
+}
+
+#[test]
+fn set_nonces_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let nonces = (1..=3).map(|i| (H160::repeat_byte(i), U256::zero())).collect();
+		assert_noop!(
+			EvmHotfix::set_nonces(Origin::root(), nonces),
+			Error::<Test>::TooManyItems,
+		);
+	});
+}
+
+#[test]
+fn clear_orphaned_storage_works() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		let other = H160::repeat_byte(2);
+		put_slot(address, 1);
+		put_slot(address, 2);
+		put_slot(address, 3);
+		put_slot(other, 1);
+
+		assert_ok!(EvmHotfix::clear_orphaned_storage(Origin::root(), address, 2));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			TestEvent::evm_hotfix(Event::StorageCleared(address, 2)),
+		);
+		assert_ok!(EvmHotfix::clear_orphaned_storage(Origin::root(), address, 2));
+
+		assert!((1..=3).all(|i| slot(address, i).is_none()));
+		assert_eq!(slot(other, 1), Some(H256::repeat_byte(0xff)));
+	});
+}
+
+#[test]
+fn clear_orphaned_storage_rejects_contracts() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		put_storage_value(EVM, b"AccountCodes", &hashed(address), vec![0u8]);
+		put_slot(address, 1);
+
+		assert_noop!(
+			EvmHotfix::clear_orphaned_storage(Origin::root(), address, 1),
+			Error::<Test>::AccountHasCode,
+		);
+		assert_noop!(
+			EvmHotfix::clear_orphaned_storage(Origin::root(), address, 3),
+			Error::<Test>::TooManyItems,
+		);
+	});
+}
+
+#[test]
+fn clear_suicided_storage_works() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::repeat_byte(1);
+		let suicided = H160::repeat_byte(2);
+		put_storage_value(EVM, b"AccountCodes", &hashed(contract), vec![0u8]);
+		put_slot(contract, 1);
+		put_slot(suicided, 1);
+		put_slot(suicided, 2);
+		put_slot(suicided, 3);
+
+		assert_ok!(EvmHotfix::clear_suicided_storage(Origin::signed(1), vec![contract, suicided], 2));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			TestEvent::evm_hotfix(Event::StorageCleared(suicided, 2)),
+		);
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Balances::free_balance(CleanupRewardPot::get()), 500);
+
+		assert_eq!(slot(contract, 1), Some(H256::repeat_byte(0xff)));
+		assert_eq!((1..=3).filter(|i| slot(suicided, *i).is_some()).count(), 1);
+	});
+}
+
+#[test]
+fn clear_suicided_storage_does_not_mint_rewards() {
+	new_test_ext().execute_with(|| {
+		let suicided = H160::repeat_byte(2);
+		put_slot(suicided, 1);
+		put_slot(suicided, 2);
+		assert_ok!(EvmHotfix::clear_suicided_storage(Origin::signed(1), vec![suicided], 2));
+
+		// The pot keeps its existential deposit, so the next reward is skipped.
+		put_slot(suicided, 3);
+		assert_ok!(EvmHotfix::clear_suicided_storage(Origin::signed(2), vec![suicided], 1));
+		assert_eq!(slot(suicided, 3), None);
+		assert_eq!(Balances::free_balance(2), 0);
+		assert_eq!(Balances::free_balance(CleanupRewardPot::get()), 500);
+		assert_eq!(Balances::total_issuance(), 1_000);
+	});
+}
+
+#[test]
+fn clear_suicided_storage_is_bounded() {
+	new_test_ext().execute_with(|| {
+		let addresses: Vec<_> = (1..=3).map(H160::repeat_byte).collect();
+		assert_noop!(
+			EvmHotfix::clear_suicided_storage(Origin::signed(1), addresses, 1),
+			Error::<Test>::TooManyItems,
+		);
+		assert_noop!(
+			EvmHotfix::clear_suicided_storage(Origin::signed(1), vec![H160::repeat_byte(1)], 3),
+			Error::<Test>::TooManyItems,
+		);
+		assert_noop!(
+			EvmHotfix::clear_suicided_storage(Origin::root(), vec![H160::repeat_byte(1)], 1),
+			BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn set_code_works() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		assert_noop!(
+			EvmHotfix::set_code(Origin::signed(1), address, vec![0x60, 0x00]),
+			BadOrigin,
+		);
+
+		assert_ok!(EvmHotfix::set_code(Origin::root(), address, vec![0x60, 0x00]));
+		assert_eq!(Evm::account_codes(address), vec![0x60, 0x00]);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			TestEvent::evm_hotfix(Event::CodeSet(address)),
+		);
+	});
+}
+
+#[test]
+fn set_code_should_reject_code_over_max_size() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		assert_noop!(
+			EvmHotfix::set_code(Origin::root(), address, vec![0; MAX_CODE_SIZE + 1]),
+			Error::<Test>::CodeTooLarge,
+		);
+
+		assert_ok!(EvmHotfix::set_code(Origin::root(), address, vec![0; MAX_CODE_SIZE]));
+		assert_eq!(Evm::account_codes(address).len(), MAX_CODE_SIZE);
+	});
+}
+
+#[test]
+fn remove_code_works() {
+	new_test_ext().execute_with(|| {
+		let address = H160::repeat_byte(1);
+		assert_noop!(
+			EvmHotfix::remove_code(Origin::root(), address),
+			Error::<Test>::NoCode,
+		);
+
+		assert_ok!(EvmHotfix::set_code(Origin::root(), address, vec![0x60, 0x00]));
+		put_slot(address, 1);
+		assert_ok!(EvmHotfix::remove_code(Origin::root(), address));
+
+		assert!(Evm::account_codes(address).is_empty());
+		assert_eq!(slot(address, 1), Some(H256::repeat_byte(0xff)));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			TestEvent::evm_hotfix(Event::CodeRemoved(address)),
+		);
+	});
+}
@@ -0,0 +1,187 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! # EVM migrations pallet
+//!
+//! Versions the storage layout of pallet-evm, and migrates it on runtime
+//! upgrades, so that layout changes can ship to live chains.
+//!
+//! `StorageVersion` holds the version the chain storage is at, starting at 0.
+//! The runtime lists its `Migrations` in increasing version order, and the
+//! first block of a runtime upgrade runs those above the stored version,
+//! then stores the version of the last one. `migrations` holds the migrations
+//! of the known layout changes.
+//!
+//! With the `try-runtime` feature, `try_runtime_upgrade` runs the pending
+//! migrations between the checks of each migration, to test them on a copy of
+//! the state of a live chain before enacting the upgrade.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod migrations;
+
+#[cfg(test)]
+mod tests;
+
+use frame_support::{decl_module, decl_storage, decl_event, weights::Weight, traits::Get};
+
+/// Storage prefix of pallet-evm.
+const EVM: &[u8] = b"EVM";
+
+/// Migration of the pallet-evm storage to a new layout version.
+pub trait Migration {
+	/// Version of the storage layout after the migration.
+	const VERSION: u16;
+
+	/// Migrate the storage from the previous version, returning the weight
+	/// used.
+	fn migrate() -> Weight;
+
+	/// Check the storage before migrating.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		Ok(())
+	}
+
+	/// Check the storage after migrating.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
+/// Migrations of a runtime, implemented for tuples of `Migration` in
+/// increasing version order.
+pub trait Migrations {
+	/// Version of the storage layout after all the migrations.
+	fn latest() -> u16;
+
+	/// Run the migrations above version `from`, returning the weight used.
+	fn migrate(from: u16) -> Weight;
+
+	/// Check the storage before running the migrations above version `from`.
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade(from: u16) -> Result<(), &'static str>;
+
+	/// Check the storage after running the migrations above version `from`.
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(from: u16) -> Result<(), &'static str>;
+}
+
+macro_rules! impl_migrations {
+	($($migration:ident),*) => {
+		impl<$($migration: Migration),*> Migrations for ($($migration,)*) {
+			fn latest() -> u16 {
+				let latest: u16 = 0;
+				latest $(.max($migration::VERSION))*
+			}
+
+			#[allow(unused_mut, unused_variables)]
+			fn migrate(from: u16) -> Weight {
+				let mut weight: Weight = 0;
+				$(if $migration::VERSION > from {
+					weight = weight.saturating_add($migration::migrate());
+				})*
+				weight
+			}
+
+			#[cfg(feature = "try-runtime")]
+			#[allow(unused_variables)]
+			fn pre_upgrade(from: u16) -> Result<(), &'static str> {
+				$(if $migration::VERSION > from {
+					$migration::pre_upgrade()?;
+				})*
+				Ok(())
+			}
+
+			#[cfg(feature = "try-runtime")]
+			#[allow(unused_variables)]
+			fn post_upgrade(from: u16) -> Result<(), &'static str> {
+				$(if $migration::VERSION > from {
+					$migration::post_upgrade()?;
+				})*
+				Ok(())
+			}
+		}
+	}
+}
+
+impl_migrations!();
+impl_migrations!(A);
+impl_migrations!(A, B);
+impl_migrations!(A, B, C);
+impl_migrations!(A, B, C, D);
+impl_migrations!(A, B, C, D, E);
+impl_migrations!(A, B, C, D, E, F);
+impl_migrations!(A, B, C, D, E, F, G);
+impl_migrations!(A, B, C, D, E, F, G, H);
+
+pub trait Trait: frame_system::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// Migrations of the pallet-evm storage, in increasing version order.
+	type Migrations: Migrations;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as EvmMigrations {
+		/// Version of the pallet-evm storage layout.
+		pub StorageVersion get(fn storage_version): u16;
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// The pallet-evm storage was migrated. [from, to]
+		Migrated(u16, u16),
+	}
+);
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		fn on_runtime_upgrade() -> Weight {
+			let from = StorageVersion::get();
+			let to = T::Migrations::latest();
+			if from >= to {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let weight = T::Migrations::migrate(from);
+			StorageVersion::put(to);
+			Self::deposit_event(Event::Migrated(from, to));
+			weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		}
+	}
+}
+
+#[cfg(feature = "try-runtime")]
+impl<T: Trait> Module<T> {
+	/// Run the pending migrations as the runtime upgrade does, checking the
+	/// storage before and after them.
+	pub fn try_runtime_upgrade() -> Result<Weight, &'static str> {
+		use frame_support::traits::OnRuntimeUpgrade;
+
+		let from = StorageVersion::get();
+		T::Migrations::pre_upgrade(from)?;
+		let weight = <Self as OnRuntimeUpgrade>::on_runtime_upgrade();
+		T::Migrations::post_upgrade(from)?;
+		Ok(weight)
+	}
+}
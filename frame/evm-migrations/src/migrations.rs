@@ -0,0 +1,128 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Migrations of the known pallet-evm storage layout changes.
+//!
+//! Each migration runs in the block of the runtime upgrade, so its weight
+//! grows with the number of contracts of the chain.
+
+use codec::{Encode, Decode};
+use frame_support::{
+	weights::Weight, traits::Get,
+	storage::migration::{get_storage_value, put_storage_value, StorageIterator},
+	Blake2_128Concat, StorageHasher,
+};
+use sp_core::{H160, H256};
+use sp_std::{prelude::*, collections::btree_set::BTreeSet, marker::PhantomData};
+
+use crate::{Migration, EVM};
+
+/// Size and hash of the code of a contract, stored in `AccountCodesMetadata`
+/// so that `EXTCODESIZE` and `EXTCODEHASH` do not load the code.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, sp_core::RuntimeDebug)]
+pub struct CodeMetadata {
+	pub size: u64,
+	pub hash: H256,
+}
+
+impl CodeMetadata {
+	/// Metadata of `code`.
+	pub fn from_code(code: &[u8]) -> Self {
+		CodeMetadata {
+			size: code.len() as u64,
+			hash: H256::from(sp_io::hashing::keccak_256(code)),
+		}
+	}
+}
+
+/// Version 1: backfill `AccountCodesMetadata` from `AccountCodes`, for
+/// pallet-evm versions reading code metadata.
+pub struct BackfillCodeMetadata<T>(PhantomData<T>);
+
+impl<T: frame_system::Trait> Migration for BackfillCodeMetadata<T> {
+	const VERSION: u16 = 1;
+
+	fn migrate() -> Weight {
+		let mut count: Weight = 0;
+		for (key, code) in StorageIterator::<Vec<u8>>::new(EVM, b"AccountCodes") {
+			put_storage_value(EVM, b"AccountCodesMetadata", &key, CodeMetadata::from_code(&code));
+			count += 1;
+		}
+		T::DbWeight::get().reads_writes(count, count)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		for (key, code) in StorageIterator::<Vec<u8>>::new(EVM, b"AccountCodes") {
+			let metadata = get_storage_value::<CodeMetadata>(EVM, b"AccountCodesMetadata", &key);
+			if metadata != Some(CodeMetadata::from_code(&code)) {
+				return Err("code metadata does not match the code")
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Version 2: record the addresses whose storage outlived their code, like
+/// self-destructed contracts, in `Suicided`, so that their storage can be
+/// removed without scanning the storage of every account.
+pub struct TrackSuicided<T>(PhantomData<T>);
+
+impl<T: frame_system::Trait> Migration for TrackSuicided<T> {
+	const VERSION: u16 = 2;
+
+	fn migrate() -> Weight {
+		let (addresses, reads) = suicided();
+		for address in &addresses {
+			put_storage_value(EVM, b"Suicided", &Blake2_128Concat::hash(&address.encode()), ());
+		}
+		T::DbWeight::get().reads_writes(reads, addresses.len() as Weight)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		for address in suicided().0 {
+			let key = Blake2_128Concat::hash(&address.encode());
+			if get_storage_value::<()>(EVM, b"Suicided", &key).is_none() {
+				return Err("address with storage and no code is not tracked as suicided")
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Addresses with storage and no code, and the number of storage items read
+/// to find them.
+fn suicided() -> (BTreeSet<H160>, Weight) {
+	let mut reads: Weight = 0;
+	let mut with_storage = BTreeSet::new();
+	for (key, _) in StorageIterator::<H256>::new(EVM, b"AccountStorages") {
+		reads += 1;
+		if key.len() == 16 + 20 + 16 + 32 {
+			with_storage.insert(H160::from_slice(&key[16..36]));
+		}
+	}
+
+	let addresses = with_storage.into_iter()
+		.filter(|address| {
+			reads += 1;
+			get_storage_value::<Vec<u8>>(EVM, b"AccountCodes", &Blake2_128Concat::hash(&address.encode()))
+				.map_or(true, |code| code.is_empty())
+		})
+		.collect();
+	(addresses, reads)
+}
@@ -0,0 +1,189 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use super::*;
+use codec::Encode;
+use frame_support::{
+	impl_outer_origin, impl_outer_event, parameter_types,
+	traits::OnRuntimeUpgrade,
+	storage::migration::{get_storage_value, put_storage_value},
+	Blake2_128Concat, StorageHasher,
+};
+use sp_core::{H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use crate::migrations::{BackfillCodeMetadata, CodeMetadata, TrackSuicided};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+mod evm_migrations {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		evm_migrations,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl Trait for Test {
+	type Event = TestEvent;
+	type Migrations = (BackfillCodeMetadata<Test>, TrackSuicided<Test>);
+}
+
+type System = frame_system::Module<Test>;
+type EvmMigrations = Module<Test>;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	// Events are not stored in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+fn hashed(address: H160) -> Vec<u8> {
+	Blake2_128Concat::hash(&address.encode())
+}
+
+fn put_code(address: H160, code: Vec<u8>) {
+	put_storage_value(EVM, b"AccountCodes", &hashed(address), code);
+}
+
+fn put_slot(address: H160) {
+	let mut key = hashed(address);
+	key.extend(Blake2_128Concat::hash(&H256::repeat_byte(1).encode()));
+	put_storage_value(EVM, b"AccountStorages", &key, H256::repeat_byte(0xff));
+}
+
+fn metadata(address: H160) -> Option<CodeMetadata> {
+	get_storage_value(EVM, b"AccountCodesMetadata", &hashed(address))
+}
+
+fn is_suicided(address: H160) -> bool {
+	get_storage_value::<()>(EVM, b"Suicided", &hashed(address)).is_some()
+}
+
+fn last_event() -> TestEvent {
+	System::events().pop().expect("an event was deposited").event
+}
+
+#[test]
+fn runtime_upgrade_should_run_all_migrations_from_version_zero() {
+	let contract = H160::repeat_byte(1);
+	let suicided = H160::repeat_byte(2);
+	new_test_ext().execute_with(|| {
+		put_code(contract, vec![0x60, 0x00]);
+		put_slot(contract);
+		put_slot(suicided);
+
+		EvmMigrations::on_runtime_upgrade();
+
+		assert_eq!(metadata(contract), Some(CodeMetadata::from_code(&[0x60, 0x00])));
+		assert!(!is_suicided(contract));
+		assert!(is_suicided(suicided));
+		assert_eq!(EvmMigrations::storage_version(), 2);
+		assert_eq!(last_event(), TestEvent::evm_migrations(Event::Migrated(0, 2)));
+	});
+}
+
+#[test]
+fn runtime_upgrade_should_skip_applied_migrations() {
+	let contract = H160::repeat_byte(1);
+	let suicided = H160::repeat_byte(2);
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(1);
+		put_code(contract, vec![0x60, 0x00]);
+		put_slot(suicided);
+
+		EvmMigrations::on_runtime_upgrade();
+
+		assert_eq!(metadata(contract), None);
+		assert!(is_suicided(suicided));
+		assert_eq!(EvmMigrations::storage_version(), 2);
+		assert_eq!(last_event(), TestEvent::evm_migrations(Event::Migrated(1, 2)));
+	});
+}
+
+#[test]
+fn runtime_upgrade_should_do_nothing_when_up_to_date() {
+	let contract = H160::repeat_byte(1);
+	new_test_ext().execute_with(|| {
+		StorageVersion::put(2);
+		put_code(contract, vec![0x60, 0x00]);
+
+		EvmMigrations::on_runtime_upgrade();
+
+		assert_eq!(metadata(contract), None);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_runtime_upgrade_should_pass_the_migration_checks() {
+	new_test_ext().execute_with(|| {
+		put_code(H160::repeat_byte(1), vec![0x60, 0x00]);
+		put_slot(H160::repeat_byte(2));
+
+		assert!(EvmMigrations::try_runtime_upgrade().is_ok());
+		assert_eq!(EvmMigrations::storage_version(), 2);
+	});
+}
@@ -0,0 +1,131 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! # EVM gas benchmark
+//!
+//! Calibrates the weight a runtime charges per unit of EVM gas
+//! (`WeightPerGas`) on the hardware running it. Each workload is a contract
+//! looping over a representative operation, called through pallet-evm in a
+//! mock runtime. The weight of a call is its execution time, plus the database
+//! weight of the storage items it writes, which in-memory externalities do not
+//! account for.
+//!
+//! The recommended `WeightPerGas` is the highest weight per gas among the
+//! workloads, so that none of them fits more work in a block than its weight
+//! budget allows.
+//!
+//! ```sh
+//! cargo run --release -p pallet-evm-gas-benchmark -- --iterations 1000 --runs 20
+//! ```
+
+mod runtime;
+mod workloads;
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::BTreeMap, time::Instant};
+use frame_support::{traits::Get, weights::{Weight, constants::{RocksDbWeight, WEIGHT_PER_NANOS}}};
+use runtime::{Evm, genesis_account, new_test_ext};
+use sp_core::{H160, U256};
+use structopt::StructOpt;
+use workloads::{Workload, WORKLOADS};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "pallet-evm-gas-benchmark")]
+struct Options {
+	/// Iterations of the loop of each workload contract.
+	#[structopt(long, default_value = "1000")]
+	iterations: u64,
+	/// Calls of each workload, keeping the median one.
+	#[structopt(long, default_value = "20")]
+	runs: usize,
+}
+
+/// Cost of a workload call.
+pub struct Measure {
+	pub used_gas: u64,
+	pub nanos: u64,
+	pub writes: u64,
+}
+
+impl Measure {
+	/// Execution time and database writes of the call, as weight.
+	pub fn weight(&self) -> Weight {
+		self.nanos.saturating_mul(WEIGHT_PER_NANOS)
+			.saturating_add(RocksDbWeight::get().writes(self.writes))
+	}
+
+	/// Weight of a unit of gas of the call, rounded up.
+	pub fn weight_per_gas(&self) -> Weight {
+		let used_gas = self.used_gas.max(1);
+		(self.weight() + used_gas - 1) / used_gas
+	}
+}
+
+/// Call `workload` with `iterations` in fresh externalities.
+pub fn measure(workload: &Workload, iterations: u64) -> Measure {
+	let caller = H160::repeat_byte(0x01);
+	let mut accounts = BTreeMap::new();
+	accounts.insert(caller, genesis_account(U256::from(u64::max_value()), Vec::new()));
+	accounts.insert(workloads::contract(), genesis_account(U256::zero(), workload.code()));
+	accounts.insert(workloads::callee(), genesis_account(U256::zero(), vec![0x00]));
+	let mut ext = new_test_ext(accounts);
+
+	ext.execute_with(|| {
+		let mut input = [0u8; 32];
+		U256::from(iterations).to_big_endian(&mut input);
+
+		let start = Instant::now();
+		let (reason, _, used_gas) = Evm::execute_call(
+			caller,
+			workloads::contract(),
+			input.to_vec(),
+			U256::zero(),
+			u32::max_value(),
+			U256::one(),
+			None,
+			true,
+		).expect("the caller can pay for the gas limit; qed");
+		let nanos = start.elapsed().as_nanos() as u64;
+
+		assert!(reason.is_succeed(), "{} workload failed: {:?}", workload.name, reason);
+		Measure { used_gas: used_gas.low_u64(), nanos, writes: (workload.writes)(iterations) }
+	})
+}
+
+fn main() {
+	let options = Options::from_args();
+
+	println!("{:<16} {:>12} {:>12} {:>12}", "workload", "gas", "time (µs)", "weight/gas");
+	let mut recommended = 0;
+	for workload in WORKLOADS {
+		let mut measures = (0..options.runs.max(1))
+			.map(|_| measure(workload, options.iterations))
+			.collect::<Vec<_>>();
+		measures.sort_by_key(Measure::weight_per_gas);
+		let median = &measures[measures.len() / 2];
+
+		println!(
+			"{:<16} {:>12} {:>12} {:>12}",
+			workload.name, median.used_gas, median.nanos / 1_000, median.weight_per_gas(),
+		);
+		recommended = recommended.max(median.weight_per_gas());
+	}
+	println!();
+	println!("Recommended WeightPerGas: {}", recommended);
+}
@@ -0,0 +1,36 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use frame_support::{traits::Get, weights::constants::RocksDbWeight};
+use crate::{measure, workloads::WORKLOADS};
+
+#[test]
+fn workloads_should_use_gas_per_iteration() {
+	for workload in WORKLOADS {
+		let once = measure(workload, 1);
+		let ten = measure(workload, 10);
+		assert!(ten.used_gas > once.used_gas, "{} workload does not loop", workload.name);
+	}
+}
+
+#[test]
+fn storage_writes_should_cost_database_weight() {
+	let storage = &WORKLOADS[0];
+	let measure = measure(storage, 10);
+	assert_eq!(measure.writes, 11);
+	assert!(measure.weight() >= RocksDbWeight::get().writes(11));
+}
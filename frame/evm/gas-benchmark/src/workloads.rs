@@ -0,0 +1,108 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Contracts of the benchmark workloads.
+//!
+//! Each contract loops over the body of its workload as many times as the
+//! 32-byte big endian number of its calldata.
+
+use sp_core::H160;
+
+/// Address of the workload contract.
+pub fn contract() -> H160 {
+	H160::from_low_u64_be(0x1000)
+}
+
+/// Address of the contract called by the `calls` workload, which only stops.
+pub fn callee() -> H160 {
+	H160::from_low_u64_be(0x1001)
+}
+
+/// Representative EVM operation, looped over by a contract.
+pub struct Workload {
+	pub name: &'static str,
+	/// Body of the loop. It must leave the stack as it finds it, with the
+	/// remaining iterations on top.
+	body: fn() -> Vec<u8>,
+	/// Storage items written by a call with this many iterations.
+	pub writes: fn(u64) -> u64,
+}
+
+impl Workload {
+	/// Code of the contract looping over the body.
+	pub fn code(&self) -> Vec<u8> {
+		looped(&(self.body)())
+	}
+}
+
+/// The workloads, each also writing the account of its caller.
+pub const WORKLOADS: &[Workload] = &[
+	Workload {
+		name: "storage writes",
+		// DUP1 DUP1 SSTORE: store the remaining iterations at their own slot.
+		body: || vec![0x80, 0x80, 0x55],
+		writes: |iterations| iterations + 1,
+	},
+	Workload {
+		name: "keccak",
+		// PUSH1 32 PUSH1 0 SHA3 POP: hash the first word of memory.
+		body: || vec![0x60, 0x20, 0x60, 0x00, 0x20, 0x50],
+		writes: |_| 1,
+	},
+	Workload {
+		name: "calls",
+		body: call_body,
+		writes: |_| 1,
+	},
+];
+
+/// Call of `callee` with all the gas left and no value, input or output.
+fn call_body() -> Vec<u8> {
+	// PUSH1 0 five times: output size and offset, input size and offset, value.
+	let mut body = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00];
+	// PUSH20 callee
+	body.push(0x73);
+	body.extend_from_slice(callee().as_bytes());
+	// GAS CALL POP
+	body.extend_from_slice(&[0x5a, 0xf1, 0x50]);
+	body
+}
+
+/// Code running `body` as many times as the number of the calldata.
+fn looped(body: &[u8]) -> Vec<u8> {
+	let end = 16 + body.len();
+	assert!(end <= 0xff, "loop bodies fit PUSH1 jump destinations");
+
+	let mut code = vec![
+		// PUSH1 0 CALLDATALOAD: iterations.
+		0x60, 0x00, 0x35,
+		// JUMPDEST: start of the loop, at 3.
+		0x5b,
+		// DUP1 ISZERO PUSH1 end JUMPI: leave the loop after the last iteration.
+		0x80, 0x15, 0x60, end as u8, 0x57,
+	];
+	code.extend_from_slice(body);
+	code.extend_from_slice(&[
+		// PUSH1 1 SWAP1 SUB: count the iteration.
+		0x60, 0x01, 0x90, 0x03,
+		// PUSH1 3 JUMP: back to the start of the loop.
+		0x60, 0x03, 0x56,
+		// JUMPDEST STOP: end.
+		0x5b, 0x00,
+	]);
+	code
+}
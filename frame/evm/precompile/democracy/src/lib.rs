@@ -0,0 +1,132 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Democracy precompile
+//!
+//! Exposes the proposals and referenda of `pallet-democracy` to EVM
+//! contracts through a Solidity interface:
+//!
+//! ```solidity
+//! interface Democracy {
+//!     function publicPropCount() external view returns (uint256);
+//!     function referendumCount() external view returns (uint256);
+//!     function lowestUnbaked() external view returns (uint256);
+//!     function ongoingReferendumInfo(uint256 refIndex) external view returns (
+//!         uint256 end,
+//!         bytes32 proposalHash,
+//!         uint256 threshold,
+//!         uint256 delay,
+//!         uint256 ayes,
+//!         uint256 nays,
+//!         uint256 turnout
+//!     );
+//!     function finishedReferendumInfo(uint256 refIndex) external view returns (
+//!         bool approved,
+//!         uint256 end
+//!     );
+//! }
+//! ```
+//!
+//! `threshold` is 0 for a super-majority approve, 1 for a super-majority
+//! against and 2 for a simple majority threshold.
+//!
+//! Precompiles are not told the address calling them, so voting and
+//! delegating on behalf of the calling contract are not supported.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::marker::PhantomData;
+use sp_core::{H256, U256};
+use sp_runtime::traits::UniqueSaturatedInto;
+use pallet_democracy::{ReferendumInfo, VoteThreshold};
+use pallet_evm_precompile_utils::{EvmResult, EvmDataReader, EvmDataWriter, Gasometer, error};
+
+#[cfg(test)]
+mod tests;
+
+/// Selector of `publicPropCount()`.
+pub const SELECTOR_PUBLIC_PROP_COUNT: [u8; 4] = [0x31, 0x30, 0x54, 0x62];
+/// Selector of `referendumCount()`.
+pub const SELECTOR_REFERENDUM_COUNT: [u8; 4] = [0x3a, 0x42, 0xee, 0x31];
+/// Selector of `lowestUnbaked()`.
+pub const SELECTOR_LOWEST_UNBAKED: [u8; 4] = [0xd4, 0x9d, 0xcc, 0xf0];
+/// Selector of `ongoingReferendumInfo(uint256)`.
+pub const SELECTOR_ONGOING_REFERENDUM_INFO: [u8; 4] = [0xe5, 0xa1, 0x83, 0x59];
+/// Selector of `finishedReferendumInfo(uint256)`.
+pub const SELECTOR_FINISHED_REFERENDUM_INFO: [u8; 4] = [0x07, 0xdf, 0x49, 0x5b];
+
+/// Gas charged for any call to the precompile.
+pub const GAS_COST: usize = 3_000;
+
+/// Democracy precompile, reading the state of `pallet_democracy`.
+pub struct Democracy<T>(PhantomData<T>);
+
+impl<T: pallet_democracy::Trait> Democracy<T> {
+	pub fn execute(input: &[u8], target_gas: Option<usize>) -> EvmResult {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_cost(GAS_COST)?;
+
+		let (selector, mut reader) = EvmDataReader::new_with_selector(input)?;
+		let output = if selector == SELECTOR_PUBLIC_PROP_COUNT {
+			reader.expect_arguments(0)?;
+			EvmDataWriter::new()
+				.write_u256(pallet_democracy::Module::<T>::public_prop_count().into())
+		} else if selector == SELECTOR_REFERENDUM_COUNT {
+			reader.expect_arguments(0)?;
+			EvmDataWriter::new()
+				.write_u256(pallet_democracy::Module::<T>::referendum_count().into())
+		} else if selector == SELECTOR_LOWEST_UNBAKED {
+			reader.expect_arguments(0)?;
+			EvmDataWriter::new()
+				.write_u256(pallet_democracy::Module::<T>::lowest_unbaked().into())
+		} else if selector == SELECTOR_ONGOING_REFERENDUM_INFO {
+			reader.expect_arguments(1)?;
+			match pallet_democracy::Module::<T>::referendum_info(reader.read_u32()?) {
+				Some(ReferendumInfo::Ongoing(status)) => EvmDataWriter::new()
+					.write_u256(to_u256(status.end))
+					.write_h256(H256::from_slice(status.proposal_hash.as_ref()))
+					.write_u256(match status.threshold {
+						VoteThreshold::SuperMajorityApprove => 0,
+						VoteThreshold::SuperMajorityAgainst => 1,
+						VoteThreshold::SimpleMajority => 2,
+					}.into())
+					.write_u256(to_u256(status.delay))
+					.write_u256(to_u256(status.tally.ayes))
+					.write_u256(to_u256(status.tally.nays))
+					.write_u256(to_u256(status.tally.turnout)),
+				Some(ReferendumInfo::Finished { .. }) => return Err(error("referendum is finished")),
+				None => return Err(error("unknown referendum")),
+			}
+		} else if selector == SELECTOR_FINISHED_REFERENDUM_INFO {
+			reader.expect_arguments(1)?;
+			match pallet_democracy::Module::<T>::referendum_info(reader.read_u32()?) {
+				Some(ReferendumInfo::Finished { approved, end }) => EvmDataWriter::new()
+					.write_bool(approved)
+					.write_u256(to_u256(end)),
+				Some(ReferendumInfo::Ongoing(_)) => return Err(error("referendum is ongoing")),
+				None => return Err(error("unknown referendum")),
+			}
+		} else {
+			return Err(error("unknown selector"))
+		};
+
+		gasometer.succeed(output.build())
+	}
+}
+
+fn to_u256<N: UniqueSaturatedInto<u128>>(value: N) -> U256 {
+	U256::from(value.unique_saturated_into())
+}
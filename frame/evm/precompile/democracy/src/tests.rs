@@ -0,0 +1,265 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{impl_outer_dispatch, impl_outer_origin, parameter_types, weights::Weight};
+use frame_system::{EnsureRoot, EnsureSigned};
+use sp_core::H160;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use pallet_democracy::{ReferendumStatus, Tally};
+use pallet_evm_precompile_utils::testing::precompiles;
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		frame_system::System,
+		pallet_democracy::Democracy,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub const MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+}
+
+impl pallet_scheduler::Trait for Test {
+	type Event = ();
+	type Origin = Origin;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
+parameter_types! {
+	pub const LaunchPeriod: u64 = 2;
+	pub const VotingPeriod: u64 = 2;
+	pub const FastTrackVotingPeriod: u64 = 2;
+	pub const MinimumDeposit: u64 = 1;
+	pub const EnactmentPeriod: u64 = 2;
+	pub const CooloffPeriod: u64 = 2;
+	pub const PreimageByteDeposit: u64 = 0;
+	pub const InstantAllowed: bool = false;
+}
+
+impl pallet_democracy::Trait for Test {
+	type Proposal = Call;
+	type Event = ();
+	type Currency = pallet_balances::Module<Self>;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	type ExternalOrigin = EnsureRoot<u64>;
+	type ExternalMajorityOrigin = EnsureRoot<u64>;
+	type ExternalDefaultOrigin = EnsureRoot<u64>;
+	type FastTrackOrigin = EnsureRoot<u64>;
+	type InstantOrigin = EnsureRoot<u64>;
+	type InstantAllowed = InstantAllowed;
+	type CancellationOrigin = EnsureRoot<u64>;
+	type VetoOrigin = EnsureSigned<u64>;
+	type OperationalPreimageOrigin = EnsureSigned<u64>;
+	type CooloffPeriod = CooloffPeriod;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type Slash = ();
+	type Scheduler = pallet_scheduler::Module<Self>;
+}
+
+type System = frame_system::Module<Test>;
+type Democracy = pallet_democracy::Module<Test>;
+
+const DEMOCRACY: u64 = 0x803;
+
+fn democracy() -> H160 {
+	H160::from_low_u64_be(DEMOCRACY)
+}
+
+/// The democracy precompile at `DEMOCRACY`.
+struct Precompiles;
+
+impl pallet_evm::Precompiles for Precompiles {
+	fn execute(address: H160, input: &[u8], target_gas: Option<usize>) -> Option<EvmResult> {
+		if address == H160::from_low_u64_be(DEMOCRACY) {
+			Some(crate::Democracy::<Test>::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_democracy::GenesisConfig::default()
+		.assimilate_storage::<Test>(&mut storage)
+		.unwrap();
+	sp_io::TestExternalities::new(storage)
+}
+
+fn input(selector: [u8; 4], ref_index: Option<u32>) -> Vec<u8> {
+	let mut input = selector.to_vec();
+	if let Some(ref_index) = ref_index {
+		input.extend(EvmDataWriter::new().write_u256(ref_index.into()).build());
+	}
+	input
+}
+
+fn word(value: u64) -> Vec<u8> {
+	EvmDataWriter::new().write_u256(value.into()).build()
+}
+
+#[test]
+fn counts_should_be_read_from_the_pallet() {
+	new_test_ext().execute_with(|| {
+		pallet_democracy::PublicPropCount::put(3);
+		pallet_democracy::ReferendumCount::put(5);
+		pallet_democracy::LowestUnbaked::put(4);
+
+		for (selector, count) in [
+			(SELECTOR_PUBLIC_PROP_COUNT, 3),
+			(SELECTOR_REFERENDUM_COUNT, 5),
+			(SELECTOR_LOWEST_UNBAKED, 4),
+		].iter() {
+			precompiles::<Precompiles>()
+				.prepare_test(democracy(), input(*selector, None))
+				.expect_cost(GAS_COST)
+				.execute_returns(word(*count));
+		}
+	});
+}
+
+#[test]
+fn ongoing_referendum_info_should_be_returned() {
+	new_test_ext().execute_with(|| {
+		let status = ReferendumStatus {
+			end: 10,
+			proposal_hash: H256::repeat_byte(1),
+			threshold: VoteThreshold::SimpleMajority,
+			delay: 2,
+			tally: Tally { ayes: 30, nays: 20, turnout: 50 },
+		};
+		pallet_democracy::ReferendumInfoOf::<Test>::insert(0, ReferendumInfo::Ongoing(status));
+
+		precompiles::<Precompiles>()
+			.prepare_test(democracy(), input(SELECTOR_ONGOING_REFERENDUM_INFO, Some(0)))
+			.expect_cost(GAS_COST)
+			.execute_returns(
+				EvmDataWriter::new()
+					.write_u256(10.into())
+					.write_h256(H256::repeat_byte(1))
+					.write_u256(2.into())
+					.write_u256(2.into())
+					.write_u256(30.into())
+					.write_u256(20.into())
+					.write_u256(50.into())
+					.build()
+			);
+		precompiles::<Precompiles>()
+			.prepare_test(democracy(), input(SELECTOR_FINISHED_REFERENDUM_INFO, Some(0)))
+			.execute_error(error("referendum is ongoing"));
+	});
+}
+
+#[test]
+fn finished_referendum_info_should_be_returned() {
+	new_test_ext().execute_with(|| {
+		pallet_democracy::ReferendumInfoOf::<Test>::insert(
+			0,
+			ReferendumInfo::Finished { approved: true, end: 10 },
+		);
+
+		precompiles::<Precompiles>()
+			.prepare_test(democracy(), input(SELECTOR_FINISHED_REFERENDUM_INFO, Some(0)))
+			.expect_cost(GAS_COST)
+			.execute_returns(EvmDataWriter::new().write_bool(true).write_u256(10.into()).build());
+		precompiles::<Precompiles>()
+			.prepare_test(democracy(), input(SELECTOR_ONGOING_REFERENDUM_INFO, Some(0)))
+			.execute_error(error("referendum is finished"));
+	});
+}
+
+#[test]
+fn unknown_referendum_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		for selector in [SELECTOR_ONGOING_REFERENDUM_INFO, SELECTOR_FINISHED_REFERENDUM_INFO].iter() {
+			precompiles::<Precompiles>()
+				.prepare_test(democracy(), input(*selector, Some(7)))
+				.execute_error(error("unknown referendum"));
+		}
+	});
+}
+
+#[test]
+fn unknown_selectors_should_be_rejected() {
+	new_test_ext().execute_with(|| {
+		precompiles::<Precompiles>()
+			.prepare_test(democracy(), input([0; 4], None))
+			.execute_error(error("unknown selector"));
+	});
+}
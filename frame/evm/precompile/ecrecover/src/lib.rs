@@ -0,0 +1,72 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # ECRecover precompile
+//!
+//! The standard Ethereum precompile at address `0x01`, recovering the address
+//! that signed a message hash. Recovery goes through the secp256k1 host
+//! function rather than running in wasm.
+//!
+//! The input is `hash`, `v`, `r` and `s` as 32 byte words, right-padded with
+//! zeros if shorter. The output is the signer address as a 32 byte word, or
+//! empty if the signature is invalid.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use sp_io::hashing::keccak_256;
+use pallet_evm_precompile_utils::{EvmResult, Gasometer};
+
+#[cfg(test)]
+mod tests;
+
+/// Gas charged for any call to the precompile.
+pub const GAS_COST: usize = 3_000;
+
+/// ECRecover precompile.
+pub struct ECRecover;
+
+impl ECRecover {
+	pub fn execute(input: &[u8], target_gas: Option<usize>) -> EvmResult {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_cost(GAS_COST)?;
+
+		let mut input = input.to_vec();
+		input.resize(128, 0);
+
+		// `v` is a 32 byte word that must hold 27 or 28.
+		if input[32..63].iter().any(|byte| *byte != 0) || (input[63] != 27 && input[63] != 28) {
+			return gasometer.succeed(Vec::new())
+		}
+
+		let mut msg = [0u8; 32];
+		let mut sig = [0u8; 65];
+		msg.copy_from_slice(&input[0..32]);
+		sig[0..64].copy_from_slice(&input[64..128]);
+		sig[64] = input[63] - 27;
+
+		let output = match sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg) {
+			Ok(pubkey) => {
+				let mut address = keccak_256(&pubkey);
+				address[0..12].copy_from_slice(&[0u8; 12]);
+				address.to_vec()
+			},
+			Err(_) => Vec::new(),
+		};
+
+		gasometer.succeed(output)
+	}
+}
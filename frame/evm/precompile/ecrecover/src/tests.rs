@@ -0,0 +1,60 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use hex_literal::hex;
+use sp_core::H160;
+use pallet_evm_precompile_utils::testing::precompiles;
+
+/// ECRecover at its standard address.
+struct Precompiles;
+
+impl pallet_evm::Precompiles for Precompiles {
+	fn execute(address: H160, input: &[u8], target_gas: Option<usize>) -> Option<EvmResult> {
+		if address == H160::from_low_u64_be(1) {
+			Some(ECRecover::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+fn input(v: u8) -> Vec<u8> {
+	let mut input = hex!("456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3").to_vec();
+	input.extend_from_slice(&[0u8; 31]);
+	input.push(v);
+	input.extend_from_slice(&hex!("9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"));
+	input.extend_from_slice(&hex!("4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"));
+	input
+}
+
+#[test]
+fn ecrecover_should_return_signer() {
+	precompiles::<Precompiles>()
+		.prepare_test(H160::from_low_u64_be(1), input(28))
+		.expect_cost(GAS_COST)
+		.execute_returns(
+			hex!("0000000000000000000000007156526fbd7a3c72969b54f64e42c10fbb768c8a").to_vec()
+		);
+}
+
+#[test]
+fn ecrecover_should_return_nothing_for_invalid_v() {
+	precompiles::<Precompiles>()
+		.prepare_test(H160::from_low_u64_be(1), input(29))
+		.expect_cost(GAS_COST)
+		.execute_returns(Vec::new());
+}
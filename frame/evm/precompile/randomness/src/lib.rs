@@ -0,0 +1,101 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Randomness precompile
+//!
+//! Exposes the runtime randomness to EVM contracts through a Solidity
+//! interface:
+//!
+//! ```solidity
+//! interface Randomness {
+//!     function currentRandomness(bytes32 subject) external view returns (bytes32);
+//!     function randomnessAt(uint256 blockNumber, bytes32 subject) external view returns (bytes32);
+//! }
+//! ```
+//!
+//! `currentRandomness` is known to the author of the current block. Contracts
+//! that need unbiased randomness should instead commit to a future block and
+//! call `randomnessAt` once that block is in the past, which is available for the
+//! last `BlockHashCount` blocks.
+//!
+//! The storage read by each call is charged at the `ExternalCostRates` of
+//! `C`, which charge nothing by default.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::marker::PhantomData;
+use sp_runtime::traits::{Hash, UniqueSaturatedFrom};
+use frame_support::traits::{Get, Randomness as RandomnessT};
+use pallet_evm_precompile_utils::{EvmResult, EvmDataReader, ExternalCostRates, Gasometer, error};
+
+#[cfg(test)]
+mod tests;
+
+/// Selector of `currentRandomness(bytes32)`.
+pub const SELECTOR_CURRENT_RANDOMNESS: [u8; 4] = [0xa6, 0x78, 0x4a, 0xc0];
+/// Selector of `randomnessAt(uint256,bytes32)`.
+pub const SELECTOR_RANDOMNESS_AT: [u8; 4] = [0x9a, 0xb7, 0x5e, 0x15];
+
+/// Gas charged for any call to the precompile.
+pub const GAS_COST: usize = 3_000;
+
+/// Proof size of `currentRandomness`: the key and the 81 hashes of the random
+/// material of `pallet-randomness-collective-flip`.
+pub const CURRENT_RANDOMNESS_PROOF_SIZE: usize = 32 + 1 + 81 * 32;
+
+/// Proof size of `randomnessAt`: the keys and values of the block number and
+/// of a block hash, for 32-bit block numbers.
+pub const RANDOMNESS_AT_PROOF_SIZE: usize = (32 + 4) + (32 + 8 + 4 + 32);
+
+/// Randomness precompile, reading current randomness from `R` and past block
+/// hashes from `frame_system`, and charging external costs at the rates of `C`.
+pub struct Randomness<T, R, C = ()>(PhantomData<(T, R, C)>);
+
+impl<T, R, C> Randomness<T, R, C> where
+	T: frame_system::Trait,
+	R: RandomnessT<T::Hash>,
+	C: Get<ExternalCostRates>,
+{
+	pub fn execute(input: &[u8], target_gas: Option<usize>) -> EvmResult {
+		let mut gasometer = Gasometer::new(target_gas).with_external_cost_rates(C::get());
+		gasometer.record_cost(GAS_COST)?;
+
+		let (selector, mut reader) = EvmDataReader::new_with_selector(input)?;
+		let output = if selector == SELECTOR_CURRENT_RANDOMNESS {
+			reader.expect_arguments(1)?;
+			let subject = reader.read_h256()?;
+			gasometer.record_proof_size(CURRENT_RANDOMNESS_PROOF_SIZE)?;
+			R::random(subject.as_bytes())
+		} else if selector == SELECTOR_RANDOMNESS_AT {
+			reader.expect_arguments(2)?;
+			let number = T::BlockNumber::unique_saturated_from(reader.read_u32()?);
+			let subject = reader.read_h256()?;
+			gasometer.record_proof_size(RANDOMNESS_AT_PROOF_SIZE)?;
+			if number >= frame_system::Module::<T>::block_number() {
+				return Err(error("randomness is not available yet"))
+			}
+			let block_hash = frame_system::Module::<T>::block_hash(number);
+			if block_hash == Default::default() {
+				return Err(error("randomness is no longer available"))
+			}
+			T::Hashing::hash_of(&(block_hash, subject))
+		} else {
+			return Err(error("unknown selector"))
+		};
+
+		gasometer.succeed(output.as_ref().to_vec())
+	}
+}
@@ -0,0 +1,186 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use pallet_evm_precompile_utils::{EvmDataWriter, testing::precompiles};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+type System = frame_system::Module<Test>;
+
+/// Randomness that is the hash of the subject.
+pub struct SubjectHash;
+
+impl RandomnessT<H256> for SubjectHash {
+	fn random(subject: &[u8]) -> H256 {
+		BlakeTwo256::hash(subject)
+	}
+}
+
+parameter_types! {
+	pub const ProofByteRates: ExternalCostRates = ExternalCostRates {
+		gas_per_proof_byte: 1,
+		gas_per_storage_item: 0,
+	};
+}
+
+const RANDOMNESS: u64 = 0x800;
+
+/// The randomness precompile at `RANDOMNESS`, charging external costs at the
+/// rates of `C`.
+struct Precompiles<C = ()>(PhantomData<C>);
+
+impl<C: Get<ExternalCostRates>> pallet_evm::Precompiles for Precompiles<C> {
+	fn execute(address: H160, input: &[u8], target_gas: Option<usize>) -> Option<EvmResult> {
+		if address == H160::from_low_u64_be(RANDOMNESS) {
+			Some(Randomness::<Test, SubjectHash, C>::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let storage = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(10));
+	ext
+}
+
+fn current_randomness(subject: H256) -> Vec<u8> {
+	let mut input = SELECTOR_CURRENT_RANDOMNESS.to_vec();
+	input.extend(EvmDataWriter::new().write_h256(subject).build());
+	input
+}
+
+fn randomness_at(number: u64, subject: H256) -> Vec<u8> {
+	let mut input = SELECTOR_RANDOMNESS_AT.to_vec();
+	input.extend(EvmDataWriter::new().write_u256(U256::from(number)).write_h256(subject).build());
+	input
+}
+
+#[test]
+fn current_randomness_should_be_that_of_the_source() {
+	new_test_ext().execute_with(|| {
+		let subject = H256::repeat_byte(1);
+		precompiles::<Precompiles>()
+			.prepare_test(H160::from_low_u64_be(RANDOMNESS), current_randomness(subject))
+			.expect_cost(GAS_COST)
+			.execute_returns(BlakeTwo256::hash(subject.as_bytes()).as_bytes().to_vec());
+	});
+}
+
+#[test]
+fn current_randomness_should_charge_its_proof_size() {
+	new_test_ext().execute_with(|| {
+		precompiles::<Precompiles<ProofByteRates>>()
+			.prepare_test(H160::from_low_u64_be(RANDOMNESS), current_randomness(H256::zero()))
+			.expect_cost(GAS_COST + CURRENT_RANDOMNESS_PROOF_SIZE)
+			.execute_returns(BlakeTwo256::hash(&[0; 32]).as_bytes().to_vec());
+	});
+}
+
+#[test]
+fn randomness_at_should_hash_the_block_hash_with_the_subject() {
+	new_test_ext().execute_with(|| {
+		let block_hash = H256::repeat_byte(7);
+		let subject = H256::repeat_byte(1);
+		frame_system::BlockHash::<Test>::insert(5, block_hash);
+
+		precompiles::<Precompiles<ProofByteRates>>()
+			.prepare_test(H160::from_low_u64_be(RANDOMNESS), randomness_at(5, subject))
+			.expect_cost(GAS_COST + RANDOMNESS_AT_PROOF_SIZE)
+			.execute_returns(BlakeTwo256::hash_of(&(block_hash, subject)).as_bytes().to_vec());
+	});
+}
+
+#[test]
+fn randomness_at_should_reject_blocks_not_in_the_past() {
+	new_test_ext().execute_with(|| {
+		for number in [10, 11].iter() {
+			precompiles::<Precompiles>()
+				.prepare_test(
+					H160::from_low_u64_be(RANDOMNESS),
+					randomness_at(*number, H256::zero()),
+				)
+				.execute_error(error("randomness is not available yet"));
+		}
+	});
+}
+
+#[test]
+fn randomness_at_should_reject_pruned_blocks() {
+	new_test_ext().execute_with(|| {
+		precompiles::<Precompiles>()
+			.prepare_test(H160::from_low_u64_be(RANDOMNESS), randomness_at(5, H256::zero()))
+			.execute_error(error("randomness is no longer available"));
+	});
+}
+
+#[test]
+fn randomness_should_reject_unknown_selectors() {
+	new_test_ext().execute_with(|| {
+		precompiles::<Precompiles>()
+			.prepare_test(H160::from_low_u64_be(RANDOMNESS), vec![0; 4])
+			.execute_error(error("unknown selector"));
+	});
+}
@@ -0,0 +1,74 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Precompile registry
+//!
+//! Tells EVM contracts which addresses hold precompiles, through a Solidity
+//! interface:
+//!
+//! ```solidity
+//! interface PrecompileRegistry {
+//!     function isPrecompile(address a) external view returns (bool);
+//!     function isActivePrecompile(address a) external view returns (bool);
+//! }
+//! ```
+//!
+//! Every precompile of the runtime is active, so both functions agree.
+//!
+//! Precompiles have no code of their own, so `extcodesize` is zero at their
+//! address and Solidity `isContract` checks reject them. Chains should deploy
+//! `PRECOMPILE_CODE` at each precompile address to avoid that.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::{marker::PhantomData, vec::Vec};
+use sp_core::H160;
+use frame_support::traits::Get;
+use pallet_evm_precompile_utils::{EvmResult, EvmDataReader, EvmDataWriter, Gasometer, error};
+
+#[cfg(test)]
+mod tests;
+
+/// Selector of `isPrecompile(address)`.
+pub const SELECTOR_IS_PRECOMPILE: [u8; 4] = [0x44, 0x6b, 0x45, 0x0e];
+/// Selector of `isActivePrecompile(address)`.
+pub const SELECTOR_IS_ACTIVE_PRECOMPILE: [u8; 4] = [0x6f, 0x5e, 0x23, 0xcf];
+
+/// Gas charged for any call to the precompile.
+pub const GAS_COST: usize = 1_000;
+
+/// Code to deploy at precompile addresses: `PUSH1 0 PUSH1 0 REVERT`, which is
+/// never run since calls to these addresses execute the precompile.
+pub const PRECOMPILE_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+/// Registry precompile, reporting on the precompile addresses of `A`.
+pub struct PrecompileRegistry<A>(PhantomData<A>);
+
+impl<A: Get<Vec<H160>>> PrecompileRegistry<A> {
+	pub fn execute(input: &[u8], target_gas: Option<usize>) -> EvmResult {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_cost(GAS_COST)?;
+
+		let (selector, mut reader) = EvmDataReader::new_with_selector(input)?;
+		if selector != SELECTOR_IS_PRECOMPILE && selector != SELECTOR_IS_ACTIVE_PRECOMPILE {
+			return Err(error("unknown selector"))
+		}
+		reader.expect_arguments(1)?;
+		let address = reader.read_address()?;
+
+		gasometer.succeed(EvmDataWriter::new().write_bool(A::get().contains(&address)).build())
+	}
+}
@@ -0,0 +1,75 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use pallet_evm_precompile_utils::testing::precompiles;
+
+const REGISTRY: u64 = 0x801;
+
+struct Addresses;
+
+impl Get<Vec<H160>> for Addresses {
+	fn get() -> Vec<H160> {
+		vec![H160::from_low_u64_be(1), H160::from_low_u64_be(REGISTRY)]
+	}
+}
+
+/// The registry at `REGISTRY`, reporting on itself and ecrecover.
+struct Precompiles;
+
+impl pallet_evm::Precompiles for Precompiles {
+	fn execute(address: H160, input: &[u8], target_gas: Option<usize>) -> Option<EvmResult> {
+		if address == H160::from_low_u64_be(REGISTRY) {
+			Some(PrecompileRegistry::<Addresses>::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+fn input(selector: [u8; 4], address: H160) -> Vec<u8> {
+	let mut input = selector.to_vec();
+	input.extend(EvmDataWriter::new().write_address(address).build());
+	input
+}
+
+#[test]
+fn registry_should_report_precompiles() {
+	for selector in [SELECTOR_IS_PRECOMPILE, SELECTOR_IS_ACTIVE_PRECOMPILE].iter() {
+		precompiles::<Precompiles>()
+			.prepare_test(H160::from_low_u64_be(REGISTRY), input(*selector, H160::from_low_u64_be(1)))
+			.expect_cost(GAS_COST)
+			.execute_returns(EvmDataWriter::new().write_bool(true).build());
+	}
+}
+
+#[test]
+fn registry_should_not_report_other_addresses() {
+	precompiles::<Precompiles>()
+		.prepare_test(
+			H160::from_low_u64_be(REGISTRY),
+			input(SELECTOR_IS_PRECOMPILE, H160::from_low_u64_be(2)),
+		)
+		.expect_cost(GAS_COST)
+		.execute_returns(EvmDataWriter::new().write_bool(false).build());
+}
+
+#[test]
+fn registry_should_reject_unknown_selectors() {
+	precompiles::<Precompiles>()
+		.prepare_test(H160::from_low_u64_be(REGISTRY), input([0; 4], H160::from_low_u64_be(1)))
+		.execute_error(error("unknown selector"));
+}
@@ -0,0 +1,243 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Precompile utilities
+//!
+//! Shared helpers for precompile authors: gas accounting against the target
+//! gas of a call, and reading and writing Solidity ABI encoded data.
+//!
+//! Gas measures execution, not the size of the storage proof of a parachain
+//! block or the growth of the state. Precompiles record these costs with
+//! `Gasometer::record_proof_size` and `Gasometer::record_storage_items`,
+//! which charge them as gas at the `ExternalCostRates` of the runtime, so
+//! that a call reading a lot of state is not cheap in gas.
+//!
+//! Precompiles only receive their input and gas limit, so emitting logs and
+//! executing sub-calls are not supported.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::vec::Vec;
+use sp_core::{H160, H256, U256};
+use pallet_evm::{ExitSucceed, ExitError};
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(test)]
+mod tests;
+
+/// Result of a precompile, or of a step of it.
+pub type EvmResult<T = (ExitSucceed, Vec<u8>, usize)> = core::result::Result<T, ExitError>;
+
+/// Build an error with a static message.
+pub fn error(message: &'static str) -> ExitError {
+	ExitError::Other(message.into())
+}
+
+/// Gas charged for the costs of a precompile that gas does not measure. The
+/// default rates charge nothing for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExternalCostRates {
+	/// Gas charged for each byte of storage proof read.
+	pub gas_per_proof_byte: usize,
+	/// Gas charged for each storage item written.
+	pub gas_per_storage_item: usize,
+}
+
+/// Tracks the gas used by a precompile against its target gas.
+#[derive(Clone, Copy, Debug)]
+pub struct Gasometer {
+	target_gas: Option<usize>,
+	used_gas: usize,
+	rates: ExternalCostRates,
+	proof_size: usize,
+	storage_items: usize,
+}
+
+impl Gasometer {
+	pub fn new(target_gas: Option<usize>) -> Self {
+		Self {
+			target_gas,
+			used_gas: 0,
+			rates: ExternalCostRates::default(),
+			proof_size: 0,
+			storage_items: 0,
+		}
+	}
+
+	/// Charge external costs at `rates`.
+	pub fn with_external_cost_rates(mut self, rates: ExternalCostRates) -> Self {
+		self.rates = rates;
+		self
+	}
+
+	/// Record `cost`, failing with `OutOfGas` if it exceeds the target gas.
+	pub fn record_cost(&mut self, cost: usize) -> EvmResult<()> {
+		let used_gas = self.used_gas.checked_add(cost).ok_or(ExitError::OutOfGas)?;
+		match self.target_gas {
+			Some(target_gas) if used_gas > target_gas => Err(ExitError::OutOfGas),
+			_ => {
+				self.used_gas = used_gas;
+				Ok(())
+			}
+		}
+	}
+
+	/// Record `bytes` of storage proof read, charging them as gas.
+	pub fn record_proof_size(&mut self, bytes: usize) -> EvmResult<()> {
+		let cost = bytes.checked_mul(self.rates.gas_per_proof_byte).ok_or(ExitError::OutOfGas)?;
+		self.record_cost(cost)?;
+		self.proof_size = self.proof_size.saturating_add(bytes);
+		Ok(())
+	}
+
+	/// Record `items` storage items written, charging them as gas.
+	pub fn record_storage_items(&mut self, items: usize) -> EvmResult<()> {
+		let cost = items.checked_mul(self.rates.gas_per_storage_item).ok_or(ExitError::OutOfGas)?;
+		self.record_cost(cost)?;
+		self.storage_items = self.storage_items.saturating_add(items);
+		Ok(())
+	}
+
+	/// Gas used so far, including the external costs.
+	pub fn used_gas(&self) -> usize {
+		self.used_gas
+	}
+
+	/// Bytes of storage proof recorded so far.
+	pub fn proof_size(&self) -> usize {
+		self.proof_size
+	}
+
+	/// Storage items written recorded so far.
+	pub fn storage_items(&self) -> usize {
+		self.storage_items
+	}
+
+	/// Successful result returning `output`, charging the gas used so far.
+	pub fn succeed(&self, output: Vec<u8>) -> EvmResult {
+		Ok((ExitSucceed::Returned, output, self.used_gas))
+	}
+}
+
+/// Reads Solidity ABI encoded input, one 32 bytes word at a time.
+pub struct EvmDataReader<'a> {
+	input: &'a [u8],
+	cursor: usize,
+}
+
+impl<'a> EvmDataReader<'a> {
+	/// Reader over call arguments, after the function selector.
+	pub fn new(input: &'a [u8]) -> Self {
+		Self { input, cursor: 0 }
+	}
+
+	/// Split the function selector off `input`, returning it along with a
+	/// reader over the arguments.
+	pub fn new_with_selector(input: &'a [u8]) -> EvmResult<([u8; 4], Self)> {
+		if input.len() < 4 {
+			return Err(error("input must contain a selector"))
+		}
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&input[..4]);
+		Ok((selector, Self::new(&input[4..])))
+	}
+
+	/// Ensure exactly `count` words of arguments are left to read.
+	pub fn expect_arguments(&self, count: usize) -> EvmResult<()> {
+		if self.input.len() - self.cursor == count * 32 {
+			Ok(())
+		} else {
+			Err(error("invalid number of arguments"))
+		}
+	}
+
+	fn read_word(&mut self) -> EvmResult<&'a [u8]> {
+		let end = self.cursor + 32;
+		let word = self.input.get(self.cursor..end).ok_or(error("tried to read out of bounds"))?;
+		self.cursor = end;
+		Ok(word)
+	}
+
+	pub fn read_h256(&mut self) -> EvmResult<H256> {
+		Ok(H256::from_slice(self.read_word()?))
+	}
+
+	pub fn read_u256(&mut self) -> EvmResult<U256> {
+		Ok(U256::from_big_endian(self.read_word()?))
+	}
+
+	pub fn read_address(&mut self) -> EvmResult<H160> {
+		let word = self.read_word()?;
+		if word[..12].iter().any(|b| *b != 0) {
+			return Err(error("invalid address"))
+		}
+		Ok(H160::from_slice(&word[12..]))
+	}
+
+	pub fn read_bool(&mut self) -> EvmResult<bool> {
+		let value = self.read_u256()?;
+		if value > U256::one() {
+			return Err(error("invalid bool"))
+		}
+		Ok(value == U256::one())
+	}
+
+	/// Read a `uint256` that must fit in a `u32`.
+	pub fn read_u32(&mut self) -> EvmResult<u32> {
+		let value = self.read_u256()?;
+		if value > U256::from(u32::max_value()) {
+			return Err(error("value out of range"))
+		}
+		Ok(value.low_u32())
+	}
+}
+
+/// Writes Solidity ABI encoded output, one 32 bytes word at a time.
+#[derive(Default)]
+pub struct EvmDataWriter {
+	data: Vec<u8>,
+}
+
+impl EvmDataWriter {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn write_h256(mut self, value: H256) -> Self {
+		self.data.extend_from_slice(value.as_bytes());
+		self
+	}
+
+	pub fn write_u256(mut self, value: U256) -> Self {
+		let mut word = [0u8; 32];
+		value.to_big_endian(&mut word);
+		self.data.extend_from_slice(&word);
+		self
+	}
+
+	pub fn write_address(self, value: H160) -> Self {
+		self.write_h256(value.into())
+	}
+
+	pub fn write_bool(self, value: bool) -> Self {
+		self.write_u256(if value { U256::one() } else { U256::zero() })
+	}
+
+	pub fn build(self) -> Vec<u8> {
+		self.data
+	}
+}
@@ -0,0 +1,103 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers to test precompiles against expectations without a full runtime.
+//!
+//! ```ignore
+//! precompiles::<MyPrecompiles>()
+//!     .prepare_test(address, input)
+//!     .expect_cost(3_000)
+//!     .execute_returns(output);
+//! ```
+
+use sp_std::marker::PhantomData;
+use pallet_evm::Precompiles;
+use super::*;
+
+/// Entry point to test a set of precompiles.
+pub fn precompiles<P: Precompiles>() -> PrecompilesTesterBuilder<P> {
+	PrecompilesTesterBuilder(PhantomData)
+}
+
+pub struct PrecompilesTesterBuilder<P>(PhantomData<P>);
+
+impl<P: Precompiles> PrecompilesTesterBuilder<P> {
+	/// Prepare a call of the precompile at `to` with `input`.
+	pub fn prepare_test(&self, to: impl Into<H160>, input: impl Into<Vec<u8>>) -> PrecompilesTester<P> {
+		PrecompilesTester {
+			to: to.into(),
+			input: input.into(),
+			target_gas: None,
+			expected_cost: None,
+			_marker: PhantomData,
+		}
+	}
+}
+
+/// A prepared precompile call, checked against expectations when executed.
+pub struct PrecompilesTester<P> {
+	to: H160,
+	input: Vec<u8>,
+	target_gas: Option<usize>,
+	expected_cost: Option<usize>,
+	_marker: PhantomData<P>,
+}
+
+impl<P: Precompiles> PrecompilesTester<P> {
+	/// Execute with the given target gas instead of unbounded gas.
+	pub fn with_target_gas(mut self, target_gas: Option<usize>) -> Self {
+		self.target_gas = target_gas;
+		self
+	}
+
+	/// Expect a successful execution to use exactly `cost` gas.
+	pub fn expect_cost(mut self, cost: usize) -> Self {
+		self.expected_cost = Some(cost);
+		self
+	}
+
+	fn execute(&self) -> Option<EvmResult> {
+		P::execute(self.to, &self.input, self.target_gas)
+	}
+
+	/// Execute and expect `output` to be returned.
+	pub fn execute_returns(self, output: Vec<u8>) {
+		match self.execute() {
+			Some(Ok((_, returned, cost))) => {
+				assert_eq!(returned, output, "unexpected output");
+				if let Some(expected_cost) = self.expected_cost {
+					assert_eq!(cost, expected_cost, "unexpected cost");
+				}
+			},
+			Some(Err(e)) => panic!("expected success, got error {:?}", e),
+			None => panic!("no precompile at {:?}", self.to),
+		}
+	}
+
+	/// Execute and expect `error` to be returned.
+	pub fn execute_error(self, error: ExitError) {
+		match self.execute() {
+			Some(Err(e)) => assert_eq!(e, error, "unexpected error"),
+			Some(Ok((_, returned, _))) => panic!("expected error, got output {:?}", returned),
+			None => panic!("no precompile at {:?}", self.to),
+		}
+	}
+
+	/// Execute and expect no precompile to exist at the address.
+	pub fn execute_none(self) {
+		assert!(self.execute().is_none(), "expected no precompile at {:?}", self.to);
+	}
+}
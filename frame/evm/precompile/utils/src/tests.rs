@@ -0,0 +1,107 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use testing::precompiles;
+
+/// Echoes its input at address `1`, charging one gas per byte.
+struct Echo;
+
+impl pallet_evm::Precompiles for Echo {
+	fn execute(address: H160, input: &[u8], target_gas: Option<usize>) -> Option<EvmResult> {
+		if address != H160::from_low_u64_be(1) {
+			return None
+		}
+		let mut gasometer = Gasometer::new(target_gas);
+		Some(gasometer.record_cost(input.len()).and_then(|_| gasometer.succeed(input.to_vec())))
+	}
+}
+
+#[test]
+fn gasometer_should_fail_past_target_gas() {
+	let mut gasometer = Gasometer::new(Some(100));
+	assert!(gasometer.record_cost(60).is_ok());
+	assert_eq!(gasometer.record_cost(60), Err(ExitError::OutOfGas));
+	assert_eq!(gasometer.used_gas(), 60);
+
+	let mut unbounded = Gasometer::new(None);
+	assert!(unbounded.record_cost(usize::max_value()).is_ok());
+}
+
+#[test]
+fn gasometer_should_charge_external_costs_as_gas() {
+	let rates = ExternalCostRates { gas_per_proof_byte: 4, gas_per_storage_item: 100 };
+	let mut gasometer = Gasometer::new(Some(1_000)).with_external_cost_rates(rates);
+	assert!(gasometer.record_proof_size(50).is_ok());
+	assert!(gasometer.record_storage_items(2).is_ok());
+	assert_eq!(gasometer.used_gas(), 400);
+	assert_eq!(gasometer.proof_size(), 50);
+	assert_eq!(gasometer.storage_items(), 2);
+
+	assert_eq!(gasometer.record_proof_size(200), Err(ExitError::OutOfGas));
+	assert_eq!(gasometer.record_storage_items(usize::max_value()), Err(ExitError::OutOfGas));
+	assert_eq!(gasometer.used_gas(), 400);
+	assert_eq!(gasometer.proof_size(), 50);
+
+	let mut free = Gasometer::new(Some(0));
+	assert!(free.record_proof_size(1_000).is_ok());
+	assert_eq!(free.used_gas(), 0);
+	assert_eq!(free.proof_size(), 1_000);
+}
+
+#[test]
+fn reader_should_read_written_data() {
+	let address = H160::repeat_byte(0x11);
+	let data = EvmDataWriter::new()
+		.write_address(address)
+		.write_u256(U256::from(42))
+		.write_bool(true)
+		.build();
+
+	let mut input = vec![1, 2, 3, 4];
+	input.extend_from_slice(&data);
+
+	let (selector, mut reader) = EvmDataReader::new_with_selector(&input).unwrap();
+	assert_eq!(selector, [1, 2, 3, 4]);
+	assert!(reader.expect_arguments(3).is_ok());
+	assert_eq!(reader.read_address(), Ok(address));
+	assert_eq!(reader.read_u32(), Ok(42));
+	assert_eq!(reader.read_bool(), Ok(true));
+	assert!(reader.read_h256().is_err());
+}
+
+#[test]
+fn reader_should_reject_dirty_address() {
+	let data = EvmDataWriter::new().write_u256(U256::max_value()).build();
+	assert!(EvmDataReader::new(&data).read_address().is_err());
+}
+
+#[test]
+fn tester_should_check_expectations() {
+	precompiles::<Echo>()
+		.prepare_test(H160::from_low_u64_be(1), vec![1, 2, 3])
+		.expect_cost(3)
+		.execute_returns(vec![1, 2, 3]);
+
+	precompiles::<Echo>()
+		.prepare_test(H160::from_low_u64_be(1), vec![1, 2, 3])
+		.with_target_gas(Some(2))
+		.execute_error(ExitError::OutOfGas);
+
+	precompiles::<Echo>()
+		.prepare_test(H160::from_low_u64_be(2), vec![])
+		.execute_none();
+}
@@ -0,0 +1,361 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # EVM state tests
+//!
+//! Runs the GeneralStateTests of github.com/ethereum/tests against pallet-evm,
+//! to catch divergences from mainnet semantics. `load` parses a filled fixture
+//! file, and `run` executes every post state of a test for a fork in a fresh
+//! mock runtime, comparing the resulting state root and logs hash with the
+//! expected ones.
+//!
+//! pallet-evm implements the Istanbul rules, so `FORK` is the only fork whose
+//! post states are expected to match. Where Ethereum and pallet-evm differ by
+//! design, the runner bridges the gap: it credits the coinbase with the fee,
+//! which pallet-evm leaves to the runtime. Only the ecrecover precompile is
+//! available, so tests of other precompiles report mismatches.
+
+use std::collections::BTreeMap;
+use codec::Encode;
+use ethereum_types::{BigEndianHash, H160, H256, U256};
+use frame_support::{
+	impl_outer_origin, impl_outer_event, parameter_types, weights::Weight, traits::Get,
+	storage::migration::put_storage_value, Blake2_128Concat, StorageHasher,
+};
+use pallet_ethereum::EthereumStateRoot;
+use pallet_evm::{Account, FeeCalculator, HashTruncateConvertAccountId};
+use pallet_evm_precompile_ecrecover::ECRecover;
+use rustc_hex::FromHex;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	ModuleId, Perbill,
+};
+
+/// Fork whose rules pallet-evm implements.
+pub const FORK: &str = "Istanbul";
+
+/// Storage prefix of pallet-evm.
+const EVM: &[u8] = b"EVM";
+
+/// Test of a GeneralStateTests fixture file. Values are hex strings.
+#[derive(Deserialize, Debug)]
+pub struct StateTest {
+	pub env: Env,
+	pub pre: BTreeMap<String, PreAccount>,
+	pub transaction: Transaction,
+	/// Expected post states, by fork.
+	pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+/// Block environment of a test.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+	pub current_coinbase: String,
+	pub current_number: String,
+}
+
+/// Account of the state a test starts from.
+#[derive(Deserialize, Debug)]
+pub struct PreAccount {
+	pub balance: String,
+	pub code: String,
+	pub nonce: String,
+	pub storage: BTreeMap<String, String>,
+}
+
+/// Transaction of a test, with the alternative data, gas limits and values
+/// that post states pick from.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub data: Vec<String>,
+	pub gas_limit: Vec<String>,
+	pub gas_price: String,
+	pub nonce: String,
+	pub secret_key: String,
+	/// Empty for contract creation.
+	pub to: String,
+	pub value: Vec<String>,
+}
+
+/// Expected outcome of the transaction built from `indexes`.
+#[derive(Deserialize, Debug)]
+pub struct PostState {
+	/// State root.
+	pub hash: String,
+	/// Keccak hash of the RLP list of logs.
+	pub logs: String,
+	pub indexes: Indexes,
+}
+
+/// Indexes of the data, gas limit and value of a transaction.
+#[derive(Deserialize, Debug)]
+pub struct Indexes {
+	pub data: usize,
+	pub gas: usize,
+	pub value: usize,
+}
+
+/// Difference between the outcome of a post state and the expected one.
+#[derive(Debug, PartialEq)]
+pub struct Mismatch {
+	/// Index of the post state.
+	pub index: usize,
+	/// `state root` or `logs hash`.
+	pub what: &'static str,
+	pub expected: H256,
+	pub actual: H256,
+}
+
+/// Parse the tests of a fixture file, by name.
+pub fn load(json: &str) -> serde_json::Result<BTreeMap<String, StateTest>> {
+	serde_json::from_str(json)
+}
+
+/// Execute the post states of `test` for `fork`, returning their mismatches.
+pub fn run(test: &StateTest, fork: &str) -> Vec<Mismatch> {
+	let posts = match test.post.get(fork) {
+		Some(posts) => posts,
+		None => return Vec::new(),
+	};
+
+	let mut mismatches = Vec::new();
+	for (index, post) in posts.iter().enumerate() {
+		let (state_root, logs_hash) = execute(test, &post.indexes);
+		for (what, expected, actual) in vec![
+			("state root", h256(&post.hash), state_root),
+			("logs hash", h256(&post.logs), logs_hash),
+		] {
+			if expected != actual {
+				mismatches.push(Mismatch { index, what, expected, actual });
+			}
+		}
+	}
+	mismatches
+}
+
+/// Execute the transaction built from `indexes` on the pre state of `test`,
+/// returning the state root and logs hash.
+fn execute(test: &StateTest, indexes: &Indexes) -> (H256, H256) {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+
+	ext.execute_with(|| {
+		// Events are not stored in the genesis block.
+		System::set_block_number(u256(&test.env.current_number).low_u64().max(1));
+
+		for (address, account) in &test.pre {
+			let address = h160(address);
+			put_account(address, Account {
+				nonce: u256(&account.nonce),
+				balance: u256(&account.balance),
+			});
+			put_storage_value(EVM, b"AccountCodes", &account_key(address), bytes(&account.code));
+			for (index, value) in &account.storage {
+				let mut key = account_key(address);
+				key.extend(Blake2_128Concat::hash(&h256(index).encode()));
+				put_storage_value(EVM, b"AccountStorages", &key, h256(value));
+			}
+		}
+
+		let transaction = &test.transaction;
+		let source = address_of(&bytes(&transaction.secret_key));
+		let data = bytes(&transaction.data[indexes.data]);
+		// pallet-evm takes 32-bit gas limits, above any block gas limit.
+		let gas_limit = u256(&transaction.gas_limit[indexes.gas])
+			.min(U256::from(u32::max_value()))
+			.low_u32();
+		let value = u256(&transaction.value[indexes.value]);
+		let gas_price = u256(&transaction.gas_price);
+		let nonce = Some(u256(&transaction.nonce));
+
+		// Transactions pallet-evm rejects leave the state unchanged, like
+		// invalid transactions on Ethereum.
+		let used_gas = if transaction.to.is_empty() {
+			Evm::execute_create(source, data, value, gas_limit, gas_price, nonce, true)
+				.ok()
+				.map(|(_, _, used_gas)| used_gas)
+		} else {
+			Evm::execute_call(source, h160(&transaction.to), data, value, gas_limit, gas_price, nonce, true)
+				.ok()
+				.map(|(_, _, used_gas)| used_gas)
+		};
+
+		if let Some(used_gas) = used_gas {
+			let coinbase = h160(&test.env.current_coinbase);
+			let mut account = Evm::accounts(coinbase);
+			account.balance = account.balance.saturating_add(used_gas.saturating_mul(gas_price));
+			put_account(coinbase, account);
+		}
+
+		(EthereumStateRoot::<Test>::get(), logs_hash())
+	})
+}
+
+fn account_key(address: H160) -> Vec<u8> {
+	Blake2_128Concat::hash(&address.encode())
+}
+
+fn put_account(address: H160, account: Account) {
+	put_storage_value(EVM, b"Accounts", &account_key(address), account);
+}
+
+/// Keccak hash of the RLP list of the logs emitted so far.
+fn logs_hash() -> H256 {
+	let logs = System::events().into_iter()
+		.filter_map(|record| match record.event {
+			TestEvent::pallet_evm(pallet_evm::RawEvent::Log(log)) => Some(ethereum::Log {
+				address: log.address,
+				topics: log.topics,
+				data: log.data,
+			}),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	H256::from_slice(Keccak256::digest(&rlp::encode_list(&logs)).as_slice())
+}
+
+/// Address of the account controlled by `secret_key`.
+fn address_of(secret_key: &[u8]) -> H160 {
+	let secret_key = secp256k1::SecretKey::parse_slice(secret_key).expect("fixture secret key is valid");
+	let public_key = secp256k1::PublicKey::from_secret_key(&secret_key);
+	H160::from_slice(&Keccak256::digest(&public_key.serialize()[1..])[12..])
+}
+
+fn bytes(hex: &str) -> Vec<u8> {
+	let hex = hex.trim_start_matches("0x");
+	let hex = if hex.len() % 2 == 1 { format!("0{}", hex) } else { hex.to_string() };
+	hex.from_hex().expect("fixture hex is valid")
+}
+
+fn u256(hex: &str) -> U256 {
+	U256::from_big_endian(&bytes(hex))
+}
+
+fn h256(hex: &str) -> H256 {
+	H256::from_uint(&u256(hex))
+}
+
+fn h160(hex: &str) -> H160 {
+	H160::from_slice(&bytes(hex))
+}
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_balances<T>,
+		pallet_evm<T>,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+
+/// Gas price floor of zero, as the fixtures set their own gas prices.
+pub struct ZeroGasPrice;
+impl FeeCalculator for ZeroGasPrice {
+	fn min_gas_price() -> U256 {
+		U256::zero()
+	}
+}
+
+/// Precompiles available to the tests.
+pub struct StatePrecompiles;
+
+impl pallet_evm::Precompiles for StatePrecompiles {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+	) -> Option<Result<(pallet_evm::ExitSucceed, Vec<u8>, usize), pallet_evm::ExitError>> {
+		if address == H160::from_low_u64_be(1) {
+			Some(ECRecover::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+parameter_types! {
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = ZeroGasPrice;
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = TestEvent;
+	type Precompiles = StatePrecompiles;
+}
+
+type System = frame_system::Module<Test>;
+type Balances = pallet_balances::Module<Test>;
+type Evm = pallet_evm::Module<Test>;
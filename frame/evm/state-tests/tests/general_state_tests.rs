@@ -0,0 +1,108 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runs the GeneralStateTests of a checkout of github.com/ethereum/tests,
+//! pointed to by `ETHEREUM_TESTS`, e.g.
+//!
+//! ```sh
+//! ETHEREUM_TESTS=../tests cargo test -p pallet-evm-state-tests -- --nocapture
+//! ```
+//!
+//! Nothing runs when the variable is unset.
+
+use std::{env, fs, path::Path};
+use pallet_evm_state_tests::{load, run, FORK};
+
+fn visit(dir: &Path, failures: &mut Vec<String>) {
+	let mut entries = fs::read_dir(dir).unwrap()
+		.map(|entry| entry.unwrap().path())
+		.collect::<Vec<_>>();
+	entries.sort();
+
+	for path in entries {
+		if path.is_dir() {
+			visit(&path, failures);
+		} else if path.extension().map_or(false, |extension| extension == "json") {
+			let tests = load(&fs::read_to_string(&path).unwrap())
+				.unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+			for (name, test) in tests {
+				for mismatch in run(&test, FORK) {
+					failures.push(format!(
+						"{}/{}[{}]: {} is {:?}, expected {:?}",
+						path.display(), name, mismatch.index, mismatch.what, mismatch.actual, mismatch.expected,
+					));
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn general_state_tests() {
+	let root = match env::var("ETHEREUM_TESTS") {
+		Ok(root) => root,
+		Err(_) => return,
+	};
+
+	let mut failures = Vec::new();
+	visit(&Path::new(&root).join("GeneralStateTests"), &mut failures);
+
+	assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn fixtures_parse() {
+	let tests = load(r#"{
+		"add11": {
+			"env": {
+				"currentCoinbase": "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+				"currentDifficulty": "0x020000",
+				"currentGasLimit": "0xff112233445566",
+				"currentNumber": "0x01",
+				"currentTimestamp": "0x03e8"
+			},
+			"post": {
+				"Istanbul": [{
+					"hash": "0xe8010ce590f401c9d61fef8ab05bea9bcec24281b795e5868809bc4e515aa530",
+					"indexes": { "data": 0, "gas": 0, "value": 0 },
+					"logs": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+				}]
+			},
+			"pre": {
+				"0x095e7baea6a6c7c4c2dfeb977efac326af552d87": {
+					"balance": "0x0de0b6b3a7640000",
+					"code": "0x600160010160005500",
+					"nonce": "0x00",
+					"storage": {}
+				}
+			},
+			"transaction": {
+				"data": ["0x"],
+				"gasLimit": ["0x061a80"],
+				"gasPrice": "0x01",
+				"nonce": "0x00",
+				"secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8",
+				"to": "0x095e7baea6a6c7c4c2dfeb977efac326af552d87",
+				"value": ["0x0186a0"]
+			}
+		}
+	}"#).unwrap();
+
+	let test = &tests["add11"];
+	assert_eq!(test.post[FORK][0].indexes.gas, 0);
+	assert_eq!(test.transaction.gas_limit, vec!["0x061a80".to_string()]);
+	assert!(test.pre["0x095e7baea6a6c7c4c2dfeb977efac326af552d87"].storage.is_empty());
+}
@@ -0,0 +1,184 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Account id and signature types of a runtime whose accounts are Ethereum
+//! addresses, so that extrinsics can be signed with secp256k1 keys such as
+//! those held by MetaMask.
+//!
+//! A runtime opts in with `type AccountId = AccountId20` and
+//! `type Signature = EthereumSignature`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use sp_core::{H160, ecdsa};
+use sp_io::hashing::keccak_256;
+use sp_runtime::traits::{IdentifyAccount, Lazy, Verify};
+
+/// Account id made of the 20 bytes of an Ethereum address.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Hash, serde::Serialize, serde::Deserialize))]
+pub struct AccountId20(pub [u8; 20]);
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for AccountId20 {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{:?}", H160::from(self.0))
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::str::FromStr for AccountId20 {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		<H160 as std::str::FromStr>::from_str(s.trim_start_matches("0x"))
+			.map(Into::into)
+			.map_err(|_| "invalid hex address")
+	}
+}
+
+impl From<H160> for AccountId20 {
+	fn from(address: H160) -> Self {
+		AccountId20(address.0)
+	}
+}
+
+impl From<AccountId20> for H160 {
+	fn from(account_id: AccountId20) -> Self {
+		H160(account_id.0)
+	}
+}
+
+impl AsRef<[u8]> for AccountId20 {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+/// Ethereum address of the uncompressed secp256k1 public key `pubkey`, without
+/// its leading tag byte.
+fn address_of(pubkey: &[u8]) -> AccountId20 {
+	H160::from_slice(&keccak_256(pubkey)[12..]).into()
+}
+
+/// Signer of an extrinsic, identified by its Ethereum address.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Hash, serde::Serialize, serde::Deserialize))]
+pub struct EthereumSigner([u8; 20]);
+
+impl IdentifyAccount for EthereumSigner {
+	type AccountId = AccountId20;
+
+	fn into_account(self) -> AccountId20 {
+		AccountId20(self.0)
+	}
+}
+
+impl From<[u8; 20]> for EthereumSigner {
+	fn from(address: [u8; 20]) -> Self {
+		EthereumSigner(address)
+	}
+}
+
+impl From<ecdsa::Public> for EthereumSigner {
+	fn from(public: ecdsa::Public) -> Self {
+		let pubkey = secp256k1::PublicKey::parse_slice(
+			public.as_ref(),
+			Some(secp256k1::PublicKeyFormat::Compressed),
+		).expect("ecdsa::Public is a valid compressed public key; qed");
+		EthereumSigner(address_of(&pubkey.serialize()[1..]).0)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for EthereumSigner {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{:?}", H160::from(self.0))
+	}
+}
+
+/// secp256k1 signature of the keccak-256 hash of a message, checked against
+/// the Ethereum address of the signer.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct EthereumSignature(ecdsa::Signature);
+
+impl From<ecdsa::Signature> for EthereumSignature {
+	fn from(signature: ecdsa::Signature) -> Self {
+		EthereumSignature(signature)
+	}
+}
+
+impl Verify for EthereumSignature {
+	type Signer = EthereumSigner;
+
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId20) -> bool {
+		let message = keccak_256(msg.get());
+		match sp_io::crypto::secp256k1_ecdsa_recover(self.0.as_ref(), &message) {
+			Ok(pubkey) => address_of(&pubkey[..]) == *signer,
+			Err(_) => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	/// Key of the `privateKeyToAccount` example of web3.js.
+	fn secret_key() -> secp256k1::SecretKey {
+		secp256k1::SecretKey::parse(
+			&hex!("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+		).unwrap()
+	}
+
+	const ADDRESS: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+
+	fn sign(message: &[u8]) -> EthereumSignature {
+		let message = secp256k1::Message::parse(&keccak_256(message));
+		let (signature, recovery_id) = secp256k1::sign(&message, &secret_key());
+
+		let mut raw = [0u8; 65];
+		raw[..64].copy_from_slice(&signature.serialize()[..]);
+		raw[64] = recovery_id.serialize();
+		ecdsa::Signature::from_raw(raw).into()
+	}
+
+	#[test]
+	fn signer_should_be_the_address_of_the_public_key() {
+		let public_key = secp256k1::PublicKey::from_secret_key(&secret_key());
+		let public = ecdsa::Public::from_raw(public_key.serialize_compressed());
+
+		assert_eq!(EthereumSigner::from(public).into_account(), AccountId20(ADDRESS));
+	}
+
+	#[test]
+	fn signature_should_verify_against_the_signer_address() {
+		let signature = sign(b"frontier");
+
+		assert!(signature.verify(&b"frontier"[..], &AccountId20(ADDRESS)));
+	}
+
+	#[test]
+	fn signature_should_not_verify_another_message_or_signer() {
+		let signature = sign(b"frontier");
+
+		assert!(!signature.verify(&b"substrate"[..], &AccountId20(ADDRESS)));
+		assert!(!signature.verify(&b"frontier"[..], &AccountId20([1; 20])));
+	}
+}
@@ -0,0 +1,98 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Digest log deposited by the Ethereum pallet at the end of each block, which
+//! lets the client map Ethereum hashes to Substrate blocks without executing
+//! runtime calls.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use sp_core::H256;
+use sp_runtime::{ConsensusEngineId, generic::{Digest, OpaqueDigestItemId}};
+use sp_std::vec::Vec;
+
+/// Engine id of the Frontier consensus log.
+pub const FRONTIER_ENGINE_ID: ConsensusEngineId = [b'f', b'r', b'o', b'n'];
+
+#[derive(Clone, PartialEq, Eq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ConsensusLog {
+	/// The Ethereum block built at the end of this block.
+	#[codec(index = "1")]
+	EndBlock {
+		/// Ethereum block hash.
+		block_hash: H256,
+		/// Ethereum transaction hashes, in block order.
+		transaction_hashes: Vec<H256>,
+	},
+	/// The Ethereum block built at the end of this block, with the number of
+	/// logs of each of its transactions.
+	#[codec(index = "2")]
+	EndBlockWithLogs {
+		/// Ethereum block hash.
+		block_hash: H256,
+		/// Ethereum transaction hashes, in block order.
+		transaction_hashes: Vec<H256>,
+		/// Number of logs of each transaction, in block order.
+		log_counts: Vec<u32>,
+	},
+}
+
+impl ConsensusLog {
+	/// Hash of the Ethereum block.
+	pub fn block_hash(&self) -> H256 {
+		match self {
+			ConsensusLog::EndBlock { block_hash, .. } |
+			ConsensusLog::EndBlockWithLogs { block_hash, .. } => *block_hash,
+		}
+	}
+
+	/// Hashes of the Ethereum transactions, in block order.
+	pub fn transaction_hashes(&self) -> &[H256] {
+		match self {
+			ConsensusLog::EndBlock { transaction_hashes, .. } |
+			ConsensusLog::EndBlockWithLogs { transaction_hashes, .. } => transaction_hashes,
+		}
+	}
+
+	/// Number of logs of each transaction, unless the log predates them.
+	pub fn log_counts(&self) -> Option<&[u32]> {
+		match self {
+			ConsensusLog::EndBlock { .. } => None,
+			ConsensusLog::EndBlockWithLogs { log_counts, .. } => Some(log_counts),
+		}
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum FindLogError {
+	NotFound,
+	MultipleLogs,
+}
+
+/// Find the Frontier consensus log in `digest`, which must contain exactly one.
+pub fn find_log<Hash: Encode + Decode>(digest: &Digest<Hash>) -> Result<ConsensusLog, FindLogError> {
+	let mut found = None;
+	for log in digest.logs() {
+		let log = log.try_to::<ConsensusLog>(OpaqueDigestItemId::Consensus(&FRONTIER_ENGINE_ID));
+		match (log, found.is_some()) {
+			(Some(_), true) => return Err(FindLogError::MultipleLogs),
+			(Some(log), false) => found = Some(log),
+			(None, _) => (),
+		}
+	}
+	found.ok_or(FindLogError::NotFound)
+}
@@ -0,0 +1,36 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Account mapping rpc interface.
+use ethereum_types::H160;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_AccountApi::gen_server::AccountApi as AccountApiServer;
+
+/// Account mapping rpc interface, relating Substrate accounts and EVM
+/// addresses the way the runtime does.
+#[rpc(server)]
+pub trait AccountApi<AccountId> {
+	/// Returns the Substrate account controlling an EVM address, or null if the
+	/// runtime cannot tell, as for addresses derived by hashing an account id.
+	#[rpc(name = "frontier_accountId")]
+	fn account_id(&self, _: H160) -> Result<Option<AccountId>>;
+
+	/// Returns the EVM address of a Substrate account.
+	#[rpc(name = "frontier_evmAddress")]
+	fn evm_address(&self, _: AccountId) -> Result<H160>;
+}
@@ -0,0 +1,35 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Admin rpc interface.
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{NodeInfo, PeerInfo};
+
+pub use rpc_impl_AdminApi::gen_server::AdminApi as AdminApiServer;
+
+/// Admin rpc interface, describing the node and its peers.
+#[rpc(server)]
+pub trait AdminApi {
+	/// Returns the peers the node is connected to.
+	#[rpc(name = "admin_peers")]
+	fn peers(&self) -> Result<Vec<PeerInfo>>;
+
+	/// Returns the identity and listen address of the node.
+	#[rpc(name = "admin_nodeInfo")]
+	fn node_info(&self) -> Result<NodeInfo>;
+}
@@ -0,0 +1,50 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Development chain rpc interface, compatible with Hardhat and Anvil.
+use ethereum_types::U256;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_DevApi::gen_server::DevApi as DevApiServer;
+
+/// Development chain rpc interface.
+#[rpc(server)]
+pub trait DevApi {
+	/// Moves the time of the next blocks forward by `seconds`. Returns the total
+	/// time added so far, in seconds.
+	#[rpc(name = "evm_increaseTime")]
+	fn increase_time(&self, seconds: u64) -> Result<u64>;
+
+	/// Sets the timestamp, in seconds, of the next block.
+	#[rpc(name = "evm_setNextBlockTimestamp")]
+	fn set_next_block_timestamp(&self, timestamp: u64) -> Result<()>;
+
+	/// Authors a new block.
+	#[rpc(name = "evm_mine")]
+	fn mine(&self) -> BoxFuture<String>;
+
+	/// Records the current chain state and returns its snapshot id.
+	#[rpc(name = "evm_snapshot")]
+	fn snapshot(&self) -> Result<U256>;
+
+	/// Reverts the chain to the snapshot `id`, which is consumed along with any
+	/// later snapshot. Returns whether the snapshot existed. Unsafe: it is
+	/// denied unless the node allows unsafe rpc methods.
+	#[rpc(name = "evm_revert")]
+	fn revert(&self, id: U256) -> Result<bool>;
+}
@@ -16,13 +16,15 @@
 
 //! Eth rpc interface.
 
+use std::collections::BTreeMap;
 use ethereum_types::{H160, H256, H64, U256, U64};
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 use crate::types::{
-	BlockNumber, Bytes, CallRequest, EthAccount, Filter, FilterChanges, Index, Log, Receipt,
-	RichBlock, SyncStatus, Transaction, Work,
+	AccountState, BlockNumber, Bytes, CallRequest, CallStateOverride, CallBlockOverride, EthAccount,
+	Filter, FilterChanges, Index, Log, Receipt, RichBlock, RichHeader, SimulatePayload,
+	SimulatedBlock, SyncStatus, Transaction, Work,
 };
 pub use rpc_impl_EthApi::gen_server::EthApi as EthApiServer;
 
@@ -59,6 +61,11 @@ pub trait EthApi {
 	#[rpc(name = "eth_gasPrice")]
 	fn gas_price(&self) -> Result<U256>;
 
+	/// Returns the base fee per blob gas. Blob transactions are not supported,
+	/// so this is the minimum blob base fee of EIP-4844.
+	#[rpc(name = "eth_blobBaseFee")]
+	fn blob_base_fee(&self) -> Result<U256>;
+
 	/// Returns accounts list.
 	#[rpc(name = "eth_accounts")]
 	fn accounts(&self) -> Result<Vec<H160>>;
@@ -69,15 +76,20 @@ pub trait EthApi {
 
 	/// Returns balance of the given account.
 	#[rpc(name = "eth_getBalance")]
-	fn balance(&self, _: H160, _: Option<BlockNumber>) -> Result<U256>;
+	fn balance(&self, _: H160, _: Option<BlockNumber>) -> BoxFuture<U256>;
 
 	/// Returns the account- and storage-values of the specified account including the Merkle-proof
 	#[rpc(name = "eth_getProof")]
 	fn proof(&self, _: H160, _: Vec<H256>, _: Option<BlockNumber>) -> BoxFuture<EthAccount>;
 
+	/// Returns the balance, nonce, code hash and storage root of an account.
+	/// `pending` reads the latest block.
+	#[rpc(name = "eth_getAccount")]
+	fn account(&self, _: H160, _: Option<BlockNumber>) -> Result<AccountState>;
+
 	/// Returns content of the storage at given address.
 	#[rpc(name = "eth_getStorageAt")]
-	fn storage_at(&self, _: H160, _: U256, _: Option<BlockNumber>) -> Result<H256>;
+	fn storage_at(&self, _: H160, _: U256, _: Option<BlockNumber>) -> BoxFuture<H256>;
 
 	/// Returns block with given hash.
 	#[rpc(name = "eth_getBlockByHash")]
@@ -87,6 +99,14 @@ pub trait EthApi {
 	#[rpc(name = "eth_getBlockByNumber")]
 	fn block_by_number(&self, _: BlockNumber, _: bool) -> Result<Option<RichBlock>>;
 
+	/// Returns the header of the block with given hash, without its transactions.
+	#[rpc(name = "eth_getHeaderByHash")]
+	fn header_by_hash(&self, _: H256) -> Result<Option<RichHeader>>;
+
+	/// Returns the header of the block with given number, without its transactions.
+	#[rpc(name = "eth_getHeaderByNumber")]
+	fn header_by_number(&self, _: BlockNumber) -> Result<Option<RichHeader>>;
+
 	/// Returns the number of transactions sent from given address at given time (block number).
 	#[rpc(name = "eth_getTransactionCount")]
 	fn transaction_count(&self, _: H160, _: Option<BlockNumber>) -> Result<U256>;
@@ -109,7 +129,7 @@ pub trait EthApi {
 
 	/// Returns the code at given address at given time (block number).
 	#[rpc(name = "eth_getCode")]
-	fn code_at(&self, _: H160, _: Option<BlockNumber>) -> Result<Bytes>;
+	fn code_at(&self, _: H160, _: Option<BlockNumber>) -> BoxFuture<Bytes>;
 
 	/// Sends signed transaction, returning its hash.
 	#[rpc(name = "eth_sendRawTransaction")]
@@ -119,18 +139,37 @@ pub trait EthApi {
 	#[rpc(name = "eth_submitTransaction")]
 	fn submit_transaction(&self, _: Bytes) -> Result<H256>;
 
-	/// Call contract, returning the output data.
+	/// Call contract, returning the output data. The optional state override set
+	/// and block override are applied for the duration of the call only.
 	#[rpc(name = "eth_call")]
-	fn call(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<Bytes>;
+	fn call(
+		&self,
+		_: CallRequest,
+		_: Option<BlockNumber>,
+		_: Option<BTreeMap<H160, CallStateOverride>>,
+		_: Option<CallBlockOverride>,
+	) -> BoxFuture<Bytes>;
+
+	/// Simulate blocks of calls on top of the given block, each block and call
+	/// seeing the state changes of the previous ones, returning the output,
+	/// gas used and logs of each call. Simulated blocks only report their
+	/// number and gas used, and `validation` and `traceTransfers` are rejected.
+	#[rpc(name = "eth_simulateV1")]
+	fn simulate(&self, _: SimulatePayload, _: Option<BlockNumber>) -> BoxFuture<Vec<SimulatedBlock>>;
 
 	/// Estimate gas needed for execution of given contract.
 	#[rpc(name = "eth_estimateGas")]
-	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<U256>;
+	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<U256>;
 
 	/// Get transaction by its hash.
 	#[rpc(name = "eth_getTransactionByHash")]
 	fn transaction_by_hash(&self, _: H256) -> Result<Option<Transaction>>;
 
+	/// Returns the Ethereum transactions ready in the pool of this node,
+	/// optionally only those sent by the given address.
+	#[rpc(name = "eth_pendingTransactions")]
+	fn pending_transactions(&self, _: Option<H160>) -> Result<Vec<Transaction>>;
+
 	/// Returns transaction at given block hash and index.
 	#[rpc(name = "eth_getTransactionByBlockHashAndIndex")]
 	fn transaction_by_block_hash_and_index(
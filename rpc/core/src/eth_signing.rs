@@ -16,11 +16,13 @@
 
 //! Eth rpc interface.
 
-use jsonrpc_core::BoxFuture;
+use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 use ethereum_types::{H160, H256, H520};
-use crate::types::{Bytes, TransactionRequest, RichRawTransaction};
+use crate::types::{Bytes, TransactionRequest, RichRawTransaction, TypedData};
+
+pub use rpc_impl_EthSignApi::gen_server::EthSignApi as EthSignApiServer;
 
 /// Signing methods implementation relying on unlocked accounts.
 #[rpc(server)]
@@ -44,3 +46,13 @@ pub trait EthSigningApi {
 	#[rpc(meta, name = "eth_signTransaction")]
 	fn sign_transaction(&self, _: Self::Metadata, _: TransactionRequest) -> BoxFuture<RichRawTransaction>;
 }
+
+/// Signing methods implementation relying on accounts whose keys the node
+/// manages.
+#[rpc(server)]
+pub trait EthSignApi {
+	/// Signs EIP-712 typed data with the key of the given address. Returns the
+	/// 65 bytes `r ‖ s ‖ v` signature.
+	#[rpc(name = "eth_signTypedData_v4")]
+	fn sign_typed_data_v4(&self, _: H160, _: TypedData) -> Result<Bytes>;
+}
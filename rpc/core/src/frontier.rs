@@ -0,0 +1,75 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Frontier rpc interface, relating Ethereum hashes to Substrate blocks and
+//! extrinsics.
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{
+	BlockNumber, BlockWithReceipts, ContractCreation, Filter, LogsPage, SubstrateExtrinsic,
+	TransactionFinality,
+};
+
+pub use rpc_impl_FrontierApi::gen_server::FrontierApi as FrontierApiServer;
+
+/// Frontier rpc interface.
+#[rpc(server)]
+pub trait FrontierApi {
+	/// Returns the hash of the Substrate block containing an Ethereum block, or
+	/// null if that block is not canonical.
+	#[rpc(name = "frontier_getSubstrateHash")]
+	fn substrate_hash(&self, _: H256) -> Result<Option<H256>>;
+
+	/// Returns the Ethereum block hash of a Substrate block.
+	#[rpc(name = "frontier_getEthereumHash")]
+	fn ethereum_hash(&self, _: H256) -> Result<Option<H256>>;
+
+	/// Returns the Substrate extrinsic of an Ethereum transaction, or null if
+	/// its block is not canonical.
+	#[rpc(name = "frontier_getSubstrateExtrinsic")]
+	fn substrate_extrinsic(&self, _: H256) -> Result<Option<SubstrateExtrinsic>>;
+
+	/// Returns the Ethereum transaction hash of an extrinsic, given the
+	/// Substrate block hash and the extrinsic index.
+	#[rpc(name = "frontier_getEthereumTransactionHash")]
+	fn ethereum_transaction_hash(&self, _: H256, _: U256) -> Result<Option<H256>>;
+
+	/// Returns the blocks from `from` to `to` inclusive, with full transactions
+	/// and, if requested, their receipts.
+	#[rpc(name = "frontier_getBlockRange")]
+	fn block_range(&self, _: BlockNumber, _: BlockNumber, _: bool) -> Result<Vec<BlockWithReceipts>>;
+
+	/// Returns the Ethereum transaction that created a contract, with its init
+	/// code, or null if the contract was not created by a transaction. Contracts
+	/// created by another contract through CREATE or CREATE2 are not recorded,
+	/// and return null too.
+	#[rpc(name = "frontier_getContractCreation")]
+	fn contract_creation(&self, _: H160) -> Result<Option<ContractCreation>>;
+
+	/// Returns whether the block of an Ethereum transaction is finalized, and
+	/// its number of confirmations, or null if the transaction is not in a
+	/// canonical block.
+	#[rpc(name = "frontier_getTransactionFinality")]
+	fn transaction_finality(&self, _: H256) -> Result<Option<TransactionFinality>>;
+
+	/// Returns a page of the logs matching a filter, from the block of the
+	/// cursor of the previous page if given, and the cursor of the next page.
+	/// The filter limit sets the number of logs after which a page ends.
+	#[rpc(name = "frontier_getLogsPaginated")]
+	fn logs_paginated(&self, _: Filter, _: Option<U256>) -> Result<LogsPage>;
+}
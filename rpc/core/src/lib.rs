@@ -16,14 +16,28 @@
 
 pub mod types;
 
+mod account;
+mod admin;
+mod dev;
 mod eth;
 mod eth_pubsub;
 mod eth_signing;
+mod frontier;
+mod modules;
 mod net;
+mod personal;
+mod trace;
 mod web3;
 
+pub use account::{AccountApi, AccountApiServer};
+pub use admin::{AdminApi, AdminApiServer};
+pub use dev::{DevApi, DevApiServer};
 pub use eth::{EthApi, EthApiServer, EthFilterApi};
-pub use eth_pubsub::EthPubSubApi;
-pub use eth_signing::EthSigningApi;
+pub use eth_pubsub::{EthPubSubApi, EthPubSubApiServer};
+pub use eth_signing::{EthSigningApi, EthSignApi, EthSignApiServer};
+pub use frontier::{FrontierApi, FrontierApiServer};
+pub use modules::{RpcModulesApi, RpcModulesApiServer};
 pub use net::NetApi;
+pub use personal::{PersonalApi, PersonalApiServer};
+pub use trace::{TraceApi, TraceApiServer};
 pub use web3::Web3Api;
@@ -0,0 +1,30 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rpc module discovery interface.
+use std::collections::BTreeMap;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_RpcModulesApi::gen_server::RpcModulesApi as RpcModulesApiServer;
+
+/// Rpc module discovery interface.
+#[rpc(server)]
+pub trait RpcModulesApi {
+	/// Returns the rpc namespaces the node serves, with their versions.
+	#[rpc(name = "rpc_modules")]
+	fn modules(&self) -> Result<BTreeMap<String, String>>;
+}
@@ -0,0 +1,48 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Personal rpc interface, for development nodes.
+use ethereum_types::{H160, H256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::{Bytes, TransactionRequest};
+
+pub use rpc_impl_PersonalApi::gen_server::PersonalApi as PersonalApiServer;
+
+/// Personal rpc interface, managing password protected accounts in the node.
+#[rpc(server)]
+pub trait PersonalApi {
+	/// Creates an account protected by `password`. Returns its address.
+	#[rpc(name = "personal_newAccount")]
+	fn new_account(&self, password: String) -> Result<H160>;
+
+	/// Imports the hex encoded private key of an account, protected by
+	/// `password`. Returns its address.
+	#[rpc(name = "personal_importRawKey")]
+	fn import_raw_key(&self, key: String, password: String) -> Result<H160>;
+
+	/// Signs a transaction from an account with its password, filling the
+	/// missing nonce, gas price and gas, and sends it. Returns its hash.
+	#[rpc(name = "personal_sendTransaction")]
+	fn send_transaction(&self, request: TransactionRequest, password: String) -> BoxFuture<H256>;
+
+	/// Signs `data` prefixed with `\x19Ethereum Signed Message:\n<length>`,
+	/// with the key of an account and its password. Returns the 65 bytes
+	/// `r ‖ s ‖ v` signature.
+	#[rpc(name = "personal_sign")]
+	fn sign(&self, data: Bytes, address: H160, password: String) -> Result<Bytes>;
+}
@@ -0,0 +1,82 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Trace rpc interface.
+use jsonrpc_core::BoxFuture;
+use jsonrpc_derive::rpc;
+
+use crate::types::{
+	AccountRange, BlockNumber, Bytes, CallBlockOverride, CallFrame, CallRequest, TraceCallConfig,
+	TraceResults, TraceType,
+};
+
+pub use rpc_impl_TraceApi::gen_server::TraceApi as TraceApiServer;
+
+/// Trace rpc interface.
+#[rpc(server)]
+pub trait TraceApi {
+	/// Executes a call on the given block, with the optional block override,
+	/// and returns the requested traces. The `vmTrace` type is not supported.
+	#[rpc(name = "trace_call")]
+	fn call(
+		&self,
+		_: CallRequest,
+		_: Vec<TraceType>,
+		_: Option<BlockNumber>,
+		_: Option<CallBlockOverride>,
+	) -> BoxFuture<TraceResults>;
+
+	/// Executes several calls in sequence on the given block, with the optional
+	/// block override, each one seeing the state changes of the previous ones,
+	/// and returns the requested traces.
+	#[rpc(name = "trace_callMany")]
+	fn call_many(
+		&self,
+		_: Vec<(CallRequest, Vec<TraceType>)>,
+		_: Option<BlockNumber>,
+		_: Option<CallBlockOverride>,
+	) -> BoxFuture<Vec<TraceResults>>;
+
+	/// Executes a call on the given block, with the block override of the
+	/// config, and returns its frame in the format of the geth call tracer. The
+	/// config must select the `callTracer`.
+	#[rpc(name = "debug_traceCall")]
+	fn debug_call(
+		&self,
+		_: CallRequest,
+		_: Option<BlockNumber>,
+		_: Option<TraceCallConfig>,
+	) -> BoxFuture<CallFrame>;
+
+	/// Executes a bundle of calls in sequence on the given block, with the
+	/// optional block override, each one seeing the state changes of the
+	/// previous ones, and returns the frame of each call in the format of the
+	/// geth call tracer.
+	#[rpc(name = "debug_traceCallMany")]
+	fn debug_call_many(
+		&self,
+		_: Vec<CallRequest>,
+		_: Option<BlockNumber>,
+		_: Option<CallBlockOverride>,
+	) -> BoxFuture<Vec<CallFrame>>;
+
+	/// Lists up to the given number of EVM accounts of the given block, from
+	/// the given key on, in the order of their keys. The `next` key of the
+	/// result starts the following page.
+	#[rpc(name = "debug_accountRange")]
+	fn account_range(&self, _: Option<BlockNumber>, _: Bytes, _: u32) -> BoxFuture<AccountRange>;
+}
@@ -49,6 +49,37 @@ pub struct EthAccount {
 	pub storage_proof: Vec<StorageProof>,
 }
 
+/// Account state without proofs (used by `eth_getAccount`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+	pub code_hash: H256,
+	pub storage_root: H256,
+	pub balance: U256,
+	pub nonce: U256,
+}
+
+/// Account listed by `debug_accountRange`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRangeEntry {
+	pub address: H160,
+	pub balance: U256,
+	pub nonce: U256,
+	pub code_hash: H256,
+	/// Key of the account, ordering the listing.
+	pub key: Bytes,
+}
+
+/// Page of accounts (used by `debug_accountRange`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRange {
+	pub accounts: Vec<AccountRangeEntry>,
+	/// Key to start the next page from, `None` after the last account.
+	pub next: Option<Bytes>,
+}
+
 /// Extended account information (used by `parity_allAccountInfo`).
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct ExtAccountInfo {
@@ -0,0 +1,99 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Serialize;
+use ethereum_types::{H256, U256};
+
+/// Peer returned by `admin_peers`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerInfo {
+	/// Address the peer is reached at, ending with its peer id
+	pub enode: String,
+	/// Peer id
+	pub id: String,
+	/// Client the peer identifies as
+	pub name: String,
+	/// Protocols of the peer
+	pub caps: Vec<String>,
+	/// Connection to the peer
+	pub network: PeerNetworkInfo,
+}
+
+/// Connection to a peer.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerNetworkInfo {
+	/// Local end of the connection, if known
+	pub local_address: Option<String>,
+	/// Remote end of the connection
+	pub remote_address: String,
+	/// Whether the peer dialed the node
+	pub inbound: bool,
+	/// Whether the peer may connect beyond the peer limit
+	pub trusted: bool,
+	/// Whether the node keeps reconnecting to the peer
+	#[serde(rename = "static")]
+	pub is_static: bool,
+}
+
+/// Node description returned by `admin_nodeInfo`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+	/// Listen address of the node, ending with its peer id
+	pub enode: String,
+	/// Peer id
+	pub id: String,
+	/// Client name and version
+	pub name: String,
+	/// Listen ip
+	pub ip: String,
+	/// Listen ports
+	pub ports: NodePorts,
+	/// Listen ip and port
+	pub listen_addr: String,
+	/// Protocols of the node
+	pub protocols: NodeProtocols,
+}
+
+/// Listen ports of a node.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodePorts {
+	/// Discovery port, the listen port as discovery shares the connections
+	pub discovery: u16,
+	/// Listen port
+	pub listener: u16,
+}
+
+/// Protocols of a node.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeProtocols {
+	/// Ethereum chain of the node
+	pub eth: EthProtocolInfo,
+}
+
+/// Ethereum chain of a node.
+#[derive(Debug, Clone, Serialize)]
+pub struct EthProtocolInfo {
+	/// Chain id
+	pub network: u64,
+	/// Total difficulty, always zero
+	pub difficulty: U256,
+	/// Hash of the genesis Ethereum block
+	pub genesis: Option<H256>,
+	/// Hash of the best Ethereum block
+	pub head: Option<H256>,
+}
@@ -20,7 +20,7 @@ use std::collections::BTreeMap;
 use ethereum_types::{H160, H256, U256, Bloom as H2048};
 use serde::ser::Error;
 use serde::{Serialize, Serializer};
-use crate::types::{Bytes, Transaction};
+use crate::types::{Bytes, Receipt, Transaction};
 
 /// Block Transactions
 #[derive(Debug)]
@@ -132,6 +132,17 @@ pub struct Header {
 /// Block representation with additional info.
 pub type RichBlock = Rich<Block>;
 
+/// Block returned by a block range query, with its receipts when requested
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockWithReceipts {
+	/// Block, with full transactions
+	pub block: RichBlock,
+	/// Receipts of the block's transactions, in order
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub receipts: Option<Vec<Receipt>>,
+}
+
 /// Header representation with additional info.
 pub type RichHeader = Rich<Header>;
 
@@ -14,14 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::{collections::BTreeMap, convert::TryFrom};
 use serde::Deserialize;
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use crate::types::Bytes;
 
-/// Call request
-#[derive(Debug, Default, PartialEq, Deserialize)]
+/// Access list entry (EIP-2930)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	/// Accessed address
+	pub address: H160,
+	/// Accessed storage keys of the address
+	pub storage_keys: Vec<H256>,
+}
+
+/// Call request
+///
+/// `input` is accepted as an alias of `data`, as sent by recent libraries. A
+/// request setting both to different values is rejected.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(try_from = "CallRequestFields")]
 pub struct CallRequest {
 	/// From
 	pub from: Option<H160>,
@@ -29,6 +43,10 @@ pub struct CallRequest {
 	pub to: Option<H160>,
 	/// Gas Price
 	pub gas_price: Option<U256>,
+	/// Max fee per gas (EIP-1559)
+	pub max_fee_per_gas: Option<U256>,
+	/// Max priority fee per gas (EIP-1559)
+	pub max_priority_fee_per_gas: Option<U256>,
 	/// Gas
 	pub gas: Option<U256>,
 	/// Value
@@ -37,4 +55,128 @@ pub struct CallRequest {
 	pub data: Option<Bytes>,
 	/// Nonce
 	pub nonce: Option<U256>,
+	/// Access list (EIP-2930). Accepted for compatibility, it does not change
+	/// execution under the Istanbul rules of the EVM.
+	pub access_list: Option<Vec<AccessListItem>>,
+}
+
+/// Fields of a call request as sent.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+struct CallRequestFields {
+	from: Option<H160>,
+	to: Option<H160>,
+	gas_price: Option<U256>,
+	max_fee_per_gas: Option<U256>,
+	max_priority_fee_per_gas: Option<U256>,
+	gas: Option<U256>,
+	value: Option<U256>,
+	data: Option<Bytes>,
+	input: Option<Bytes>,
+	nonce: Option<U256>,
+	access_list: Option<Vec<AccessListItem>>,
+}
+
+impl TryFrom<CallRequestFields> for CallRequest {
+	type Error = String;
+
+	fn try_from(fields: CallRequestFields) -> Result<Self, Self::Error> {
+		let data = match (fields.data, fields.input) {
+			(Some(data), Some(input)) if data != input =>
+				return Err("data and input are both set and differ".to_string()),
+			(data, input) => input.or(data),
+		};
+		Ok(CallRequest {
+			from: fields.from,
+			to: fields.to,
+			gas_price: fields.gas_price,
+			max_fee_per_gas: fields.max_fee_per_gas,
+			max_priority_fee_per_gas: fields.max_priority_fee_per_gas,
+			gas: fields.gas,
+			value: fields.value,
+			data,
+			nonce: fields.nonce,
+			access_list: fields.access_list,
+		})
+	}
+}
+
+/// Per-account state override used by `eth_call` (geth-compatible).
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct CallStateOverride {
+	/// Fake balance to set for the account before executing the call.
+	pub balance: Option<U256>,
+	/// Fake nonce to set for the account before executing the call.
+	pub nonce: Option<U256>,
+	/// Fake EVM bytecode to inject into the account before executing the call.
+	pub code: Option<Bytes>,
+	/// Fake key-value mapping to override all slots in the account storage before
+	/// executing the call.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Fake key-value mapping to override individual slots in the account storage before
+	/// executing the call.
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Block context override used by `eth_call` (geth-compatible).
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct CallBlockOverride {
+	/// Block number
+	pub number: Option<U256>,
+	/// Block timestamp, in seconds
+	pub time: Option<U256>,
+	/// Block gas limit, rejected: the `GASLIMIT` of pallet-evm is fixed
+	pub gas_limit: Option<U256>,
+	/// Block author, rejected: the `COINBASE` of pallet-evm is fixed
+	pub coinbase: Option<H160>,
+	/// Block randomness (PREVRANDAO), rejected: the `DIFFICULTY` of
+	/// pallet-evm is fixed
+	pub random: Option<H256>,
+	/// Block base fee, which gas prices are checked against
+	pub base_fee: Option<U256>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	#[test]
+	fn input_is_an_alias_of_data() {
+		let request: CallRequest = serde_json::from_str(r#"{"input":"0x12"}"#).unwrap();
+		assert_eq!(request.data, Some(Bytes(vec![0x12])));
+
+		let request: CallRequest = serde_json::from_str(r#"{"data":"0x12","input":"0x12"}"#).unwrap();
+		assert_eq!(request.data, Some(Bytes(vec![0x12])));
+
+		assert!(serde_json::from_str::<CallRequest>(r#"{"data":"0x12","input":"0x34"}"#).is_err());
+	}
+
+	#[test]
+	fn deserializes_fee_market_fields() {
+		let request: CallRequest = serde_json::from_str(r#"{
+			"to": "0x0000000000000000000000000000000000000001",
+			"nonce": "0x1",
+			"maxFeePerGas": "0x10",
+			"maxPriorityFeePerGas": "0x2",
+			"accessList": [{
+				"address": "0x0000000000000000000000000000000000000002",
+				"storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000003"]
+			}]
+		}"#).unwrap();
+
+		assert_eq!(request.nonce, Some(U256::from(1)));
+		assert_eq!(request.max_fee_per_gas, Some(U256::from(0x10)));
+		assert_eq!(request.max_priority_fee_per_gas, Some(U256::from(2)));
+		assert_eq!(request.access_list, Some(vec![AccessListItem {
+			address: H160::from_low_u64_be(2),
+			storage_keys: vec![H256::from_low_u64_be(3)],
+		}]));
+		assert!(serde_json::from_str::<CallRequest>(r#"{"unknown":"0x1"}"#).is_err());
+	}
 }
@@ -47,6 +47,18 @@ impl<'a, T> Deserialize<'a> for VariadicValue<T> where T: DeserializeOwned {
 	}
 }
 
+impl<T> VariadicValue<T> where T: DeserializeOwned + PartialEq {
+	/// Whether `value` is accepted. `Null` and an empty list act as wildcards,
+	/// a list matches if any of its entries does.
+	pub fn matches(&self, value: &T) -> bool {
+		match self {
+			VariadicValue::Null => true,
+			VariadicValue::Single(expected) => expected == value,
+			VariadicValue::Multiple(expected) => expected.is_empty() || expected.contains(value),
+		}
+	}
+}
+
 /// Filter Address
 pub type FilterAddress = VariadicValue<H160>;
 /// Topic
@@ -71,6 +83,24 @@ pub struct Filter {
 	pub limit: Option<usize>,
 }
 
+impl Filter {
+	/// Whether a log emitted by `address` with `topics` matches this filter.
+	///
+	/// Topics are matched by position. A filter with more topic positions than
+	/// the log has never matches, even if the extra positions are wildcards.
+	pub fn matches(&self, address: &H160, topics: &[H256]) -> bool {
+		let address_matches = self.address.as_ref()
+			.map_or(true, |filter_address| filter_address.matches(address));
+		let topics_match = self.topics.as_ref()
+			.map_or(true, |filter_topics| {
+				filter_topics.len() <= topics.len() &&
+					filter_topics.iter().zip(topics).all(|(filter_topic, topic)| filter_topic.matches(topic))
+			});
+
+		address_matches && topics_match
+	}
+}
+
 /// Results of the filter_changes RPC.
 #[derive(Debug, PartialEq)]
 pub enum FilterChanges {
@@ -91,3 +121,52 @@ impl Serialize for FilterChanges {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	fn filter(json: &str) -> Filter {
+		serde_json::from_str(json).unwrap()
+	}
+
+	#[test]
+	fn filter_address_matching() {
+		let address = H160::from_low_u64_be(1);
+		let other = H160::from_low_u64_be(2);
+
+		let single = filter(r#"{"address":"0x0000000000000000000000000000000000000001"}"#);
+		assert!(single.matches(&address, &[]));
+		assert!(!single.matches(&other, &[]));
+
+		let multiple = filter(r#"{"address":["0x0000000000000000000000000000000000000002","0x0000000000000000000000000000000000000001"]}"#);
+		assert!(multiple.matches(&address, &[]));
+		assert!(multiple.matches(&other, &[]));
+
+		let any = filter(r#"{"address":null}"#);
+		assert!(any.matches(&address, &[]));
+	}
+
+	#[test]
+	fn filter_topics_matching() {
+		let address = H160::zero();
+		let a = H256::from_low_u64_be(0xa);
+		let b = H256::from_low_u64_be(0xb);
+		let c = H256::from_low_u64_be(0xc);
+
+		let wildcard_then_or = filter(r#"{"topics":[null,["0x000000000000000000000000000000000000000000000000000000000000000b","0x000000000000000000000000000000000000000000000000000000000000000c"]]}"#);
+		assert!(wildcard_then_or.matches(&address, &[a, b]));
+		assert!(wildcard_then_or.matches(&address, &[c, c]));
+		assert!(!wildcard_then_or.matches(&address, &[b, a]));
+		// More filter positions than log topics never matches.
+		assert!(!wildcard_then_or.matches(&address, &[a]));
+
+		let single = filter(r#"{"topics":["0x000000000000000000000000000000000000000000000000000000000000000a"]}"#);
+		assert!(single.matches(&address, &[a, b, c]));
+		assert!(!single.matches(&address, &[b]));
+
+		let empty = filter(r#"{"topics":[]}"#);
+		assert!(empty.matches(&address, &[]));
+	}
+}
@@ -0,0 +1,32 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::Serialize;
+use ethereum_types::{H256, U256};
+
+/// Finality of the block including a transaction.
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFinality {
+	/// Substrate hash of the block including the transaction
+	pub block_hash: H256,
+	/// Number of the block including the transaction
+	pub block_number: U256,
+	/// Number of blocks of the best chain from the including block, itself included
+	pub confirmations: U256,
+	/// Whether the including block is finalized
+	pub finalized: bool,
+}
@@ -17,36 +17,60 @@
 //! RPC types
 
 mod account_info;
+mod admin;
 mod block;
 mod block_number;
 mod bytes;
 mod call_request;
+mod contract_creation;
 mod filter;
+mod finality;
 mod index;
 mod log;
+mod logs_page;
 mod receipt;
+mod simulate;
+mod substrate;
 mod sync;
+mod trace;
 mod transaction;
 mod transaction_request;
 mod transaction_condition;
+mod typed_data;
 mod work;
 
 pub mod pubsub;
 
-pub use self::account_info::{AccountInfo, ExtAccountInfo, EthAccount, StorageProof, RecoveredAccount};
+pub use self::account_info::{
+	AccountInfo, AccountState, AccountRange, AccountRangeEntry, ExtAccountInfo, EthAccount,
+	StorageProof, RecoveredAccount,
+};
+pub use self::admin::{PeerInfo, PeerNetworkInfo, NodeInfo, NodePorts, NodeProtocols, EthProtocolInfo};
 pub use self::bytes::Bytes;
-pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
+pub use self::block::{RichBlock, Block, BlockTransactions, BlockWithReceipts, Header, RichHeader, Rich};
 pub use self::block_number::BlockNumber;
-pub use self::call_request::CallRequest;
-pub use self::filter::{Filter, FilterChanges};
+pub use self::call_request::{AccessListItem, CallRequest, CallStateOverride, CallBlockOverride};
+pub use self::contract_creation::ContractCreation;
+pub use self::filter::{Filter, FilterChanges, VariadicValue};
+pub use self::finality::TransactionFinality;
 pub use self::index::Index;
 pub use self::log::Log;
+pub use self::logs_page::LogsPage;
 pub use self::receipt::Receipt;
+pub use self::simulate::{
+	SimulatePayload, SimulateBlock, SimulatedBlock, SimulatedCall, SimulatedCallError,
+};
+pub use self::substrate::SubstrateExtrinsic;
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
 };
+pub use self::trace::{
+	TraceType, TraceResults, Trace, Action, ActionResult, CallAction, CallResult, CreateAction,
+	CreateResult, CallFrame, TraceCallConfig, StateDiff, AccountDiff, Diff, ChangedType,
+};
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
 pub use self::transaction_condition::TransactionCondition;
+pub use self::typed_data::{TypedData, TypedDataField};
 pub use self::work::Work;
@@ -20,7 +20,7 @@ use ethereum_types::H256;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use crate::types::{RichHeader, Filter, Log};
+use crate::types::{RichHeader, Filter, Log, Transaction};
 
 /// Subscription result.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -31,6 +31,8 @@ pub enum Result {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Transaction
+	Transaction(Box<Transaction>),
 	/// SyncStatus
 	SyncState(PubSubSyncStatus)
 }
@@ -51,6 +53,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::Transaction(ref transaction) => transaction.serialize(serializer),
 			Result::SyncState(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -78,6 +81,8 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Whether pending transactions are sent in full rather than by hash.
+	Bool(bool),
 }
 
 impl Default for Params {
@@ -95,6 +100,10 @@ impl<'a> Deserialize<'a> for Params {
 			return Ok(Params::None);
 		}
 
+		if let Value::Bool(full) = v {
+			return Ok(Params::Bool(full));
+		}
+
 		from_value(v.clone()).map(Params::Logs)
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
@@ -0,0 +1,86 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use ethereum_types::{H160, U256, U64};
+use crate::types::{Bytes, CallBlockOverride, CallRequest, CallStateOverride, Log};
+
+/// Blocks of calls simulated by `eth_simulateV1`
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// Blocks to simulate, in order
+	pub block_state_calls: Vec<SimulateBlock>,
+	/// Whether to check transactions as for block inclusion, not supported
+	#[serde(default)]
+	pub validation: bool,
+	/// Whether to report value transfers as logs, not supported
+	#[serde(default)]
+	pub trace_transfers: bool,
+}
+
+/// Block of calls simulated by `eth_simulateV1`
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// Block context override
+	pub block_overrides: Option<CallBlockOverride>,
+	/// State overrides, applied before the calls of the block
+	pub state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	/// Calls of the block, in order
+	#[serde(default)]
+	pub calls: Vec<CallRequest>,
+}
+
+/// Simulated block. Unlike a block of the chain, it only has a number, the
+/// gas used and the results of its calls.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+	/// Block number
+	pub number: U256,
+	/// Gas used by the calls of the block
+	pub gas_used: U256,
+	/// Results of the calls of the block
+	pub calls: Vec<SimulatedCall>,
+}
+
+/// Simulated call
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+	/// Output of the call
+	pub return_data: Bytes,
+	/// Logs emitted by the call
+	pub logs: Vec<Log>,
+	/// Gas used by the call
+	pub gas_used: U256,
+	/// 1 if the call succeeded, 0 otherwise
+	pub status: U64,
+	/// Error, if the call failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulatedCallError>,
+}
+
+/// Error of a simulated call
+#[derive(Debug, Serialize, Clone)]
+pub struct SimulatedCallError {
+	/// Error code
+	pub code: i64,
+	/// Error message
+	pub message: String,
+}
@@ -0,0 +1,256 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use ethereum_types::{H160, H256, U256};
+use crate::types::{Bytes, CallBlockOverride};
+
+/// Kind of output requested from a trace query. `VmTrace` is not served, and
+/// is rejected.
+#[derive(Debug, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+	/// Call traces
+	Trace,
+	/// Virtual machine execution traces
+	VmTrace,
+	/// State differences
+	StateDiff,
+}
+
+/// Trace results of a single call
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+	/// Output of the call
+	pub output: Bytes,
+	/// Call traces, empty unless requested
+	pub trace: Vec<Trace>,
+	/// State differences, if requested
+	pub state_diff: Option<StateDiff>,
+}
+
+/// Accounts a call changed, with their differences
+pub type StateDiff = BTreeMap<H160, AccountDiff>;
+
+/// Differences of an account
+#[derive(Debug, Serialize, Clone)]
+pub struct AccountDiff {
+	/// Balance
+	pub balance: Diff<U256>,
+	/// Nonce
+	pub nonce: Diff<U256>,
+	/// Code
+	pub code: Diff<Bytes>,
+	/// Changed storage slots
+	pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+/// Difference of a value
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub enum Diff<T> {
+	/// Unchanged
+	#[serde(rename = "=")]
+	Same,
+	/// Added
+	#[serde(rename = "+")]
+	Born(T),
+	/// Removed
+	#[serde(rename = "-")]
+	Died(T),
+	/// Changed
+	#[serde(rename = "*")]
+	Changed(ChangedType<T>),
+}
+
+impl<T: PartialEq> Diff<T> {
+	/// Difference between `from` and `to`, `None` for an absent value.
+	pub fn new(from: Option<T>, to: Option<T>) -> Self {
+		match (from, to) {
+			(None, None) => Diff::Same,
+			(None, Some(to)) => Diff::Born(to),
+			(Some(from), None) => Diff::Died(from),
+			(Some(from), Some(to)) if from == to => Diff::Same,
+			(Some(from), Some(to)) => Diff::Changed(ChangedType { from, to }),
+		}
+	}
+
+	/// Whether the value is unchanged.
+	pub fn is_same(&self) -> bool {
+		*self == Diff::Same
+	}
+}
+
+/// Value before and after a change
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ChangedType<T> {
+	/// Value before
+	pub from: T,
+	/// Value after
+	pub to: T,
+}
+
+/// Call trace
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Trace {
+	/// Action
+	pub action: Action,
+	/// Result, if the call succeeded
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub result: Option<ActionResult>,
+	/// Error, if the call failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Number of subtraces
+	pub subtraces: usize,
+	/// Position in the trace tree
+	pub trace_address: Vec<usize>,
+	/// Trace type
+	#[serde(rename = "type")]
+	pub trace_type: String,
+}
+
+/// Action of a trace
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Action {
+	/// Call
+	Call(CallAction),
+	/// Contract creation
+	Create(CreateAction),
+}
+
+/// Result of a trace
+#[derive(Debug, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ActionResult {
+	/// Call result
+	Call(CallResult),
+	/// Contract creation result
+	Create(CreateResult),
+}
+
+/// Call action
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+	/// Sender
+	pub from: H160,
+	/// Recipient
+	pub to: H160,
+	/// Transferred value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Input data
+	pub input: Bytes,
+	/// Call type
+	pub call_type: String,
+}
+
+/// Call result
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CallResult {
+	/// Gas used
+	pub gas_used: U256,
+	/// Output bytes
+	pub output: Bytes,
+}
+
+/// Contract creation action
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+	/// Sender
+	pub from: H160,
+	/// Transferred value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Initialization code
+	pub init: Bytes,
+}
+
+/// Contract creation result
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateResult {
+	/// Gas used
+	pub gas_used: U256,
+	/// Code of the created contract
+	pub code: Bytes,
+	/// Address of the created contract
+	pub address: H160,
+}
+
+/// Call frame, in the format of the geth call tracer
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+	/// Call type
+	#[serde(rename = "type")]
+	pub call_type: String,
+	/// Sender
+	pub from: H160,
+	/// Recipient, or the created contract
+	pub to: H160,
+	/// Transferred value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Gas used
+	pub gas_used: U256,
+	/// Input data
+	pub input: Bytes,
+	/// Output bytes
+	pub output: Bytes,
+	/// Error, if the call failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// Options of `debug_traceCall`, in the format of geth. Only the call tracer
+/// is served, so the tracer must be given, and the options of the struct
+/// logger, `tracerConfig` and `stateOverrides` are rejected.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallConfig {
+	/// Tracer, which must be `callTracer`
+	pub tracer: Option<String>,
+	/// Block context override
+	pub block_overrides: Option<CallBlockOverride>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	#[test]
+	fn trace_call_config_rejects_unknown_fields() {
+		let config: TraceCallConfig = serde_json::from_str(r#"{"tracer":"callTracer"}"#).unwrap();
+		assert_eq!(config.tracer, Some("callTracer".to_string()));
+
+		assert!(serde_json::from_str::<TraceCallConfig>(
+			r#"{"tracer":"callTracer","tracerConfig":{"onlyTopCall":true}}"#
+		).is_err());
+		assert!(serde_json::from_str::<TraceCallConfig>(r#"{"disableStorage":true}"#).is_err());
+	}
+}
@@ -20,7 +20,7 @@ use ethereum_types::{H160, H256, H512, U64, U256};
 use crate::types::{Bytes, TransactionCondition};
 
 /// Transaction
-#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
 	/// Hash
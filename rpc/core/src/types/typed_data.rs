@@ -0,0 +1,101 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Member of an EIP-712 struct type.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TypedDataField {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub type_: String,
+}
+
+/// EIP-712 typed data, as signed by `eth_signTypedData_v4`. Wallets send it
+/// either as a JSON object or as a string holding one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedData {
+	/// Struct types, by name, including `EIP712Domain`.
+	pub types: BTreeMap<String, Vec<TypedDataField>>,
+	pub primary_type: String,
+	/// Value of the `EIP712Domain` type.
+	pub domain: Value,
+	/// Value of the `primary_type` type.
+	pub message: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedDataObject {
+	types: BTreeMap<String, Vec<TypedDataField>>,
+	primary_type: String,
+	domain: Value,
+	message: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TypedDataParam {
+	Object(TypedDataObject),
+	Json(String),
+}
+
+impl<'a> Deserialize<'a> for TypedData {
+	fn deserialize<D>(deserializer: D) -> Result<TypedData, D::Error> where D: Deserializer<'a> {
+		let object = match TypedDataParam::deserialize(deserializer)? {
+			TypedDataParam::Object(object) => object,
+			TypedDataParam::Json(json) => serde_json::from_str(&json).map_err(serde::de::Error::custom)?,
+		};
+		Ok(TypedData {
+			types: object.types,
+			primary_type: object.primary_type,
+			domain: object.domain,
+			message: object.message,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json;
+
+	const MAIL: &str = r#"{
+		"types": {
+			"EIP712Domain": [{ "name": "name", "type": "string" }],
+			"Mail": [{ "name": "contents", "type": "string" }]
+		},
+		"primaryType": "Mail",
+		"domain": { "name": "Ether Mail" },
+		"message": { "contents": "Hello, Bob!" }
+	}"#;
+
+	#[test]
+	fn typed_data_deserialization() {
+		let data: TypedData = serde_json::from_str(MAIL).unwrap();
+		assert_eq!(data.primary_type, "Mail");
+		assert_eq!(data.types["Mail"], vec![TypedDataField {
+			name: "contents".into(),
+			type_: "string".into(),
+		}]);
+		assert_eq!(data.message["contents"], "Hello, Bob!");
+
+		let encoded: TypedData = serde_json::from_value(Value::String(MAIL.into())).unwrap();
+		assert_eq!(encoded, data);
+	}
+}
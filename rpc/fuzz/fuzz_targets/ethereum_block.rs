@@ -0,0 +1,35 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Ethereum blocks and receipts as the rpc decodes them from runtime storage
+//! and runtime api results, then hashes them.
+
+#![no_main]
+
+use codec::Decode;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(block) = ethereum::Block::decode(&mut &data[..]) {
+		let _ = rlp::encode(&block.header);
+		for transaction in &block.transactions {
+			let _ = frontier_rpc::recover_sender(transaction);
+		}
+	}
+	let _ = <(ethereum::Block, Vec<ethereum::Receipt>)>::decode(&mut &data[..]);
+	let _ = <Vec<ethereum::Receipt>>::decode(&mut &data[..]);
+});
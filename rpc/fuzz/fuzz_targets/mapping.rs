@@ -0,0 +1,33 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Frontier consensus logs, which come with block headers from the network,
+//! and the transaction mappings the client stores from them.
+
+#![no_main]
+
+use codec::Decode;
+use ethereum_types::H256;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(log) = fp_consensus::ConsensusLog::decode(&mut &data[..]) {
+		let _ = log.transaction_hashes().len();
+		let _ = log.log_counts();
+	}
+	let _ = fc_api::TransactionMetadata::<H256>::decode(&mut &data[..]);
+});
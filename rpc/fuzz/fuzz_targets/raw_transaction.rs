@@ -0,0 +1,33 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Raw transactions of `eth_sendRawTransaction`, decoded and then checked as
+//! the rpc does before submitting them to the pool. The hash returned is that
+//! of the payload, so accepted payloads must be the encoding of their
+//! transaction.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok((transaction, _)) = frontier_rpc::decode_raw_transaction(data) {
+		assert_eq!(rlp::encode(&transaction), data);
+		let _ = transaction.signature.chain_id();
+		let _ = frontier_rpc::recover_sender(&transaction);
+	}
+});
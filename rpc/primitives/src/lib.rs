@@ -50,15 +50,236 @@ impl Default for TransactionStatus {
 	}
 }
 
+/// Overrides applied to an account's EVM state before a dry-run call.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct AccountOverride {
+	pub balance: Option<U256>,
+	pub nonce: Option<U256>,
+	pub code: Option<Vec<u8>>,
+	/// Replaces the whole account storage.
+	pub state: Option<Vec<(H256, H256)>>,
+	/// Overrides individual storage slots, keeping the others.
+	pub state_diff: Option<Vec<(H256, H256)>>,
+}
+
+/// Overrides applied to the block context before a dry-run call.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct BlockOverride {
+	pub number: Option<u32>,
+	/// Timestamp in seconds.
+	pub timestamp: Option<u64>,
+	/// Base fee per gas, which gas prices are checked against.
+	pub base_fee: Option<U256>,
+}
+
+/// `BlockOverride` of the runtimes from before version 4 of the api, which
+/// cannot override the base fee.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct LegacyBlockOverride {
+	pub number: Option<u32>,
+	/// Timestamp in seconds.
+	pub timestamp: Option<u64>,
+}
+
+impl From<BlockOverride> for LegacyBlockOverride {
+	fn from(block_override: BlockOverride) -> Self {
+		LegacyBlockOverride { number: block_override.number, timestamp: block_override.timestamp }
+	}
+}
+
+/// A call executed by `call_many`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct DryRunCall {
+	pub from: H160,
+	/// Recipient, `None` to create a contract.
+	pub to: Option<H160>,
+	pub data: Vec<u8>,
+	pub value: U256,
+	pub gas_limit: U256,
+	pub gas_price: U256,
+	pub nonce: Option<U256>,
+}
+
+/// Why an EVM execution did not succeed.
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExecutionError {
+	/// The execution reverted, its output being the revert data.
+	Reverted,
+	OutOfGas,
+	/// The caller cannot pay the value it transfers.
+	OutOfFund,
+	StackOverflow,
+	StackUnderflow,
+	/// Calls and creations are nested too deep.
+	CallTooDeep,
+	/// A contract already exists at the address of the created one.
+	CreateCollision,
+	/// The code of the created contract is larger than the limit.
+	CreateContractLimit,
+	/// The code runs a designated invalid opcode, jumps to an invalid
+	/// destination or uses an operand out of range.
+	InvalidCode,
+	/// The code reads the return data past its end.
+	OutOfOffset,
+	/// Any other error of the EVM or of a precompile.
+	Other,
+	/// The EVM could not continue.
+	Fatal,
+}
+
+impl ExecutionError {
+	/// Message reporting the error over rpc.
+	pub fn message(&self) -> &'static str {
+		match self {
+			ExecutionError::Reverted => "execution reverted",
+			ExecutionError::OutOfGas => "out of gas",
+			ExecutionError::OutOfFund => "insufficient funds for transfer",
+			ExecutionError::StackOverflow => "stack limit reached",
+			ExecutionError::StackUnderflow => "stack underflow",
+			ExecutionError::CallTooDeep => "max call depth exceeded",
+			ExecutionError::CreateCollision => "contract address collision",
+			ExecutionError::CreateContractLimit => "max code size exceeded",
+			ExecutionError::InvalidCode => "invalid code",
+			ExecutionError::OutOfOffset => "return data out of bounds",
+			ExecutionError::Other => "evm error",
+			ExecutionError::Fatal => "fatal evm error",
+		}
+	}
+}
+
+/// Outcome of a call executed by `call` or `call_many`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct DryRunOutcome {
+	/// Whether the call exited successfully, as opposed to reverting or erroring.
+	pub succeeded: bool,
+	/// Why the call did not succeed.
+	pub error: Option<ExecutionError>,
+	/// Output of the call, or code of the created contract.
+	pub output: Vec<u8>,
+	pub used_gas: U256,
+	/// Address of the contract created by a call without recipient.
+	pub created: Option<H160>,
+}
+
+/// Storage changes of executing the ready pool transactions with
+/// `apply_pending`, as collected by the client: each key with its new value, or
+/// `None` if removed.
+pub type PendingChanges = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// An EVM account listed by `account_range`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct AccountRangeEntry {
+	/// Key of the account in the pallet-evm account map, after the map prefix.
+	pub key: Vec<u8>,
+	pub address: H160,
+	pub basic: pallet_evm::Account,
+	/// Keccak-256 hash of the account code.
+	pub code_hash: H256,
+}
+
+/// A page of the EVM accounts, listed by `account_range`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct AccountRange {
+	pub accounts: Vec<AccountRangeEntry>,
+	/// Key of the account following the last listed one, if any.
+	pub next: Option<Vec<u8>>,
+}
+
+/// Constants of the runtime that the rpc checks requests against.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct ValidationConstants {
+	pub block_gas_limit: U256,
+	/// Gas available to calls executed off-chain, as a multiple of the block
+	/// gas limit.
+	pub execute_gas_limit_multiplier: u64,
+	/// Maximum length of the RLP encoding of a transaction.
+	pub max_transaction_size: u32,
+	/// Whether transactions without EIP-155 replay protection are accepted.
+	pub allow_unprotected_txs: bool,
+}
+
+/// A block of calls executed by `simulate`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct SimulatedBlock {
+	pub state_overrides: Vec<(H160, AccountOverride)>,
+	/// Block context, the block number defaulting to the one after the
+	/// previous block.
+	pub block_override: BlockOverride,
+	pub calls: Vec<DryRunCall>,
+}
+
+/// `SimulatedBlock` of the runtimes from before version 4 of the api.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct LegacySimulatedBlock {
+	pub state_overrides: Vec<(H160, AccountOverride)>,
+	pub block_override: LegacyBlockOverride,
+	pub calls: Vec<DryRunCall>,
+}
+
+impl From<SimulatedBlock> for LegacySimulatedBlock {
+	fn from(block: SimulatedBlock) -> Self {
+		LegacySimulatedBlock {
+			state_overrides: block.state_overrides,
+			block_override: block.block_override.into(),
+			calls: block.calls,
+		}
+	}
+}
+
+/// Outcome of a call executed by `simulate`.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub struct SimulatedOutcome {
+	/// Whether the call exited successfully, as opposed to reverting or erroring.
+	pub succeeded: bool,
+	/// Why the call did not succeed.
+	pub error: Option<ExecutionError>,
+	pub output: Vec<u8>,
+	pub used_gas: U256,
+	pub logs: Vec<Log>,
+}
+
+/// Storage key of the `EthereumStorageSchema` of the blocks stored by
+/// pallet-ethereum.
+pub const PALLET_ETHEREUM_SCHEMA: &[u8] = b":ethereum_schema";
+
+/// Layout of the Ethereum blocks, receipts and statuses stored by
+/// pallet-ethereum, so that clients decode the blocks of every runtime of a
+/// chain with the types they were stored with. A variant is added whenever
+/// the `ethereum` types of the pallet storage change.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum EthereumStorageSchema {
+	/// Stored by a runtime predating the schema, its layout is unknown.
+	Undefined,
+	/// Blocks of legacy transactions with `ethereum` 0.2 receipts.
+	V1,
+}
+
+impl Default for EthereumStorageSchema {
+	fn default() -> Self {
+		EthereumStorageSchema::Undefined
+	}
+}
+
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
+	#[api_version(4)]
 	pub trait EthereumRuntimeApi {
 		fn chain_id() -> u64;
 		fn account_basic(address: H160) -> pallet_evm::Account;
 		fn gas_price() -> U256;
+		fn block_gas_limit() -> U256;
+		/// Constants that requests are checked against before reaching the
+		/// runtime.
+		fn validation_constants() -> ValidationConstants;
 		fn account_code_at(address: H160) -> Vec<u8>;
 		fn author() -> H160;
 		fn storage_at(address: H160, index: U256) -> H256;
+		/// Root of the Ethereum storage trie of `address`.
+		fn account_storage_root(address: H160) -> H256;
+		/// Up to `max_results` EVM accounts, in the order of their keys, from
+		/// the key `start` on.
+		fn account_range(start: Vec<u8>, max_results: u32) -> AccountRange;
+		#[changed_in(2)]
 		fn call(
 			from: H160,
 			to: H160,
@@ -68,17 +289,79 @@ sp_api::decl_runtime_apis! {
 			gas_price: U256,
 			nonce: Option<U256>,
 		) -> Option<(Vec<u8>, U256)>;
+		#[changed_in(4)]
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			gas_price: U256,
+			nonce: Option<U256>,
+			state_overrides: Vec<(H160, AccountOverride)>,
+			block_override: LegacyBlockOverride,
+			pending: PendingChanges,
+		) -> Option<DryRunOutcome>;
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			gas_price: U256,
+			nonce: Option<U256>,
+			state_overrides: Vec<(H160, AccountOverride)>,
+			block_override: BlockOverride,
+			pending: PendingChanges,
+		) -> Option<DryRunOutcome>;
+		/// Execute the `pending` transactions from their senders, in order, up
+		/// to the block gas limit. Only meant for the client to collect the
+		/// storage changes, which `call` takes back as `pending`.
+		fn apply_pending(pending: Vec<(H160, EthereumTransaction)>);
+		#[changed_in(3)]
+		fn call_many(calls: Vec<DryRunCall>) -> Vec<Option<DryRunOutcome>>;
+		#[changed_in(4)]
+		fn call_many(
+			calls: Vec<DryRunCall>,
+			block_override: LegacyBlockOverride,
+		) -> Vec<Option<DryRunOutcome>>;
+		/// Execute calls in sequence in the block context of `block_override`,
+		/// each one seeing the state changes of the previous ones. `None` for a
+		/// call that could not be executed at all.
+		fn call_many(calls: Vec<DryRunCall>, block_override: BlockOverride) -> Vec<Option<DryRunOutcome>>;
+		#[changed_in(4)]
+		fn simulate(blocks: Vec<LegacySimulatedBlock>) -> Vec<Vec<Option<SimulatedOutcome>>>;
+		/// Execute blocks of calls in sequence, each block and call seeing the
+		/// state changes of the previous ones.
+		fn simulate(blocks: Vec<SimulatedBlock>) -> Vec<Vec<Option<SimulatedOutcome>>>;
 		fn block_by_number(number: u32) -> (Option<EthereumBlock>, Vec<Option<TransactionStatus>>);
 		fn block_transaction_count_by_number(number: u32) -> Option<U256>;
 		fn block_by_hash(hash: H256) -> Option<EthereumBlock>;
 		fn block_by_hash_with_statuses(hash: H256) -> (Option<EthereumBlock>, Vec<Option<TransactionStatus>>);
 		fn block_transaction_count_by_hash(hash: H256) -> Option<U256>;
+		/// Receipts of the Ethereum block `number`, in transaction order.
+		fn block_receipts_by_number(number: u32) -> Option<Vec<EthereumReceipt>>;
+		/// Logs bloom of the Ethereum block `number`. Blocks from before the
+		/// runtime computed it have an empty one.
+		fn block_logs_bloom(number: u32) -> Option<Bloom>;
+		/// Hash of the Ethereum transaction that created the contract `address`,
+		/// if it was created by a top-level CREATE transaction.
+		fn contract_creation(address: H160) -> Option<H256>;
 		fn transaction_by_hash(hash: H256) -> Option<(
 			EthereumTransaction,
 			EthereumBlock,
 			TransactionStatus,
 			EthereumReceipt
 		)>;
+		/// Transaction at `index` in the Ethereum block `hash`, with its block,
+		/// status and receipt, and the gas used in the block before it.
+		fn transaction_receipt(hash: H256, index: u32) -> Option<(
+			EthereumTransaction,
+			EthereumBlock,
+			TransactionStatus,
+			EthereumReceipt,
+			U256
+		)>;
 		fn transaction_by_block_hash_and_index(
 			hash: H256,
 			index: u32
@@ -95,6 +378,22 @@ sp_api::decl_runtime_apis! {
 			EthereumBlock,
 			TransactionStatus
 		)>;
+		/// Indices of the extrinsics that are Ethereum transactions, in order.
+		fn ethereum_extrinsic_indices(
+			xts: Vec<<Block as sp_runtime::traits::Block>::Extrinsic>,
+		) -> Vec<u32>;
+		/// Ethereum transactions among `xts`, in order.
+		fn extrinsic_filter(
+			xts: Vec<<Block as sp_runtime::traits::Block>::Extrinsic>,
+		) -> Vec<EthereumTransaction>;
+	}
+
+	/// API relating Substrate accounts and EVM addresses.
+	pub trait AccountMappingRuntimeApi<AccountId: codec::Codec> {
+		/// EVM address of a Substrate account.
+		fn evm_address(account_id: AccountId) -> H160;
+		/// Substrate account controlling an EVM address, when the runtime can tell.
+		fn account_id(address: H160) -> Option<AccountId>;
 	}
 }
 
@@ -0,0 +1,62 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+use codec::Codec;
+use ethereum_types::H160;
+use jsonrpc_core::Result;
+use sp_runtime::traits::Block as BlockT;
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use frontier_rpc_core::AccountApi as AccountApiT;
+use frontier_rpc_primitives::AccountMappingRuntimeApi;
+
+use crate::internal_err;
+
+pub use frontier_rpc_core::AccountApiServer;
+
+pub struct AccountApi<B: BlockT, C, AccountId> {
+	client: Arc<C>,
+	_marker: PhantomData<(B, AccountId)>,
+}
+
+impl<B: BlockT, C, AccountId> AccountApi<B, C, AccountId> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: PhantomData }
+	}
+}
+
+impl<B, C, AccountId> AccountApiT<AccountId> for AccountApi<B, C, AccountId> where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: AccountMappingRuntimeApi<B, AccountId>,
+	B: BlockT + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn account_id(&self, address: H160) -> Result<Option<AccountId>> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		self.client.runtime_api()
+			.account_id(&id, address)
+			.map_err(|_| internal_err("fetch runtime account id failed"))
+	}
+
+	fn evm_address(&self, account_id: AccountId) -> Result<H160> {
+		let id = BlockId::Hash(self.client.info().best_hash);
+		self.client.runtime_api()
+			.evm_address(&id, account_id)
+			.map_err(|_| internal_err("fetch runtime evm address failed"))
+	}
+}
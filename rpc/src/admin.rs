@@ -0,0 +1,211 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Admin rpc namespace, describing the node and its peers in geth shapes.
+//!
+//! The network service only starts once the rpc extensions are built, so the
+//! node hands its network state over through a `NetworkStateHandle`, updated
+//! from the periodic network status of the service. Libp2p peer ids and
+//! multiaddresses stand in for enode ids and urls.
+
+use std::{marker::PhantomData, sync::{Arc, Mutex}};
+use ethereum_types::{H256, U256};
+use jsonrpc_core::Result;
+use sc_rpc_api::DenyUnsafe;
+use sha3::{Keccak256, Digest};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use frontier_rpc_core::AdminApi as AdminApiT;
+use frontier_rpc_core::types::{
+	EthProtocolInfo, NodeInfo, NodePorts, NodeProtocols, PeerInfo, PeerNetworkInfo,
+};
+use frontier_rpc_primitives::EthereumRuntimeApi;
+
+use crate::internal_err;
+
+pub use frontier_rpc_core::AdminApiServer;
+
+/// Network state of the node, as last reported by the network service.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkState {
+	/// Peer id of the node.
+	pub peer_id: String,
+	/// Multiaddresses the node listens on.
+	pub listen_addresses: Vec<String>,
+	/// Connected peers.
+	pub peers: Vec<NetworkPeer>,
+}
+
+/// Peer connected to the node.
+#[derive(Debug, Clone)]
+pub struct NetworkPeer {
+	pub peer_id: String,
+	/// Agent the peer identified as, if it did.
+	pub version: Option<String>,
+	/// Local multiaddress of the connection, for inbound ones.
+	pub local_address: Option<String>,
+	/// Remote multiaddress of the connection.
+	pub remote_address: String,
+	/// Whether the peer dialed the node.
+	pub inbound: bool,
+}
+
+/// Network state shared between the service, which updates it, and the admin
+/// namespace.
+#[derive(Clone, Default)]
+pub struct NetworkStateHandle {
+	state: Arc<Mutex<Option<NetworkState>>>,
+}
+
+impl NetworkStateHandle {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Replaces the network state served by the admin namespace.
+	pub fn update(&self, state: NetworkState) {
+		*self.state.lock().expect("network state lock is never held across a panic; qed") = Some(state);
+	}
+
+	fn get(&self) -> Result<NetworkState> {
+		self.state.lock().expect("network state lock is never held across a panic; qed")
+			.clone()
+			.ok_or_else(|| internal_err("network state not available yet"))
+	}
+}
+
+/// Admin namespace, serving the network state of a `NetworkStateHandle`.
+pub struct AdminApi<B: BlockT, C> {
+	client: Arc<C>,
+	network: NetworkStateHandle,
+	name: String,
+	deny_unsafe: DenyUnsafe,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> AdminApi<B, C> {
+	/// `name` is the client name and version reported by `admin_nodeInfo`.
+	pub fn new(
+		client: Arc<C>,
+		network: NetworkStateHandle,
+		name: String,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, network, name, deny_unsafe, _marker: PhantomData }
+	}
+}
+
+impl<B, C> AdminApi<B, C> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	/// Ethereum chain of the best block.
+	fn eth_protocol(&self) -> Result<EthProtocolInfo> {
+		let info = self.client.info();
+		let id = BlockId::Hash(info.best_hash);
+		let api = self.client.runtime_api();
+		let network = api.chain_id(&id)
+			.map_err(|_| internal_err("fetch runtime chain id failed"))?;
+		let block_hash = |number: u32| -> Result<Option<H256>> {
+			Ok(api.block_by_number(&id, number)
+				.map_err(|_| internal_err("fetch runtime block failed"))?
+				.0
+				.map(|block| H256::from_slice(
+					Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+				)))
+		};
+		Ok(EthProtocolInfo {
+			network,
+			difficulty: U256::zero(),
+			genesis: block_hash(0)?,
+			head: block_hash(UniqueSaturatedInto::<u32>::unique_saturated_into(info.best_number))?,
+		})
+	}
+}
+
+impl<B, C> AdminApiT for AdminApi<B, C> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	fn peers(&self) -> Result<Vec<PeerInfo>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.network.get()?.peers.into_iter().map(|peer| PeerInfo {
+			enode: with_peer_id(&peer.remote_address, &peer.peer_id),
+			id: peer.peer_id,
+			name: peer.version.unwrap_or_default(),
+			caps: Vec::new(),
+			network: PeerNetworkInfo {
+				local_address: peer.local_address,
+				remote_address: peer.remote_address,
+				inbound: peer.inbound,
+				trusted: false,
+				is_static: false,
+			},
+		}).collect())
+	}
+
+	fn node_info(&self) -> Result<NodeInfo> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let state = self.network.get()?;
+		let (address, ip, port) = state.listen_addresses.iter()
+			.find_map(|address| ip_and_port(address).map(|(ip, port)| (address.clone(), ip, port)))
+			.unwrap_or_else(|| (String::new(), "0.0.0.0".to_string(), 0));
+		let listen_addr = if ip.contains(':') {
+			format!("[{}]:{}", ip, port)
+		} else {
+			format!("{}:{}", ip, port)
+		};
+		Ok(NodeInfo {
+			enode: with_peer_id(&address, &state.peer_id),
+			id: state.peer_id,
+			name: self.name.clone(),
+			ip,
+			ports: NodePorts { discovery: port, listener: port },
+			listen_addr,
+			protocols: NodeProtocols { eth: self.eth_protocol()? },
+		})
+	}
+}
+
+/// `address` ending with the `/p2p/<peer id>` component of `peer_id`.
+fn with_peer_id(address: &str, peer_id: &str) -> String {
+	if address.contains("/p2p/") {
+		address.to_string()
+	} else {
+		format!("{}/p2p/{}", address, peer_id)
+	}
+}
+
+/// Ip, or host name, and tcp port of a multiaddress such as
+/// `/ip4/127.0.0.1/tcp/30333`.
+fn ip_and_port(address: &str) -> Option<(String, u16)> {
+	let (mut ip, mut port) = (None, None);
+	let mut components = address.split('/').skip(1);
+	while let Some(protocol) = components.next() {
+		match protocol {
+			"ip4" | "ip6" | "dns" | "dns4" | "dns6" => ip = components.next(),
+			"tcp" => port = components.next().and_then(|port| port.parse().ok()),
+			"udp" | "p2p" => { components.next(); },
+			_ => (),
+		}
+	}
+	Some((ip?.to_string(), port?))
+}
@@ -0,0 +1,144 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Shared secret authentication of sensitive rpc calls.
+//!
+//! The methods wrapped by `Authenticator::guard` can only be called from a
+//! connection that first called `frontier_authenticate` with the node's
+//! secret. Authentication lasts for the connection's pubsub session, so the
+//! guarded methods are only available over WebSocket.
+
+use std::{collections::HashSet, sync::{Arc, Mutex}};
+use jsonrpc_core::{
+	BoxFuture, Error, ErrorCode, Params, RemoteProcedure, RpcMethod, Value,
+	futures::future,
+};
+use jsonrpc_pubsub::PubSubMetadata;
+
+/// Error code of calls rejected for lack of authentication.
+pub const UNAUTHORIZED: i64 = -32006;
+
+/// Authenticates connections with a shared secret.
+#[derive(Clone)]
+pub struct Authenticator {
+	secret: Arc<Vec<u8>>,
+	/// Addresses of the authenticated sessions.
+	sessions: Arc<Mutex<HashSet<usize>>>,
+}
+
+/// Compares `a` and `b` in a time independent of where they differ.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+impl Authenticator {
+	pub fn new(secret: Vec<u8>) -> Self {
+		Self {
+			secret: Arc::new(secret),
+			sessions: Arc::new(Mutex::new(HashSet::new())),
+		}
+	}
+
+	/// The `frontier_authenticate` method, taking the secret and returning
+	/// whether it was correct.
+	pub fn delegate<M: PubSubMetadata>(&self) -> Vec<(String, RemoteProcedure<M>)> {
+		vec![(
+			"frontier_authenticate".to_string(),
+			RemoteProcedure::Method(Arc::new(Authenticate(self.clone()))),
+		)]
+	}
+
+	/// Wraps the methods of `delegate` so that they require authentication.
+	pub fn guard<M, D>(&self, delegate: D) -> Vec<(String, RemoteProcedure<M>)> where
+		M: PubSubMetadata,
+		D: IntoIterator<Item=(String, RemoteProcedure<M>)>,
+	{
+		delegate.into_iter().map(|(name, procedure)| {
+			let procedure = match procedure {
+				RemoteProcedure::Method(method) => RemoteProcedure::Method(Arc::new(GuardedMethod {
+					method,
+					authenticator: self.clone(),
+				})),
+				other => other,
+			};
+			(name, procedure)
+		}).collect()
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<HashSet<usize>> {
+		self.sessions.lock().expect("authenticator lock is never held across a panic; qed")
+	}
+
+	fn is_authenticated<M: PubSubMetadata>(&self, meta: &M) -> bool {
+		match meta.session() {
+			Some(session) => self.lock().contains(&(&*session as *const _ as usize)),
+			None => false,
+		}
+	}
+}
+
+struct Authenticate(Authenticator);
+
+impl<M: PubSubMetadata> RpcMethod<M> for Authenticate {
+	fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
+		let (secret,): (String,) = match params.parse() {
+			Ok(params) => params,
+			Err(e) => return Box::new(future::err(e)),
+		};
+		let session = match meta.session() {
+			Some(session) => session,
+			None => return Box::new(future::err(Error {
+				code: ErrorCode::ServerError(UNAUTHORIZED),
+				message: "authentication requires a WebSocket connection".to_string(),
+				data: None,
+			})),
+		};
+		if !constant_time_eq(secret.as_bytes(), &self.0.secret) {
+			return Box::new(future::ok(Value::Bool(false)))
+		}
+
+		let address = &*session as *const _ as usize;
+		if self.0.lock().insert(address) {
+			// Forget the session with the connection, since its address may be
+			// reused by a later one.
+			let sessions = self.0.sessions.clone();
+			session.on_drop(move || {
+				if let Ok(mut sessions) = sessions.lock() {
+					sessions.remove(&address);
+				}
+			});
+		}
+		Box::new(future::ok(Value::Bool(true)))
+	}
+}
+
+struct GuardedMethod<M> {
+	method: Arc<dyn RpcMethod<M>>,
+	authenticator: Authenticator,
+}
+
+impl<M: PubSubMetadata> RpcMethod<M> for GuardedMethod<M> {
+	fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
+		if !self.authenticator.is_authenticated(&meta) {
+			return Box::new(future::err(Error {
+				code: ErrorCode::ServerError(UNAUTHORIZED),
+				message: "unauthorized".to_string(),
+				data: None,
+			}))
+		}
+		self.method.call(params, meta)
+	}
+}
@@ -0,0 +1,90 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cache of the Ethereum data of recent blocks.
+//!
+//! Wallets poll the latest block and the receipts of their transactions as
+//! soon as a block lands. `warm_block_cache` decodes the Ethereum block,
+//! statuses and receipts of each new best block as it is imported, so that
+//! these calls are answered from memory rather than with runtime calls.
+
+use std::sync::{Arc, Mutex};
+use ethereum::{Block as EthereumBlock, Receipt as EthereumReceipt};
+use ethereum_types::H256;
+use futures::{future, Future, StreamExt};
+use lru::LruCache;
+use sp_runtime::traits::{Block as BlockT, Header as _, UniqueSaturatedInto};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sc_client_api::{BlockchainEvents, backend::{StorageProvider, Backend}};
+use frontier_rpc_primitives::{EthereumRuntimeApi, TransactionStatus};
+
+use crate::schema::read_block_data;
+
+/// Ethereum data of a Substrate block.
+pub struct EthBlockData {
+	pub block: EthereumBlock,
+	pub statuses: Vec<Option<TransactionStatus>>,
+	pub receipts: Vec<EthereumReceipt>,
+}
+
+/// Ethereum data of the last read blocks, by Substrate block hash.
+pub struct EthBlockDataCache {
+	blocks: Mutex<LruCache<H256, Arc<EthBlockData>>>,
+}
+
+impl EthBlockDataCache {
+	pub fn new(capacity: usize) -> Self {
+		EthBlockDataCache { blocks: Mutex::new(LruCache::new(capacity)) }
+	}
+
+	/// Ethereum data of the Substrate block `hash` numbered `number`, decoded
+	/// from its state on a cache miss. `None` for a block without an Ethereum
+	/// block, such as genesis.
+	pub fn block_data<B, C, BE>(&self, client: &C, hash: H256, number: u32) -> Option<Arc<EthBlockData>> where
+		B: BlockT<Hash=H256>,
+		C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+		C::Api: EthereumRuntimeApi<B>,
+		BE: Backend<B>,
+	{
+		if let Some(data) = self.lock().get(&hash) {
+			return Some(data.clone());
+		}
+		let data = Arc::new(read_block_data(client, &BlockId::Hash(hash), number)?);
+		self.lock().put(hash, data.clone());
+		Some(data)
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<LruCache<H256, Arc<EthBlockData>>> {
+		self.blocks.lock().expect("block data cache lock is never held across a panic; qed")
+	}
+}
+
+/// Read the Ethereum data of every new best block imported by `client` into
+/// `cache`.
+pub fn warm_block_cache<B, C, BE>(client: Arc<C>, cache: Arc<EthBlockDataCache>) -> impl Future<Output = ()> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + BlockchainEvents<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeApi<B>,
+	BE: Backend<B>,
+{
+	client.import_notification_stream().for_each(move |notification| {
+		if notification.is_new_best {
+			let number = UniqueSaturatedInto::<u32>::unique_saturated_into(*notification.header.number());
+			cache.block_data(&*client, notification.hash, number);
+		}
+		future::ready(())
+	})
+}
@@ -0,0 +1,107 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pool of threads running the expensive rpc calls.
+//!
+//! Trace re-execution, `eth_getLogs` scans and gas estimation run on the
+//! threads of a `BlockingTaskPool` rather than on the rpc server ones, so
+//! that cheap calls stay responsive while they run. Calls arriving when the
+//! queue of the pool is full are rejected with a limit exceeded error, and so
+//! are calls that waited in the queue for longer than its timeout.
+//!
+//! Traces get a pool of their own, so that a burst of them neither delays the
+//! other expensive calls nor uses more than its threads.
+
+use std::{sync::{Arc, Mutex, mpsc}, thread, time::{Duration, Instant}};
+use jsonrpc_core::{BoxFuture, Result, futures::{future, sync::oneshot, Future}};
+
+use crate::{internal_err, limit_exceeded_err};
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Threads running expensive rpc calls, fed by a bounded queue.
+#[derive(Clone)]
+pub struct BlockingTaskPool {
+	queue: Arc<Mutex<mpsc::SyncSender<Task>>>,
+	queue_timeout: Option<Duration>,
+}
+
+impl BlockingTaskPool {
+	/// Starts `threads` threads, queueing up to `queue_size` calls waiting for
+	/// one of them.
+	pub fn new(threads: usize, queue_size: usize) -> std::io::Result<Self> {
+		let (queue, tasks) = mpsc::sync_channel::<Task>(queue_size);
+		let tasks = Arc::new(Mutex::new(tasks));
+		for index in 0..threads.max(1) {
+			let tasks = tasks.clone();
+			thread::Builder::new()
+				.name(format!("rpc-blocking-{}", index))
+				.spawn(move || loop {
+					let task = match tasks.lock() {
+						Ok(tasks) => tasks.recv(),
+						Err(_) => return,
+					};
+					match task {
+						Ok(task) => task(),
+						// The pool was dropped.
+						Err(_) => return,
+					}
+				})?;
+		}
+		Ok(BlockingTaskPool { queue: Arc::new(Mutex::new(queue)), queue_timeout: None })
+	}
+
+	/// Rejects the calls still queued after `queue_timeout`, rather than
+	/// running them for clients that may have given up already.
+	pub fn with_queue_timeout(mut self, queue_timeout: Duration) -> Self {
+		self.queue_timeout = Some(queue_timeout);
+		self
+	}
+
+	/// Runs `call` on the pool, resolving to its result.
+	pub fn spawn<T, F>(&self, call: F) -> BoxFuture<T> where
+		T: Send + 'static,
+		F: FnOnce() -> Result<T> + Send + 'static,
+	{
+		let (sender, receiver) = oneshot::channel();
+		let deadline = self.queue_timeout.map(|timeout| Instant::now() + timeout);
+		let task: Task = Box::new(move || {
+			let result = match deadline {
+				Some(deadline) if Instant::now() > deadline =>
+					Err(limit_exceeded_err("timed out waiting for a free thread")),
+				_ => call(),
+			};
+			let _ = sender.send(result);
+		});
+		let queued = self.queue
+			.lock()
+			.expect("blocking task queue lock is never held across a panic; qed")
+			.try_send(task);
+		match queued {
+			Ok(()) => Box::new(
+				receiver
+					.map_err(|_| internal_err("blocking task cancelled"))
+					.and_then(future::result)
+			),
+			Err(mpsc::TrySendError::Full(_)) => Box::new(
+				future::err(limit_exceeded_err("too many expensive calls queued"))
+			),
+			Err(mpsc::TrySendError::Disconnected(_)) => Box::new(
+				future::err(internal_err("blocking task pool stopped"))
+			),
+		}
+	}
+}
@@ -0,0 +1,262 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Construction of `EthApi`.
+//!
+//! `EthApiBuilder` takes the components every node provides, and defaults the
+//! optional ones, so that a new option does not change the construction code
+//! of existing nodes. `EthConfig` names the types of a node's `EthApi` once.
+//! `EthConfiguration` gathers the tunables that nodes expose to operators.
+
+use std::{marker::PhantomData, sync::Arc};
+use ethereum_types::U256;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{
+	BlockingTaskPool, EthApi, EthApiLimits, EthApiSet, EthBlockDataCache, EthMiningWorker,
+	EthSigner, Hashrates, LatestBlock, PendingCache, RuntimeCache, SenderCache,
+};
+
+/// Number of transaction senders kept by default.
+const SENDER_CACHE_SIZE: usize = 4096;
+
+/// Number of blocks whose Ethereum data is kept by default.
+const BLOCK_DATA_CACHE_SIZE: usize = 256;
+
+/// Tunables of the Ethereum rpc, which nodes expose as command line flags so
+/// that operators adjust them without recompiling.
+#[derive(Clone, Debug)]
+pub struct EthConfiguration {
+	/// Maximum number of logs returned by a single `eth_getLogs` query.
+	pub max_past_logs: Option<usize>,
+	/// Maximum number of blocks a ranged query is allowed to span.
+	pub max_block_range: Option<u64>,
+	/// Maximum number of traces returned by a single trace query.
+	pub max_traces: Option<usize>,
+	/// Maximum number of calls of a single `eth_simulateV1` request.
+	pub max_simulate_calls: Option<usize>,
+	/// Maximum size in bytes of a serialized response.
+	pub max_response_size: Option<usize>,
+	/// Gas available to calls executed off-chain, whatever the block gas limit.
+	pub rpc_gas_cap: Option<U256>,
+	/// Gas available to calls executed off-chain, as a multiple of the block
+	/// gas limit. `None` follows the runtime.
+	pub execute_gas_limit_multiplier: Option<u64>,
+	/// Number of recovered transaction senders kept in memory.
+	pub sender_cache_size: usize,
+	/// Number of blocks whose Ethereum data is kept in memory.
+	pub block_data_cache_size: usize,
+	/// Rpc namespaces served.
+	pub namespaces: EthApiSet,
+}
+
+impl Default for EthConfiguration {
+	fn default() -> Self {
+		EthConfiguration {
+			max_past_logs: None,
+			max_block_range: None,
+			max_traces: None,
+			max_simulate_calls: None,
+			max_response_size: None,
+			rpc_gas_cap: None,
+			execute_gas_limit_multiplier: None,
+			sender_cache_size: SENDER_CACHE_SIZE,
+			block_data_cache_size: BLOCK_DATA_CACHE_SIZE,
+			namespaces: EthApiSet::default(),
+		}
+	}
+}
+
+impl EthConfiguration {
+	/// Limits of the requests, the ones not configured being the defaults.
+	pub fn limits(&self) -> EthApiLimits {
+		EthApiLimits {
+			max_logs: self.max_past_logs,
+			max_block_range: self.max_block_range,
+			max_traces: self.max_traces,
+			max_simulate_calls: self.max_simulate_calls,
+			max_response_size: self.max_response_size,
+			gas_cap: self.rpc_gas_cap,
+			execute_gas_limit_multiplier: self.execute_gas_limit_multiplier,
+			..Default::default()
+		}
+	}
+}
+
+/// Types of the `EthApi` of a node.
+pub trait EthConfig {
+	/// Substrate block.
+	type Block: BlockT;
+	/// Client of the node.
+	type Client;
+	/// Transaction pool.
+	type Pool;
+	/// Converter of Ethereum transactions to extrinsics.
+	type ConvertTransaction;
+	/// Substrate backend.
+	type Backend;
+}
+
+/// `EthApi` with the types of `T`.
+pub type EthApiFor<T> = EthApi<
+	<T as EthConfig>::Block,
+	<T as EthConfig>::Client,
+	<T as EthConfig>::Pool,
+	<T as EthConfig>::ConvertTransaction,
+	<T as EthConfig>::Backend,
+>;
+
+/// Builder of an `EthApi`, see `EthApi::builder`.
+pub struct EthApiBuilder<B: BlockT, C, P, CT, BE> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	convert_transaction: CT,
+	frontier_backend: Arc<dyn fc_api::Backend<B>>,
+	blocking_pool: BlockingTaskPool,
+	latest: LatestBlock,
+	is_authority: bool,
+	signers: Vec<Box<dyn EthSigner>>,
+	mining_worker: Option<Arc<dyn EthMiningWorker>>,
+	limits: EthApiLimits,
+	sender_cache_size: usize,
+	block_data_cache_size: usize,
+	block_data_cache: Option<Arc<EthBlockDataCache>>,
+	backend: Option<Arc<BE>>,
+}
+
+impl<B: BlockT, C, P, CT, BE> EthApi<B, C, P, CT, BE> {
+	/// Builder of an `EthApi` serving the best block as `latest`, without
+	/// signers nor mining worker, and with the default limits.
+	pub fn builder(
+		client: Arc<C>,
+		pool: Arc<P>,
+		convert_transaction: CT,
+		frontier_backend: Arc<dyn fc_api::Backend<B>>,
+		blocking_pool: BlockingTaskPool,
+	) -> EthApiBuilder<B, C, P, CT, BE> {
+		EthApiBuilder {
+			client,
+			pool,
+			convert_transaction,
+			frontier_backend,
+			blocking_pool,
+			latest: LatestBlock::default(),
+			is_authority: false,
+			signers: Vec::new(),
+			mining_worker: None,
+			limits: EthApiLimits::default(),
+			sender_cache_size: SENDER_CACHE_SIZE,
+			block_data_cache_size: BLOCK_DATA_CACHE_SIZE,
+			block_data_cache: None,
+			backend: None,
+		}
+	}
+}
+
+impl<B: BlockT, C, P, CT, BE> EthApiBuilder<B, C, P, CT, BE> {
+	/// Block served as `latest`.
+	pub fn latest(mut self, latest: LatestBlock) -> Self {
+		self.latest = latest;
+		self
+	}
+
+	/// Whether the node authors blocks, reported by `eth_mining`.
+	pub fn authority(mut self, is_authority: bool) -> Self {
+		self.is_authority = is_authority;
+		self
+	}
+
+	/// Signers of the accounts managed by the node.
+	pub fn signers(mut self, signers: Vec<Box<dyn EthSigner>>) -> Self {
+		self.signers = signers;
+		self
+	}
+
+	/// Worker serving `eth_getWork` and `eth_submitWork`.
+	pub fn mining_worker(mut self, mining_worker: Arc<dyn EthMiningWorker>) -> Self {
+		self.mining_worker = Some(mining_worker);
+		self
+	}
+
+	/// Limits of each request.
+	pub fn limits(mut self, limits: EthApiLimits) -> Self {
+		self.limits = limits;
+		self
+	}
+
+	/// Number of recovered transaction senders kept in memory.
+	pub fn sender_cache_size(mut self, sender_cache_size: usize) -> Self {
+		self.sender_cache_size = sender_cache_size;
+		self
+	}
+
+	/// Limits and cache sizes of `configuration`, keeping the limits it does
+	/// not set.
+	pub fn configuration(mut self, configuration: &EthConfiguration) -> Self {
+		self.limits.max_logs = configuration.max_past_logs.or(self.limits.max_logs);
+		self.limits.max_block_range = configuration.max_block_range.or(self.limits.max_block_range);
+		self.limits.max_traces = configuration.max_traces.or(self.limits.max_traces);
+		self.limits.max_simulate_calls = configuration.max_simulate_calls
+			.or(self.limits.max_simulate_calls);
+		self.limits.max_response_size = configuration.max_response_size
+			.or(self.limits.max_response_size);
+		self.limits.gas_cap = configuration.rpc_gas_cap.or(self.limits.gas_cap);
+		self.limits.execute_gas_limit_multiplier = configuration.execute_gas_limit_multiplier
+			.or(self.limits.execute_gas_limit_multiplier);
+		self.sender_cache_size = configuration.sender_cache_size;
+		self.block_data_cache_size = configuration.block_data_cache_size;
+		self
+	}
+
+	/// Cache of the Ethereum data of recent blocks, shared with the task
+	/// warming it.
+	pub fn block_data_cache(mut self, block_data_cache: Arc<EthBlockDataCache>) -> Self {
+		self.block_data_cache = Some(block_data_cache);
+		self
+	}
+
+	/// Substrate backend, which the `pending` state is collected from. Without
+	/// it, `pending` reads the latest state.
+	pub fn backend(mut self, backend: Arc<BE>) -> Self {
+		self.backend = Some(backend);
+		self
+	}
+
+	pub fn build(self) -> EthApi<B, C, P, CT, BE> {
+		let block_data_cache_size = self.block_data_cache_size;
+		EthApi {
+			client: self.client,
+			pool: self.pool,
+			convert_transaction: self.convert_transaction,
+			frontier_backend: self.frontier_backend,
+			blocking_pool: self.blocking_pool,
+			latest: self.latest,
+			is_authority: self.is_authority,
+			signers: self.signers,
+			mining_worker: self.mining_worker,
+			limits: self.limits,
+			hashrates: Hashrates::default(),
+			sender_cache: SenderCache::new(self.sender_cache_size),
+			runtime_cache: RuntimeCache::default(),
+			block_data_cache: self.block_data_cache.unwrap_or_else(
+				|| Arc::new(EthBlockDataCache::new(block_data_cache_size))
+			),
+			backend: self.backend,
+			pending_cache: Arc::new(PendingCache::default()),
+			_marker: PhantomData,
+		}
+	}
+}
@@ -0,0 +1,172 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of the Ethereum runtime api.
+//!
+//! A chain may add pallet-ethereum with a runtime upgrade, and a node binary
+//! may serve chains without it. Calls at a block whose runtime lacks the
+//! Ethereum runtime api are rejected with `METHOD_NOT_AVAILABLE`, rather than
+//! with the internal error of a failed runtime call.
+//!
+//! Runtimes from before version 2 of the api only serve the block and
+//! transaction lookups of version 1, the rpc falling back on them for the
+//! methods added since, such as `transaction_receipt`. Their `call` takes no
+//! overrides nor pending changes and only returns the output and gas used.
+//! They do not expose `validation_constants` either, the rpc checking requests
+//! to them against `legacy_validation_constants`. Runtimes from before version
+//! 3 have a `call_many` that takes no block override, and runtimes from before
+//! version 4 take block overrides without a base fee.
+
+use jsonrpc_core::{Error, ErrorCode, Result};
+use sp_api::{ApiExt, ProvideRuntimeApi, BlockId};
+use sp_runtime::traits::Block as BlockT;
+use ethereum_types::U256;
+use frontier_rpc_primitives::{EthereumRuntimeApi, ValidationConstants};
+
+use crate::internal_err;
+
+/// Error code of calls at a block without the Ethereum runtime api.
+pub const METHOD_NOT_AVAILABLE: i64 = -32004;
+
+/// Whether the runtime of block `id` exposes the Ethereum runtime api.
+pub fn ethereum_api_available<B, C>(client: &C, id: &BlockId<B>) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	client.runtime_api()
+		.has_api::<dyn EthereumRuntimeApi<B>>(id)
+		.map_err(|_| internal_err("fetch runtime version failed"))
+}
+
+/// Whether the runtime of block `id` has at least `version` of the Ethereum
+/// runtime api.
+fn api_version_available<B, C>(client: &C, id: &BlockId<B>, version: u32) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	client.runtime_api()
+		.has_api_with::<dyn EthereumRuntimeApi<B>, _>(id, |available| available >= version)
+		.map_err(|_| internal_err("fetch runtime version failed"))
+}
+
+/// Version of the Ethereum runtime api whose `call` returns a `DryRunOutcome`.
+pub(crate) const DRY_RUN_OUTCOME_VERSION: u32 = 2;
+
+/// Whether the runtime of block `id` has the `call` returning a
+/// `DryRunOutcome`, as opposed to the one of version 1 of the api.
+pub(crate) fn dry_run_outcome_available<B, C>(client: &C, id: &BlockId<B>) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	api_version_available(client, id, DRY_RUN_OUTCOME_VERSION)
+}
+
+/// Version of the Ethereum runtime api adding the methods beyond the lookups of
+/// version 1: `transaction_receipt`, `block_receipts_by_number`,
+/// `block_logs_bloom`, `extrinsic_filter`, `ethereum_extrinsic_indices`,
+/// `contract_creation`, `account_storage_root`, `account_range`...
+pub(crate) const EXTENDED_API_VERSION: u32 = 2;
+
+/// Whether the runtime of block `id` has the methods added by
+/// `EXTENDED_API_VERSION`.
+pub(crate) fn extended_api_available<B, C>(client: &C, id: &BlockId<B>) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	api_version_available(client, id, EXTENDED_API_VERSION)
+}
+
+/// Version of the Ethereum runtime api whose `call_many` takes a block override.
+pub(crate) const CALL_MANY_BLOCK_OVERRIDE_VERSION: u32 = 3;
+
+/// Whether the runtime of block `id` has the `call_many` taking a block
+/// override, as opposed to the one of version 2 of the api.
+pub(crate) fn call_many_block_override_available<B, C>(client: &C, id: &BlockId<B>) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	api_version_available(client, id, CALL_MANY_BLOCK_OVERRIDE_VERSION)
+}
+
+/// Version of the Ethereum runtime api whose block overrides take a base fee.
+pub(crate) const BASE_FEE_OVERRIDE_VERSION: u32 = 4;
+
+/// Whether the runtime of block `id` takes the block overrides with a base
+/// fee, as opposed to the `LegacyBlockOverride` of the versions before.
+pub(crate) fn base_fee_override_available<B, C>(client: &C, id: &BlockId<B>) -> Result<bool> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	api_version_available(client, id, BASE_FEE_OVERRIDE_VERSION)
+}
+
+/// Constants that requests to the runtime of block `id` are checked against.
+pub(crate) fn validation_constants<B, C>(client: &C, id: &BlockId<B>) -> Result<ValidationConstants> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if !dry_run_outcome_available(client, id)? {
+		return Ok(legacy_validation_constants());
+	}
+	client.runtime_api()
+		.validation_constants(id)
+		.map_err(|_| internal_err("fetch runtime validation constants failed"))
+}
+
+/// Constants of the runtimes from before version 2 of the api, which checked
+/// none: calls executed with all the gas a `u32` holds, and transactions of
+/// any size and without replay protection reached the pool.
+pub(crate) fn legacy_validation_constants() -> ValidationConstants {
+	ValidationConstants {
+		block_gas_limit: U256::from(u32::max_value()),
+		execute_gas_limit_multiplier: 1,
+		max_transaction_size: u32::max_value(),
+		allow_unprotected_txs: true,
+	}
+}
+
+/// Rejects calls at block `id` if its runtime lacks the Ethereum runtime api.
+pub(crate) fn ensure_ethereum_api<B, C>(client: &C, id: &BlockId<B>) -> Result<()> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if ethereum_api_available(client, id)? {
+		Ok(())
+	} else {
+		Err(Error {
+			code: ErrorCode::ServerError(METHOD_NOT_AVAILABLE),
+			message: format!("method not available at block {}", id),
+			data: None,
+		})
+	}
+}
+
+/// Error of a feature that the runtime of block `id` predates.
+pub(crate) fn not_supported_at<B: BlockT>(what: &str, id: &BlockId<B>) -> Error {
+	Error {
+		code: ErrorCode::ServerError(METHOD_NOT_AVAILABLE),
+		message: format!("{} not supported by the runtime at block {}", what, id),
+		data: None,
+	}
+}
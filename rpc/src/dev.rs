@@ -0,0 +1,207 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{collections::BTreeMap, sync::{Arc, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+use ethereum_types::U256;
+use futures::{FutureExt, SinkExt, TryFutureExt, channel::{mpsc, oneshot}};
+use jsonrpc_core::{BoxFuture, Result, Error};
+use sp_runtime::traits::{Block as BlockT, Header as _, NumberFor};
+use sp_consensus::SelectChain;
+use sc_client_api::backend::Backend;
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use sc_rpc_api::DenyUnsafe;
+use frontier_rpc_core::DevApi as DevApiT;
+
+use crate::internal_err;
+
+pub use frontier_rpc_core::DevApiServer;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct DevClockState {
+	/// Timestamp of the last authored block, in milliseconds.
+	last: u64,
+	/// Time to add to the next block, in milliseconds.
+	pending_increase: u64,
+	/// Total time added by `evm_increaseTime`, in milliseconds.
+	total_increase: u64,
+	/// Timestamp of the next block set by `evm_setNextBlockTimestamp`, in
+	/// milliseconds.
+	next: Option<u64>,
+}
+
+/// Block timestamps of a manual seal development chain, shared between the
+/// timestamp inherent data provider and the development RPC.
+///
+/// Timestamps advance by at least `step` milliseconds from one block to the
+/// next, so that each block gets a new Aura slot.
+#[derive(Clone)]
+pub struct DevClock {
+	step: u64,
+	state: Arc<Mutex<DevClockState>>,
+}
+
+impl DevClock {
+	pub fn new(step: u64) -> Self {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|now| now.as_millis() as u64)
+			.unwrap_or_default();
+		DevClock {
+			step,
+			state: Arc::new(Mutex::new(DevClockState { last: now, ..Default::default() })),
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<DevClockState> {
+		self.state.lock().expect("dev clock lock is never held across a panic; qed")
+	}
+
+	/// Timestamp of the next block, in milliseconds.
+	pub fn next_timestamp(&self) -> u64 {
+		let mut state = self.lock();
+		let timestamp = match state.next.take() {
+			Some(next) => next,
+			None => state.last + self.step + state.pending_increase,
+		};
+		state.last = timestamp;
+		state.pending_increase = 0;
+		timestamp
+	}
+}
+
+struct Snapshots<N> {
+	next_id: u64,
+	taken: BTreeMap<u64, (N, DevClockState)>,
+}
+
+pub struct DevApi<B: BlockT, SC, BE> {
+	select_chain: SC,
+	backend: Arc<BE>,
+	command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+	clock: DevClock,
+	snapshots: Mutex<Snapshots<NumberFor<B>>>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<B: BlockT, SC, BE> DevApi<B, SC, BE> {
+	pub fn new(
+		select_chain: SC,
+		backend: Arc<BE>,
+		command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+		clock: DevClock,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self {
+			select_chain,
+			backend,
+			command_sink,
+			clock,
+			snapshots: Mutex::new(Snapshots { next_id: 1, taken: BTreeMap::new() }),
+			deny_unsafe,
+		}
+	}
+}
+
+impl<B, SC, BE> DevApiT for DevApi<B, SC, BE> where
+	B: BlockT,
+	SC: SelectChain<B> + 'static,
+	BE: Backend<B> + 'static,
+{
+	fn increase_time(&self, seconds: u64) -> Result<u64> {
+		let mut state = self.clock.lock();
+		let increase = seconds.saturating_mul(1000);
+		state.pending_increase = state.pending_increase.saturating_add(increase);
+		state.total_increase = state.total_increase.saturating_add(increase);
+		Ok(state.total_increase / 1000)
+	}
+
+	fn set_next_block_timestamp(&self, timestamp: u64) -> Result<()> {
+		let mut state = self.clock.lock();
+		let timestamp = timestamp.saturating_mul(1000);
+		if timestamp < state.last + self.clock.step {
+			return Err(Error::invalid_params(format!(
+				"timestamp must be at least {} seconds after the last block",
+				self.clock.step / 1000,
+			)));
+		}
+		state.next = Some(timestamp);
+		Ok(())
+	}
+
+	fn mine(&self) -> BoxFuture<String> {
+		let mut command_sink = self.command_sink.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			command_sink.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			}).await.map_err(|_| internal_err("manual seal is not running"))?;
+
+			match receiver.await {
+				Ok(Ok(_)) => Ok("0x0".to_string()),
+				Ok(Err(e)) => Err(internal_err(&format!("sealing block failed: {:?}", e))),
+				Err(_) => Err(internal_err("manual seal is not running")),
+			}
+		};
+		Box::new(future.boxed().compat())
+	}
+
+	fn snapshot(&self) -> Result<U256> {
+		let header = self.select_chain
+			.best_chain()
+			.map_err(|_| internal_err("fetch header failed"))?;
+		let clock = *self.clock.lock();
+
+		let mut snapshots = self.snapshots.lock()
+			.expect("snapshots lock is never held across a panic; qed");
+		let id = snapshots.next_id;
+		snapshots.next_id += 1;
+		snapshots.taken.insert(id, (*header.number(), clock));
+		Ok(U256::from(id))
+	}
+
+	fn revert(&self, id: U256) -> Result<bool> {
+		// Reverting discards blocks from the database.
+		self.deny_unsafe.check_if_safe()?;
+		if id > U256::from(u64::max_value()) {
+			return Ok(false)
+		}
+		let id = id.low_u64();
+
+		let mut snapshots = self.snapshots.lock()
+			.expect("snapshots lock is never held across a panic; qed");
+		let (number, clock) = match snapshots.taken.get(&id) {
+			Some(snapshot) => *snapshot,
+			None => return Ok(false),
+		};
+		// Later snapshots refer to blocks that are reverted.
+		snapshots.taken.split_off(&id);
+
+		let header = self.select_chain
+			.best_chain()
+			.map_err(|_| internal_err("fetch header failed"))?;
+		if *header.number() > number {
+			self.backend
+				.revert(*header.number() - number)
+				.map_err(|e| internal_err(&format!("revert failed: {:?}", e)))?;
+		}
+		*self.clock.lock() = clock;
+
+		Ok(true)
+	}
+}
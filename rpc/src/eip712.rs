@@ -0,0 +1,246 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! EIP-712 hashing of typed data.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use ethereum_types::{H160, H256, U256};
+use serde_json::Value;
+use sha3::{Keccak256, Digest};
+use frontier_rpc_core::types::{Bytes, TypedData, TypedDataField};
+
+type Types = BTreeMap<String, Vec<TypedDataField>>;
+
+/// Hash of `data` to sign: `keccak256(0x19 ‖ 0x01 ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn hash(data: &TypedData) -> Result<H256, String> {
+	let mut bytes = vec![0x19, 0x01];
+	bytes.extend_from_slice(hash_struct(&data.types, "EIP712Domain", &data.domain)?.as_bytes());
+	bytes.extend_from_slice(hash_struct(&data.types, &data.primary_type, &data.message)?.as_bytes());
+	Ok(keccak(&bytes))
+}
+
+fn keccak(bytes: &[u8]) -> H256 {
+	H256::from_slice(Keccak256::digest(bytes).as_slice())
+}
+
+/// Type without its array dimensions.
+fn base_type(type_: &str) -> &str {
+	type_.split('[').next().unwrap_or(type_)
+}
+
+fn fields<'a>(types: &'a Types, name: &str) -> Result<&'a [TypedDataField], String> {
+	types.get(name).map(|fields| &fields[..]).ok_or_else(|| format!("unknown type {}", name))
+}
+
+/// Struct types referenced by `name`, directly or not.
+fn dependencies(types: &Types, name: &str, found: &mut BTreeSet<String>) {
+	for field in types.get(name).into_iter().flatten() {
+		let base = base_type(&field.type_);
+		if types.contains_key(base) && found.insert(base.to_string()) {
+			dependencies(types, base, found);
+		}
+	}
+}
+
+/// `encodeType`: the struct, then the structs it references sorted by name.
+fn encode_type(types: &Types, name: &str) -> Result<String, String> {
+	let mut referenced = BTreeSet::new();
+	dependencies(types, name, &mut referenced);
+	referenced.remove(name);
+
+	let mut encoded = String::new();
+	for name in std::iter::once(name).chain(referenced.iter().map(|name| name.as_str())) {
+		let members = fields(types, name)?.iter()
+			.map(|field| format!("{} {}", field.type_, field.name))
+			.collect::<Vec<_>>();
+		encoded.push_str(&format!("{}({})", name, members.join(",")));
+	}
+	Ok(encoded)
+}
+
+/// `hashStruct`: hash of the type followed by the encoded members.
+fn hash_struct(types: &Types, name: &str, value: &Value) -> Result<H256, String> {
+	let mut bytes = keccak(encode_type(types, name)?.as_bytes()).as_bytes().to_vec();
+	for field in fields(types, name)? {
+		let member = value.get(&field.name)
+			.ok_or_else(|| format!("missing value of {}.{}", name, field.name))?;
+		bytes.extend_from_slice(encode_value(types, &field.type_, member)?.as_bytes());
+	}
+	Ok(keccak(&bytes))
+}
+
+/// `encodeData` of a member, as a 32 bytes word.
+fn encode_value(types: &Types, type_: &str, value: &Value) -> Result<H256, String> {
+	let invalid = || format!("invalid {} value {}", type_, value);
+
+	if type_.ends_with(']') {
+		let element = &type_[..type_.rfind('[').ok_or_else(invalid)?];
+		let mut bytes = Vec::new();
+		for item in value.as_array().ok_or_else(invalid)? {
+			bytes.extend_from_slice(encode_value(types, element, item)?.as_bytes());
+		}
+		return Ok(keccak(&bytes));
+	}
+	if types.contains_key(type_) {
+		return hash_struct(types, type_, value);
+	}
+
+	match type_ {
+		"string" => Ok(keccak(value.as_str().ok_or_else(invalid)?.as_bytes())),
+		"bytes" => Ok(keccak(&parse_bytes(value).ok_or_else(invalid)?)),
+		"bool" => Ok(H256::from_low_u64_be(value.as_bool().ok_or_else(invalid)? as u64)),
+		"address" => {
+			let address: H160 = serde_json::from_value(value.clone()).map_err(|_| invalid())?;
+			Ok(address.into())
+		},
+		_ if type_.starts_with("bytes") => {
+			let bytes = parse_bytes(value).ok_or_else(invalid)?;
+			if bytes.len() > 32 {
+				return Err(invalid());
+			}
+			let mut word = H256::zero();
+			word.as_bytes_mut()[..bytes.len()].copy_from_slice(&bytes);
+			Ok(word)
+		},
+		_ if type_.starts_with("uint") => parse_integer(value, false).map(from_uint).ok_or_else(invalid),
+		_ if type_.starts_with("int") => parse_integer(value, true).map(from_uint).ok_or_else(invalid),
+		_ => Err(format!("unknown type {}", type_)),
+	}
+}
+
+fn from_uint(value: U256) -> H256 {
+	let mut word = H256::zero();
+	value.to_big_endian(word.as_bytes_mut());
+	word
+}
+
+fn parse_bytes(value: &Value) -> Option<Vec<u8>> {
+	serde_json::from_value::<Bytes>(value.clone()).ok().map(|bytes| bytes.0)
+}
+
+/// Integer given as a JSON number, or as a decimal or `0x` hex string.
+/// Negative values of signed types are encoded in two's complement.
+fn parse_integer(value: &Value, signed: bool) -> Option<U256> {
+	let (negative, magnitude) = match value {
+		Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+			(Some(number), _) => (false, U256::from(number)),
+			(None, Some(number)) => (true, U256::from(number.wrapping_neg() as u64)),
+			_ => return None,
+		},
+		Value::String(string) => {
+			let (negative, digits) = if string.starts_with('-') {
+				(true, &string[1..])
+			} else {
+				(false, &string[..])
+			};
+			let magnitude = if digits.starts_with("0x") {
+				U256::from_str(&digits[2..]).ok()?
+			} else {
+				U256::from_dec_str(digits).ok()?
+			};
+			(negative, magnitude)
+		},
+		_ => return None,
+	};
+
+	match (negative, signed) {
+		(false, _) => Some(magnitude),
+		(true, true) => Some((!magnitude).overflowing_add(U256::one()).0),
+		(true, false) => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The `Mail` example of the EIP-712 specification.
+	fn mail() -> TypedData {
+		serde_json::from_str(r#"{
+			"types": {
+				"EIP712Domain": [
+					{ "name": "name", "type": "string" },
+					{ "name": "version", "type": "string" },
+					{ "name": "chainId", "type": "uint256" },
+					{ "name": "verifyingContract", "type": "address" }
+				],
+				"Person": [
+					{ "name": "name", "type": "string" },
+					{ "name": "wallet", "type": "address" }
+				],
+				"Mail": [
+					{ "name": "from", "type": "Person" },
+					{ "name": "to", "type": "Person" },
+					{ "name": "contents", "type": "string" }
+				]
+			},
+			"primaryType": "Mail",
+			"domain": {
+				"name": "Ether Mail",
+				"version": "1",
+				"chainId": 1,
+				"verifyingContract": "0xcccccccccccccccccccccccccccccccccccccccc"
+			},
+			"message": {
+				"from": { "name": "Cow", "wallet": "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826" },
+				"to": { "name": "Bob", "wallet": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb" },
+				"contents": "Hello, Bob!"
+			}
+		}"#).unwrap()
+	}
+
+	fn h256(hex: &str) -> H256 {
+		H256::from_str(hex).unwrap()
+	}
+
+	#[test]
+	fn mail_type_should_be_encoded() {
+		let data = mail();
+		assert_eq!(
+			encode_type(&data.types, "Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+		);
+		assert_eq!(
+			keccak(encode_type(&data.types, "Mail").unwrap().as_bytes()),
+			h256("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"),
+		);
+	}
+
+	#[test]
+	fn mail_should_be_hashed() {
+		let data = mail();
+		assert_eq!(
+			hash_struct(&data.types, "EIP712Domain", &data.domain).unwrap(),
+			h256("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"),
+		);
+		assert_eq!(
+			hash_struct(&data.types, "Mail", &data.message).unwrap(),
+			h256("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"),
+		);
+		assert_eq!(
+			hash(&data).unwrap(),
+			h256("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"),
+		);
+	}
+
+	#[test]
+	fn missing_member_should_be_rejected() {
+		let mut data = mail();
+		data.message.as_object_mut().unwrap().remove("contents");
+		assert_eq!(hash(&data), Err("missing value of Mail.contents".to_string()));
+	}
+}
@@ -0,0 +1,266 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export of the Ethereum data of imported blocks, for data pipelines that
+//! would otherwise poll the rpc.
+//!
+//! `export_blocks` writes one JSON object per line for every imported block: its
+//! Substrate hash, whether it became the best block, and the Ethereum block
+//! with its full transactions and receipts.
+//!
+//! `export_range` writes the blocks, transactions or receipts of a range of the
+//! best chain, as newline delimited JSON or CSV.
+
+use std::{io::Write, str::FromStr, sync::Arc};
+use ethereum_types::H256;
+use futures::{future, Future, StreamExt};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sp_runtime::traits::{Block as BlockT, Header as _, UniqueSaturatedInto};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use sc_client_api::BlockchainEvents;
+use frontier_rpc_core::types::{BlockTransactions, BlockWithReceipts};
+use frontier_rpc_primitives::EthereumRuntimeApi;
+
+use crate::{block_with_receipts_build, capability::extended_api_available};
+
+/// Line of the export.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedBlock {
+	/// Hash of the Substrate block
+	substrate_hash: H256,
+	/// Whether the block became the best block when imported
+	is_new_best: bool,
+	#[serde(flatten)]
+	block: BlockWithReceipts,
+}
+
+/// Write the Ethereum data of every block imported by `client` to `sink`, as
+/// newline delimited JSON. Blocks of every fork are exported as they are
+/// imported, so consumers follow reorgs with `isNewBest`.
+pub fn export_blocks<B, C, W>(client: Arc<C>, mut sink: W) -> impl Future<Output = ()> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockchainEvents<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeApi<B>,
+	W: Write + Send + 'static,
+{
+	client.import_notification_stream().for_each(move |notification| {
+		let id = BlockId::Hash(notification.hash);
+		let number = UniqueSaturatedInto::<u32>::unique_saturated_into(*notification.header.number());
+		let api = client.runtime_api();
+
+		// Blocks without an Ethereum block, such as genesis, are skipped.
+		if let Ok((Some(block), statuses)) = api.block_by_number(&id, number) {
+			// Runtimes without `block_receipts_by_number` export no receipts.
+			let receipts = match extended_api_available(client.as_ref(), &id) {
+				Ok(true) => api.block_receipts_by_number(&id, number).ok().flatten(),
+				_ => None,
+			};
+			let line = ExportedBlock {
+				substrate_hash: notification.hash,
+				is_new_best: notification.is_new_best,
+				block: block_with_receipts_build(block, statuses, receipts),
+			};
+
+			let written = serde_json::to_writer(&mut sink, &line)
+				.map_err(std::io::Error::from)
+				.and_then(|_| sink.write_all(b"\n"))
+				.and_then(|_| sink.flush());
+			if let Err(e) = written {
+				log::warn!(target: "export", "Failed to export block {}: {}", notification.hash, e);
+			}
+		}
+
+		future::ready(())
+	})
+}
+
+/// Records written by `export_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportRecords {
+	/// One record per block, with its full transactions.
+	Blocks,
+	/// One record per transaction.
+	Transactions,
+	/// One record per receipt.
+	Receipts,
+}
+
+impl FromStr for ExportRecords {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"blocks" => Ok(ExportRecords::Blocks),
+			"transactions" => Ok(ExportRecords::Transactions),
+			"receipts" => Ok(ExportRecords::Receipts),
+			other => Err(format!(
+				"unknown records `{}`, expected blocks, transactions or receipts", other
+			)),
+		}
+	}
+}
+
+/// Output format of `export_range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+	/// One JSON object per line.
+	Json,
+	/// Comma separated values, with a header line.
+	Csv,
+}
+
+impl FromStr for ExportFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"json" => Ok(ExportFormat::Json),
+			"csv" => Ok(ExportFormat::Csv),
+			other => Err(format!("unknown format `{}`, expected json or csv", other)),
+		}
+	}
+}
+
+/// Write the `records` of the Ethereum blocks `from..=to` of the best chain to
+/// `sink`, `to` defaulting to the best block. Returns the number of records
+/// written.
+///
+/// Records hold the fields of the rpc representation. Only `fields` are written
+/// when not empty, and they are the CSV columns in that order; CSV columns
+/// otherwise are the fields of the first record. In CSV, objects and arrays are
+/// written as JSON.
+pub fn export_range<B, C, W>(
+	client: &C,
+	from: u32,
+	to: Option<u32>,
+	records: ExportRecords,
+	format: ExportFormat,
+	fields: &[String],
+	sink: &mut W,
+) -> Result<u64, String> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+	W: Write,
+{
+	let info = client.info();
+	let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(info.best_number);
+	let to = to.unwrap_or(best_number).min(best_number);
+	let id = BlockId::Hash(info.best_hash);
+	let api = client.runtime_api();
+	let with_receipts = extended_api_available(client, &id)
+		.map_err(|_| "cannot read the runtime version".to_string())?;
+
+	let mut columns: Option<Vec<String>> = None;
+	let mut written = 0u64;
+	for number in from..=to {
+		let (block, statuses) = api.block_by_number(&id, number)
+			.map_err(|_| format!("cannot read block {}", number))?;
+		// Blocks without an Ethereum block, such as genesis, are skipped.
+		let block = match block {
+			Some(block) => block,
+			None => continue,
+		};
+		let receipts = if with_receipts {
+			api.block_receipts_by_number(&id, number)
+				.map_err(|_| format!("cannot read receipts of block {}", number))?
+		} else {
+			None
+		};
+		let block = block_with_receipts_build(block, statuses, receipts);
+
+		let values = match records {
+			ExportRecords::Blocks => vec![serde_json::to_value(&block.block)],
+			ExportRecords::Transactions => match &block.block.transactions {
+				BlockTransactions::Full(transactions) =>
+					transactions.iter().map(serde_json::to_value).collect(),
+				BlockTransactions::Hashes(_) => Vec::new(),
+			},
+			ExportRecords::Receipts => block.receipts.iter().flatten()
+				.map(serde_json::to_value)
+				.collect(),
+		};
+
+		for value in values {
+			let record = match value.map_err(|e| e.to_string())? {
+				Value::Object(record) => record,
+				_ => return Err("records must be JSON objects".into()),
+			};
+			let line = match format {
+				ExportFormat::Json => json_line(record, fields)?,
+				ExportFormat::Csv => {
+					let mut line = String::new();
+					if columns.is_none() {
+						let header = if fields.is_empty() {
+							record.keys().cloned().collect()
+						} else {
+							fields.to_vec()
+						};
+						line = csv_line(header.iter().map(|column| Value::String(column.clone())));
+						columns = Some(header);
+					}
+					let columns = columns.as_ref().expect("columns are set above; qed");
+					line.push_str(&csv_line(
+						columns.iter().map(|column| record.get(column).cloned().unwrap_or(Value::Null))
+					));
+					line
+				},
+			};
+			sink.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+			written += 1;
+		}
+	}
+
+	sink.flush().map_err(|e| e.to_string())?;
+	Ok(written)
+}
+
+/// JSON line of `record`, restricted to `fields` when not empty.
+fn json_line(record: Map<String, Value>, fields: &[String]) -> Result<String, String> {
+	let record = if fields.is_empty() {
+		record
+	} else {
+		fields.iter()
+			.map(|field| (field.clone(), record.get(field).cloned().unwrap_or(Value::Null)))
+			.collect()
+	};
+	let mut line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+	line.push('\n');
+	Ok(line)
+}
+
+/// CSV line of `values`. Strings are written as is, nulls as empty cells and
+/// other values as JSON, quoted when needed.
+fn csv_line(values: impl Iterator<Item=Value>) -> String {
+	let cells: Vec<String> = values.map(|value| {
+		let cell = match value {
+			Value::Null => String::new(),
+			Value::String(s) => s,
+			other => other.to_string(),
+		};
+		if cell.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+			format!("\"{}\"", cell.replace('"', "\"\""))
+		} else {
+			cell
+		}
+	}).collect();
+	let mut line = cells.join(",");
+	line.push('\n');
+	line
+}
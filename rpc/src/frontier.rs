@@ -0,0 +1,315 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use sp_runtime::traits::{Block as BlockT, Header as _, UniqueSaturatedInto};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use sc_client_api::BlockBackend;
+use fp_consensus::ConsensusLog;
+use fc_api::TransactionMetadata;
+use sha3::{Keccak256, Digest};
+use frontier_rpc_core::FrontierApi as FrontierApiT;
+use frontier_rpc_core::types::{
+	BlockNumber, BlockWithReceipts, Bytes, ContractCreation, Filter, LogsPage, SubstrateExtrinsic,
+	TransactionFinality,
+};
+use frontier_rpc_primitives::EthereumRuntimeApi;
+
+use crate::{
+	EthApiLimits, LatestBlock, internal_err, latest_header, lookup_span,
+	native_block_number, block_with_receipts_build, filter_logs_page,
+	capability::{extended_api_available, not_supported_at},
+};
+
+pub use frontier_rpc_core::FrontierApiServer;
+
+/// Number of logs after which a `frontier_getLogsPaginated` page ends, unless
+/// set by the filter limit or the logs limit.
+pub const LOGS_PAGE_SIZE: usize = 10_000;
+
+pub struct FrontierApi<B: BlockT, C> {
+	client: Arc<C>,
+	frontier_backend: Arc<dyn fc_api::Backend<B>>,
+	latest: LatestBlock,
+	limits: EthApiLimits,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> FrontierApi<B, C> {
+	pub fn new(
+		client: Arc<C>,
+		frontier_backend: Arc<dyn fc_api::Backend<B>>,
+		latest: LatestBlock,
+		limits: EthApiLimits,
+	) -> Self {
+		Self { client, frontier_backend, latest, limits, _marker: PhantomData }
+	}
+}
+
+impl<B, C> FrontierApi<B, C> where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+{
+	/// Ethereum block built in a Substrate block, from its consensus log.
+	fn consensus_log(&self, block_hash: H256) -> Result<Option<ConsensusLog>> {
+		let header = self.client.header(BlockId::Hash(block_hash))
+			.map_err(|_| internal_err("fetch header failed"))?;
+		Ok(header.and_then(|header| fp_consensus::find_log(header.digest()).ok()))
+	}
+
+	/// Whether a Substrate block is on the canonical chain. Mappings may point
+	/// to blocks of abandoned forks, which are not served.
+	fn is_canonical(&self, block_hash: H256) -> Result<bool> {
+		let header = match self.client.header(BlockId::Hash(block_hash))
+			.map_err(|_| internal_err("fetch header failed"))?
+		{
+			Some(header) => header,
+			None => return Ok(false),
+		};
+		let canonical_hash = self.client.hash(*header.number())
+			.map_err(|_| internal_err("fetch canonical hash failed"))?;
+		Ok(canonical_hash == Some(block_hash))
+	}
+
+	/// Inclusion of an Ethereum transaction. A transaction without mapping is
+	/// reported as not found rather than unknown once older transactions
+	/// were pruned, as it may be one of them.
+	fn transaction_metadata(&self, hash: H256) -> Result<Option<TransactionMetadata<B::Hash>>> {
+		let metadata = self.frontier_backend.transaction_metadata(&hash)
+			.map_err(|_| internal_err("fetch transaction mapping failed"))?;
+		if metadata.is_some() {
+			return Ok(metadata)
+		}
+		match self.frontier_backend.transactions_indexed_from()
+			.map_err(|_| internal_err("fetch transaction index range failed"))?
+		{
+			Some(from) => Err(Error {
+				code: ErrorCode::ServerError(-32000),
+				message: format!(
+					"transaction not found, only transactions from block {} on are indexed", from
+				),
+				data: None,
+			}),
+			None => Ok(None),
+		}
+	}
+
+	/// Indices of the Ethereum transaction extrinsics of a Substrate block.
+	fn ethereum_extrinsic_indices(&self, block_hash: H256) -> Result<Option<Vec<u32>>> {
+		let id = BlockId::Hash(block_hash);
+		let body = match self.client.block_body(&id)
+			.map_err(|_| internal_err("fetch block body failed"))?
+		{
+			Some(body) => body,
+			None => return Ok(None),
+		};
+		if !extended_api_available(self.client.as_ref(), &id)? {
+			return Err(not_supported_at("Ethereum extrinsic lookups", &id));
+		}
+		self.client.runtime_api()
+			.ethereum_extrinsic_indices(&id, body)
+			.map(Some)
+			.map_err(|_| internal_err("fetch runtime extrinsic indices failed"))
+	}
+}
+
+impl<B, C> FrontierApiT for FrontierApi<B, C> where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+{
+	fn substrate_hash(&self, hash: H256) -> Result<Option<H256>> {
+		let block_hash = self.frontier_backend.block_hash(&hash)
+			.map_err(|_| internal_err("fetch block mapping failed"))?;
+		match block_hash {
+			Some(block_hash) if self.is_canonical(block_hash)? => Ok(Some(block_hash)),
+			_ => Ok(None),
+		}
+	}
+
+	fn ethereum_hash(&self, hash: H256) -> Result<Option<H256>> {
+		Ok(self.consensus_log(hash)?.map(|log| log.block_hash()))
+	}
+
+	fn substrate_extrinsic(&self, hash: H256) -> Result<Option<SubstrateExtrinsic>> {
+		let metadata = match self.transaction_metadata(hash)? {
+			Some(metadata) => metadata,
+			None => return Ok(None),
+		};
+		if !self.is_canonical(metadata.block_hash)? {
+			return Ok(None)
+		}
+		let indices = match self.ethereum_extrinsic_indices(metadata.block_hash)? {
+			Some(indices) => indices,
+			None => return Ok(None),
+		};
+
+		Ok(indices.get(metadata.ethereum_index as usize).map(|index| SubstrateExtrinsic {
+			block_hash: metadata.block_hash,
+			extrinsic_index: U256::from(*index),
+		}))
+	}
+
+	fn ethereum_transaction_hash(&self, hash: H256, index: U256) -> Result<Option<H256>> {
+		if index > U256::from(u32::max_value()) {
+			return Ok(None)
+		}
+		let index = index.low_u32();
+
+		let indices = match self.ethereum_extrinsic_indices(hash)? {
+			Some(indices) => indices,
+			None => return Ok(None),
+		};
+		let ethereum_index = match indices.iter().position(|i| *i == index) {
+			Some(ethereum_index) => ethereum_index,
+			None => return Ok(None),
+		};
+
+		Ok(self.consensus_log(hash)?
+			.and_then(|log| log.transaction_hashes().get(ethereum_index).cloned()))
+	}
+
+	fn block_range(
+		&self,
+		from: BlockNumber,
+		to: BlockNumber,
+		with_receipts: bool,
+	) -> Result<Vec<BlockWithReceipts>> {
+		let header = latest_header(self.client.as_ref(), self.latest)?;
+		let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(*header.number());
+
+		let from = native_block_number(&*self.client, self.latest, Some(from))?
+			.unwrap_or(best_number);
+		let to = native_block_number(&*self.client, self.latest, Some(to))?
+			.unwrap_or(best_number)
+			.min(best_number);
+		if from > to {
+			return Ok(Vec::new())
+		}
+		self.limits.check_block_range(from as u64, to as u64)?;
+
+		let id = BlockId::Hash(header.hash());
+		if with_receipts && !extended_api_available(self.client.as_ref(), &id)? {
+			return Err(not_supported_at("block receipts", &id));
+		}
+		let mut blocks = Vec::new();
+		for number in from..=to {
+			let span = lookup_span("block_by_number", &number);
+			let _enter = span.enter();
+			let (block, statuses) = match self.client.runtime_api().block_by_number(&id, number)
+				.map_err(|_| internal_err("fetch runtime block failed"))?
+			{
+				(Some(block), statuses) => (block, statuses),
+				(None, _) => continue,
+			};
+
+			let receipts = if with_receipts {
+				Some(
+					self.client.runtime_api().block_receipts_by_number(&id, number)
+						.map_err(|_| internal_err("fetch runtime receipts failed"))?
+						.unwrap_or_default()
+				)
+			} else {
+				None
+			};
+
+			blocks.push(block_with_receipts_build(block, statuses, receipts));
+		}
+
+		self.limits.check_response_size(&blocks)?;
+		Ok(blocks)
+	}
+
+	fn contract_creation(&self, address: H160) -> Result<Option<ContractCreation>> {
+		let header = latest_header(self.client.as_ref(), self.latest)?;
+		let id = BlockId::Hash(header.hash());
+		if !extended_api_available(self.client.as_ref(), &id)? {
+			return Err(not_supported_at("contract creation lookups", &id));
+		}
+
+		let transaction_hash = match self.client.runtime_api().contract_creation(&id, address)
+			.map_err(|_| internal_err("fetch runtime contract creation failed"))?
+		{
+			Some(transaction_hash) => transaction_hash,
+			None => return Ok(None),
+		};
+		let (transaction, block, status, _) = match self.client.runtime_api()
+			.transaction_by_hash(&id, transaction_hash)
+			.map_err(|_| internal_err("fetch runtime transaction failed"))?
+		{
+			Some(found) => found,
+			None => return Ok(None),
+		};
+
+		Ok(Some(ContractCreation {
+			transaction_hash,
+			block_hash: H256::from_slice(
+				Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+			),
+			block_number: block.header.number,
+			creator: status.from,
+			creation_code: Bytes(transaction.input),
+		}))
+	}
+
+	fn transaction_finality(&self, hash: H256) -> Result<Option<TransactionFinality>> {
+		let metadata = match self.transaction_metadata(hash)? {
+			Some(metadata) => metadata,
+			None => return Ok(None),
+		};
+		if !self.is_canonical(metadata.block_hash)? {
+			return Ok(None)
+		}
+		let number = match self.client.number(metadata.block_hash)
+			.map_err(|_| internal_err("fetch block number failed"))?
+		{
+			Some(number) => UniqueSaturatedInto::<u64>::unique_saturated_into(number),
+			None => return Ok(None),
+		};
+
+		let info = self.client.info();
+		let best_number = UniqueSaturatedInto::<u64>::unique_saturated_into(info.best_number);
+		let finalized_number = UniqueSaturatedInto::<u64>::unique_saturated_into(info.finalized_number);
+		Ok(Some(TransactionFinality {
+			block_hash: metadata.block_hash,
+			block_number: U256::from(number),
+			confirmations: U256::from(best_number.saturating_sub(number) + 1),
+			finalized: number <= finalized_number,
+		}))
+	}
+
+	fn logs_paginated(&self, filter: Filter, cursor: Option<U256>) -> Result<LogsPage> {
+		let page_size = filter.limit
+			.or(self.limits.max_logs)
+			.unwrap_or(LOGS_PAGE_SIZE)
+			.min(self.limits.max_logs.unwrap_or(usize::max_value()))
+			.max(1);
+		filter_logs_page(
+			self.client.as_ref(),
+			self.frontier_backend.as_ref(),
+			self.latest,
+			&self.limits,
+			filter,
+			cursor,
+			page_size,
+		)
+	}
+}
@@ -0,0 +1,75 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block served as `latest`.
+//!
+//! Integrations that must never observe a block which could still be reorged
+//! away have the rpc serve an older block as `latest`: the one a fixed number
+//! of blocks behind the best block, or the last finalized one.
+
+use std::str::FromStr;
+use jsonrpc_core::Result;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Saturating}};
+use sp_blockchain::HeaderBackend;
+
+use crate::internal_err;
+
+/// Block served as `latest` by the Ethereum rpc.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatestBlock {
+	/// The best block.
+	Best,
+	/// The block this many blocks behind the best block.
+	Depth(u32),
+	/// The last finalized block.
+	Finalized,
+}
+
+impl Default for LatestBlock {
+	fn default() -> Self {
+		LatestBlock::Best
+	}
+}
+
+impl FromStr for LatestBlock {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"best" => Ok(LatestBlock::Best),
+			"finalized" => Ok(LatestBlock::Finalized),
+			other => other.parse().map(LatestBlock::Depth).map_err(|_| format!(
+				"unknown latest block `{}`, expected best, finalized or a number of blocks", other
+			)),
+		}
+	}
+}
+
+/// Header of the block served as `latest`, from the chain info of `client`.
+pub(crate) fn latest_header<B, C>(client: &C, latest: LatestBlock) -> Result<B::Header> where
+	B: BlockT,
+	C: HeaderBackend<B>,
+{
+	let info = client.info();
+	let id = match latest {
+		LatestBlock::Best => BlockId::Hash(info.best_hash),
+		LatestBlock::Depth(depth) => BlockId::Number(info.best_number.saturating_sub(depth.into())),
+		LatestBlock::Finalized => BlockId::Hash(info.finalized_hash),
+	};
+	client.header(id)
+		.map_err(|_| internal_err("fetch header failed"))?
+		.ok_or_else(|| internal_err("fetch header failed"))
+}
@@ -17,25 +17,100 @@
 use std::{marker::PhantomData, sync::Arc};
 use std::collections::BTreeMap;
 use ethereum::{Block as EthereumBlock, Transaction as EthereumTransaction};
-use ethereum_types::{H160, H256, H64, U256, U64};
-use jsonrpc_core::{BoxFuture, Result, ErrorCode, Error, futures::future::{self, Future}};
+use ethereum_types::{H160, H256, H64, U256, U64, Bloom, BloomInput};
+use jsonrpc_core::{BoxFuture, Result, ErrorCode, Error, Value, futures::future::{self, Future}};
 use futures::future::TryFutureExt;
 use sp_runtime::traits::{Block as BlockT, Header as _, UniqueSaturatedInto};
-use sp_runtime::transaction_validity::TransactionSource;
-use sp_api::{ProvideRuntimeApi, BlockId};
-use sp_consensus::SelectChain;
-use sp_transaction_pool::TransactionPool;
+use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
+use sp_api::{ApiExt, ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use sp_transaction_pool::{
+	TransactionPool, InPoolTransaction, error::{Error as PoolError, IntoPoolError},
+};
 use sc_client_api::backend::{StorageProvider, Backend, StateBackend};
 use sha3::{Keccak256, Digest};
+use serde::Serialize;
 use sp_runtime::traits::BlakeTwo256;
 use frontier_rpc_core::EthApi as EthApiT;
 use frontier_rpc_core::types::{
-	BlockNumber, Bytes, CallRequest, EthAccount, Filter, Index, Log, Receipt, RichBlock,
-	SyncStatus, Transaction, Work, Rich, Block, BlockTransactions
+	AccountState, BlockNumber, Bytes, CallRequest, CallStateOverride, CallBlockOverride, EthAccount,
+	Filter, Header, Index, Log, LogsPage, Receipt, RichBlock, RichHeader, SimulatePayload,
+	SimulatedBlock, SimulatedCall, SimulatedCallError, SyncStatus, Transaction, Work, Rich, Block,
+	BlockTransactions, BlockWithReceipts, VariadicValue,
+};
+use frontier_rpc_primitives::{
+	EthereumRuntimeApi, ConvertTransaction, TransactionStatus, AccountOverride, BlockOverride,
+	DryRunCall, DryRunOutcome, ExecutionError, PendingChanges, SimulatedBlock as SimulatedRuntimeBlock,
 };
-use frontier_rpc_primitives::{EthereumRuntimeApi, ConvertTransaction, TransactionStatus};
+use pallet_ethereum::trie;
+
+mod account;
+mod admin;
+mod auth;
+mod block_cache;
+mod blocking;
+mod builder;
+mod capability;
+mod dev;
+mod eip712;
+mod export;
+mod frontier;
+mod latest;
+mod light;
+mod logger;
+mod mining;
+mod modules;
+mod namespace;
+mod pending;
+mod personal;
+mod pubsub;
+mod rate_limit;
+mod runtime_cache;
+mod schema;
+mod signer;
+mod spans;
+mod tasks;
+mod trace;
 
 pub use frontier_rpc_core::EthApiServer;
+pub use account::{AccountApi, AccountApiServer};
+pub use admin::{AdminApi, AdminApiServer, NetworkPeer, NetworkState, NetworkStateHandle};
+pub use auth::{Authenticator, UNAUTHORIZED};
+pub use block_cache::{EthBlockData, EthBlockDataCache, warm_block_cache};
+pub use blocking::BlockingTaskPool;
+pub use builder::{EthApiBuilder, EthConfig, EthConfiguration, EthApiFor};
+pub use capability::{ethereum_api_available, METHOD_NOT_AVAILABLE};
+use capability::{
+	BASE_FEE_OVERRIDE_VERSION, DRY_RUN_OUTCOME_VERSION, dry_run_outcome_available,
+	call_many_block_override_available, base_fee_override_available, extended_api_available,
+	ensure_ethereum_api, legacy_validation_constants, not_supported_at, validation_constants,
+};
+pub use dev::{DevApi, DevApiServer, DevClock};
+pub use export::{export_blocks, export_range, ExportFormat, ExportRecords};
+pub use frontier::{FrontierApi, FrontierApiServer};
+pub use latest::LatestBlock;
+use latest::latest_header;
+pub use light::LightEthApi;
+pub use logger::CallLogger;
+pub use mining::EthMiningWorker;
+use mining::Hashrates;
+pub use modules::{RpcModules, RpcModulesApiServer};
+pub use namespace::{EthApiNamespace, EthApiSet};
+use pending::{PendingCache, PendingState};
+pub use personal::{PersonalApi, PersonalApiServer, PersonalSigner};
+pub use pubsub::{
+	EthPubSubApi, EthPubSubApiServer, EthPubSubHub, EthPubSubWorker, OverflowPolicy,
+	SubscriptionLimits,
+};
+pub use rate_limit::{PeerAddress, RateLimiter, LIMIT_EXCEEDED};
+use runtime_cache::RuntimeCache;
+pub use signer::{
+	EthSigner, EthDevSigner, EthSignApi, EthSignApiServer, SenderCache, PoolSender, recover_sender,
+};
+pub use spans::instrument_spans;
+pub use tasks::{spawn_frontier_tasks, FrontierTasksConfig};
+use spans::lookup_span;
+pub use trace::{TraceApi, TraceApiServer};
 
 fn internal_err(message: &str) -> Error {
 	Error {
@@ -45,25 +120,130 @@ fn internal_err(message: &str) -> Error {
 	}
 }
 
-pub struct EthApi<B: BlockT, C, SC, P, CT, BE> {
+/// Error of a call that did not succeed, carrying the revert data of reverted
+/// calls like geth.
+fn execution_err(error: ExecutionError, output: Vec<u8>) -> Error {
+	match error {
+		ExecutionError::Reverted => Error {
+			code: ErrorCode::ServerError(3),
+			message: error.message().to_string(),
+			data: Some(Value::String(format!(
+				"0x{}",
+				output.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+			))),
+		},
+		_ => Error {
+			code: ErrorCode::ServerError(-32000),
+			message: error.message().to_string(),
+			data: None,
+		},
+	}
+}
+
+fn limit_exceeded_err(message: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(-32005),
+		message: message.to_string(),
+		data: None
+	}
+}
+
+/// Limits on what a single RPC request is allowed to use or return, so that a
+/// public endpoint can bound the worst-case resources used per request. `None`
+/// means unbounded.
+#[derive(Clone, Debug)]
+pub struct EthApiLimits {
+	/// Maximum size in bytes of a serialized response.
+	pub max_response_size: Option<usize>,
+	/// Maximum number of logs returned by a single `eth_getLogs` query.
+	pub max_logs: Option<usize>,
+	/// Maximum number of traces returned by a single trace query.
+	pub max_traces: Option<usize>,
+	/// Maximum number of calls, over all its blocks, of a single
+	/// `eth_simulateV1` request.
+	pub max_simulate_calls: Option<usize>,
+	/// Maximum number of blocks a ranged query is allowed to span.
+	pub max_block_range: Option<u64>,
+	/// Gas available to calls executed off-chain (`eth_call`, `eth_estimateGas`),
+	/// as a multiple of the block gas limit. `None` follows the runtime.
+	pub execute_gas_limit_multiplier: Option<u64>,
+	/// Gas available to calls executed off-chain, whatever the block gas limit.
+	pub gas_cap: Option<U256>,
+	/// Whether `eth_sendRawTransaction` accepts transactions without EIP-155
+	/// replay protection.
+	pub allow_unprotected_txs: bool,
+}
+
+impl Default for EthApiLimits {
+	fn default() -> Self {
+		EthApiLimits {
+			max_response_size: None,
+			max_logs: None,
+			max_traces: None,
+			max_simulate_calls: None,
+			max_block_range: None,
+			execute_gas_limit_multiplier: None,
+			gas_cap: None,
+			allow_unprotected_txs: false,
+		}
+	}
+}
+
+impl EthApiLimits {
+	/// Ensure the serialized form of `response` fits in `max_response_size`.
+	pub fn check_response_size<T: Serialize>(&self, response: &T) -> Result<()> {
+		if let Some(max) = self.max_response_size {
+			let size = serde_json::to_vec(response)
+				.map_err(|_| internal_err("serialize response failed"))?
+				.len();
+			if size > max {
+				return Err(limit_exceeded_err(
+					&format!("response size {} exceeds limit of {} bytes", size, max)
+				));
+			}
+		}
+		Ok(())
+	}
+
+	/// Ensure a result set of `count` items fits in `max`.
+	pub fn check_result_count(&self, count: usize, max: Option<usize>, what: &str) -> Result<()> {
+		match max {
+			Some(max) if count > max => Err(limit_exceeded_err(
+				&format!("query returned more than {} {}", max, what)
+			)),
+			_ => Ok(()),
+		}
+	}
+
+	/// Ensure the block range `from..=to` spans no more than `max_block_range` blocks.
+	pub fn check_block_range(&self, from: u64, to: u64) -> Result<()> {
+		match self.max_block_range {
+			Some(max) if to.saturating_sub(from) >= max => Err(limit_exceeded_err(
+				&format!("block range exceeds limit of {} blocks", max)
+			)),
+			_ => Ok(()),
+		}
+	}
+}
+
+pub struct EthApi<B: BlockT, C, P, CT, BE> {
 	pool: Arc<P>,
 	client: Arc<C>,
-	select_chain: SC,
+	latest: LatestBlock,
 	convert_transaction: CT,
 	is_authority: bool,
-	_marker: PhantomData<(B,BE)>,
-}
-
-impl<B: BlockT, C, SC, P, CT, BE> EthApi<B, C, SC, P, CT, BE> {
-	pub fn new(
-		client: Arc<C>,
-		select_chain: SC,
-		pool: Arc<P>,
-		convert_transaction: CT,
-		is_authority: bool
-	) -> Self {
-		Self { client, select_chain, pool, convert_transaction, is_authority, _marker: PhantomData }
-	}
+	signers: Vec<Box<dyn EthSigner>>,
+	mining_worker: Option<Arc<dyn EthMiningWorker>>,
+	hashrates: Hashrates,
+	frontier_backend: Arc<dyn fc_api::Backend<B>>,
+	blocking_pool: BlockingTaskPool,
+	limits: EthApiLimits,
+	sender_cache: SenderCache,
+	runtime_cache: RuntimeCache,
+	block_data_cache: Arc<EthBlockDataCache>,
+	backend: Option<Arc<BE>>,
+	pending_cache: Arc<PendingCache>,
+	_marker: PhantomData<B>,
 }
 
 fn rich_block_build(
@@ -71,17 +251,18 @@ fn rich_block_build(
 	statuses: Vec<Option<TransactionStatus>>, 
 	hash: Option<H256>
 ) -> RichBlock {
+	let hash = hash.unwrap_or_else(|| {
+		H256::from_slice(
+			Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+		)
+	});
 	Rich {
 		inner: Block {
-			hash: Some(hash.unwrap_or_else(|| {
-				H256::from_slice(
-					Keccak256::digest(&rlp::encode(&block.header)).as_slice()
-				)
-			})),
+			hash: Some(hash),
 			parent_hash: block.header.parent_hash,
 			uncles_hash: H256::zero(), // TODO
-			author: H160::default(), // TODO
-			miner: H160::default(), // TODO
+			author: block.header.beneficiary,
+			miner: block.header.beneficiary,
 			state_root: block.header.state_root,
 			transactions_root: block.header.transactions_root,
 			receipts_root: block.header.receipts_root,
@@ -100,12 +281,14 @@ fn rich_block_build(
 			uncles: vec![], // TODO
 			transactions: BlockTransactions::Full(
 				block.transactions.iter().enumerate().map(|(index, transaction)|{
-					let mut status = statuses[index].clone();
-					// A fallback to default check
-					if status.is_none() {
-						status = Some(TransactionStatus::default());
-					}
-					transaction_build(transaction.clone(), block.clone(), status.unwrap())
+					// A fallback to a default status, still hashing the transaction.
+					let status = statuses[index].clone().unwrap_or_else(|| TransactionStatus {
+						transaction_hash: H256::from_slice(
+							Keccak256::digest(&rlp::encode(transaction)).as_slice()
+						),
+						..Default::default()
+					});
+					transaction_build(transaction.clone(), &block, Some(hash), status)
 				}).collect()
 			),
 			size: None // TODO
@@ -114,19 +297,903 @@ fn rich_block_build(
 	}
 }
 
+/// EIP-2718 type of the EIP-4844 blob transactions.
+const BLOB_TRANSACTION_TYPE: u8 = 0x03;
+
+/// Base fee per blob gas of a chain without blobs, the minimum of EIP-4844.
+const MIN_BLOB_BASE_FEE: u64 = 1;
+
+/// Transaction of the raw `eth_sendRawTransaction` payload `bytes`, with its
+/// hash. The payload comes from the network, so decoding must fail rather than
+/// panic on any input.
+///
+/// The hash is that of `bytes` as received rather than of an encoding of the
+/// decoded transaction, which is the same since payloads followed by trailing
+/// bytes are rejected.
+///
+/// Blob transactions are recognized by their type byte and rejected as such,
+/// rather than failing to decode, so that wallets can fall back to other
+/// transaction types.
+pub fn decode_raw_transaction(bytes: &[u8]) -> Result<(EthereumTransaction, H256)> {
+	if bytes.first() == Some(&BLOB_TRANSACTION_TYPE) {
+		return Err(internal_err("blob transactions not supported"));
+	}
+	let rlp = rlp::Rlp::new(bytes);
+	match rlp.payload_info() {
+		Ok(info) if info.total() == bytes.len() => (),
+		_ => return Err(internal_err("decode transaction failed")),
+	}
+	let transaction = rlp.as_val::<EthereumTransaction>()
+		.map_err(|_| internal_err("decode transaction failed"))?;
+	let transaction_hash = H256::from_slice(Keccak256::digest(bytes).as_slice());
+	Ok((transaction, transaction_hash))
+}
+
+/// Build the RPC header of a block, without its transactions.
+pub(crate) fn rich_header_build(block: &EthereumBlock, hash: Option<H256>) -> RichHeader {
+	Rich {
+		inner: Header {
+			hash: Some(hash.unwrap_or_else(|| {
+				H256::from_slice(
+					Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+				)
+			})),
+			parent_hash: block.header.parent_hash,
+			uncles_hash: H256::zero(), // TODO
+			author: block.header.beneficiary,
+			miner: block.header.beneficiary,
+			state_root: block.header.state_root,
+			transactions_root: block.header.transactions_root,
+			receipts_root: block.header.receipts_root,
+			number: Some(block.header.number),
+			gas_used: block.header.gas_used,
+			gas_limit: block.header.gas_limit,
+			extra_data: Bytes(block.header.extra_data.as_bytes().to_vec()),
+			logs_bloom: block.header.logs_bloom,
+			timestamp: U256::from(block.header.timestamp),
+			difficulty: block.header.difficulty,
+			seal_fields: vec![
+				Bytes(block.header.mix_hash.as_bytes().to_vec()),
+				Bytes(block.header.nonce.as_bytes().to_vec())
+			],
+			size: None, // TODO
+		},
+		extra_info: BTreeMap::new(),
+	}
+}
+
+/// Build the RPC receipt of a transaction, given the gas used in its block by
+/// the transactions before it and, if known, the index in the block of its
+/// first log.
+fn receipt_build(
+	transaction: &EthereumTransaction,
+	block: &EthereumBlock,
+	status: TransactionStatus,
+	receipt: ethereum::Receipt,
+	previous_gas_used: U256,
+	log_index: Option<u32>,
+) -> Receipt {
+	let block_hash = H256::from_slice(
+		Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+	);
+	Receipt {
+		transaction_hash: Some(status.transaction_hash),
+		transaction_index: Some(status.transaction_index.into()),
+		block_hash: Some(block_hash),
+		from: Some(status.from),
+		to: status.to,
+		block_number: Some(block.header.number),
+		cumulative_gas_used: receipt.used_gas,
+		gas_used: Some(receipt.used_gas.saturating_sub(previous_gas_used)),
+		contract_address: status.contract_address,
+		logs: {
+			receipt.logs.iter().enumerate().map(|(index, log)| {
+				Log {
+					address: log.address,
+					topics: log.topics.clone(),
+					data: Bytes(log.data.clone()),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: log_index.map(|log_index| U256::from(log_index as usize + index)),
+					transaction_log_index: Some(U256::from(index)),
+					log_type: Default::default(), // TODO
+					removed: false, // TODO
+				}
+			}).collect()
+		},
+		state_root: None,
+		logs_bloom: receipt.logs_bloom,
+		// Receipts recorded before they held a status leave it unknown.
+		status_code: if trie::is_legacy_receipt(&receipt) {
+			None
+		} else {
+			Some(U64::from(trie::receipt_status(&receipt)))
+		},
+		// Only legacy transactions exist, for which the effective gas price
+		// is simply the gas price.
+		transaction_type: U64::zero(),
+		effective_gas_price: transaction.gas_price,
+	}
+}
+
+/// Build a block of a range or export, with the RPC receipts of its
+/// transactions when `receipts` is given.
+fn block_with_receipts_build(
+	block: EthereumBlock,
+	statuses: Vec<Option<TransactionStatus>>,
+	receipts: Option<Vec<ethereum::Receipt>>,
+) -> BlockWithReceipts {
+	let receipts = receipts.map(|receipts| {
+		// Receipts hold the gas used in the block up to their transaction.
+		let mut previous_gas_used = U256::zero();
+		let mut log_index = 0u32;
+		let mut block_receipts = Vec::with_capacity(receipts.len());
+		for ((transaction, status), receipt) in block.transactions.iter()
+			.zip(statuses.iter())
+			.zip(receipts)
+		{
+			let used_gas = receipt.used_gas;
+			let log_count = receipt.logs.len() as u32;
+			if let Some(status) = status {
+				block_receipts.push(receipt_build(
+					transaction,
+					&block,
+					status.clone(),
+					receipt,
+					previous_gas_used,
+					Some(log_index),
+				));
+			}
+			previous_gas_used = used_gas;
+			log_index = log_index.saturating_add(log_count);
+		}
+		block_receipts
+	});
+
+	BlockWithReceipts {
+		block: rich_block_build(block, statuses, None),
+		receipts,
+	}
+}
+
+fn filter_block_logs(
+	ret: &mut Vec<Log>,
+	filter: &Filter,
+	block: EthereumBlock,
+	statuses: Vec<Option<TransactionStatus>>,
+) {
+	filter_block_logs_within(ret, filter, block, statuses, &EthApiLimits::default())
+		.expect("logs are not limited by the default limits; qed")
+}
+
+/// Appends the logs of `block` matching `filter` to `ret`, failing before
+/// `ret` holds more than `max_logs` of them.
+fn filter_block_logs_within(
+	ret: &mut Vec<Log>,
+	filter: &Filter,
+	block: EthereumBlock,
+	statuses: Vec<Option<TransactionStatus>>,
+	limits: &EthApiLimits,
+) -> Result<()> {
+	let block_hash = H256::from_slice(
+		Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+	);
+	let mut block_log_index: u32 = 0;
+	for status in statuses.into_iter().flatten() {
+		let mut transaction_log_index: u32 = 0;
+		for log in status.logs {
+			if filter.matches(&log.address, &log.topics) {
+				limits.check_result_count(ret.len() + 1, limits.max_logs, "logs")?;
+				ret.push(Log {
+					address: log.address,
+					topics: log.topics,
+					data: Bytes(log.data),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(block_log_index.into()),
+					transaction_log_index: Some(transaction_log_index.into()),
+					log_type: Default::default(), // TODO
+					removed: false,
+				});
+			}
+			block_log_index += 1;
+			transaction_log_index += 1;
+		}
+	}
+	Ok(())
+}
+
+/// Whether a block with logs bloom `bloom` may hold logs of one of `addresses`
+/// with, at each position, one of `topics`. Empty lists are wildcards.
+fn bloom_possibly_matches(bloom: &Bloom, addresses: &[H160], topics: &[Vec<H256>]) -> bool {
+	let contains = |value: &[u8]| bloom.contains_input(BloomInput::Raw(value));
+	(addresses.is_empty() || addresses.iter().any(|address| contains(&address[..]))) &&
+		topics.iter().all(|topics| topics.is_empty() || topics.iter().any(|topic| contains(&topic[..])))
+}
+
+/// Values accepted by a filter address or topic, empty for a wildcard.
+fn variadic_values<T: Clone + serde::de::DeserializeOwned>(value: &VariadicValue<T>) -> Vec<T> {
+	match value {
+		VariadicValue::Single(value) => vec![value.clone()],
+		VariadicValue::Multiple(values) => values.clone(),
+		VariadicValue::Null => Vec::new(),
+	}
+}
+
+fn state_overrides_build(
+	overrides: Option<BTreeMap<H160, CallStateOverride>>
+) -> Result<Vec<(H160, AccountOverride)>> {
+	overrides.unwrap_or_default().into_iter().map(|(address, state_override)| {
+		if state_override.state.is_some() && state_override.state_diff.is_some() {
+			return Err(Error::invalid_params(
+				format!("both state and stateDiff overrides are set for {:?}", address)
+			));
+		}
+		Ok((address, AccountOverride {
+			balance: state_override.balance,
+			nonce: state_override.nonce,
+			code: state_override.code.map(|code| code.into_vec()),
+			state: state_override.state.map(|state| state.into_iter().collect()),
+			state_diff: state_override.state_diff.map(|state_diff| state_diff.into_iter().collect()),
+		}))
+	}).collect()
+}
+
+fn block_override_build(block_override: Option<CallBlockOverride>) -> Result<BlockOverride> {
+	let block_override = match block_override {
+		Some(block_override) => block_override,
+		None => return Ok(BlockOverride::default()),
+	};
+	// The EVM block context of pallet-evm only follows the block number,
+	// timestamp and base fee of the runtime. Its gas limit, coinbase and
+	// randomness are fixed, so these overrides would be silently ignored.
+	let unsupported = [
+		("gasLimit", block_override.gas_limit.is_some()),
+		("coinbase", block_override.coinbase.is_some()),
+		("prevRandao", block_override.random.is_some()),
+	];
+	if let Some((field, _)) = unsupported.iter().find(|(_, set)| *set) {
+		return Err(Error::invalid_params(format!(
+			"{} block override is not supported, only number, time and baseFee are", field
+		)));
+	}
+	Ok(BlockOverride {
+		number: block_override.number
+			.map(|number| number.min(U256::from(u32::max_value())).low_u32()),
+		timestamp: block_override.time
+			.map(|time| time.min(U256::from(u64::max_value())).low_u64()),
+		base_fee: block_override.base_fee,
+	})
+}
+
+/// Resolve an RPC block number to the native block number, `None` meaning the
+/// pending block.
+fn native_block_number<B, C>(
+	client: &C,
+	latest: LatestBlock,
+	number: Option<BlockNumber>,
+) -> Result<Option<u32>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let header = latest_header(client, latest)?;
+
+	let mut native_number: Option<u32> = None;
+
+	if let Some(number) = number {
+		match number {
+			BlockNumber::Hash { hash, .. } => {
+				let span = lookup_span("block_by_hash", &hash);
+				let _enter = span.enter();
+				if let Ok(Some(block)) = client.runtime_api().block_by_hash(
+					&BlockId::Hash(header.hash()),
+					hash
+				) {
+					native_number = Some(block.header.number.as_u32());
+				}
+			},
+			BlockNumber::Num(_) => {
+				if let Some(number) = number.to_min_block_num() {
+					native_number = Some(number.unique_saturated_into());
+				}
+			},
+			BlockNumber::Latest => {
+				native_number = Some(
+					header.number().clone().unique_saturated_into() as u32
+				);
+			},
+			BlockNumber::Earliest => {
+				native_number = Some(1);
+			},
+			BlockNumber::Pending => {
+				native_number = None;
+			}
+		};
+	} else {
+		native_number = Some(
+			header.number().clone().unique_saturated_into() as u32
+		);
+	}
+	Ok(native_number)
+}
+
+/// Resolve an RPC block number to the native block to read state at, `None`
+/// meaning the pending block.
+///
+/// Block hashes resolve through the Ethereum blocks of the best chain. When
+/// `requireCanonical` is false, a block of another fork is served too.
+fn native_block_id<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	number: Option<BlockNumber>,
+) -> Result<Option<BlockId<B>>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if let Some(BlockNumber::Hash { hash, require_canonical: false }) = number {
+		let span = lookup_span("load_block_hash", &hash);
+		let _enter = span.enter();
+		if let Some(substrate_hash) = frontier_backend.block_hash(&hash)
+			.map_err(|_| internal_err("fetch block mapping failed"))?
+		{
+			return Ok(Some(BlockId::Hash(substrate_hash)));
+		}
+	}
+
+	Ok(native_block_number(client, latest, number)?
+		.map(|number| BlockId::Number(number.into())))
+}
+
+/// Gas limit of a call executed off-chain: the requested gas, capped to the
+/// block gas limit times the configured multiplier, and to the gas cap.
+fn execute_gas_limit<B, C>(
+	client: &C,
+	at: &BlockId<B>,
+	requested: Option<U256>,
+	limits: &EthApiLimits,
+) -> Result<U256> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let constants = validation_constants(client, at)?;
+	let multiplier = limits.execute_gas_limit_multiplier
+		.unwrap_or(constants.execute_gas_limit_multiplier);
+	let max_gas_limit = constants.block_gas_limit.saturating_mul(U256::from(multiplier));
+	let max_gas_limit = limits.gas_cap.map_or(max_gas_limit, |gas_cap| gas_cap.min(max_gas_limit));
+
+	Ok(requested.map_or(max_gas_limit, |gas| gas.min(max_gas_limit)))
+}
+
+/// Gas price of a call executed off-chain: its `gasPrice`, or with EIP-1559
+/// fields the base fee plus the priority fee, capped to the max fee. The base
+/// fee is the one of the block override, if any.
+fn execute_gas_price<B, C>(
+	client: &C,
+	at: &BlockId<B>,
+	request: &CallRequest,
+	base_fee: Option<U256>,
+) -> Result<U256> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let (max_fee, priority_fee) = match (request.max_fee_per_gas, request.max_priority_fee_per_gas) {
+		(None, None) => return Ok(request.gas_price.unwrap_or_default()),
+		fees => fees,
+	};
+	if request.gas_price.is_some() {
+		return Err(Error::invalid_params(
+			"gasPrice cannot be combined with maxFeePerGas or maxPriorityFeePerGas"
+		));
+	}
+	let priority_fee = priority_fee.unwrap_or_default();
+	if max_fee.map_or(false, |max_fee| priority_fee > max_fee) {
+		return Err(Error::invalid_params("maxPriorityFeePerGas is higher than maxFeePerGas"));
+	}
+
+	let base_fee = match base_fee {
+		Some(base_fee) => base_fee,
+		None => client.runtime_api()
+			.gas_price(at)
+			.map_err(|_| internal_err("fetch runtime gas price failed"))?,
+	};
+	let gas_price = base_fee.saturating_add(priority_fee);
+	Ok(max_fee.map_or(gas_price, |max_fee| gas_price.min(max_fee)))
+}
+
+/// Numbers of the blocks from `from` to `to` inclusive which may have logs
+/// matching `filter`, in ascending order.
+fn log_block_numbers<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	header: &B::Header,
+	filter: &Filter,
+	from: u32,
+	to: u32,
+) -> Result<Vec<u32>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let addresses = filter.address.as_ref()
+		.map(variadic_values)
+		.unwrap_or_default();
+	let topics: Vec<Vec<H256>> = filter.topics.as_ref()
+		.map(|topics| topics.iter().map(variadic_values).collect())
+		.unwrap_or_default();
+	let wildcard = addresses.is_empty() && topics.iter().all(Vec::is_empty);
+
+	// An indexing backend narrows the blocks to read down to those with
+	// matching logs. Otherwise the logs bloom of each block tells whether
+	// reading it is needed at all. An empty bloom does not tell anything, as
+	// blocks from before the runtime computed it have one.
+	let log_indexer = frontier_backend.log_indexer();
+	if log_indexer.is_indexed() {
+		let mut numbers: Vec<u32> = log_indexer
+			.filter_logs(from, to, &addresses, &topics)
+			.map_err(|_| internal_err("fetch indexed logs failed"))?
+			.into_iter()
+			.map(|log| log.block_number)
+			.collect();
+		numbers.dedup();
+		Ok(numbers)
+	} else if wildcard || !extended_api_available(client, &BlockId::Hash(header.hash()))? {
+		// Runtimes without `block_logs_bloom` have every block read.
+		Ok((from..=to).collect())
+	} else {
+		let api = client.runtime_api();
+		Ok((from..=to)
+			.filter(|number| {
+				match api.block_logs_bloom(&BlockId::Hash(header.hash()), *number) {
+					Ok(Some(bloom)) if bloom != Bloom::default() =>
+						bloom_possibly_matches(&bloom, &addresses, &topics),
+					_ => true,
+				}
+			})
+			.collect())
+	}
+}
+
+/// Logs matching `filter`, read from the blocks of the best chain.
+fn filter_logs<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	filter: Filter,
+) -> Result<Vec<Log>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let header = latest_header(client, latest)?;
+	let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(
+		header.number().clone()
+	);
+
+	// Logs are returned in block, transaction and log order.
+	let mut logs = Vec::new();
+	if let Some(hash) = filter.block_hash {
+		if filter.from_block.is_some() || filter.to_block.is_some() {
+			return Err(Error::invalid_params(
+				"blockHash cannot be combined with fromBlock or toBlock"
+			));
+		}
+
+		let span = lookup_span("block_by_hash_with_statuses", &hash);
+		let _enter = span.enter();
+		match client.runtime_api()
+			.block_by_hash_with_statuses(&BlockId::Hash(header.hash()), hash) {
+			Ok((Some(block), statuses)) => {
+				filter_block_logs_within(&mut logs, &filter, block, statuses, limits)?;
+			},
+			_ => return Err(internal_err(&format!("unknown block {:?}", hash))),
+		}
+	} else {
+		let from = native_block_number(client, latest, filter.from_block.clone())?
+			.unwrap_or(best_number);
+		let to = native_block_number(client, latest, filter.to_block.clone())?
+			.unwrap_or(best_number)
+			.min(best_number);
+		limits.check_block_range(from as u64, to as u64)?;
+
+		let numbers = log_block_numbers(client, frontier_backend, &header, &filter, from, to)?;
+		for number in numbers {
+			let span = lookup_span("block_by_number", &number);
+			let _enter = span.enter();
+			if let Ok((Some(block), statuses)) = client.runtime_api()
+				.block_by_number(&BlockId::Hash(header.hash()), number) {
+				filter_block_logs_within(&mut logs, &filter, block, statuses, limits)?;
+			}
+		}
+	}
+
+	// The filter limit keeps the most recent logs.
+	if let Some(limit) = filter.limit {
+		if logs.len() > limit {
+			logs.drain(..logs.len() - limit);
+		}
+	}
+	Ok(logs)
+}
+
+/// Logs matching `filter` from block `cursor`, or from its `fromBlock`, on.
+///
+/// A page ends with the first block taking the page to `page_size` logs, or
+/// with the last block of the range limit, and blocks are never split across
+/// pages. The page tells the block to resume from, if any. Cursors outside the
+/// range of `filter` are rejected.
+fn filter_logs_page<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	filter: Filter,
+	cursor: Option<U256>,
+	page_size: usize,
+) -> Result<LogsPage> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if filter.block_hash.is_some() {
+		let logs = filter_logs(client, frontier_backend, latest, limits, filter)?;
+		return Ok(LogsPage { logs, cursor: None })
+	}
+
+	let header = latest_header(client, latest)?;
+	let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(
+		header.number().clone()
+	);
+	let filter_from = native_block_number(client, latest, filter.from_block.clone())?
+		.unwrap_or(best_number);
+	let to = native_block_number(client, latest, filter.to_block.clone())?
+		.unwrap_or(best_number)
+		.min(best_number);
+	// A cursor comes from a previous page of the same filter, so it is within
+	// its range.
+	let from = match cursor {
+		Some(cursor) if cursor > U256::from(u32::max_value()) =>
+			return Err(Error::invalid_params("cursor is not a block number")),
+		Some(cursor) if cursor.low_u32() < filter_from || cursor.low_u32() > to =>
+			return Err(Error::invalid_params("cursor is outside the block range of the filter")),
+		Some(cursor) => cursor.low_u32(),
+		None => filter_from,
+	};
+	if from > to {
+		return Ok(LogsPage { logs: Vec::new(), cursor: None })
+	}
+	let page_to = match limits.max_block_range {
+		Some(max) => to.min(from.saturating_add(max.saturating_sub(1) as u32)),
+		None => to,
+	};
+
+	let mut logs = Vec::new();
+	for number in log_block_numbers(client, frontier_backend, &header, &filter, from, page_to)? {
+		let span = lookup_span("block_by_number", &number);
+		let _enter = span.enter();
+		if let Ok((Some(block), statuses)) = client.runtime_api()
+			.block_by_number(&BlockId::Hash(header.hash()), number) {
+			filter_block_logs(&mut logs, &filter, block, statuses);
+		}
+		if logs.len() >= page_size && number < to {
+			return Ok(LogsPage { logs, cursor: Some(U256::from(number + 1)) })
+		}
+	}
+	let cursor = if page_to < to { Some(U256::from(page_to + 1)) } else { None };
+	Ok(LogsPage { logs, cursor })
+}
+
+/// Storage changes of executing the `pending` transactions on top of the block
+/// `hash`.
+fn pending_state<B, C, BE>(
+	client: &C,
+	backend: &BE,
+	hash: H256,
+	pending: Vec<(H160, EthereumTransaction)>,
+) -> Result<PendingState> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B>,
+{
+	let id = BlockId::Hash(hash);
+	if !dry_run_outcome_available(client, &id)? {
+		return Ok(PendingState::default());
+	}
+	let span = lookup_span("apply_pending", &id);
+	let _enter = span.enter();
+
+	let api = client.runtime_api();
+	api.apply_pending(&id, pending)
+		.map_err(|_| internal_err("executing pending transactions failed"))?;
+	let state = backend.state_at(id)
+		.map_err(|_| internal_err("fetch state failed"))?;
+	let changes = api.into_storage_changes(&state, None, hash)
+		.map_err(|_| internal_err("collect pending changes failed"))?;
+	Ok(PendingState::new(changes.main_storage_changes))
+}
+
+/// Executes `call` at block `id`, with the overrides and `pending` changes.
+/// Runtimes from before version 2 of the Ethereum runtime api execute it
+/// without them, and only report its output and gas used. Runtimes from before
+/// version 4 reject base fee overrides.
+fn dry_run_call<B, C>(
+	client: &C,
+	id: &BlockId<B>,
+	call: DryRunCall,
+	state_overrides: Vec<(H160, AccountOverride)>,
+	block_override: BlockOverride,
+	pending: PendingChanges,
+) -> Result<DryRunOutcome> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let span = lookup_span("call", id);
+	let _enter = span.enter();
+	if base_fee_override_available(client, id)? {
+		return client.runtime_api()
+			.call(
+				id,
+				call.from,
+				call.to.unwrap_or_default(),
+				call.data,
+				call.value,
+				call.gas_limit,
+				call.gas_price,
+				call.nonce,
+				state_overrides,
+				block_override,
+				pending,
+			)
+			.map_err(|_| internal_err("executing call failed"))?
+			.ok_or(internal_err("inner executing call failed"));
+	}
+	if block_override.base_fee.is_some() {
+		return Err(not_supported_at("base fee overrides", id));
+	}
+	if dry_run_outcome_available(client, id)? {
+		#[allow(deprecated)]
+		return client.runtime_api()
+			.call_before_version_4(
+				id,
+				call.from,
+				call.to.unwrap_or_default(),
+				call.data,
+				call.value,
+				call.gas_limit,
+				call.gas_price,
+				call.nonce,
+				state_overrides,
+				block_override.into(),
+				pending,
+			)
+			.map_err(|_| internal_err("executing call failed"))?
+			.ok_or(internal_err("inner executing call failed"));
+	}
+
+	if !state_overrides.is_empty() || block_override != BlockOverride::default() {
+		return Err(not_supported_at("call overrides", id));
+	}
+	#[allow(deprecated)]
+	let (output, used_gas) = client.runtime_api()
+		.call_before_version_2(
+			id,
+			call.from,
+			call.to.unwrap_or_default(),
+			call.data,
+			call.value,
+			call.gas_limit,
+			call.gas_price,
+			call.nonce,
+		)
+		.map_err(|_| internal_err("executing call failed"))?
+		.ok_or(internal_err("inner executing call failed"))?;
+	Ok(DryRunOutcome { succeeded: true, error: None, output, used_gas, created: None })
+}
+
+/// Gas used by `request` executed on the best block.
+fn estimate_gas<B, C>(
+	client: &C,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	request: CallRequest,
+) -> Result<U256> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let header = latest_header(client, latest)?;
+	let id = BlockId::Hash(header.hash());
+
+	let call = DryRunCall {
+		from: request.from.unwrap_or_default(),
+		to: request.to,
+		gas_price: execute_gas_price(client, &id, &request, None)?,
+		gas_limit: execute_gas_limit(client, &id, request.gas, limits)?,
+		value: request.value.unwrap_or_default(),
+		data: request.data.map(|d| d.0).unwrap_or_default(),
+		nonce: request.nonce,
+	};
+	let outcome = dry_run_call(client, &id, call, Vec::new(), BlockOverride::default(), Vec::new())?;
+	if let Some(error) = outcome.error {
+		return Err(execution_err(error, outcome.output));
+	}
+
+	Ok(outcome.used_gas)
+}
+
+/// Blocks of calls of `payload` simulated on top of the block `number`.
+fn simulate<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	payload: SimulatePayload,
+	number: Option<BlockNumber>,
+) -> Result<Vec<SimulatedBlock>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if payload.validation {
+		return Err(Error::invalid_params("validation is not supported"));
+	}
+	if payload.trace_transfers {
+		return Err(Error::invalid_params("traceTransfers is not supported"));
+	}
+	let calls_count = payload.block_state_calls.iter().map(|block| block.calls.len()).sum();
+	limits.check_result_count(calls_count, limits.max_simulate_calls, "calls")?;
+
+	let header = match native_block_id(client, frontier_backend, latest, number)? {
+		Some(id) => client.header(id)
+			.map_err(|_| internal_err("fetch header failed"))?
+			.ok_or_else(|| internal_err("fetch header failed"))?,
+		None => latest_header(client, latest)?,
+	};
+	let id = BlockId::Hash(header.hash());
+	ensure_ethereum_api(client, &id)?;
+	if !extended_api_available(client, &id)? {
+		return Err(not_supported_at("simulated calls", &id));
+	}
+
+	let mut number = UniqueSaturatedInto::<u32>::unique_saturated_into(header.number().clone());
+	let mut numbers = Vec::with_capacity(payload.block_state_calls.len());
+	let mut blocks = Vec::with_capacity(payload.block_state_calls.len());
+	for block in payload.block_state_calls {
+		let block_override = block_override_build(block.block_overrides)?;
+		number = block_override.number.unwrap_or(number.saturating_add(1));
+		numbers.push(number);
+
+		let mut calls = Vec::with_capacity(block.calls.len());
+		for request in block.calls {
+			calls.push(DryRunCall {
+				from: request.from.unwrap_or_default(),
+				to: request.to,
+				gas_price: execute_gas_price(client, &id, &request, block_override.base_fee)?,
+				gas_limit: execute_gas_limit(client, &id, request.gas, limits)?,
+				data: request.data.map(|d| d.0).unwrap_or_default(),
+				value: request.value.unwrap_or_default(),
+				nonce: request.nonce,
+			});
+		}
+		blocks.push(SimulatedRuntimeBlock {
+			state_overrides: state_overrides_build(block.state_overrides)?,
+			block_override,
+			calls,
+		});
+	}
+
+	let span = lookup_span("simulate", &id);
+	let _enter = span.enter();
+	let outcomes = if base_fee_override_available(client, &id)? {
+		client.runtime_api().simulate(&id, blocks)
+	} else if blocks.iter().any(|block| block.block_override.base_fee.is_some()) {
+		return Err(not_supported_at("base fee overrides", &id));
+	} else {
+		#[allow(deprecated)]
+		client.runtime_api().simulate_before_version_4(&id, blocks.into_iter().map(Into::into).collect())
+	}.map_err(|_| internal_err("executing calls failed"))?;
+
+	numbers.into_iter().zip(outcomes).map(|(number, outcomes)| {
+		let mut gas_used = U256::zero();
+		let mut log_index = 0u32;
+		let mut calls = Vec::with_capacity(outcomes.len());
+		for (transaction_index, outcome) in outcomes.into_iter().enumerate() {
+			let outcome = outcome.ok_or(internal_err("inner executing call failed"))?;
+			gas_used = gas_used.saturating_add(outcome.used_gas);
+			let logs = outcome.logs.into_iter().enumerate().map(|(transaction_log_index, log)| {
+				log_index += 1;
+				Log {
+					address: log.address,
+					topics: log.topics,
+					data: Bytes(log.data),
+					block_hash: None,
+					block_number: Some(U256::from(number)),
+					transaction_hash: None,
+					transaction_index: Some(U256::from(transaction_index)),
+					log_index: Some(U256::from(log_index - 1)),
+					transaction_log_index: Some(U256::from(transaction_log_index)),
+					log_type: Default::default(),
+					removed: false,
+				}
+			}).collect();
+			calls.push(SimulatedCall {
+				return_data: Bytes(outcome.output),
+				logs,
+				gas_used: outcome.used_gas,
+				status: if outcome.succeeded { U64::one() } else { U64::zero() },
+				error: outcome.error.map(|error| SimulatedCallError {
+					code: if error == ExecutionError::Reverted { 3 } else { -32015 },
+					message: error.message().to_string(),
+				}),
+			});
+		}
+		Ok(SimulatedBlock { number: U256::from(number), gas_used, calls })
+	}).collect()
+}
+
+/// Build the RPC representation of a transaction that is not in a block yet.
+fn pending_transaction_build(transaction: EthereumTransaction, sender: PoolSender) -> Transaction {
+	let PoolSender { sender: from, hash, raw } = sender;
+	let (to, creates) = match transaction.action {
+		ethereum::TransactionAction::Call(to) => (Some(to), None),
+		ethereum::TransactionAction::Create => {
+			let mut stream = rlp::RlpStream::new_list(2);
+			stream.append(&from);
+			stream.append(&transaction.nonce);
+			(None, Some(H160::from_slice(&Keccak256::digest(&stream.out())[12..])))
+		},
+	};
+	Transaction {
+		hash,
+		nonce: transaction.nonce,
+		block_hash: None,
+		block_number: None,
+		transaction_index: None,
+		from,
+		to,
+		value: transaction.value,
+		gas_price: transaction.gas_price,
+		gas: transaction.gas_limit,
+		input: Bytes(transaction.input),
+		creates,
+		raw: Bytes(raw),
+		public_key: None, // TODO
+		chain_id: None, // TODO
+		standard_v: U256::zero(), // TODO
+		v: U256::zero(), // TODO
+		r: U256::zero(), // TODO
+		s: U256::zero(), // TODO
+		condition: None // TODO
+	}
+}
+
+/// Build the RPC representation of a transaction of `block`, whose hash is
+/// computed unless given.
 fn transaction_build(
 	transaction: EthereumTransaction,
-	block: EthereumBlock,
+	block: &EthereumBlock,
+	block_hash: Option<H256>,
 	status: TransactionStatus
 ) -> Transaction {
 	Transaction {
-		hash: H256::from_slice(
-			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
-		),
+		hash: status.transaction_hash,
 		nonce: transaction.nonce,
-		block_hash: Some(H256::from_slice(
-			Keccak256::digest(&rlp::encode(&block.header)).as_slice()
-		)),
+		block_hash: Some(block_hash.unwrap_or_else(|| {
+			H256::from_slice(
+				Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+			)
+		})),
 		block_number: Some(block.header.number),
 		transaction_index: Some(U256::from(
 			UniqueSaturatedInto::<u32>::unique_saturated_into(
@@ -151,70 +1218,238 @@ fn transaction_build(
 	}
 }
 
-impl<B, C, SC, P, CT, BE> EthApi<B, C, SC, P, CT, BE> where
-	C: ProvideRuntimeApi<B> + StorageProvider<B,BE>,
-	C::Api: EthereumRuntimeApi<B>,
+impl<B, C, P, CT, BE> EthApi<B, C, P, CT, BE> where
+	C: ProvideRuntimeApi<B> + StorageProvider<B,BE> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 	B: BlockT<Hash=H256> + Send + Sync + 'static,
 	C: Send + Sync + 'static,
-	SC: SelectChain<B> + Clone + 'static,
-	P: TransactionPool<Block=B> + Send + Sync + 'static,
+	P: TransactionPool<Block=B, Hash=H256> + Send + Sync + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
+	fn latest_header(&self) -> Result<B::Header> {
+		let header = latest_header(self.client.as_ref(), self.latest)?;
+		ensure_ethereum_api(self.client.as_ref(), &BlockId::Hash(header.hash()))?;
+		Ok(header)
+	}
+
 	fn native_block_number(&self, number: Option<BlockNumber>) -> Result<Option<u32>> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
-
-		let mut native_number: Option<u32> = None;
-
-		if let Some(number) = number {
-			match number {
-				BlockNumber::Hash { hash, .. } => {
-					if let Ok(Some(block)) = self.client.runtime_api().block_by_hash(
-						&BlockId::Hash(header.hash()),
-						hash
-					) {
-						native_number = Some(block.header.number.as_u32());
-					}
-				},
-				BlockNumber::Num(_) => {
-					if let Some(number) = number.to_min_block_num() {
-						native_number = Some(number.unique_saturated_into());
-					}
-				},
-				BlockNumber::Latest => {
-					native_number = Some(
-						header.number().clone().unique_saturated_into() as u32
-					);
-				},
-				BlockNumber::Earliest => {
-					native_number = Some(1);
-				},
-				BlockNumber::Pending => {
-					native_number = None;
-				}
-			};
-		} else {
-			native_number = Some(
-				header.number().clone().unique_saturated_into() as u32
-			);
+		native_block_number(self.client.as_ref(), self.latest, number)
+	}
+
+	/// Substrate hash and Ethereum data of the canonical block `number`, unless
+	/// it is after the block served as `latest`.
+	fn block_data(&self, number: u32) -> Result<Option<(H256, Arc<EthBlockData>)>> {
+		let header = self.latest_header()?;
+		if number > UniqueSaturatedInto::<u32>::unique_saturated_into(*header.number()) {
+			return Ok(None)
+		}
+		let hash = match self.client.hash(number.into())
+			.map_err(|_| internal_err("fetch block hash failed"))?
+		{
+			Some(hash) => hash,
+			None => return Ok(None),
+		};
+		Ok(self.block_data_cache.block_data(&*self.client, hash, number).map(|data| (hash, data)))
+	}
+
+	/// Ethereum data of the canonical Substrate block `hash`, unless it is after
+	/// the block served as `latest`.
+	fn substrate_block_data(&self, hash: H256) -> Result<Option<Arc<EthBlockData>>> {
+		let number = match self.client.number(hash)
+			.map_err(|_| internal_err("fetch block number failed"))?
+		{
+			Some(number) => UniqueSaturatedInto::<u32>::unique_saturated_into(number),
+			None => return Ok(None),
+		};
+		Ok(self.block_data(number)?
+			.filter(|(canonical_hash, _)| *canonical_hash == hash)
+			.map(|(_, data)| data))
+	}
+
+	/// Ethereum block `hash` and the statuses of its transactions, from the
+	/// block data cache or else from the runtime.
+	fn ethereum_block_by_hash(
+		&self,
+		hash: H256,
+	) -> Result<Option<(EthereumBlock, Vec<Option<TransactionStatus>>)>> {
+		let substrate_hash = self.frontier_backend.block_hash(&hash)
+			.map_err(|_| internal_err("fetch block mapping failed"))?;
+		if let Some(substrate_hash) = substrate_hash {
+			if let Some(data) = self.substrate_block_data(substrate_hash)? {
+				return Ok(Some((data.block.clone(), data.statuses.clone())));
+			}
+		}
+
+		let header = self.latest_header()?;
+
+		match self.client.runtime_api().block_by_hash_with_statuses(
+			&BlockId::Hash(header.hash()),
+			hash
+		) {
+			Ok((Some(block), statuses)) => Ok(Some((block, statuses))),
+			_ => Ok(None),
+		}
+	}
+
+	/// Ethereum block `number` and the statuses of its transactions, from the
+	/// block data cache or else from the runtime.
+	fn ethereum_block_by_number(
+		&self,
+		number: BlockNumber,
+	) -> Result<Option<(EthereumBlock, Vec<Option<TransactionStatus>>)>> {
+		let header = self.latest_header()?;
+		if let Ok(Some(native_number)) = self.native_block_number(Some(number)) {
+			if let Some((_, data)) = self.block_data(native_number)? {
+				return Ok(Some((data.block.clone(), data.statuses.clone())));
+			}
+			if let Ok((Some(block), statuses)) = self.client.runtime_api().block_by_number(
+				&BlockId::Hash(header.hash()),
+				native_number
+			) {
+				return Ok(Some((block, statuses)));
+			}
+		}
+		Ok(None)
+	}
+
+	fn native_block_id(&self, number: Option<BlockNumber>) -> Result<Option<BlockId<B>>> {
+		let id = native_block_id(
+			self.client.as_ref(),
+			self.frontier_backend.as_ref(),
+			self.latest,
+			number,
+		)?;
+		if let Some(id) = &id {
+			ensure_ethereum_api(self.client.as_ref(), id)?;
 		}
-		Ok(native_number)
+		Ok(id)
+	}
+
+	fn execute_gas_limit(&self, at: &BlockId<B>, requested: Option<U256>) -> Result<U256> {
+		execute_gas_limit(self.client.as_ref(), at, requested, &self.limits)
+	}
+
+	/// Gas used by the transaction at `previous_index` in `block`, read from
+	/// the receipts of the block, or through the hash of its status for blocks
+	/// whose data is not readable.
+	fn previous_gas_used(&self, id: &BlockId<B>, block: &EthereumBlock, previous_index: usize) -> Result<U256> {
+		let number = block.header.number.as_u32();
+		if let Some((_, data)) = self.block_data(number)? {
+			return Ok(data.receipts.get(previous_index)
+				.map(|receipt| receipt.used_gas)
+				.unwrap_or_default());
+		}
+
+		let api = self.client.runtime_api();
+		let (_, statuses) = api.block_by_number(id, number)
+			.map_err(|_| internal_err("fetch runtime block failed"))?;
+		let previous_hash = match statuses.get(previous_index).cloned().flatten() {
+			Some(status) => status.transaction_hash,
+			None => return Ok(U256::zero()),
+		};
+		Ok(api.transaction_by_hash(id, previous_hash)
+			.map_err(|_| internal_err("fetch runtime transaction failed"))?
+			.map(|(_, _, _, previous_receipt)| previous_receipt.used_gas)
+			.unwrap_or_default())
+	}
+
+	/// Ethereum transactions ready in the pool, with their sender, decoded at
+	/// the block `id`.
+	/// Runtimes without `extrinsic_filter` have none.
+	fn ready_transactions(&self, id: &BlockId<B>) -> Result<Vec<(EthereumTransaction, PoolSender)>> {
+		if !extended_api_available(self.client.as_ref(), id)? {
+			return Ok(Vec::new());
+		}
+		let ready: Vec<_> = self.pool.ready().collect();
+		let xts: Vec<_> = ready.iter()
+			.map(|transaction| transaction.data().clone())
+			.collect();
+		// The indices pair each Ethereum transaction with its pool hash, which
+		// the senders are cached by.
+		let api = self.client.runtime_api();
+		let indices = api.ethereum_extrinsic_indices(id, xts.clone())
+			.map_err(|_| internal_err("fetch runtime extrinsic indices failed"))?;
+		let transactions = api.extrinsic_filter(id, xts)
+			.map_err(|_| internal_err("fetch runtime extrinsic filter failed"))?;
+
+		Ok(indices.into_iter().zip(transactions)
+			.filter_map(|(index, transaction)| {
+				let pool_hash = *ready.get(index as usize)?.hash();
+				let sender = self.sender_cache.sender(pool_hash, &transaction)?;
+				Some((transaction, sender))
+			})
+			.collect())
+	}
+
+	/// Runs `read` on the `pending` state, on top of the latest block. The
+	/// state is collected on the blocking pool, unless it is cached for the
+	/// latest block and ready transactions. Without a backend to collect it
+	/// from, the `pending` state is the latest one.
+	fn with_pending_state<T, F>(&self, read: F) -> BoxFuture<T> where
+		T: Send + 'static,
+		F: FnOnce(&C, &BlockId<B>, &PendingState) -> Result<T> + Send + 'static,
+	{
+		let header = match self.latest_header() {
+			Ok(header) => header,
+			Err(err) => return Box::new(future::err(err)),
+		};
+		let hash = header.hash();
+		let id = BlockId::Hash(hash);
+		let backend = match &self.backend {
+			Some(backend) => backend.clone(),
+			None => return Box::new(future::result(read(self.client.as_ref(), &id, &PendingState::default()))),
+		};
+
+		// Transactions are executed in the order of the pool, which keeps the
+		// dependencies between senders, such as a transfer funding another
+		// sender.
+		let (pending, ready): (Vec<_>, Vec<_>) = match self.ready_transactions(&id) {
+			Ok(ready) => ready.into_iter()
+				.map(|(transaction, sender)| ((sender.sender, transaction), sender.hash))
+				.unzip(),
+			Err(err) => return Box::new(future::err(err)),
+		};
+		if let Some(state) = self.pending_cache.get(hash, &ready) {
+			return Box::new(future::result(read(self.client.as_ref(), &id, &state)));
+		}
+
+		let client = self.client.clone();
+		let pending_cache = self.pending_cache.clone();
+		self.blocking_pool.spawn(move || {
+			let state = pending_state(client.as_ref(), backend.as_ref(), hash, pending)?;
+			let state = pending_cache.insert(hash, ready, state);
+			read(client.as_ref(), &id, &state)
+		})
+	}
+
+	/// Nonce of `address` once its ready pool transactions are included, so
+	/// that transactions sent in a row get consecutive nonces.
+	fn pending_nonce(&self, address: H160) -> Result<U256> {
+		let header = self.latest_header()?;
+		let id = BlockId::Hash(header.hash());
+
+		let nonce = self.client.runtime_api()
+			.account_basic(&id, address)
+			.map_err(|_| internal_err("fetch runtime account basic failed"))?
+			.nonce;
+
+		Ok(self.ready_transactions(&id)?.into_iter()
+			.filter(|(_, sender)| sender.sender == address)
+			.map(|(transaction, _)| transaction.nonce.saturating_add(U256::one()))
+			.fold(nonce, |nonce, next| nonce.max(next)))
 	}
 }
 
-impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
-	C: ProvideRuntimeApi<B> + StorageProvider<B,BE>,
-	C::Api: EthereumRuntimeApi<B>,
+impl<B, C, P, CT, BE> EthApiT for EthApi<B, C, P, CT, BE> where
+	C: ProvideRuntimeApi<B> + StorageProvider<B,BE> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 	B: BlockT<Hash=H256> + Send + Sync + 'static,
 	C: Send + Sync + 'static,
-	SC: SelectChain<B> + Clone + 'static,
-	P: TransactionPool<Block=B> + Send + Sync + 'static,
+	P: TransactionPool<Block=B, Hash=H256> + Send + Sync + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
 	/// Returns protocol version encoded as a string (quotes are necessary).
@@ -227,38 +1462,31 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 	}
 
 	fn hashrate(&self) -> Result<U256> {
-		Ok(U256::zero())
+		Ok(self.hashrates.total())
 	}
 
 	fn author(&self) -> Result<H160> {
-		let header = self.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		Ok(
 			self.client
 			.runtime_api()
 			.author(&BlockId::Hash(header.hash()))
-			.map_err(|_| internal_err("fetch runtime chain id failed"))?.into()
+			.map_err(|_| internal_err("fetch runtime author failed"))?.into()
 		)
 	}
 
 	fn is_mining(&self) -> Result<bool> {
-		Ok(self.is_authority)
+		Ok(self.is_authority || self.mining_worker.is_some())
 	}
 
 	fn chain_id(&self) -> Result<Option<U64>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
-		Ok(Some(self.client.runtime_api().chain_id(&BlockId::Hash(header.hash()))
-				.map_err(|_| internal_err("fetch runtime chain id failed"))?.into()))
+		let header = self.latest_header()?;
+		Ok(Some(self.runtime_cache.chain_id(&*self.client, header.hash())?.into()))
 	}
 
 	fn gas_price(&self) -> Result<U256> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 		Ok(
 			self.client
 				.runtime_api()
@@ -268,82 +1496,154 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		)
 	}
 
+	fn blob_base_fee(&self) -> Result<U256> {
+		Ok(U256::from(MIN_BLOB_BASE_FEE))
+	}
+
 	fn accounts(&self) -> Result<Vec<H160>> {
-		Ok(vec![])
+		Ok(self.signers.iter().flat_map(|signer| signer.accounts()).collect())
 	}
 
 	fn block_number(&self) -> Result<U256> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 		Ok(U256::from(header.number().clone().unique_saturated_into()))
 	}
 
-	fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
-		if let Ok(Some(native_number)) = self.native_block_number(number) {
-			return Ok(
-				self.client
-					.runtime_api()
-					.account_basic(&BlockId::Number(native_number.into()), address)
-					.map_err(|_| internal_err("fetch runtime chain id failed"))?
-					.balance.into(),
-			);
+	fn balance(&self, address: H160, number: Option<BlockNumber>) -> BoxFuture<U256> {
+		if let Some(BlockNumber::Pending) = number {
+			return self.with_pending_state(move |client, id, state| {
+				match state.account(address) {
+					Some(account) => Ok(account.balance),
+					None => Ok(client.runtime_api()
+						.account_basic(id, address)
+						.map_err(|_| internal_err("fetch runtime account basic failed"))?
+						.balance),
+				}
+			})
 		}
-		Ok(U256::zero())
+		let balance = || {
+			if let Some(id) = self.native_block_id(number)? {
+				let span = lookup_span("account_basic", &id);
+				let _enter = span.enter();
+				return Ok(
+					self.client
+						.runtime_api()
+						.account_basic(&id, address)
+						.map_err(|_| internal_err("fetch runtime chain id failed"))?
+						.balance.into(),
+				);
+			}
+			Ok(U256::zero())
+		};
+		Box::new(future::result(balance()))
 	}
 
 	fn proof(&self, _: H160, _: Vec<H256>, _: Option<BlockNumber>) -> BoxFuture<EthAccount> {
 		unimplemented!("proof");
 	}
 
-	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256> {
-		if let Ok(Some(native_number)) = self.native_block_number(number) {
-			return Ok(
-				self.client
-					.runtime_api()
-					.storage_at(&BlockId::Number(native_number.into()), address, index)
-					.map_err(|_| internal_err("fetch runtime chain id failed"))?
-					.into(),
-			);
+	fn account(&self, address: H160, number: Option<BlockNumber>) -> Result<AccountState> {
+		let id = match self.native_block_id(number)? {
+			Some(id) => id,
+			// The storage root of the pending state is not known, so `pending`
+			// reads the latest block.
+			None => BlockId::Hash(self.latest_header()?.hash()),
+		};
+		let span = lookup_span("account_state", &id);
+		let _enter = span.enter();
+
+		if !extended_api_available(self.client.as_ref(), &id)? {
+			return Err(not_supported_at("account storage roots", &id));
 		}
-		Ok(H256::default())
+		let api = self.client.runtime_api();
+		let basic = api.account_basic(&id, address)
+			.map_err(|_| internal_err("fetch runtime account basic failed"))?;
+		let code = api.account_code_at(&id, address)
+			.map_err(|_| internal_err("fetch runtime account code failed"))?;
+		let storage_root = api.account_storage_root(&id, address)
+			.map_err(|_| internal_err("fetch runtime account storage root failed"))?;
+
+		Ok(AccountState {
+			code_hash: H256::from_slice(Keccak256::digest(&code).as_slice()),
+			storage_root,
+			balance: basic.balance,
+			nonce: basic.nonce,
+		})
 	}
 
-	fn block_by_hash(&self, hash: H256, _: bool) -> Result<Option<RichBlock>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> BoxFuture<H256> {
+		if let Some(BlockNumber::Pending) = number {
+			return self.with_pending_state(move |client, id, state| {
+				let mut slot = [0u8; 32];
+				index.to_big_endian(&mut slot);
+				match state.storage(address, H256::from(slot)) {
+					Some(value) => Ok(value),
+					None => client.runtime_api()
+						.storage_at(id, address, index)
+						.map_err(|_| internal_err("fetch runtime storage failed")),
+				}
+			})
+		}
+		let storage = || {
+			if let Some(id) = self.native_block_id(number)? {
+				let span = lookup_span("storage_at", &id);
+				let _enter = span.enter();
+				return Ok(
+					self.client
+						.runtime_api()
+						.storage_at(&id, address, index)
+						.map_err(|_| internal_err("fetch runtime chain id failed"))?
+						.into(),
+				);
+			}
+			Ok(H256::default())
+		};
+		Box::new(future::result(storage()))
+	}
 
-		if let Ok((Some(block), statuses)) = self.client.runtime_api().block_by_hash_with_statuses(
-			&BlockId::Hash(header.hash()),
-			hash
-		) {
-			Ok(Some(rich_block_build(block, statuses, Some(hash))))
-		} else {
-			Ok(None)
+	fn block_by_hash(&self, hash: H256, _: bool) -> Result<Option<RichBlock>> {
+		match self.ethereum_block_by_hash(hash)? {
+			Some((block, statuses)) => {
+				let block = rich_block_build(block, statuses, Some(hash));
+				self.limits.check_response_size(&block)?;
+				Ok(Some(block))
+			},
+			None => Ok(None),
 		}
 	}
 
 	fn block_by_number(&self, number: BlockNumber, _: bool) -> Result<Option<RichBlock>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
-		if let Ok(Some(native_number)) = self.native_block_number(Some(number)) {
-			if let Ok((Some(block), statuses)) = self.client.runtime_api().block_by_number(
-				&BlockId::Hash(header.hash()),
-				native_number
-			) {
-				return Ok(Some(rich_block_build(block, statuses, None)));
-			}
+		match self.ethereum_block_by_number(number)? {
+			Some((block, statuses)) => {
+				let block = rich_block_build(block, statuses, None);
+				self.limits.check_response_size(&block)?;
+				Ok(Some(block))
+			},
+			None => Ok(None),
 		}
-		Ok(None)
+	}
+
+	fn header_by_hash(&self, hash: H256) -> Result<Option<RichHeader>> {
+		Ok(self.ethereum_block_by_hash(hash)?
+			.map(|(block, _)| rich_header_build(&block, Some(hash))))
+	}
+
+	fn header_by_number(&self, number: BlockNumber) -> Result<Option<RichHeader>> {
+		Ok(self.ethereum_block_by_number(number)?
+			.map(|(block, _)| rich_header_build(&block, None)))
 	}
 
 	fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
-		if let Ok(Some(native_number)) = self.native_block_number(number) {
+		if let Some(BlockNumber::Pending) = number {
+			return self.pending_nonce(address)
+		}
+		if let Some(id) = self.native_block_id(number)? {
+			let span = lookup_span("account_basic", &id);
+			let _enter = span.enter();
 			return Ok(
 				self.client
 					.runtime_api()
-					.account_basic(&BlockId::Number(native_number.into()), address)
+					.account_basic(&id, address)
 		   			.map_err(|_| internal_err("fetch runtime account basic failed"))?
 					   .nonce.into()
 			);
@@ -352,8 +1652,7 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 	}
 
 	fn block_transaction_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		let result = match self.client.runtime_api()
 			.block_transaction_count_by_hash(&BlockId::Hash(header.hash()), hash) {
@@ -364,8 +1663,7 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 	}
 
 	fn block_transaction_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		let mut result = None;
 		if let Ok(Some(native_number)) = self.native_block_number(Some(number)) {
@@ -386,36 +1684,61 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		Ok(U256::zero())
 	}
 
-	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes> {
-		if let Ok(Some(native_number)) = self.native_block_number(number) {
-			return Ok(
-				self.client
-					.runtime_api()
-					.account_code_at(&BlockId::Number(native_number.into()), address)
-					.map_err(|_| internal_err("fetch runtime chain id failed"))?
-					.into(),
-			);
+	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> BoxFuture<Bytes> {
+		if let Some(BlockNumber::Pending) = number {
+			return self.with_pending_state(move |client, id, state| {
+				match state.code(address) {
+					Some(code) => Ok(Bytes(code)),
+					None => Ok(Bytes(client.runtime_api()
+						.account_code_at(id, address)
+						.map_err(|_| internal_err("fetch runtime account code failed"))?)),
+				}
+			})
 		}
-		Ok(Bytes(vec![]))
+		let code = || {
+			if let Some(id) = self.native_block_id(number)? {
+				let span = lookup_span("account_code_at", &id);
+				let _enter = span.enter();
+				return Ok(
+					self.client
+						.runtime_api()
+						.account_code_at(&id, address)
+						.map_err(|_| internal_err("fetch runtime chain id failed"))?
+						.into(),
+				);
+			}
+			Ok(Bytes(vec![]))
+		};
+		Box::new(future::result(code()))
 	}
 
 	fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<H256> {
-		let transaction = match rlp::decode::<ethereum::Transaction>(&bytes.0[..]) {
-			Ok(transaction) => transaction,
-			Err(_) => return Box::new(
-				future::result(Err(internal_err("decode transaction failed")))
-			),
+		let (transaction, transaction_hash) = match decode_raw_transaction(&bytes.0[..]) {
+			Ok(decoded) => decoded,
+			Err(err) => return Box::new(future::result(Err(err))),
 		};
-		let transaction_hash = H256::from_slice(
-			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
-		);
-		let header = match self.select_chain.best_chain() {
-			Ok(header) => header,
-			Err(_) => return Box::new(
-				future::result(Err(internal_err("fetch header failed")))
-			),
+		// Transactions are validated on the actual best block, whichever block
+		// is served as `latest`.
+		let best_block_hash = self.client.info().best_hash;
+		let (chain_id, constants) = match self.runtime_cache.values(&*self.client, best_block_hash) {
+			Ok(values) => (values.chain_id, values.constants),
+			Err(err) => return Box::new(future::result(Err(err))),
 		};
-		let best_block_hash = header.hash();
+		if bytes.0.len() > constants.max_transaction_size as usize {
+			return Box::new(future::result(Err(internal_err("transaction too large"))))
+		}
+		if transaction.gas_limit > constants.block_gas_limit {
+			return Box::new(future::result(Err(internal_err("exceeds block gas limit"))))
+		}
+		match transaction.signature.chain_id() {
+			Some(transaction_chain_id) if transaction_chain_id != chain_id => return Box::new(
+				future::result(Err(internal_err("invalid chain id")))
+			),
+			None if !self.limits.allow_unprotected_txs || !constants.allow_unprotected_txs => return Box::new(
+				future::result(Err(internal_err("only replay-protected transactions allowed")))
+			),
+			_ => (),
+		}
 		Box::new(
 			self.pool
 				.submit_one(
@@ -425,7 +1748,11 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 				)
 				.compat()
 				.map(move |_| transaction_hash)
-				.map_err(|_| internal_err("submit transaction to pool failed"))
+				.map_err(|err| match err.into_pool_error() {
+					Ok(PoolError::InvalidTransaction(InvalidTransaction::ExhaustsResources)) =>
+						internal_err("exceeds block gas limit"),
+					_ => internal_err("submit transaction to pool failed"),
+				})
 		)
 	}
 
@@ -433,94 +1760,115 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		unimplemented!("submit_transaction");
 	}
 
-	fn call(&self, request: CallRequest, _: Option<BlockNumber>) -> Result<Bytes> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
-
-		let from = request.from.unwrap_or_default();
-		let to = request.to.unwrap_or_default();
-		let gas_price = request.gas_price.unwrap_or_default();
-		let gas_limit = request.gas.unwrap_or(U256::max_value());
-		let value = request.value.unwrap_or_default();
-		let data = request.data.map(|d| d.0).unwrap_or_default();
-		let nonce = request.nonce;
+	fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<Bytes> {
+		let prepare = || -> Result<_> {
+			let header = self.latest_header()?;
+			let id = BlockId::Hash(header.hash());
+			let block_override = block_override_build(block_override)?;
+			Ok((
+				id,
+				state_overrides_build(state_overrides)?,
+				execute_gas_price(self.client.as_ref(), &id, &request, block_override.base_fee)?,
+				block_override,
+				self.execute_gas_limit(&id, request.gas)?,
+			))
+		};
+		let (id, state_overrides, gas_price, block_override, gas_limit) = match prepare() {
+			Ok(prepared) => prepared,
+			Err(err) => return Box::new(future::err(err)),
+		};
 
-		let (ret, _) = self.client.runtime_api()
-			.call(
-				&BlockId::Hash(header.hash()),
-				from,
-				to,
-				data,
-				value,
-				gas_limit,
-				gas_price,
-				nonce,
-			)
-			.map_err(|_| internal_err("executing call failed"))?
-			.ok_or(internal_err("inner executing call failed"))?;
+		let call = DryRunCall {
+			from: request.from.unwrap_or_default(),
+			to: request.to,
+			data: request.data.map(|d| d.0).unwrap_or_default(),
+			value: request.value.unwrap_or_default(),
+			gas_limit,
+			gas_price,
+			nonce: request.nonce,
+		};
 
-		Ok(Bytes(ret))
-	}
+		let execute = move |client: &C, id: &BlockId<B>, pending: PendingChanges| -> Result<Bytes> {
+			let outcome = dry_run_call(client, id, call, state_overrides, block_override, pending)?;
+			if let Some(error) = outcome.error {
+				return Err(execution_err(error, outcome.output));
+			}
 
-	fn estimate_gas(&self, request: CallRequest, _: Option<BlockNumber>) -> Result<U256> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+			Ok(Bytes(outcome.output))
+		};
 
-		let from = request.from.unwrap_or_default();
-		let to = request.to.unwrap_or_default();
-		let gas_price = request.gas_price.unwrap_or_default();
-		let gas_limit = request.gas.unwrap_or(U256::max_value());
-		let value = request.value.unwrap_or_default();
-		let data = request.data.map(|d| d.0).unwrap_or_default();
-		let nonce = request.nonce;
+		match number {
+			Some(BlockNumber::Pending) => self.with_pending_state(move |client, id, state| {
+				execute(client, id, state.changes())
+			}),
+			_ => Box::new(future::result(execute(self.client.as_ref(), &id, Vec::new()))),
+		}
+	}
 
-		let (_, used_gas) = self.client.runtime_api()
-			.call(
-				&BlockId::Hash(header.hash()),
-				from,
-				to,
-				data,
-				value,
-				gas_limit,
-				gas_price,
-				nonce,
-			)
-			.map_err(|_| internal_err("executing call failed"))?
-			.ok_or(internal_err("inner executing call failed"))?;
+	fn simulate(
+		&self,
+		payload: SimulatePayload,
+		number: Option<BlockNumber>,
+	) -> BoxFuture<Vec<SimulatedBlock>> {
+		let client = self.client.clone();
+		let frontier_backend = self.frontier_backend.clone();
+		let latest = self.latest;
+		let limits = self.limits.clone();
+		self.blocking_pool.spawn(move || simulate(
+			client.as_ref(),
+			frontier_backend.as_ref(),
+			latest,
+			&limits,
+			payload,
+			number,
+		))
+	}
 
-		Ok(used_gas)
+	fn estimate_gas(&self, request: CallRequest, _: Option<BlockNumber>) -> BoxFuture<U256> {
+		let client = self.client.clone();
+		let latest = self.latest;
+		let limits = self.limits.clone();
+		self.blocking_pool.spawn(move || {
+			estimate_gas(client.as_ref(), latest, &limits, request)
+		})
 	}
 
 	fn transaction_by_hash(&self, hash: H256) -> Result<Option<Transaction>> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		if let Ok(Some((transaction, block, status, _receipt))) = self.client.runtime_api()
 			.transaction_by_hash(&BlockId::Hash(header.hash()), hash) {
 			return Ok(Some(transaction_build(
 				transaction,
-				block,
+				&block,
+				None,
 				status
 			)));
 		}
 		Ok(None)
 	}
 
+	fn pending_transactions(&self, from: Option<H160>) -> Result<Vec<Transaction>> {
+		let header = self.latest_header()?;
+
+		Ok(self.ready_transactions(&BlockId::Hash(header.hash()))?.into_iter()
+			.filter(|(_, sender)| from.map_or(true, |from| sender.sender == from))
+			.map(|(transaction, sender)| pending_transaction_build(transaction, sender))
+			.collect())
+	}
+
 	fn transaction_by_block_hash_and_index(
 		&self,
 		hash: H256,
 		index: Index,
 	) -> Result<Option<Transaction>> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		let index_param = index.value() as u32;
 
@@ -528,7 +1876,8 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 			.transaction_by_block_hash_and_index(&BlockId::Hash(header.hash()), hash, index_param) {
 			return Ok(Some(transaction_build(
 				transaction,
-				block,
+				&block,
+				None,
 				status
 			)));
 		}
@@ -540,10 +1889,7 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		number: BlockNumber,
 		index: Index,
 	) -> Result<Option<Transaction>> {
-		let header = self
-			.select_chain
-			.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
+		let header = self.latest_header()?;
 
 		let index_param = index.value() as u32;
 
@@ -555,7 +1901,8 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 					index_param) {
 				return Ok(Some(transaction_build(
 					transaction,
-					block,
+					&block,
+					None,
 					status
 				)));
 			}
@@ -564,45 +1911,70 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 	}
 
 	fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>> {
-		let header = self.select_chain.best_chain()
-			.map_err(|_| internal_err("fetch header failed"))?;
-		if let Ok(Some((_transaction, block, status, receipt))) = self.client.runtime_api()
-			.transaction_by_hash(&BlockId::Hash(header.hash()), hash) {
-			
-			let block_hash = H256::from_slice(
-				Keccak256::digest(&rlp::encode(&block.header)).as_slice()
-			);
-			return Ok(Some(Receipt {
-				transaction_hash: Some(status.transaction_hash),
-				transaction_index: Some(status.transaction_index.into()),
-				block_hash: Some(block_hash),
-				from: Some(status.from),
-				to: status.to,
-				block_number: Some(block.header.number),
-				cumulative_gas_used: Default::default(), // TODO
-				gas_used: Some(receipt.used_gas),
-				contract_address: status.contract_address,
-				logs: {
-					receipt.logs.iter().map(|log| {
-						Log {
-							address: log.address,
-							topics: log.topics.clone(),
-							data: Bytes(log.data.clone()),
-							block_hash: Some(block_hash),
-							block_number: Some(block.header.number),
-							transaction_hash: Some(hash),
-							transaction_index: Some(status.transaction_index.into()),
-							log_index: None, // TODO
-							transaction_log_index: None, // TODO
-							log_type: Default::default(), // TODO
-							removed: false, // TODO
-						}
-					}).collect()
-				},
-				state_root: Some(receipt.state_root),
-				logs_bloom: Default::default(), // TODO
-				status_code: None,
-			}))
+		let metadata = self.frontier_backend.transaction_metadata(&hash)
+			.map_err(|_| internal_err("fetch transaction mapping failed"))?;
+		if let Some(metadata) = &metadata {
+			if let Some(data) = self.substrate_block_data(metadata.block_hash)? {
+				let index = metadata.ethereum_index as usize;
+				let transaction = data.block.transactions.get(index);
+				let status = data.statuses.get(index).cloned().flatten();
+				let receipt = data.receipts.get(index);
+				if let (Some(transaction), Some(status), Some(receipt)) = (transaction, status, receipt) {
+					let previous_gas_used = index.checked_sub(1)
+						.and_then(|previous_index| data.receipts.get(previous_index))
+						.map(|previous_receipt| previous_receipt.used_gas)
+						.unwrap_or_default();
+					let log_index = metadata.log_index.unwrap_or_else(|| {
+						data.receipts[..index].iter().map(|receipt| receipt.logs.len() as u32).sum()
+					});
+					return Ok(Some(receipt_build(
+						transaction,
+						&data.block,
+						status,
+						receipt.clone(),
+						previous_gas_used,
+						Some(log_index),
+					)));
+				}
+			}
+		}
+
+		let header = self.latest_header()?;
+		let id = BlockId::Hash(header.hash());
+		let metadata = match metadata {
+			Some(metadata) if extended_api_available(self.client.as_ref(), &id)? => Some(metadata),
+			_ => None,
+		};
+		if let Some(metadata) = metadata {
+			// The mapping locates the transaction in its block, which a single
+			// runtime call decodes for the transaction, status and receipt.
+			// Runtimes without that call look the transaction up by hash below.
+			let found = self.client.runtime_api()
+				.transaction_receipt(&id, metadata.ethereum_block_hash, metadata.ethereum_index)
+				.map_err(|_| internal_err("fetch runtime receipt failed"))?;
+			if let Some((transaction, block, status, receipt, previous_gas_used)) = found {
+				return Ok(Some(receipt_build(
+					&transaction,
+					&block,
+					status,
+					receipt,
+					previous_gas_used,
+					metadata.log_index,
+				)));
+			}
+		}
+
+		if let Ok(Some((transaction, block, status, receipt))) = self.client.runtime_api()
+			.transaction_by_hash(&id, hash) {
+
+			// Receipts hold the gas used in the block up to their transaction.
+			let previous_gas_used = match (status.transaction_index as usize).checked_sub(1) {
+				Some(previous_index) => self.previous_gas_used(&id, &block, previous_index)?,
+				None => U256::zero(),
+			};
+			return Ok(Some(
+				receipt_build(&transaction, &block, status, receipt, previous_gas_used, None)
+			));
 		}
 		Ok(None)
 	}
@@ -635,25 +2007,33 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		unimplemented!("compile_serpent");
 	}
 
-	fn logs(&self, _: Filter) -> BoxFuture<Vec<Log>> {
-		unimplemented!("logs");
+	fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>> {
+		let client = self.client.clone();
+		let frontier_backend = self.frontier_backend.clone();
+		let latest = self.latest;
+		let limits = self.limits.clone();
+		self.blocking_pool.spawn(move || {
+			filter_logs(client.as_ref(), frontier_backend.as_ref(), latest, &limits, filter)
+		})
 	}
 
 	fn work(&self) -> Result<Work> {
-		Ok(Work {
-			pow_hash: H256::default(),
-			seed_hash: H256::default(),
-			target: H256::default(),
-			number: None,
-		})
+		self.mining_worker.as_ref()
+			.and_then(|worker| worker.work())
+			.ok_or_else(|| internal_err("no mining work available"))
 	}
 
-	fn submit_work(&self, _: H64, _: H256, _: H256) -> Result<bool> {
-		Ok(false)
+	fn submit_work(&self, nonce: H64, pow_hash: H256, mix_digest: H256) -> Result<bool> {
+		Ok(self.mining_worker.as_ref()
+			.map_or(false, |worker| worker.submit_work(nonce, pow_hash, mix_digest)))
 	}
 
-	fn submit_hashrate(&self, _: U256, _: H256) -> Result<bool> {
-		Ok(false)
+	fn submit_hashrate(&self, rate: U256, id: H256) -> Result<bool> {
+		if self.mining_worker.is_none() {
+			return Ok(false)
+		}
+		self.hashrates.submit(id, rate);
+		Ok(true)
 	}
 
 	fn is_listening(&self) -> Result<bool> {
@@ -0,0 +1,690 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Ethereum rpc of light clients.
+//!
+//! A light client only holds headers. `LightEthApi` serves the methods
+//! reading the EVM state and the Ethereum blocks by executing the Ethereum
+//! runtime api remotely: full nodes answer through the fetcher with a proof of
+//! the execution, checked against the header of the block. Methods relying on
+//! the mapping database, the pool contents or re-execution of blocks are not
+//! available, nor are block hash parameters, which need the mapping database
+//! to be resolved. The `pending` tag reads the latest block.
+//!
+//! Remote calls are retried on failure, and abandoned after
+//! `REMOTE_CALL_TIMEOUT`, since the methods answering synchronously wait for
+//! them on the rpc thread.
+
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc, time::Duration};
+use codec::{Encode, Decode};
+use ethereum::{Block as EthereumBlock, Transaction as EthereumTransaction};
+use ethereum_types::{H160, H256, H64, U256, U64};
+use futures::{
+	FutureExt, TryFutureExt, executor::block_on, future::{Either, Future as Future03, select},
+};
+use futures_timer::Delay;
+use jsonrpc_core::{BoxFuture, Result, Error, ErrorCode, futures::future};
+use sp_api::{BlockId, RuntimeApiInfo, RuntimeVersion};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
+use sp_runtime::transaction_validity::TransactionSource;
+use sp_transaction_pool::TransactionPool;
+use sc_client_api::light::{future_header, Fetcher, RemoteBlockchain, RemoteCallRequest};
+use sha3::{Keccak256, Digest};
+use frontier_rpc_core::EthApi as EthApiT;
+use frontier_rpc_core::types::{
+	AccountState, BlockNumber, Bytes, CallRequest, CallStateOverride, CallBlockOverride, EthAccount,
+	Filter, Index, Log, Receipt, RichBlock, RichHeader, SimulatePayload, SimulatedBlock, SyncStatus,
+	Transaction, Work,
+};
+use frontier_rpc_primitives::{
+	AccountOverride, BlockOverride, ConvertTransaction, EthereumRuntimeApi, TransactionStatus,
+	DryRunCall, DryRunOutcome, LegacyBlockOverride, PendingChanges, ValidationConstants,
+};
+
+use crate::{
+	BASE_FEE_OVERRIDE_VERSION, DRY_RUN_OUTCOME_VERSION, METHOD_NOT_AVAILABLE, MIN_BLOB_BASE_FEE, LatestBlock, internal_err,
+	execution_err, latest_header, legacy_validation_constants, decode_raw_transaction, rich_block_build, rich_header_build, transaction_build,
+	state_overrides_build, block_override_build,
+};
+
+/// Time after which a remote runtime call is abandoned, so that an
+/// unresponsive full node does not hold the rpc thread waiting for it.
+const REMOTE_CALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of times a failed remote runtime call is retried.
+const REMOTE_CALL_RETRIES: usize = 2;
+
+/// Full name of the Ethereum runtime api `method`.
+fn ethereum_method(method: &str) -> String {
+	format!("EthereumRuntimeApi_{}", method)
+}
+
+/// Request executing the runtime `method`, with its full name, at `header`.
+fn remote_call_request<H: HeaderT>(header: H, method: String, call_data: Vec<u8>) -> RemoteCallRequest<H> {
+	RemoteCallRequest {
+		block: header.hash(),
+		header,
+		method,
+		call_data,
+		retry_count: Some(REMOTE_CALL_RETRIES),
+	}
+}
+
+/// Arguments of the `call` of the Ethereum runtime api, from version 2 on: its
+/// block override is a `BlockOverride` from version 4 on, and a
+/// `LegacyBlockOverride` before.
+fn call_data<O: Encode>(
+	call: DryRunCall,
+	state_overrides: Vec<(H160, AccountOverride)>,
+	block_override: O,
+	pending: PendingChanges,
+) -> Vec<u8> {
+	(
+		call.from,
+		call.to.unwrap_or_default(),
+		call.data,
+		call.value,
+		call.gas_limit,
+		call.gas_price,
+		call.nonce,
+		state_overrides,
+		block_override,
+		pending,
+	).encode()
+}
+
+/// Arguments of the `call` of version 1 of the Ethereum runtime api.
+fn legacy_call_data(call: DryRunCall) -> Vec<u8> {
+	(
+		call.from,
+		call.to.unwrap_or_default(),
+		call.data,
+		call.value,
+		call.gas_limit,
+		call.gas_price,
+		call.nonce,
+	).encode()
+}
+
+/// Error of the methods a light client cannot serve.
+fn not_available(what: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(METHOD_NOT_AVAILABLE),
+		message: format!("{} not available on light clients", what),
+		data: None,
+	}
+}
+
+/// Eth rpc of a light client, executing the Ethereum runtime api remotely.
+pub struct LightEthApi<B: BlockT, C, F, P, CT> {
+	client: Arc<C>,
+	remote_blockchain: Arc<dyn RemoteBlockchain<B>>,
+	fetcher: Arc<F>,
+	pool: Arc<P>,
+	convert_transaction: CT,
+	latest: LatestBlock,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C, F, P, CT> LightEthApi<B, C, F, P, CT> {
+	pub fn new(
+		client: Arc<C>,
+		remote_blockchain: Arc<dyn RemoteBlockchain<B>>,
+		fetcher: Arc<F>,
+		pool: Arc<P>,
+		convert_transaction: CT,
+		latest: LatestBlock,
+	) -> Self {
+		Self {
+			client,
+			remote_blockchain,
+			fetcher,
+			pool,
+			convert_transaction,
+			latest,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, F, P, CT> LightEthApi<B, C, F, P, CT> where
+	B: BlockT<Hash=H256>,
+	C: HeaderBackend<B>,
+	F: Fetcher<B> + 'static,
+{
+	/// Executes the Ethereum runtime api `method` with the encoded `arguments`
+	/// on a full node, at the block `hash`.
+	fn remote_call<R: Decode>(
+		&self,
+		hash: B::Hash,
+		method: &'static str,
+		arguments: Vec<u8>,
+	) -> impl Future03<Output = Result<R>> {
+		self.remote_runtime_call(hash, ethereum_method(method), arguments)
+	}
+
+	/// Executes the runtime `method`, with its full name, on a full node.
+	fn remote_runtime_call<R: Decode>(
+		&self,
+		hash: B::Hash,
+		method: String,
+		arguments: Vec<u8>,
+	) -> impl Future03<Output = Result<R>> {
+		let header = future_header(&*self.remote_blockchain, &*self.fetcher, BlockId::Hash(hash));
+		let fetcher = self.fetcher.clone();
+		let timeout = Delay::new(REMOTE_CALL_TIMEOUT);
+		async move {
+			let call = async {
+				let header = header.await
+					.map_err(|_| internal_err("fetch header failed"))?
+					.ok_or_else(|| internal_err("fetch header failed"))?;
+				let result = fetcher.remote_call(remote_call_request(header, method.clone(), arguments))
+					.await
+					.map_err(|_| internal_err(&format!("remote runtime {} failed", method)))?;
+				R::decode(&mut &result[..])
+					.map_err(|_| internal_err(&format!("decode remote runtime {} failed", method)))
+			};
+			match select(Box::pin(call), timeout).await {
+				Either::Left((result, _)) => result,
+				Either::Right(_) => Err(internal_err(&format!("remote runtime {} timed out", method))),
+			}
+		}
+	}
+
+	/// Like `remote_call`, waiting for the result for the methods answering
+	/// synchronously.
+	fn remote_call_blocking<R: Decode>(
+		&self,
+		hash: B::Hash,
+		method: &'static str,
+		arguments: Vec<u8>,
+	) -> Result<R> {
+		block_on(self.remote_call(hash, method, arguments))
+	}
+
+	/// Whether the runtime of block `hash` has at least `version` of the
+	/// Ethereum runtime api.
+	fn api_version_available(&self, hash: B::Hash, version: u32) -> impl Future03<Output = Result<bool>> {
+		let runtime_version = self.remote_runtime_call::<RuntimeVersion>(hash, "Core_version".into(), Vec::new());
+		async move {
+			Ok(runtime_version.await?.has_api_with(
+				&<dyn EthereumRuntimeApi<B> as RuntimeApiInfo>::ID,
+				|available| available >= version,
+			))
+		}
+	}
+
+	/// Whether the runtime of block `hash` has the `call` returning a
+	/// `DryRunOutcome`.
+	fn dry_run_outcome_available(&self, hash: B::Hash) -> impl Future03<Output = Result<bool>> {
+		self.api_version_available(hash, DRY_RUN_OUTCOME_VERSION)
+	}
+
+	/// Constants that requests to the runtime of block `hash` are checked
+	/// against.
+	fn validation_constants(&self, hash: B::Hash) -> impl Future03<Output = Result<ValidationConstants>> {
+		let available = self.dry_run_outcome_available(hash);
+		let constants = self.remote_call::<ValidationConstants>(hash, "validation_constants", Vec::new());
+		async move {
+			if !available.await? {
+				return Ok(legacy_validation_constants())
+			}
+			constants.await
+		}
+	}
+
+	fn latest_hash(&self) -> Result<B::Hash> {
+		Ok(latest_header(self.client.as_ref(), self.latest)?.hash())
+	}
+
+	/// Hash of the block `number`, `latest` by default.
+	fn block_hash(&self, number: Option<BlockNumber>) -> Result<B::Hash> {
+		let number: u32 = match number {
+			None | Some(BlockNumber::Latest) | Some(BlockNumber::Pending) => return self.latest_hash(),
+			Some(BlockNumber::Hash { .. }) => return Err(not_available("block hash parameters")),
+			Some(BlockNumber::Earliest) => 1,
+			Some(BlockNumber::Num(number)) => number.unique_saturated_into(),
+		};
+		self.client.hash(number.into())
+			.map_err(|_| internal_err("fetch block hash failed"))?
+			.ok_or_else(|| internal_err("unknown block"))
+	}
+
+	/// Ethereum block `number`, with its transaction statuses, read at the
+	/// latest block.
+	fn ethereum_block_by_number(
+		&self,
+		number: BlockNumber,
+	) -> Result<Option<(EthereumBlock, Vec<Option<TransactionStatus>>)>> {
+		let latest = latest_header(self.client.as_ref(), self.latest)?;
+		let number: u32 = match number {
+			BlockNumber::Hash { .. } => return Err(not_available("block hash parameters")),
+			BlockNumber::Num(number) => number.unique_saturated_into(),
+			BlockNumber::Earliest => 1,
+			BlockNumber::Latest | BlockNumber::Pending => latest.number().clone().unique_saturated_into(),
+		};
+		let (block, statuses): (Option<EthereumBlock>, Vec<Option<TransactionStatus>>) =
+			self.remote_call_blocking(latest.hash(), "block_by_number", number.encode())?;
+		Ok(block.map(|block| (block, statuses)))
+	}
+
+	fn account_basic(&self, address: H160, number: Option<BlockNumber>) -> Result<pallet_evm::Account> {
+		let hash = self.block_hash(number)?;
+		self.remote_call_blocking(hash, "account_basic", address.encode())
+	}
+
+	fn call_blocking(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_override: Option<CallBlockOverride>,
+	) -> Result<Bytes> {
+		if request.max_fee_per_gas.is_some() || request.max_priority_fee_per_gas.is_some() {
+			return Err(not_available("maxFeePerGas and maxPriorityFeePerGas"))
+		}
+		let hash = self.block_hash(number)?;
+		let state_overrides = state_overrides_build(state_overrides)?;
+		let block_override = block_override_build(block_override)?;
+
+		let constants = block_on(self.validation_constants(hash))?;
+		let max_gas_limit = constants.block_gas_limit
+			.saturating_mul(U256::from(constants.execute_gas_limit_multiplier));
+		let call = DryRunCall {
+			from: request.from.unwrap_or_default(),
+			to: request.to,
+			data: request.data.map(|d| d.0).unwrap_or_default(),
+			value: request.value.unwrap_or_default(),
+			gas_limit: request.gas.map_or(max_gas_limit, |gas| gas.min(max_gas_limit)),
+			gas_price: request.gas_price.unwrap_or_default(),
+			nonce: request.nonce,
+		};
+
+		let base_fee_override_available =
+			block_on(self.api_version_available(hash, BASE_FEE_OVERRIDE_VERSION))?;
+		if !base_fee_override_available && block_override.base_fee.is_some() {
+			return Err(not_available("base fee overrides before version 4 of the runtime api"))
+		}
+		if !base_fee_override_available && !block_on(self.dry_run_outcome_available(hash))? {
+			if !state_overrides.is_empty() || block_override != BlockOverride::default() {
+				return Err(not_available("call overrides before version 2 of the runtime api"))
+			}
+			let result: Option<(Vec<u8>, U256)> =
+				self.remote_call_blocking(hash, "call", legacy_call_data(call))?;
+			let (output, _) = result.ok_or(internal_err("inner executing call failed"))?;
+			return Ok(Bytes(output))
+		}
+
+		let call_data = if base_fee_override_available {
+			call_data(call, state_overrides, block_override, Vec::new())
+		} else {
+			call_data(call, state_overrides, LegacyBlockOverride::from(block_override), Vec::new())
+		};
+		let outcome: Option<DryRunOutcome> = self.remote_call_blocking(hash, "call", call_data)?;
+		let outcome = outcome.ok_or(internal_err("inner executing call failed"))?;
+		if let Some(error) = outcome.error {
+			return Err(execution_err(error, outcome.output));
+		}
+
+		Ok(Bytes(outcome.output))
+	}
+}
+
+impl<B, C, F, P, CT> EthApiT for LightEthApi<B, C, F, P, CT> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	F: Fetcher<B> + 'static,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
+{
+	fn protocol_version(&self) -> Result<String> {
+		Err(not_available("eth_protocolVersion"))
+	}
+
+	fn syncing(&self) -> Result<SyncStatus> {
+		Err(not_available("eth_syncing"))
+	}
+
+	fn hashrate(&self) -> Result<U256> {
+		Ok(U256::zero())
+	}
+
+	fn author(&self) -> Result<H160> {
+		self.remote_call_blocking(self.latest_hash()?, "author", Vec::new())
+	}
+
+	fn is_mining(&self) -> Result<bool> {
+		Ok(false)
+	}
+
+	fn chain_id(&self) -> Result<Option<U64>> {
+		let chain_id: u64 = self.remote_call_blocking(self.latest_hash()?, "chain_id", Vec::new())?;
+		Ok(Some(chain_id.into()))
+	}
+
+	fn gas_price(&self) -> Result<U256> {
+		self.remote_call_blocking(self.latest_hash()?, "gas_price", Vec::new())
+	}
+
+	fn blob_base_fee(&self) -> Result<U256> {
+		Ok(U256::from(MIN_BLOB_BASE_FEE))
+	}
+
+	fn accounts(&self) -> Result<Vec<H160>> {
+		Ok(Vec::new())
+	}
+
+	fn block_number(&self) -> Result<U256> {
+		let header = latest_header(self.client.as_ref(), self.latest)?;
+		Ok(U256::from(header.number().clone().unique_saturated_into()))
+	}
+
+	fn balance(&self, address: H160, number: Option<BlockNumber>) -> BoxFuture<U256> {
+		Box::new(future::result(self.account_basic(address, number).map(|basic| basic.balance)))
+	}
+
+	fn proof(&self, _: H160, _: Vec<H256>, _: Option<BlockNumber>) -> BoxFuture<EthAccount> {
+		Box::new(future::err(not_available("eth_getProof")))
+	}
+
+	fn account(&self, address: H160, number: Option<BlockNumber>) -> Result<AccountState> {
+		let hash = self.block_hash(number)?;
+		let basic: pallet_evm::Account = self.remote_call_blocking(hash, "account_basic", address.encode())?;
+		let code: Vec<u8> = self.remote_call_blocking(hash, "account_code_at", address.encode())?;
+		let storage_root = self.remote_call_blocking(hash, "account_storage_root", address.encode())?;
+
+		Ok(AccountState {
+			code_hash: H256::from_slice(Keccak256::digest(&code).as_slice()),
+			storage_root,
+			balance: basic.balance,
+			nonce: basic.nonce,
+		})
+	}
+
+	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> BoxFuture<H256> {
+		Box::new(future::result(self.block_hash(number).and_then(|hash| {
+			self.remote_call_blocking(hash, "storage_at", (address, index).encode())
+		})))
+	}
+
+	fn block_by_hash(&self, hash: H256, _: bool) -> Result<Option<RichBlock>> {
+		let (block, statuses): (Option<EthereumBlock>, Vec<Option<TransactionStatus>>) =
+			self.remote_call_blocking(self.latest_hash()?, "block_by_hash_with_statuses", hash.encode())?;
+		Ok(block.map(|block| rich_block_build(block, statuses, Some(hash))))
+	}
+
+	fn block_by_number(&self, number: BlockNumber, _: bool) -> Result<Option<RichBlock>> {
+		Ok(self.ethereum_block_by_number(number)?
+			.map(|(block, statuses)| rich_block_build(block, statuses, None)))
+	}
+
+	fn header_by_hash(&self, hash: H256) -> Result<Option<RichHeader>> {
+		let block: Option<EthereumBlock> =
+			self.remote_call_blocking(self.latest_hash()?, "block_by_hash", hash.encode())?;
+		Ok(block.map(|block| rich_header_build(&block, Some(hash))))
+	}
+
+	fn header_by_number(&self, number: BlockNumber) -> Result<Option<RichHeader>> {
+		Ok(self.ethereum_block_by_number(number)?
+			.map(|(block, _)| rich_header_build(&block, None)))
+	}
+
+	fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+		Ok(self.account_basic(address, number)?.nonce)
+	}
+
+	fn block_transaction_count_by_hash(&self, hash: H256) -> Result<Option<U256>> {
+		self.remote_call_blocking(self.latest_hash()?, "block_transaction_count_by_hash", hash.encode())
+	}
+
+	fn block_transaction_count_by_number(&self, number: BlockNumber) -> Result<Option<U256>> {
+		Ok(self.ethereum_block_by_number(number)?
+			.map(|(block, _)| U256::from(block.transactions.len())))
+	}
+
+	fn block_uncles_count_by_hash(&self, _: H256) -> Result<U256> {
+		Ok(U256::zero())
+	}
+
+	fn block_uncles_count_by_number(&self, _: BlockNumber) -> Result<U256> {
+		Ok(U256::zero())
+	}
+
+	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> BoxFuture<Bytes> {
+		Box::new(future::result(self.block_hash(number).and_then(|hash| {
+			self.remote_call_blocking(hash, "account_code_at", address.encode()).map(Bytes)
+		})))
+	}
+
+	fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<H256> {
+		let (transaction, transaction_hash) = match decode_raw_transaction(&bytes.0[..]) {
+			Ok(decoded) => decoded,
+			Err(err) => return Box::new(future::err(err)),
+		};
+		let best_hash = self.client.info().best_hash;
+		let chain_id = self.remote_call::<u64>(best_hash, "chain_id", Vec::new());
+		let constants = self.validation_constants(best_hash);
+		let extrinsic = self.convert_transaction.convert_transaction(transaction.clone());
+		let pool = self.pool.clone();
+		let length = bytes.0.len();
+		let future = async move {
+			let (chain_id, constants) = (chain_id.await?, constants.await?);
+			if length > constants.max_transaction_size as usize {
+				return Err(internal_err("transaction too large"))
+			}
+			if transaction.gas_limit > constants.block_gas_limit {
+				return Err(internal_err("exceeds block gas limit"))
+			}
+			match transaction.signature.chain_id() {
+				Some(transaction_chain_id) if transaction_chain_id != chain_id =>
+					return Err(internal_err("invalid chain id")),
+				None if !constants.allow_unprotected_txs =>
+					return Err(internal_err("only replay-protected transactions allowed")),
+				_ => (),
+			}
+			pool.submit_one(&BlockId::Hash(best_hash), TransactionSource::Local, extrinsic)
+				.await
+				.map(|_| transaction_hash)
+				.map_err(|_| internal_err("submit transaction to pool failed"))
+		};
+		Box::new(future.boxed().compat())
+	}
+
+	fn submit_transaction(&self, _: Bytes) -> Result<H256> {
+		Err(not_available("eth_submitTransaction"))
+	}
+
+	fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<Bytes> {
+		Box::new(future::result(self.call_blocking(request, number, state_overrides, block_override)))
+	}
+
+	fn simulate(&self, _: SimulatePayload, _: Option<BlockNumber>) -> BoxFuture<Vec<SimulatedBlock>> {
+		Box::new(future::err(not_available("eth_simulateV1")))
+	}
+
+	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> BoxFuture<U256> {
+		Box::new(future::err(not_available("eth_estimateGas")))
+	}
+
+	fn transaction_by_hash(&self, hash: H256) -> Result<Option<Transaction>> {
+		let found: Option<(EthereumTransaction, EthereumBlock, TransactionStatus, ethereum::Receipt)> =
+			self.remote_call_blocking(self.latest_hash()?, "transaction_by_hash", hash.encode())?;
+		Ok(found.map(|(transaction, block, status, _receipt)| {
+			transaction_build(transaction, &block, None, status)
+		}))
+	}
+
+	fn pending_transactions(&self, _: Option<H160>) -> Result<Vec<Transaction>> {
+		Err(not_available("eth_pendingTransactions"))
+	}
+
+	fn transaction_by_block_hash_and_index(
+		&self,
+		hash: H256,
+		index: Index,
+	) -> Result<Option<Transaction>> {
+		let found: Option<(EthereumTransaction, EthereumBlock, TransactionStatus)> =
+			self.remote_call_blocking(
+				self.latest_hash()?,
+				"transaction_by_block_hash_and_index",
+				(hash, index.value() as u32).encode(),
+			)?;
+		Ok(found.map(|(transaction, block, status)| {
+			transaction_build(transaction, &block, None, status)
+		}))
+	}
+
+	fn transaction_by_block_number_and_index(
+		&self,
+		number: BlockNumber,
+		index: Index,
+	) -> Result<Option<Transaction>> {
+		Ok(self.ethereum_block_by_number(number)?.and_then(|(block, statuses)| {
+			let index = index.value();
+			let transaction = block.transactions.get(index)?.clone();
+			let status = statuses.get(index).cloned()??;
+			Some(transaction_build(transaction, &block, None, status))
+		}))
+	}
+
+	fn transaction_receipt(&self, _: H256) -> Result<Option<Receipt>> {
+		Err(not_available("eth_getTransactionReceipt"))
+	}
+
+	fn uncle_by_block_hash_and_index(&self, _: H256, _: Index) -> Result<Option<RichBlock>> {
+		Ok(None)
+	}
+
+	fn uncle_by_block_number_and_index(
+		&self,
+		_: BlockNumber,
+		_: Index,
+	) -> Result<Option<RichBlock>> {
+		Ok(None)
+	}
+
+	fn compilers(&self) -> Result<Vec<String>> {
+		Err(not_available("eth_getCompilers"))
+	}
+
+	fn compile_lll(&self, _: String) -> Result<Bytes> {
+		Err(not_available("eth_compileLLL"))
+	}
+
+	fn compile_solidity(&self, _: String) -> Result<Bytes> {
+		Err(not_available("eth_compileSolidity"))
+	}
+
+	fn compile_serpent(&self, _: String) -> Result<Bytes> {
+		Err(not_available("eth_compileSerpent"))
+	}
+
+	fn logs(&self, _: Filter) -> BoxFuture<Vec<Log>> {
+		Box::new(future::err(not_available("eth_getLogs")))
+	}
+
+	fn work(&self) -> Result<Work> {
+		Err(not_available("eth_getWork"))
+	}
+
+	fn submit_work(&self, _: H64, _: H256, _: H256) -> Result<bool> {
+		Ok(false)
+	}
+
+	fn submit_hashrate(&self, _: U256, _: H256) -> Result<bool> {
+		Ok(false)
+	}
+
+	fn is_listening(&self) -> Result<bool> {
+		Ok(true)
+	}
+
+	fn version(&self) -> Result<String> {
+		Ok("2.0".to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::testing::Header;
+
+	fn dry_run_call() -> DryRunCall {
+		DryRunCall {
+			from: H160::repeat_byte(1),
+			to: Some(H160::repeat_byte(2)),
+			data: vec![0xa9, 0x05, 0x9c, 0xbb],
+			value: U256::from(3),
+			gas_limit: U256::from(21000),
+			gas_price: U256::from(4),
+			nonce: Some(U256::from(5)),
+		}
+	}
+
+	#[test]
+	fn remote_call_request_should_target_the_ethereum_runtime_api() {
+		let header = Header::new_from_number(7);
+		let request = remote_call_request(header.clone(), ethereum_method("call"), vec![1, 2, 3]);
+
+		assert_eq!(request.block, header.hash());
+		assert_eq!(request.header, header);
+		assert_eq!(request.method, "EthereumRuntimeApi_call");
+		assert_eq!(request.call_data, vec![1, 2, 3]);
+		assert_eq!(request.retry_count, Some(REMOTE_CALL_RETRIES));
+	}
+
+	#[test]
+	fn call_data_should_follow_the_runtime_api_arguments() {
+		let state_overrides = vec![(H160::repeat_byte(3), AccountOverride::default())];
+		let block_override = BlockOverride { number: Some(8), base_fee: Some(9.into()), ..Default::default() };
+		let pending: PendingChanges = vec![(b"key".to_vec(), None)];
+		let encoded = call_data(dry_run_call(), state_overrides.clone(), block_override.clone(), pending.clone());
+
+		let call = dry_run_call();
+		assert_eq!(
+			<(
+				H160, H160, Vec<u8>, U256, U256, U256, Option<U256>,
+				Vec<(H160, AccountOverride)>, BlockOverride, PendingChanges,
+			)>::decode(&mut &encoded[..]).ok(),
+			Some((
+				call.from, call.to.unwrap(), call.data, call.value, call.gas_limit, call.gas_price,
+				call.nonce, state_overrides, block_override, pending,
+			)),
+		);
+	}
+
+	#[test]
+	fn legacy_call_data_should_follow_the_version_1_arguments() {
+		let encoded = legacy_call_data(dry_run_call());
+
+		let call = dry_run_call();
+		assert_eq!(
+			<(H160, H160, Vec<u8>, U256, U256, U256, Option<U256>)>::decode(&mut &encoded[..]).ok(),
+			Some((
+				call.from, call.to.unwrap(), call.data, call.value, call.gas_limit, call.gas_price,
+				call.nonce,
+			)),
+		);
+	}
+}
@@ -0,0 +1,102 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Logging of rpc calls, to find abusive or pathological queries.
+//!
+//! `CallLogger::instrument` wraps the methods of an rpc delegate. Every call is
+//! logged at debug level under the `rpc` target with its method, a digest of its
+//! parameters, its duration and its outcome; calls slower than the threshold
+//! are logged as warnings.
+
+use std::{sync::Arc, time::{Duration, Instant}};
+use jsonrpc_core::{
+	BoxFuture, Metadata, Params, RemoteProcedure, RpcMethod, Value,
+	futures::future::Future,
+};
+use sha3::{Keccak256, Digest};
+
+/// Logger of rpc calls.
+#[derive(Clone, Debug)]
+pub struct CallLogger {
+	slow_call_threshold: Duration,
+}
+
+impl CallLogger {
+	pub fn new(slow_call_threshold: Duration) -> Self {
+		Self { slow_call_threshold }
+	}
+
+	/// Wraps the methods of `delegate` so that their calls are logged.
+	pub fn instrument<M, D>(&self, delegate: D) -> Vec<(String, RemoteProcedure<M>)> where
+		M: Metadata,
+		D: IntoIterator<Item=(String, RemoteProcedure<M>)>,
+	{
+		delegate.into_iter().map(|(name, procedure)| {
+			let procedure = match procedure {
+				RemoteProcedure::Method(method) => RemoteProcedure::Method(Arc::new(LoggedMethod {
+					name: name.clone(),
+					method,
+					slow_call_threshold: self.slow_call_threshold,
+				})),
+				other => other,
+			};
+			(name, procedure)
+		}).collect()
+	}
+}
+
+struct LoggedMethod<M> {
+	name: String,
+	method: Arc<dyn RpcMethod<M>>,
+	slow_call_threshold: Duration,
+}
+
+/// Short digest of call parameters, to correlate calls without logging them.
+fn params_digest(params: &Params) -> String {
+	let encoded = serde_json::to_vec(params).unwrap_or_default();
+	Keccak256::digest(&encoded)[..8].iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+impl<M: Metadata> RpcMethod<M> for LoggedMethod<M> {
+	fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
+		let name = self.name.clone();
+		let slow_call_threshold = self.slow_call_threshold;
+		let digest = params_digest(&params);
+		let started = Instant::now();
+
+		Box::new(self.method.call(params, meta).then(move |result| {
+			let elapsed = started.elapsed();
+			let outcome = match &result {
+				Ok(_) => "ok".to_string(),
+				Err(e) => format!("error {}", e.code.code()),
+			};
+			if elapsed >= slow_call_threshold {
+				log::warn!(
+					target: "rpc",
+					"Slow call {} (params {}) took {:?}: {}",
+					name, digest, elapsed, outcome,
+				);
+			} else {
+				log::debug!(
+					target: "rpc",
+					"Call {} (params {}) took {:?}: {}",
+					name, digest, elapsed, outcome,
+				);
+			}
+			result
+		}))
+	}
+}
@@ -0,0 +1,64 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mining of proof of work chains by Ethereum mining software.
+//!
+//! A chain sealed with `sc-consensus-pow` and a keccak based algorithm
+//! implements `EthMiningWorker` over its mining worker, and passes it to
+//! `EthApi`. `eth_getWork` then hands out the work of the worker, and
+//! `eth_submitWork` hands solutions back to it, while `eth_hashrate` adds up
+//! the hashrates the miners report with `eth_submitHashrate`.
+
+use std::{collections::HashMap, sync::Mutex, time::{Duration, Instant}};
+use ethereum_types::{H256, H64, U256};
+use frontier_rpc_core::types::Work;
+
+/// How long a reported hashrate counts, without being reported again.
+const HASHRATE_EXPIRY: Duration = Duration::from_secs(10);
+
+/// Proof of work engine mined through `eth_getWork` and `eth_submitWork`.
+pub trait EthMiningWorker: Send + Sync {
+	/// Work of the block being mined, `None` if there is none, like while the
+	/// node is syncing.
+	fn work(&self) -> Option<Work>;
+	/// Submits the solution `nonce` and `mix_digest` of the work `pow_hash`,
+	/// returning whether it sealed a block.
+	fn submit_work(&self, nonce: H64, pow_hash: H256, mix_digest: H256) -> bool;
+}
+
+/// Hashrates reported by external miners, by miner id.
+#[derive(Default)]
+pub(crate) struct Hashrates {
+	rates: Mutex<HashMap<H256, (U256, Instant)>>,
+}
+
+impl Hashrates {
+	fn lock(&self) -> std::sync::MutexGuard<HashMap<H256, (U256, Instant)>> {
+		self.rates.lock().expect("hashrates lock is never held across a panic; qed")
+	}
+
+	/// Records `rate` as the current hashrate of miner `id`.
+	pub fn submit(&self, id: H256, rate: U256) {
+		self.lock().insert(id, (rate, Instant::now()));
+	}
+
+	/// Sum of the hashrates reported recently.
+	pub fn total(&self) -> U256 {
+		let mut rates = self.lock();
+		rates.retain(|_, (_, reported)| reported.elapsed() < HASHRATE_EXPIRY);
+		rates.values().fold(U256::zero(), |total, (rate, _)| total.saturating_add(*rate))
+	}
+}
@@ -0,0 +1,46 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use jsonrpc_core::Result;
+use frontier_rpc_core::RpcModulesApi as RpcModulesApiT;
+
+pub use frontier_rpc_core::RpcModulesApiServer;
+
+/// Version reported for every namespace, following geth.
+const MODULE_VERSION: &str = "1.0";
+
+/// `rpc_modules`, listing the namespaces a node was built with.
+pub struct RpcModules {
+	modules: BTreeMap<String, String>,
+}
+
+impl RpcModules {
+	/// Lists the namespaces `modules`, such as `eth` or `trace`.
+	pub fn new<'a>(modules: impl IntoIterator<Item = &'a str>) -> Self {
+		Self {
+			modules: modules.into_iter()
+				.map(|module| (module.to_string(), MODULE_VERSION.to_string()))
+				.collect(),
+		}
+	}
+}
+
+impl RpcModulesApiT for RpcModules {
+	fn modules(&self) -> Result<BTreeMap<String, String>> {
+		Ok(self.modules.clone())
+	}
+}
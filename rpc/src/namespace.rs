@@ -0,0 +1,113 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Selection of the rpc namespaces a node serves.
+
+use std::{collections::BTreeSet, str::FromStr};
+
+/// Ethereum-related rpc namespace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EthApiNamespace {
+	/// `eth_*`, served by `EthApi`.
+	Eth,
+	/// `trace_*`, `debug_traceCall`, `debug_traceCallMany` and
+	/// `debug_accountRange`, served by `TraceApi`.
+	Trace,
+	/// `frontier_*` hash cross-referencing, served by `FrontierApi`.
+	Frontier,
+	/// `frontier_*` account mapping, served by `AccountApi`.
+	Account,
+	/// `evm_*` development methods, served by `DevApi` under manual seal.
+	Dev,
+	/// `admin_peers` and `admin_nodeInfo`, served by `AdminApi`.
+	Admin,
+}
+
+impl EthApiNamespace {
+	/// Every namespace.
+	pub const ALL: [EthApiNamespace; 6] = [
+		EthApiNamespace::Eth,
+		EthApiNamespace::Trace,
+		EthApiNamespace::Frontier,
+		EthApiNamespace::Account,
+		EthApiNamespace::Dev,
+		EthApiNamespace::Admin,
+	];
+}
+
+impl FromStr for EthApiNamespace {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"eth" => Ok(EthApiNamespace::Eth),
+			"trace" => Ok(EthApiNamespace::Trace),
+			"frontier" => Ok(EthApiNamespace::Frontier),
+			"account" => Ok(EthApiNamespace::Account),
+			"dev" => Ok(EthApiNamespace::Dev),
+			"admin" => Ok(EthApiNamespace::Admin),
+			other => Err(format!(
+				"unknown rpc namespace `{}`, expected eth, trace, frontier, account, dev or admin",
+				other,
+			)),
+		}
+	}
+}
+
+/// Set of rpc namespaces a node serves, parsed from a comma separated list
+/// such as `eth,frontier`. Defaults to every namespace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EthApiSet(BTreeSet<EthApiNamespace>);
+
+impl EthApiSet {
+	/// Whether `namespace` is served.
+	pub fn contains(&self, namespace: EthApiNamespace) -> bool {
+		self.0.contains(&namespace)
+	}
+}
+
+impl Default for EthApiSet {
+	fn default() -> Self {
+		EthApiSet(EthApiNamespace::ALL.iter().cloned().collect())
+	}
+}
+
+impl FromStr for EthApiSet {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.split(',')
+			.map(str::trim)
+			.filter(|namespace| !namespace.is_empty())
+			.map(EthApiNamespace::from_str)
+			.collect::<Result<_, _>>()
+			.map(EthApiSet)
+	}
+}
+
+impl std::fmt::Display for EthApiSet {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let names = self.0.iter().map(|namespace| match namespace {
+			EthApiNamespace::Eth => "eth",
+			EthApiNamespace::Trace => "trace",
+			EthApiNamespace::Frontier => "frontier",
+			EthApiNamespace::Account => "account",
+			EthApiNamespace::Dev => "dev",
+			EthApiNamespace::Admin => "admin",
+		}).collect::<Vec<_>>();
+		write!(f, "{}", names.join(","))
+	}
+}
@@ -0,0 +1,149 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State of the `pending` block.
+//!
+//! The pending state is the latest one with the ready pool transactions
+//! executed on top, in the order the pool would include them, up to the gas
+//! limit of one block. Executing them costs as much as authoring a block, so
+//! it runs on the blocking pool, and the storage changes it makes are kept for
+//! the latest block and ready transactions they were collected at. Reads of
+//! the pending state look these changes up before the latest state, and
+//! `eth_call` writes them back rather than executing the transactions again.
+
+use std::{collections::{BTreeMap, BTreeSet}, sync::{Arc, Mutex}};
+use codec::{Decode, Encode};
+use ethereum_types::{H160, H256};
+use sp_core::hashing::{blake2_128, twox_128};
+use frontier_rpc_primitives::PendingChanges;
+
+/// Prefix of the storage items of pallet-evm.
+const PALLET_EVM: &[u8] = b"EVM";
+
+/// Storage changes of the ready pool transactions, on top of the latest block.
+#[derive(Default)]
+pub(crate) struct PendingState {
+	changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl PendingState {
+	pub fn new(changes: PendingChanges) -> Self {
+		PendingState { changes: changes.into_iter().collect() }
+	}
+
+	/// Changes to pass to the runtime `call`.
+	pub fn changes(&self) -> PendingChanges {
+		self.changes.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+	}
+
+	/// Account `address`, `None` if the pending transactions left it as it is.
+	pub fn account(&self, address: H160) -> Option<pallet_evm::Account> {
+		self.changed(&evm_key(b"Accounts", &[address.encode()]))
+	}
+
+	/// Code of `address`, `None` if the pending transactions left it as it is.
+	pub fn code(&self, address: H160) -> Option<Vec<u8>> {
+		self.changed(&evm_key(b"AccountCodes", &[address.encode()]))
+	}
+
+	/// Storage slot `index` of `address`, `None` if the pending transactions
+	/// left it as it is.
+	pub fn storage(&self, address: H160, index: H256) -> Option<H256> {
+		self.changed(&evm_key(b"AccountStorages", &[address.encode(), index.encode()]))
+	}
+
+	/// EVM accounts these changes write, with the storage slots written of
+	/// each.
+	pub fn touched(&self) -> BTreeMap<H160, BTreeSet<H256>> {
+		let accounts = evm_key(b"Accounts", &[]);
+		let codes = evm_key(b"AccountCodes", &[]);
+		let storages = evm_key(b"AccountStorages", &[]);
+		let mut touched = BTreeMap::<H160, BTreeSet<H256>>::new();
+		for key in self.changes.keys() {
+			if key.starts_with(&accounts) || key.starts_with(&codes) {
+				if let Some((address, _)) = map_key::<H160>(&key[accounts.len()..]) {
+					touched.entry(address).or_default();
+				}
+			} else if key.starts_with(&storages) {
+				if let Some((address, rest)) = map_key::<H160>(&key[storages.len()..]) {
+					if let Some((index, _)) = map_key::<H256>(rest) {
+						touched.entry(address).or_default().insert(index);
+					}
+				}
+			}
+		}
+		touched
+	}
+
+	/// Value of `key`, the default one if it was removed.
+	fn changed<T: Decode + Default>(&self, key: &[u8]) -> Option<T> {
+		self.changes.get(key).map(|value| match value {
+			Some(value) => T::decode(&mut &value[..]).unwrap_or_default(),
+			None => T::default(),
+		})
+	}
+}
+
+/// Key of the entry of the `blake2_128_concat` map `item` of pallet-evm, with
+/// one encoded key per map dimension.
+fn evm_key(item: &[u8], keys: &[Vec<u8>]) -> Vec<u8> {
+	let mut storage_key = twox_128(PALLET_EVM).to_vec();
+	storage_key.extend_from_slice(&twox_128(item));
+	for key in keys {
+		storage_key.extend_from_slice(&blake2_128(key));
+		storage_key.extend_from_slice(key);
+	}
+	storage_key
+}
+
+/// Key decoded from the start of the `blake2_128_concat` map `keys`, with the
+/// keys that follow it.
+fn map_key<T: Decode>(keys: &[u8]) -> Option<(T, &[u8])> {
+	let mut rest = keys.get(16..)?;
+	let key = T::decode(&mut rest).ok()?;
+	Some((key, rest))
+}
+
+/// Pending state of the last latest block and ready transactions it was
+/// collected at.
+#[derive(Default)]
+pub(crate) struct PendingCache {
+	state: Mutex<Option<(H256, Vec<H256>, Arc<PendingState>)>>,
+}
+
+impl PendingCache {
+	/// Pending state on top of the block `hash` with the `ready` transactions,
+	/// identified by their hashes in order.
+	pub fn get(&self, hash: H256, ready: &[H256]) -> Option<Arc<PendingState>> {
+		match &*self.lock() {
+			Some((cached_hash, cached_ready, state))
+				if *cached_hash == hash && cached_ready[..] == ready[..] => Some(state.clone()),
+			_ => None,
+		}
+	}
+
+	/// Keeps `state` as the pending state on top of the block `hash` with the
+	/// `ready` transactions.
+	pub fn insert(&self, hash: H256, ready: Vec<H256>, state: PendingState) -> Arc<PendingState> {
+		let state = Arc::new(state);
+		*self.lock() = Some((hash, ready, state.clone()));
+		state
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<Option<(H256, Vec<H256>, Arc<PendingState>)>> {
+		self.state.lock().expect("pending cache lock is never held across a panic; qed")
+	}
+}
@@ -0,0 +1,289 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Personal rpc namespace of development nodes.
+
+use std::{collections::BTreeMap, str::FromStr, sync::{Arc, Mutex}};
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::{BoxFuture, Result, Error, futures::{future, Future}};
+use rlp::RlpStream;
+use sc_rpc_api::DenyUnsafe;
+use sha3::{Keccak256, Digest};
+use frontier_rpc_core::{EthApi as EthApiT, PersonalApi as PersonalApiT};
+use frontier_rpc_core::types::{BlockNumber, Bytes, CallRequest, TransactionRequest};
+
+use crate::{internal_err, auth::constant_time_eq, signer::{EthSigner, address_of, sign_hash}};
+
+pub use frontier_rpc_core::PersonalApiServer;
+
+/// Accounts created or imported through the personal namespace, kept in
+/// memory with their passwords.
+///
+/// As an `EthSigner`, it lists them in `eth_accounts` but does not sign with
+/// them: they stay locked, and only sign through the personal methods given
+/// their password.
+#[derive(Clone, Default)]
+pub struct PersonalSigner {
+	accounts: Arc<Mutex<BTreeMap<H160, (secp256k1::SecretKey, String)>>>,
+}
+
+impl PersonalSigner {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<BTreeMap<H160, (secp256k1::SecretKey, String)>> {
+		self.accounts.lock().expect("personal accounts lock is never held across a panic; qed")
+	}
+
+	fn insert(&self, key: secp256k1::SecretKey, password: String) -> H160 {
+		let address = address_of(&key);
+		self.lock().insert(address, (key, password));
+		address
+	}
+
+	/// Key of `address`, if `password` is its password.
+	fn unlock(&self, address: &H160, password: &str) -> Result<secp256k1::SecretKey> {
+		match self.lock().get(address) {
+			Some((key, expected)) if constant_time_eq(expected.as_bytes(), password.as_bytes()) =>
+				Ok(key.clone()),
+			Some(_) => Err(internal_err("invalid password")),
+			None => Err(internal_err("unknown account")),
+		}
+	}
+}
+
+impl EthSigner for PersonalSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.lock().keys().cloned().collect()
+	}
+
+	fn sign_hash(&self, _address: &H160, _hash: &H256) -> Option<[u8; 65]> {
+		None
+	}
+}
+
+/// Personal namespace, signing with the accounts of a `PersonalSigner` and
+/// relying on `eth` for chain data and transaction submission.
+pub struct PersonalApi<E> {
+	eth: Arc<E>,
+	signer: PersonalSigner,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<E> PersonalApi<E> {
+	pub fn new(eth: E, signer: PersonalSigner, deny_unsafe: DenyUnsafe) -> Self {
+		PersonalApi { eth: Arc::new(eth), signer, deny_unsafe }
+	}
+}
+
+/// Hash signed by `personal_sign` for `data`, prefixed as in EIP-191.
+fn personal_message_hash(data: &[u8]) -> H256 {
+	let mut message = format!("\x19Ethereum Signed Message:\n{}", data.len()).into_bytes();
+	message.extend_from_slice(data);
+	H256::from_slice(Keccak256::digest(&message).as_slice())
+}
+
+/// Raw EIP-155 transaction of `request` on chain `chain_id`, signed with
+/// `key`, with the given nonce, gas price and gas.
+fn signed_transaction(
+	key: &secp256k1::SecretKey,
+	chain_id: u64,
+	nonce: U256,
+	gas_price: U256,
+	gas: U256,
+	request: TransactionRequest,
+) -> Result<Bytes> {
+	let action = match request.to {
+		Some(to) => ethereum::TransactionAction::Call(to),
+		None => ethereum::TransactionAction::Create,
+	};
+	let value = request.value.unwrap_or_default();
+	let input = request.data.map(|data| data.0).unwrap_or_default();
+
+	// EIP-155 signing payload.
+	let mut payload = RlpStream::new_list(9);
+	payload.append(&nonce);
+	payload.append(&gas_price);
+	payload.append(&gas);
+	payload.append(&action);
+	payload.append(&value);
+	payload.append(&input);
+	payload.append(&chain_id);
+	payload.append(&0u8);
+	payload.append(&0u8);
+	let hash = H256::from_slice(Keccak256::digest(&payload.out()).as_slice());
+
+	let signature = sign_hash(key, &hash);
+	let signature = ethereum::TransactionSignature::new(
+		(signature[64] - 27) as u64 + chain_id * 2 + 35,
+		H256::from_slice(&signature[0..32]),
+		H256::from_slice(&signature[32..64]),
+	).ok_or_else(|| internal_err("sign transaction failed"))?;
+
+	let transaction = ethereum::Transaction {
+		nonce,
+		gas_price,
+		gas_limit: gas,
+		action,
+		value,
+		input,
+		signature,
+	};
+	Ok(Bytes(rlp::encode(&transaction)))
+}
+
+impl<E: EthApiT> PersonalApi<E> {
+	/// Raw signed transaction of `request`, filling missing fields from the
+	/// state of the best block. The gas is estimated without blocking the
+	/// rpc thread.
+	fn sign_transaction(&self, request: TransactionRequest, password: &str) -> BoxFuture<Bytes> {
+		let prepare = || -> Result<_> {
+			self.deny_unsafe.check_if_safe()?;
+
+			let from = request.from.ok_or_else(|| Error::invalid_params("missing from"))?;
+			let key = self.signer.unlock(&from, password)?;
+			let chain_id = self.eth.chain_id()?.map(|chain_id| chain_id.as_u64()).unwrap_or_default();
+			let nonce = match request.nonce {
+				Some(nonce) => nonce,
+				None => self.eth.transaction_count(from, Some(BlockNumber::Latest))?,
+			};
+			let gas_price = match request.gas_price {
+				Some(gas_price) => gas_price,
+				None => self.eth.gas_price()?,
+			};
+			Ok((from, key, chain_id, nonce, gas_price))
+		};
+		let (from, key, chain_id, nonce, gas_price) = match prepare() {
+			Ok(prepared) => prepared,
+			Err(e) => return Box::new(future::err(e)),
+		};
+
+		let gas: BoxFuture<U256> = match request.gas {
+			Some(gas) => Box::new(future::ok(gas)),
+			None => self.eth.estimate_gas(CallRequest {
+				from: Some(from),
+				to: request.to,
+				gas_price: Some(gas_price),
+				value: request.value,
+				data: request.data.clone(),
+				..Default::default()
+			}, None),
+		};
+		Box::new(gas.and_then(move |gas| {
+			signed_transaction(&key, chain_id, nonce, gas_price, gas, request)
+		}))
+	}
+}
+
+impl<E: EthApiT + Send + Sync + 'static> PersonalApiT for PersonalApi<E> {
+	fn new_account(&self, password: String) -> Result<H160> {
+		self.deny_unsafe.check_if_safe()?;
+		Ok(self.signer.insert(secp256k1::SecretKey::random(&mut rand::thread_rng()), password))
+	}
+
+	fn import_raw_key(&self, key: String, password: String) -> Result<H160> {
+		self.deny_unsafe.check_if_safe()?;
+		let key = H256::from_str(key.trim_start_matches("0x"))
+			.ok()
+			.and_then(|key| secp256k1::SecretKey::parse(key.as_fixed_bytes()).ok())
+			.ok_or_else(|| Error::invalid_params("invalid private key"))?;
+		Ok(self.signer.insert(key, password))
+	}
+
+	fn send_transaction(&self, request: TransactionRequest, password: String) -> BoxFuture<H256> {
+		let eth = self.eth.clone();
+		Box::new(
+			self.sign_transaction(request, &password)
+				.and_then(move |transaction| eth.send_raw_transaction(transaction))
+		)
+	}
+
+	fn sign(&self, data: Bytes, address: H160, password: String) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+		let key = self.signer.unlock(&address, &password)?;
+		Ok(Bytes(sign_hash(&key, &personal_message_hash(&data.0)).to_vec()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use hex_literal::hex;
+
+	#[test]
+	fn personal_message_should_be_signed_as_in_web3() {
+		// The `web3.eth.accounts.sign` example of web3.js.
+		let key = secp256k1::SecretKey::parse(
+			&hex!("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318")
+		).unwrap();
+		let hash = personal_message_hash(b"Some data");
+
+		assert_eq!(
+			hash,
+			H256::from(hex!("1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655")),
+		);
+		assert_eq!(
+			sign_hash(&key, &hash).to_vec(),
+			[
+				&hex!("b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd")[..],
+				&hex!("6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029")[..],
+				&[0x1c][..],
+			].concat(),
+		);
+	}
+
+	#[test]
+	fn personal_accounts_should_only_sign_given_their_password() {
+		let signer = PersonalSigner::new();
+		let address = signer.insert(secp256k1::SecretKey::parse(&[0x46; 32]).unwrap(), "secret".into());
+
+		assert_eq!(signer.accounts(), vec![address]);
+		assert_eq!(EthSigner::sign_hash(&signer, &address, &H256::zero()), None);
+		assert!(signer.unlock(&address, "guess").is_err());
+		assert!(signer.unlock(&address, "secret").is_ok());
+	}
+
+	#[test]
+	fn transaction_should_be_signed_as_in_eip155() {
+		// The example transaction of EIP-155.
+		let key = secp256k1::SecretKey::parse(&[0x46; 32]).unwrap();
+		let request = TransactionRequest {
+			to: Some(H160::repeat_byte(0x35)),
+			value: Some(U256::from(1_000_000_000_000_000_000u64)),
+			..Default::default()
+		};
+
+		let transaction = signed_transaction(
+			&key,
+			1,
+			U256::from(9),
+			U256::from(20_000_000_000u64),
+			U256::from(21000),
+			request,
+		).unwrap();
+
+		assert_eq!(
+			transaction.0,
+			[
+				&hex!("f86c098504a817c800825208943535353535353535353535353535353535353535")[..],
+				&hex!("880de0b6b3a76400008025")[..],
+				&hex!("a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276")[..],
+				&hex!("a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83")[..],
+			].concat(),
+		);
+	}
+}
@@ -0,0 +1,690 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! `eth_subscribe` for `newHeads`, `logs` and `newPendingTransactions`, with
+//! per-connection limits.
+//!
+//! Subscriptions are notified by `EthPubSubWorker`, a background task following
+//! the best blocks. Each subscription buffers a bounded number of notifications
+//! for its client. When a client does not keep up and its buffer is full,
+//! `OverflowPolicy` decides whether further notifications are dropped or the
+//! subscription is closed, so a stalled connection cannot grow node memory.
+//!
+//! A `logs` subscription with a `fromBlock` first replays the matching logs of
+//! past blocks, at a bounded rate and without dropping any, then switches to
+//! the logs of new blocks.
+//!
+//! A `newPendingTransactions` subscription is sent the hashes of the Ethereum
+//! transactions entering the ready queue of the pool, or the whole transactions
+//! with the geth extension `eth_subscribe("newPendingTransactions", true)`.
+
+use std::{
+	collections::{HashMap, HashSet}, marker::PhantomData, pin::Pin, str::FromStr,
+	sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}}, time::Duration,
+};
+use ethereum::Transaction as EthereumTransaction;
+use ethereum_types::H256;
+use futures::{
+	Future, FutureExt, Stream, StreamExt, channel::mpsc, compat::Future01CompatExt,
+	future, stream::{self, FuturesUnordered},
+};
+use futures_timer::Delay;
+use jsonrpc_core::{Result, Error};
+use jsonrpc_pubsub::{typed::{Sink, Subscriber}, PubSubMetadata, SubscriptionId};
+use sp_runtime::traits::{Block as BlockT, Header as _, UniqueSaturatedInto};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sc_client_api::BlockchainEvents;
+use frontier_rpc_core::EthPubSubApi as EthPubSubApiT;
+use sp_blockchain::HeaderBackend;
+use sp_transaction_pool::{TransactionPool, InPoolTransaction};
+use frontier_rpc_core::types::{BlockNumber, Filter};
+use frontier_rpc_core::types::pubsub::{Kind, Params, Result as PubSubResult};
+use frontier_rpc_primitives::EthereumRuntimeApi;
+
+use crate::{
+	internal_err, limit_exceeded_err, filter_block_logs, rich_header_build,
+	pending_transaction_build, capability::extended_api_available, PoolSender,
+};
+
+pub use frontier_rpc_core::EthPubSubApiServer;
+
+/// What to do with a subscription whose buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+	/// Drop the notifications that do not fit.
+	Drop,
+	/// Close the subscription, with an error notification to its client.
+	Disconnect,
+}
+
+impl FromStr for OverflowPolicy {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+		match s {
+			"drop" => Ok(OverflowPolicy::Drop),
+			"disconnect" => Ok(OverflowPolicy::Disconnect),
+			other => Err(format!("unknown overflow policy `{}`, expected drop or disconnect", other)),
+		}
+	}
+}
+
+/// Limits of the Ethereum subscriptions.
+#[derive(Debug, Clone)]
+pub struct SubscriptionLimits {
+	/// Maximum number of active subscriptions of a connection.
+	pub max_per_connection: usize,
+	/// Number of notifications buffered for a subscription.
+	pub buffer_size: usize,
+	/// What to do with a subscription whose buffer is full.
+	pub overflow: OverflowPolicy,
+	/// Number of past blocks per second whose logs are replayed, shared by
+	/// all the replaying subscriptions.
+	pub replay_rate: u32,
+}
+
+impl Default for SubscriptionLimits {
+	fn default() -> Self {
+		Self {
+			max_per_connection: 32,
+			buffer_size: 512,
+			overflow: OverflowPolicy::Drop,
+			replay_rate: 1000,
+		}
+	}
+}
+
+/// Interval between two replay steps of the logs of past blocks.
+const REPLAY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Progress of the replay of the logs of past blocks.
+#[derive(Clone, Copy)]
+struct Replay {
+	/// Next block to replay.
+	next: u32,
+	/// Number of logs of `next` already sent, when the buffer filled up.
+	sent: usize,
+}
+
+struct Subscription {
+	/// Address of the session of the subscribing connection.
+	session: usize,
+	kind: Kind,
+	filter: Filter,
+	/// Whether pending transactions are sent in full rather than by hash.
+	full_transactions: bool,
+	queue: mpsc::Sender<PubSubResult>,
+	/// Set when the subscription is closed for not keeping up.
+	overflowed: Arc<AtomicBool>,
+	/// Set while the logs of past blocks are replayed, before the live ones.
+	replay: Option<Replay>,
+}
+
+#[derive(Default)]
+struct Subscriptions {
+	by_id: HashMap<u64, Subscription>,
+	/// Number of active subscriptions of each session, by session address.
+	sessions: HashMap<usize, usize>,
+	/// Number of active `newPendingTransactions` subscriptions.
+	pending: usize,
+	next_id: u64,
+	/// Last best block notified, up to which replays go.
+	best: Option<(H256, u32)>,
+}
+
+/// Future sending the notifications of one subscription to its client.
+type Forwarder = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Active Ethereum subscriptions, shared by the rpc handlers of every
+/// connection and by `EthPubSubWorker`.
+#[derive(Clone)]
+pub struct EthPubSubHub {
+	limits: SubscriptionLimits,
+	subscriptions: Arc<Mutex<Subscriptions>>,
+	forwarders: mpsc::UnboundedSender<Forwarder>,
+}
+
+/// Ethereum transactions entering the ready queue of the pool, with their
+/// sender.
+type PendingTransactions = Pin<Box<dyn Stream<Item = (EthereumTransaction, PoolSender)> + Send>>;
+
+/// Background task notifying the subscriptions of an `EthPubSubHub`.
+pub struct EthPubSubWorker {
+	hub: EthPubSubHub,
+	forwarders: mpsc::UnboundedReceiver<Forwarder>,
+	pending: Option<PendingTransactions>,
+}
+
+/// Filter matching every log.
+fn any_log() -> Filter {
+	Filter {
+		from_block: None,
+		to_block: None,
+		block_hash: None,
+		address: None,
+		topics: None,
+		limit: None,
+	}
+}
+
+fn subscription_id(id: u64) -> SubscriptionId {
+	SubscriptionId::String(format!("0x{:x}", id))
+}
+
+fn parse_subscription_id(id: &SubscriptionId) -> Option<u64> {
+	match id {
+		SubscriptionId::Number(id) => Some(*id),
+		SubscriptionId::String(id) if id.starts_with("0x") => u64::from_str_radix(&id[2..], 16).ok(),
+		SubscriptionId::String(_) => None,
+	}
+}
+
+impl EthPubSubHub {
+	/// A hub with no subscription, and the worker that must be spawned to notify
+	/// its subscriptions.
+	pub fn new(limits: SubscriptionLimits) -> (Self, EthPubSubWorker) {
+		let (forwarders, forwarders_rx) = mpsc::unbounded();
+		let hub = Self {
+			limits,
+			subscriptions: Arc::new(Mutex::new(Subscriptions::default())),
+			forwarders,
+		};
+		(hub.clone(), EthPubSubWorker { hub, forwarders: forwarders_rx, pending: None })
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<Subscriptions> {
+		self.subscriptions.lock().expect("subscriptions lock is never held across a panic; qed")
+	}
+
+	/// Register a subscription of the connection of `meta`, returning its id and
+	/// the receiving end of its buffer.
+	fn add<M: PubSubMetadata>(
+		&self,
+		meta: &M,
+		kind: Kind,
+		filter: Filter,
+		full_transactions: bool,
+	) -> Result<(u64, mpsc::Receiver<PubSubResult>, Arc<AtomicBool>)> {
+		let session = meta.session()
+			.ok_or_else(|| internal_err("subscriptions require a WebSocket connection"))?;
+		let address = &*session as *const _ as usize;
+		let replay = match kind {
+			Kind::Logs => replay_start(&filter)?,
+			_ => None,
+		};
+
+		let mut subscriptions = self.lock();
+		let count = subscriptions.sessions.get(&address).cloned();
+		if count.unwrap_or(0) >= self.limits.max_per_connection {
+			return Err(limit_exceeded_err(&format!(
+				"connection already has {} subscriptions, the maximum",
+				self.limits.max_per_connection,
+			)))
+		}
+		if count.is_none() {
+			// Close the subscriptions of the session with the connection, since its
+			// address may be reused by a later one.
+			let hub = self.clone();
+			session.on_drop(move || hub.remove_session(address));
+		}
+		*subscriptions.sessions.entry(address).or_insert(0) += 1;
+		if kind == Kind::NewPendingTransactions {
+			subscriptions.pending += 1;
+		}
+
+		let id = subscriptions.next_id;
+		subscriptions.next_id += 1;
+		let (queue, receiver) = mpsc::channel(self.limits.buffer_size);
+		let overflowed = Arc::new(AtomicBool::new(false));
+		subscriptions.by_id.insert(id, Subscription {
+			session: address,
+			kind,
+			filter,
+			full_transactions,
+			queue,
+			overflowed: overflowed.clone(),
+			replay,
+		});
+		Ok((id, receiver, overflowed))
+	}
+
+	/// Close the subscription `id`, returning whether it was active.
+	fn remove(&self, id: u64) -> bool {
+		let mut subscriptions = self.lock();
+		match subscriptions.by_id.remove(&id) {
+			Some(subscription) => {
+				if let Some(count) = subscriptions.sessions.get_mut(&subscription.session) {
+					*count = count.saturating_sub(1);
+				}
+				if subscription.kind == Kind::NewPendingTransactions {
+					subscriptions.pending = subscriptions.pending.saturating_sub(1);
+				}
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Close the subscriptions of the session at `address`.
+	fn remove_session(&self, address: usize) {
+		let mut subscriptions = self.lock();
+		subscriptions.sessions.remove(&address);
+		let mut pending = subscriptions.pending;
+		subscriptions.by_id.retain(|_, subscription| {
+			let keep = subscription.session != address;
+			if !keep && subscription.kind == Kind::NewPendingTransactions {
+				pending = pending.saturating_sub(1);
+			}
+			keep
+		});
+		subscriptions.pending = pending;
+	}
+
+	/// Whether some `newPendingTransactions` subscription is active.
+	fn has_pending_subscriptions(&self) -> bool {
+		self.lock().pending > 0
+	}
+
+	/// Have the worker send the notifications buffered in `receiver` to `sink`.
+	fn forward(
+		&self,
+		id: u64,
+		mut receiver: mpsc::Receiver<PubSubResult>,
+		sink: Sink<PubSubResult>,
+		overflowed: Arc<AtomicBool>,
+	) {
+		let hub = self.clone();
+		let forwarder = async move {
+			while let Some(result) = receiver.next().await {
+				if overflowed.load(Ordering::Relaxed) {
+					break
+				}
+				if sink.notify(Ok(result)).compat().await.is_err() {
+					// The client is gone.
+					hub.remove(id);
+					return
+				}
+			}
+			if overflowed.load(Ordering::Relaxed) {
+				let _ = sink.notify(Err(limit_exceeded_err(
+					"subscription closed: notifications were not consumed fast enough"
+				))).compat().await;
+			}
+		};
+		if self.forwarders.unbounded_send(Box::pin(forwarder)).is_err() {
+			log::warn!(target: "rpc", "Ethereum subscription worker is not running");
+			self.remove(id);
+		}
+	}
+
+	/// Buffer the notifications of the Ethereum block built in `hash`.
+	fn notify<B, C>(&self, client: &C, hash: B::Hash, number: u32) where
+		B: BlockT<Hash=H256>,
+		C: ProvideRuntimeApi<B>,
+		C::Api: EthereumRuntimeApi<B>,
+	{
+		let (block, statuses) = match client.runtime_api()
+			.block_by_number(&BlockId::Hash(hash), number)
+		{
+			Ok((Some(block), statuses)) => (block, statuses),
+			_ => return,
+		};
+		let header = rich_header_build(&block, None);
+
+		let mut subscriptions = self.lock();
+		subscriptions.best = Some((hash, number));
+		let mut closed = Vec::new();
+		for (id, subscription) in subscriptions.by_id.iter_mut() {
+			if subscription.replay.is_some() {
+				// The replay reaches this block later.
+				continue
+			}
+			let results = match subscription.kind {
+				Kind::NewHeads => vec![PubSubResult::Header(Box::new(header.clone()))],
+				Kind::Logs => {
+					let mut logs = Vec::new();
+					filter_block_logs(&mut logs, &subscription.filter, block.clone(), statuses.clone());
+					logs.into_iter().map(|log| PubSubResult::Log(Box::new(log))).collect()
+				},
+				Kind::NewPendingTransactions | Kind::Syncing => continue,
+			};
+
+			if !self.push(*id, subscription, results) {
+				closed.push(*id);
+			}
+		}
+		drop(subscriptions);
+
+		for id in closed {
+			self.remove(id);
+		}
+	}
+
+	/// Buffer the notifications of an Ethereum transaction entering the ready
+	/// queue of the pool.
+	fn notify_pending(&self, transaction: EthereumTransaction, sender: PoolSender) {
+		let transaction = pending_transaction_build(transaction, sender);
+
+		let mut subscriptions = self.lock();
+		let mut closed = Vec::new();
+		for (id, subscription) in subscriptions.by_id.iter_mut() {
+			if subscription.kind != Kind::NewPendingTransactions {
+				continue
+			}
+			let result = if subscription.full_transactions {
+				PubSubResult::Transaction(Box::new(transaction.clone()))
+			} else {
+				PubSubResult::TransactionHash(transaction.hash)
+			};
+			if !self.push(*id, subscription, vec![result]) {
+				closed.push(*id);
+			}
+		}
+		drop(subscriptions);
+
+		for id in closed {
+			self.remove(id);
+		}
+	}
+
+	/// Buffer `results` for the subscription `id`, applying the overflow
+	/// policy. Returns whether the subscription is still open.
+	fn push(&self, id: u64, subscription: &mut Subscription, results: Vec<PubSubResult>) -> bool {
+		for result in results {
+			match subscription.queue.try_send(result) {
+				Ok(()) => {},
+				Err(e) if e.is_disconnected() => return false,
+				Err(_) => match self.limits.overflow {
+					OverflowPolicy::Drop => {
+						log::debug!(
+							target: "rpc",
+							"Dropped a notification of subscription {} for a slow client", id,
+						);
+					},
+					OverflowPolicy::Disconnect => {
+						log::debug!(target: "rpc", "Closing subscription {} of a slow client", id);
+						subscription.overflowed.store(true, Ordering::Relaxed);
+						return false
+					},
+				},
+			}
+		}
+		true
+	}
+
+	/// Buffer the logs of up to `blocks` past blocks, shared in turn by the
+	/// replaying subscriptions. A subscription whose buffer is full resumes at
+	/// the next step, and switches to live notifications once it reaches the
+	/// last notified block. The blocks are read without holding the lock of the
+	/// subscriptions, and once per step whatever the number of subscriptions
+	/// replaying them.
+	fn replay<B, C>(&self, client: &C, blocks: u32) where
+		B: BlockT<Hash=H256>,
+		C: ProvideRuntimeApi<B>,
+		C::Api: EthereumRuntimeApi<B>,
+	{
+		let (best_hash, best_number, mut replays) = {
+			let subscriptions = self.lock();
+			let (best_hash, best_number) = match subscriptions.best {
+				Some(best) => best,
+				None => return,
+			};
+			let replays = subscriptions.by_id.iter()
+				.filter_map(|(id, subscription)| subscription.replay.map(|replay| (
+					*id,
+					subscription.filter.clone(),
+					subscription.queue.clone(),
+					replay,
+				)))
+				.collect::<Vec<_>>();
+			(best_hash, best_number, replays)
+		};
+
+		let mut read = HashMap::new();
+		let mut budget = blocks;
+		let mut closed = Vec::new();
+		// Subscriptions closed or with a full buffer, which stop for this step.
+		let mut stopped = HashSet::new();
+		loop {
+			let mut progressed = false;
+			'replays: for (id, filter, queue, replay) in replays.iter_mut() {
+				if replay.next > best_number || stopped.contains(id) {
+					continue
+				}
+				if !read.contains_key(&replay.next) {
+					if budget == 0 {
+						continue
+					}
+					budget -= 1;
+					let block = match client.runtime_api()
+						.block_by_number(&BlockId::Hash(best_hash), replay.next)
+					{
+						Ok((Some(block), statuses)) => Some((block, statuses)),
+						_ => None,
+					};
+					read.insert(replay.next, block);
+				}
+				if let Some((block, statuses)) = &read[&replay.next] {
+					let mut logs = Vec::new();
+					filter_block_logs(&mut logs, filter, block.clone(), statuses.clone());
+					for log in logs.into_iter().skip(replay.sent) {
+						match queue.try_send(PubSubResult::Log(Box::new(log))) {
+							Ok(()) => replay.sent += 1,
+							Err(e) => {
+								if e.is_disconnected() {
+									closed.push(*id);
+								}
+								stopped.insert(*id);
+								continue 'replays
+							},
+						}
+					}
+				}
+				replay.next += 1;
+				replay.sent = 0;
+				progressed = true;
+			}
+			if !progressed {
+				break
+			}
+		}
+
+		let mut subscriptions = self.lock();
+		let notified = subscriptions.best.map_or(best_number, |(_, number)| number);
+		for (id, _, _, replay) in replays {
+			if let Some(subscription) = subscriptions.by_id.get_mut(&id) {
+				subscription.replay = if replay.next > notified { None } else { Some(replay) };
+			}
+		}
+		drop(subscriptions);
+
+		for id in closed {
+			self.remove(id);
+		}
+	}
+}
+
+/// Replay of a `logs` subscription starting at the `fromBlock` of its filter.
+fn replay_start(filter: &Filter) -> Result<Option<Replay>> {
+	let next = match &filter.from_block {
+		None | Some(BlockNumber::Latest) | Some(BlockNumber::Pending) => return Ok(None),
+		Some(BlockNumber::Earliest) => 0,
+		Some(BlockNumber::Num(number)) => UniqueSaturatedInto::<u32>::unique_saturated_into(*number),
+		Some(BlockNumber::Hash { .. }) =>
+			return Err(Error::invalid_params("fromBlock of a subscription must be a number")),
+	};
+	Ok(Some(Replay { next, sent: 0 }))
+}
+
+impl EthPubSubWorker {
+	/// Also notify the `newPendingTransactions` subscriptions of the Ethereum
+	/// transactions entering the ready queue of `pool`, decoded at the best
+	/// block of `client`. Transactions entering the queue while no such
+	/// subscription is active are neither read from the pool nor decoded.
+	pub fn with_pool<B, C, P>(mut self, client: Arc<C>, pool: Arc<P>) -> Self where
+		B: BlockT<Hash=H256> + Send + Sync + 'static,
+		C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+		C::Api: EthereumRuntimeApi<B>,
+		P: TransactionPool<Block=B> + 'static,
+	{
+		let hub = self.hub.clone();
+		let imports = pool.import_notification_stream();
+		let transactions = imports.filter_map(move |hash| future::ready(
+			Some(hash)
+				.filter(|_| hub.has_pending_subscriptions())
+				.and_then(|hash| pool.ready_transaction(&hash))
+				.and_then(|transaction| {
+					let best = BlockId::Hash(client.info().best_hash);
+					// Runtimes without `extrinsic_filter` notify no transaction.
+					if !extended_api_available(client.as_ref(), &best).ok()? {
+						return None;
+					}
+					client.runtime_api()
+						.extrinsic_filter(&best, vec![transaction.data().clone()])
+						.ok()?
+						.pop()
+				})
+				.and_then(|transaction| {
+					let sender = PoolSender::recover(&transaction)?;
+					Some((transaction, sender))
+				})
+		));
+		self.pending = Some(Box::pin(transactions));
+		self
+	}
+
+	/// Notify the subscriptions of every new best block imported by `client`,
+	/// and replay the logs of past blocks for the subscriptions asking for it.
+	pub fn run<B, C>(self, client: Arc<C>) -> impl Future<Output = ()> where
+		B: BlockT<Hash=H256> + Send + Sync + 'static,
+		C: ProvideRuntimeApi<B> + BlockchainEvents<B> + HeaderBackend<B> + Send + Sync + 'static,
+		C::Api: EthereumRuntimeApi<B>,
+	{
+		let EthPubSubWorker { hub, forwarders, pending } = self;
+		let info = client.info();
+		hub.lock().best = Some((
+			info.best_hash,
+			UniqueSaturatedInto::<u32>::unique_saturated_into(info.best_number),
+		));
+		let replay_blocks = (hub.limits.replay_rate / 10).max(1);
+		async move {
+			let mut imports = client.import_notification_stream().fuse();
+			let mut forwarders = forwarders.fuse();
+			let mut pending = pending
+				.unwrap_or_else(|| Box::pin(stream::pending()))
+				.fuse();
+			let mut running = FuturesUnordered::new();
+			let mut replay_step = Delay::new(REPLAY_INTERVAL).fuse();
+			loop {
+				futures::select! {
+					notification = imports.next() => match notification {
+						Some(notification) if notification.is_new_best => {
+							let number = UniqueSaturatedInto::<u32>::unique_saturated_into(
+								*notification.header.number()
+							);
+							hub.notify(client.as_ref(), notification.hash, number);
+						},
+						Some(_) => {},
+						None => return,
+					},
+					transaction = pending.next() => {
+						if let Some((transaction, sender)) = transaction {
+							hub.notify_pending(transaction, sender);
+						}
+					},
+					forwarder = forwarders.next() => {
+						if let Some(forwarder) = forwarder {
+							running.push(forwarder);
+						}
+					},
+					_ = running.select_next_some() => {},
+					_ = replay_step => {
+						hub.replay(client.as_ref(), replay_blocks);
+						replay_step = Delay::new(REPLAY_INTERVAL).fuse();
+					},
+				}
+			}
+		}
+	}
+}
+
+/// The `eth_subscribe` and `eth_unsubscribe` methods.
+pub struct EthPubSubApi<M> {
+	hub: EthPubSubHub,
+	_marker: PhantomData<M>,
+}
+
+impl<M> EthPubSubApi<M> {
+	pub fn new(hub: EthPubSubHub) -> Self {
+		Self { hub, _marker: PhantomData }
+	}
+}
+
+impl<M: PubSubMetadata> EthPubSubApiT for EthPubSubApi<M> {
+	type Metadata = M;
+
+	fn subscribe(
+		&self,
+		meta: Self::Metadata,
+		subscriber: Subscriber<PubSubResult>,
+		kind: Kind,
+		params: Option<Params>,
+	) {
+		let (filter, full_transactions) = match (&kind, params.unwrap_or_default()) {
+			(Kind::NewHeads, Params::None) | (Kind::Logs, Params::None) => (any_log(), false),
+			(Kind::Logs, Params::Logs(filter)) => (filter, false),
+			(Kind::NewPendingTransactions, Params::None) => (any_log(), false),
+			(Kind::NewPendingTransactions, Params::Bool(full)) => (any_log(), full),
+			(Kind::NewHeads, _) => {
+				let _ = subscriber.reject(Error::invalid_params("newHeads takes no parameters"));
+				return
+			},
+			(Kind::Logs, Params::Bool(_)) => {
+				let _ = subscriber.reject(Error::invalid_params("logs takes a filter"));
+				return
+			},
+			(Kind::NewPendingTransactions, Params::Logs(_)) => {
+				let _ = subscriber.reject(Error::invalid_params(
+					"newPendingTransactions takes whether to send full transactions"
+				));
+				return
+			},
+			(Kind::Syncing, _) => {
+				let _ = subscriber.reject(Error::invalid_params(
+					format!("{:?} subscriptions are not supported", kind)
+				));
+				return
+			},
+		};
+
+		match self.hub.add(&meta, kind, filter, full_transactions) {
+			Ok((id, receiver, overflowed)) => match subscriber.assign_id(subscription_id(id)) {
+				Ok(sink) => self.hub.forward(id, receiver, sink, overflowed),
+				Err(()) => {
+					self.hub.remove(id);
+				},
+			},
+			Err(e) => {
+				let _ = subscriber.reject(e);
+			},
+		}
+	}
+
+	fn unsubscribe(&self, _: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+		Ok(parse_subscription_id(&id).map_or(false, |id| self.hub.remove(id)))
+	}
+}
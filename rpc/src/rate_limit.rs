@@ -0,0 +1,160 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rate limiting of expensive rpc calls.
+//!
+//! `RateLimiter::limit` wraps the `debug_*`, `trace_*`, `eth_getLogs` and
+//! `frontier_getBlockRange` methods of an rpc delegate with a token bucket per
+//! connection. Connections are told apart by their pubsub session, and calls
+//! over HTTP, which have none, by the address of their peer. Calls without a
+//! session or a peer address share a single bucket.
+
+use std::{collections::HashMap, net::IpAddr, sync::{Arc, Mutex}, time::Instant};
+use jsonrpc_core::{
+	BoxFuture, Error, ErrorCode, Params, RemoteProcedure, RpcMethod, Value,
+	futures::future,
+};
+use jsonrpc_pubsub::PubSubMetadata;
+
+/// Error code of calls rejected by the rate limiter.
+pub const LIMIT_EXCEEDED: i64 = -32005;
+
+/// Whether calls to `method` are rate limited.
+fn is_limited(method: &str) -> bool {
+	method.starts_with("debug_") || method.starts_with("trace_") ||
+		method == "eth_getLogs" || method == "eth_simulateV1" || method == "frontier_getBlockRange"
+}
+
+/// Rpc metadata that may know the address of the peer of a call.
+pub trait PeerAddress {
+	/// Address of the peer of the call, if the server passed it on.
+	fn peer_address(&self) -> Option<IpAddr> {
+		None
+	}
+}
+
+/// The rpc servers of Substrate do not pass the peer address on, so HTTP calls
+/// made through them share a single bucket.
+impl PeerAddress for sc_service::RpcMetadata {}
+
+struct Bucket {
+	tokens: f64,
+	updated: Instant,
+}
+
+/// Identifies the bucket of a connection.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Connection {
+	/// Calls without a session or a peer address.
+	Shared,
+	/// Calls without a session from this peer.
+	Peer(IpAddr),
+	/// Calls of the session at this address.
+	Session(usize),
+}
+
+/// Token bucket rate limiter of expensive rpc calls, per connection.
+#[derive(Clone)]
+pub struct RateLimiter {
+	calls_per_second: f64,
+	burst: f64,
+	buckets: Arc<Mutex<HashMap<Connection, Bucket>>>,
+}
+
+impl RateLimiter {
+	/// Allows `calls_per_second` limited calls per connection on average, and up
+	/// to `burst` calls at once.
+	pub fn new(calls_per_second: u32, burst: u32) -> Self {
+		Self {
+			calls_per_second: calls_per_second as f64,
+			burst: burst.max(1) as f64,
+			buckets: Arc::new(Mutex::new(HashMap::new())),
+		}
+	}
+
+	/// Wraps the expensive methods of `delegate` with the rate limiter.
+	pub fn limit<M, D>(&self, delegate: D) -> Vec<(String, RemoteProcedure<M>)> where
+		M: PubSubMetadata + PeerAddress,
+		D: IntoIterator<Item=(String, RemoteProcedure<M>)>,
+	{
+		delegate.into_iter().map(|(name, procedure)| {
+			let procedure = match procedure {
+				RemoteProcedure::Method(method) if is_limited(&name) =>
+					RemoteProcedure::Method(Arc::new(LimitedMethod {
+						method,
+						limiter: self.clone(),
+					})),
+				other => other,
+			};
+			(name, procedure)
+		}).collect()
+	}
+
+	/// Takes a token from the bucket of the connection of `meta`.
+	fn acquire<M: PubSubMetadata + PeerAddress>(&self, meta: &M) -> bool {
+		let mut buckets = self.buckets.lock().expect("rate limiter lock is never held across a panic; qed");
+
+		let connection = match meta.session() {
+			Some(session) => {
+				let connection = Connection::Session(&*session as *const _ as usize);
+				if !buckets.contains_key(&connection) {
+					// Forget the bucket with the connection, since the session address
+					// may be reused by a later one.
+					let buckets = self.buckets.clone();
+					session.on_drop(move || {
+						if let Ok(mut buckets) = buckets.lock() {
+							buckets.remove(&connection);
+						}
+					});
+				}
+				connection
+			},
+			None => meta.peer_address().map_or(Connection::Shared, Connection::Peer),
+		};
+
+		let now = Instant::now();
+		let burst = self.burst;
+		let bucket = buckets.entry(connection).or_insert(Bucket { tokens: burst, updated: now });
+		let refill = now.duration_since(bucket.updated).as_secs_f64() * self.calls_per_second;
+		bucket.tokens = (bucket.tokens + refill).min(burst);
+		bucket.updated = now;
+
+		if bucket.tokens >= 1.0 {
+			bucket.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+struct LimitedMethod<M> {
+	method: Arc<dyn RpcMethod<M>>,
+	limiter: RateLimiter,
+}
+
+impl<M: PubSubMetadata + PeerAddress> RpcMethod<M> for LimitedMethod<M> {
+	fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
+		if !self.limiter.acquire(&meta) {
+			return Box::new(future::err(Error {
+				code: ErrorCode::ServerError(LIMIT_EXCEEDED),
+				message: "limit exceeded".to_string(),
+				data: None,
+			}))
+		}
+		self.method.call(params, meta)
+	}
+}
@@ -0,0 +1,90 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Values fixed by the runtime code.
+//!
+//! `eth_chainId` is among the most frequent calls, and its result only changes
+//! with the runtime, as do the constants transactions are checked against.
+//! Such values are fetched with a runtime call once per runtime code,
+//! identified by the hash of the `:code` storage item, so that a runtime
+//! upgrade is seen from its first block on.
+
+use std::sync::Mutex;
+use ethereum_types::H256;
+use jsonrpc_core::Result;
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_runtime::traits::Block as BlockT;
+use sp_storage::{StorageKey, well_known_keys};
+use sc_client_api::backend::{StorageProvider, Backend};
+use frontier_rpc_primitives::{EthereumRuntimeApi, ValidationConstants};
+
+use crate::{internal_err, validation_constants};
+
+/// Values fixed by a runtime code.
+#[derive(Clone)]
+pub(crate) struct RuntimeValues {
+	pub chain_id: u64,
+	pub constants: ValidationConstants,
+}
+
+/// Values of the last runtime code seen, keyed by the hash of that code.
+#[derive(Default)]
+pub(crate) struct RuntimeCache {
+	values: Mutex<Option<(H256, RuntimeValues)>>,
+}
+
+impl RuntimeCache {
+	/// Chain id of the runtime of block `hash`.
+	pub fn chain_id<B, C, BE>(&self, client: &C, hash: H256) -> Result<u64> where
+		B: BlockT<Hash=H256>,
+		C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+		C::Api: EthereumRuntimeApi<B>,
+		BE: Backend<B>,
+	{
+		self.values(client, hash).map(|values| values.chain_id)
+	}
+
+	/// Values fixed by the runtime of block `hash`.
+	pub fn values<B, C, BE>(&self, client: &C, hash: H256) -> Result<RuntimeValues> where
+		B: BlockT<Hash=H256>,
+		C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+		C::Api: EthereumRuntimeApi<B>,
+		BE: Backend<B>,
+	{
+		let id = BlockId::Hash(hash);
+		let code_hash = client.storage_hash(&id, &StorageKey(well_known_keys::CODE.to_vec()))
+			.map_err(|_| internal_err("fetch runtime code hash failed"))?;
+		if let (Some(code_hash), Some((cached_code_hash, values))) = (code_hash, &*self.lock()) {
+			if code_hash == *cached_code_hash {
+				return Ok(values.clone());
+			}
+		}
+		let api = client.runtime_api();
+		let values = RuntimeValues {
+			chain_id: api.chain_id(&id)
+				.map_err(|_| internal_err("fetch runtime chain id failed"))?,
+			constants: validation_constants(client, &id)?,
+		};
+		if let Some(code_hash) = code_hash {
+			*self.lock() = Some((code_hash, values.clone()));
+		}
+		Ok(values)
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<Option<(H256, RuntimeValues)>> {
+		self.values.lock().expect("runtime cache lock is never held across a panic; qed")
+	}
+}
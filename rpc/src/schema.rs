@@ -0,0 +1,146 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Versioned decoding of the Ethereum blocks stored by pallet-ethereum.
+//!
+//! Long-lived chains hold blocks stored by every runtime they ran, and runtime
+//! upgrades may change the `ethereum` types the pallet stores them with. The
+//! data of a block is therefore decoded from its storage by the decoder of the
+//! `EthereumStorageSchema` recorded in that storage, rather than with the types
+//! of the client. Blocks stored before any schema was recorded are read through
+//! the runtime api of their block, as before.
+
+use codec::{Decode, Encode};
+use ethereum::{Block as EthereumBlock, Receipt as EthereumReceipt};
+use ethereum_types::H256;
+use sha3::{Digest, Keccak256};
+use sp_api::{ProvideRuntimeApi, BlockId};
+use sp_core::hashing::{blake2_128, twox_128};
+use sp_runtime::traits::Block as BlockT;
+use sp_storage::StorageKey;
+use sc_client_api::backend::{StorageProvider, Backend};
+use frontier_rpc_primitives::{
+	EthereumRuntimeApi, EthereumStorageSchema, TransactionStatus, PALLET_ETHEREUM_SCHEMA,
+};
+
+use crate::{EthBlockData, capability::extended_api_available};
+
+/// Prefix of the storage items of pallet-ethereum.
+const PALLET_ETHEREUM: &[u8] = b"Example";
+
+/// Schema of the Ethereum data stored in the state of block `id`.
+pub(crate) fn storage_schema<B, C, BE>(client: &C, id: &BlockId<B>) -> EthereumStorageSchema where
+	B: BlockT<Hash=H256>,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+{
+	match client.storage(id, &StorageKey(PALLET_ETHEREUM_SCHEMA.to_vec())) {
+		Ok(Some(data)) => Decode::decode(&mut &data.0[..]).unwrap_or_default(),
+		_ => EthereumStorageSchema::Undefined,
+	}
+}
+
+/// Ethereum data of the Ethereum block `number`, in the state of block `id`,
+/// decoded according to the schema of that state.
+pub(crate) fn read_block_data<B, C, BE>(client: &C, id: &BlockId<B>, number: u32) -> Option<EthBlockData> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C::Api: EthereumRuntimeApi<B>,
+	BE: Backend<B>,
+{
+	match storage_schema(client, id) {
+		EthereumStorageSchema::V1 => v1::block_data(client, id, number),
+		EthereumStorageSchema::Undefined => runtime_block_data(client, id, number),
+	}
+}
+
+/// Ethereum data read through the runtime api of block `id`.
+fn runtime_block_data<B, C>(client: &C, id: &BlockId<B>, number: u32) -> Option<EthBlockData> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	if !extended_api_available(client, id).ok()? {
+		return None;
+	}
+	let api = client.runtime_api();
+	let (block, statuses) = match api.block_by_number(id, number) {
+		Ok((Some(block), statuses)) => (block, statuses),
+		_ => return None,
+	};
+	let receipts = api.block_receipts_by_number(id, number).ok().flatten()?;
+	Some(EthBlockData { block, statuses, receipts })
+}
+
+/// Key of the `key` entry of the `blake2_128_concat` map `item` of
+/// pallet-ethereum.
+fn map_key<K: Encode>(item: &[u8], key: K) -> StorageKey {
+	let key = key.encode();
+	let mut storage_key = twox_128(PALLET_ETHEREUM).to_vec();
+	storage_key.extend_from_slice(&twox_128(item));
+	storage_key.extend_from_slice(&blake2_128(&key));
+	storage_key.extend_from_slice(&key);
+	StorageKey(storage_key)
+}
+
+/// Value of the `key` entry of the map `item` of pallet-ethereum, `None` if
+/// it is missing or was stored with other types than `T`.
+fn read_map<B, C, BE, K, T>(client: &C, id: &BlockId<B>, item: &[u8], key: K) -> Option<T> where
+	B: BlockT<Hash=H256>,
+	C: StorageProvider<B, BE>,
+	BE: Backend<B>,
+	K: Encode,
+	T: Decode,
+{
+	let data = client.storage(id, &map_key(item, key)).ok()??;
+	Decode::decode(&mut &data.0[..]).ok()
+}
+
+/// Decoder of the `V1` schema: `ethereum` 0.2 blocks of legacy transactions,
+/// stored with their receipts by Ethereum block hash.
+///
+/// `BlockNumbers` is keyed by the `BlockNumber` of the runtime, which the
+/// decoder assumes to be a `u32`, as the Ethereum runtime api does. Runtimes
+/// with another block number type encode their keys differently, so their
+/// blocks are not found.
+mod v1 {
+	use super::*;
+
+	pub fn block_data<B, C, BE>(client: &C, id: &BlockId<B>, number: u32) -> Option<EthBlockData> where
+		B: BlockT<Hash=H256>,
+		C: StorageProvider<B, BE>,
+		BE: Backend<B>,
+	{
+		let hash: H256 = read_map(client, id, b"BlockNumbers", number)?;
+		let (block, receipts): (EthereumBlock, Vec<EthereumReceipt>) =
+			read_map(client, id, b"BlocksAndReceipts", hash)?;
+		// Blocks stored before `BlockTransactionHashes` have their transactions
+		// encoded to hash them.
+		let stored: Option<Vec<H256>> = read_map(client, id, b"BlockTransactionHashes", hash);
+		let hashes = match stored {
+			Some(hashes) if hashes.len() == block.transactions.len() => hashes,
+			_ => block.transactions.iter()
+				.map(|transaction| H256::from_slice(
+					Keccak256::digest(&rlp::encode(transaction)).as_slice()
+				))
+				.collect(),
+		};
+		let statuses: Vec<Option<TransactionStatus>> = hashes.into_iter()
+			.map(|transaction_hash| read_map(client, id, b"TransactionStatuses", transaction_hash))
+			.collect();
+		Some(EthBlockData { block, statuses, receipts })
+	}
+}
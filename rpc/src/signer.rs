@@ -0,0 +1,187 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounts whose keys the node manages, and the methods signing with them.
+
+use std::{str::FromStr, sync::Mutex};
+use lru::LruCache;
+use ethereum_types::{H160, H256};
+use jsonrpc_core::{Result, Error};
+use sc_rpc_api::DenyUnsafe;
+use sha3::{Keccak256, Digest};
+use frontier_rpc_core::EthSignApi as EthSignApiT;
+use frontier_rpc_core::types::{Bytes, TypedData};
+
+use crate::{eip712, internal_err};
+
+pub use frontier_rpc_core::EthSignApiServer;
+
+/// Keys of the development signer. They are public, so accounts using them
+/// must only hold funds on local chains.
+const DEV_KEYS: [&str; 2] = [
+	"5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133",
+	"8075991ce870b93a8870eca0c0f91913d12f47948ca0fd25b49c6fa7cdbeee8b",
+];
+
+/// Holder of account keys that the rpc signs with.
+pub trait EthSigner: Send + Sync {
+	/// Addresses of the accounts.
+	fn accounts(&self) -> Vec<H160>;
+	/// Signs `hash` with the key of `address`, returning the 65 bytes
+	/// `r ‖ s ‖ v` signature, with `v` of 27 or 28.
+	fn sign_hash(&self, address: &H160, hash: &H256) -> Option<[u8; 65]>;
+}
+
+/// Signer with the well-known development keys.
+pub struct EthDevSigner {
+	keys: Vec<secp256k1::SecretKey>,
+}
+
+impl EthDevSigner {
+	pub fn new() -> Self {
+		EthDevSigner {
+			keys: DEV_KEYS.iter()
+				.map(|key| {
+					let key = H256::from_str(key).expect("development keys are hex; qed");
+					secp256k1::SecretKey::parse(key.as_fixed_bytes())
+						.expect("development keys are valid secret keys; qed")
+				})
+				.collect(),
+		}
+	}
+}
+
+impl Default for EthDevSigner {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Address of the account of `key`.
+pub fn address_of(key: &secp256k1::SecretKey) -> H160 {
+	let public = secp256k1::PublicKey::from_secret_key(key);
+	H160::from_slice(&Keccak256::digest(&public.serialize()[1..])[12..])
+}
+
+/// Sender of a signed Ethereum transaction, `None` if its signature is
+/// invalid.
+pub fn recover_sender(transaction: &ethereum::Transaction) -> Option<H160> {
+	let hash = transaction.message_hash(transaction.signature.chain_id());
+	let message = secp256k1::Message::parse(hash.as_fixed_bytes());
+	let mut signature = [0u8; 64];
+	signature[..32].copy_from_slice(&transaction.signature.r()[..]);
+	signature[32..].copy_from_slice(&transaction.signature.s()[..]);
+	let signature = secp256k1::Signature::parse(&signature);
+	let recovery_id = secp256k1::RecoveryId::parse(transaction.signature.standard_v()).ok()?;
+	let public = secp256k1::recover(&message, &signature, &recovery_id).ok()?;
+	Some(H160::from_slice(&Keccak256::digest(&public.serialize()[1..])[12..]))
+}
+
+/// Sender of a pool transaction, with the hash and encoding of its Ethereum
+/// transaction, so that they are computed once per transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSender {
+	pub sender: H160,
+	pub hash: H256,
+	pub raw: Vec<u8>,
+}
+
+impl PoolSender {
+	/// Sender of `transaction`, `None` if its signature is invalid.
+	pub fn recover(transaction: &ethereum::Transaction) -> Option<Self> {
+		let sender = recover_sender(transaction)?;
+		let raw = rlp::encode(transaction);
+		let hash = H256::from_slice(Keccak256::digest(&raw).as_slice());
+		Some(PoolSender { sender, hash, raw })
+	}
+}
+
+/// Senders of recently seen transactions, keyed by pool transaction hash, so
+/// that transactions served repeatedly, like those waiting in the pool, only
+/// have their signature recovered and their hash computed once.
+pub struct SenderCache {
+	senders: Mutex<LruCache<H256, Option<PoolSender>>>,
+}
+
+impl SenderCache {
+	pub fn new(capacity: usize) -> Self {
+		SenderCache { senders: Mutex::new(LruCache::new(capacity)) }
+	}
+
+	/// Sender of `transaction`, the Ethereum transaction of the pool
+	/// transaction `pool_hash`, recovered on a cache miss.
+	pub fn sender(&self, pool_hash: H256, transaction: &ethereum::Transaction) -> Option<PoolSender> {
+		if let Some(sender) = self.lock().get(&pool_hash) {
+			return sender.clone();
+		}
+		let sender = PoolSender::recover(transaction);
+		self.lock().put(pool_hash, sender.clone());
+		sender
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<LruCache<H256, Option<PoolSender>>> {
+		self.senders.lock().expect("sender cache lock is never held across a panic; qed")
+	}
+}
+
+/// 65 bytes `r ‖ s ‖ v` signature of `hash` by `key`.
+pub fn sign_hash(key: &secp256k1::SecretKey, hash: &H256) -> [u8; 65] {
+	let message = secp256k1::Message::parse(hash.as_fixed_bytes());
+	let (signature, recovery_id) = secp256k1::sign(&message, key);
+	let mut bytes = [0u8; 65];
+	bytes[..64].copy_from_slice(&signature.serialize());
+	bytes[64] = recovery_id.serialize() + 27;
+	bytes
+}
+
+impl EthSigner for EthDevSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.keys.iter().map(address_of).collect()
+	}
+
+	fn sign_hash(&self, address: &H160, hash: &H256) -> Option<[u8; 65]> {
+		self.keys.iter()
+			.find(|key| address_of(key) == *address)
+			.map(|key| sign_hash(key, hash))
+	}
+}
+
+/// Signs `hash` with the first of `signers` holding the key of `address`.
+pub(crate) fn sign_with(signers: &[Box<dyn EthSigner>], address: &H160, hash: &H256) -> Result<[u8; 65]> {
+	signers.iter()
+		.find_map(|signer| signer.sign_hash(address, hash))
+		.ok_or_else(|| internal_err("unknown account"))
+}
+
+pub struct EthSignApi {
+	signers: Vec<Box<dyn EthSigner>>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl EthSignApi {
+	pub fn new(signers: Vec<Box<dyn EthSigner>>, deny_unsafe: DenyUnsafe) -> Self {
+		EthSignApi { signers, deny_unsafe }
+	}
+}
+
+impl EthSignApiT for EthSignApi {
+	fn sign_typed_data_v4(&self, address: H160, data: TypedData) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+		let hash = eip712::hash(&data)
+			.map_err(|e| Error::invalid_params(format!("invalid typed data: {}", e)))?;
+		sign_with(&self.signers, &address, &hash).map(|signature| Bytes(signature.to_vec()))
+	}
+}
@@ -0,0 +1,83 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracing spans around rpc calls, for operators running distributed tracing.
+//!
+//! `instrument_spans` wraps the methods of an rpc delegate so that each call
+//! runs in an `rpc_call` span under the `rpc` target, carrying the method name.
+//! Runtime api calls and backend lookups made while serving the call open
+//! nested spans carrying the block they read, so a slow call can be broken
+//! down. Spans are recorded with `--tracing-targets rpc`.
+
+use std::{fmt, sync::Arc};
+use jsonrpc_core::{
+	BoxFuture, Metadata, Params, RemoteProcedure, RpcMethod, Value,
+	futures::{Future, Poll},
+};
+use tracing::Span;
+
+/// Wraps the methods of `delegate` so that their calls run in a span.
+pub fn instrument_spans<M, D>(delegate: D) -> Vec<(String, RemoteProcedure<M>)> where
+	M: Metadata,
+	D: IntoIterator<Item=(String, RemoteProcedure<M>)>,
+{
+	delegate.into_iter().map(|(name, procedure)| {
+		let procedure = match procedure {
+			RemoteProcedure::Method(method) => RemoteProcedure::Method(Arc::new(SpannedMethod {
+				name: name.clone(),
+				method,
+			})),
+			other => other,
+		};
+		(name, procedure)
+	}).collect()
+}
+
+/// Span of a runtime api call or backend lookup made at `block` while serving
+/// a call.
+pub(crate) fn lookup_span(lookup: &'static str, block: &dyn fmt::Debug) -> Span {
+	tracing::debug_span!(target: "rpc", "lookup", lookup, block = ?block)
+}
+
+struct SpannedMethod<M> {
+	name: String,
+	method: Arc<dyn RpcMethod<M>>,
+}
+
+impl<M: Metadata> RpcMethod<M> for SpannedMethod<M> {
+	fn call(&self, params: Params, meta: M) -> BoxFuture<Value> {
+		let span = tracing::info_span!(target: "rpc", "rpc_call", method = %self.name);
+		// Most methods do their work before returning their future.
+		let inner = span.in_scope(|| self.method.call(params, meta));
+		Box::new(InSpan { span, inner })
+	}
+}
+
+/// Future polled within a span.
+struct InSpan<F> {
+	span: Span,
+	inner: F,
+}
+
+impl<F: Future> Future for InSpan<F> {
+	type Item = F::Item;
+	type Error = F::Error;
+
+	fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+		let _enter = self.span.enter();
+		self.inner.poll()
+	}
+}
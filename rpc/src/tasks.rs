@@ -0,0 +1,71 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+//! Background tasks of a Frontier node, spawned together by
+//! `spawn_frontier_tasks` so that every node wires them the same way.
+
+use std::{io::Write, pin::Pin, sync::Arc};
+use ethereum_types::H256;
+use futures::Future;
+use sp_runtime::traits::Block as BlockT;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sc_client_api::{BlockchainEvents, backend::{AuxStore, StorageProvider, Backend}};
+use frontier_rpc_primitives::EthereumRuntimeApi;
+
+use crate::{export_blocks, warm_block_cache, EthBlockDataCache, EthPubSubWorker};
+
+/// Configuration of the Frontier background tasks.
+#[derive(Default)]
+pub struct FrontierTasksConfig {
+	/// Sink of the export of every imported block, if enabled.
+	pub export_blocks: Option<Box<dyn Write + Send>>,
+	/// Worker notifying the Ethereum subscriptions, if they are served.
+	pub eth_pubsub: Option<EthPubSubWorker>,
+	/// Number of last blocks whose transaction mappings are kept, if not all.
+	pub transaction_retention: Option<u32>,
+	/// Cache of the Ethereum data of recent blocks, to warm with every new
+	/// best block.
+	pub block_data_cache: Option<Arc<EthBlockDataCache>>,
+}
+
+/// Spawn the Frontier background tasks enabled by `config` with `spawn`, which
+/// takes the name and future of each task.
+///
+/// Ethereum hash mappings are written at block import by `fc-consensus`, so no
+/// task is needed for them, only for pruning the old transaction ones.
+pub fn spawn_frontier_tasks<B, C, BE, S>(client: Arc<C>, config: FrontierTasksConfig, spawn: S) where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + BlockchainEvents<B> + HeaderBackend<B> + AuxStore,
+	C: Send + Sync + 'static,
+	C::Api: EthereumRuntimeApi<B>,
+	BE: Backend<B>,
+	S: Fn(&'static str, Pin<Box<dyn Future<Output = ()> + Send>>),
+{
+	if let Some(sink) = config.export_blocks {
+		spawn("block-export", Box::pin(export_blocks(client.clone(), sink)));
+	}
+	if let Some(retention) = config.transaction_retention {
+		spawn("transaction-pruning", Box::pin(fc_db::prune_transactions(client.clone(), retention)));
+	}
+	if let Some(cache) = config.block_data_cache {
+		spawn("eth-block-cache", Box::pin(warm_block_cache(client.clone(), cache)));
+	}
+	if let Some(worker) = config.eth_pubsub {
+		spawn("eth-pubsub", Box::pin(worker.run(client)));
+	}
+}
@@ -0,0 +1,626 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::{BoxFuture, Result, Error, futures::{future, Future}};
+use sp_runtime::traits::{Block as BlockT, Header as _};
+use sp_api::{ApiExt, ProvideRuntimeApi, BlockId};
+use sp_blockchain::HeaderBackend;
+use sc_client_api::backend::Backend;
+use frontier_rpc_core::TraceApi as TraceApiT;
+use frontier_rpc_core::types::{
+	BlockNumber, Bytes, CallRequest, CallBlockOverride, TraceType, TraceResults, Trace, Action,
+	ActionResult, CallAction, CallResult, CreateAction, CreateResult, CallFrame, TraceCallConfig,
+	AccountRange, AccountRangeEntry, StateDiff, AccountDiff, Diff,
+};
+use frontier_rpc_primitives::{
+	EthereumRuntimeApi, BlockOverride, DryRunCall, DryRunOutcome, ExecutionError,
+	AccountRange as RuntimeAccountRange,
+};
+
+use crate::{
+	BlockingTaskPool, EthApiLimits, LatestBlock, PendingState, internal_err, latest_header,
+	lookup_span, native_block_id, execute_gas_limit, execute_gas_price, block_override_build,
+	dry_run_outcome_available, call_many_block_override_available, base_fee_override_available,
+	extended_api_available, not_supported_at,
+};
+
+pub use frontier_rpc_core::TraceApiServer;
+
+/// Most accounts listed by a single `debug_accountRange` call, as in geth.
+const ACCOUNT_RANGE_MAX_RESULTS: u32 = 256;
+
+pub struct TraceApi<B: BlockT, C, BE> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	frontier_backend: Arc<dyn fc_api::Backend<B>>,
+	latest: LatestBlock,
+	blocking_pool: BlockingTaskPool,
+	limits: EthApiLimits,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C, BE> TraceApi<B, C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		frontier_backend: Arc<dyn fc_api::Backend<B>>,
+		latest: LatestBlock,
+		blocking_pool: BlockingTaskPool,
+		limits: EthApiLimits,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			frontier_backend,
+			latest,
+			blocking_pool,
+			limits,
+			_marker: PhantomData,
+		}
+	}
+}
+
+fn account_range_build(range: RuntimeAccountRange) -> AccountRange {
+	AccountRange {
+		accounts: range.accounts.into_iter().map(|entry| AccountRangeEntry {
+			address: entry.address,
+			balance: entry.basic.balance,
+			nonce: entry.basic.nonce,
+			code_hash: entry.code_hash,
+			key: Bytes(entry.key),
+		}).collect(),
+		next: range.next.map(Bytes),
+	}
+}
+
+fn trace_results_build(call: DryRunCall, outcome: DryRunOutcome) -> TraceResults {
+	let (result, error) = if outcome.succeeded {
+		let result = match outcome.created {
+			Some(address) => ActionResult::Create(CreateResult {
+				gas_used: outcome.used_gas,
+				code: Bytes(outcome.output.clone()),
+				address,
+			}),
+			None => ActionResult::Call(CallResult {
+				gas_used: outcome.used_gas,
+				output: Bytes(outcome.output.clone()),
+			}),
+		};
+		(Some(result), None)
+	} else {
+		// Parity traces report reverts as `Reverted`.
+		let error = match outcome.error {
+			Some(ExecutionError::Reverted) | None => "Reverted",
+			Some(error) => error.message(),
+		};
+		(None, Some(error.to_string()))
+	};
+	let (action, trace_type) = match call.to {
+		Some(to) => (Action::Call(CallAction {
+			from: call.from,
+			to,
+			value: call.value,
+			gas: call.gas_limit,
+			input: Bytes(call.data),
+			call_type: "call".to_string(),
+		}), "call"),
+		None => (Action::Create(CreateAction {
+			from: call.from,
+			value: call.value,
+			gas: call.gas_limit,
+			init: Bytes(call.data),
+		}), "create"),
+	};
+	TraceResults {
+		output: Bytes(outcome.output),
+		state_diff: None,
+		// Only the top-level call is traced, the EVM does not expose its
+		// internal calls.
+		trace: vec![Trace {
+			action,
+			result,
+			error,
+			subtraces: 0,
+			trace_address: vec![],
+			trace_type: trace_type.to_string(),
+		}],
+	}
+}
+
+fn call_frame_build(call: DryRunCall, outcome: DryRunOutcome) -> CallFrame {
+	CallFrame {
+		call_type: if call.to.is_some() { "CALL" } else { "CREATE" }.to_string(),
+		from: call.from,
+		to: call.to.or(outcome.created).unwrap_or_default(),
+		value: call.value,
+		gas: call.gas_limit,
+		gas_used: outcome.used_gas,
+		input: Bytes(call.data),
+		output: Bytes(outcome.output),
+		error: outcome.error.map(|error| error.message().to_string()),
+	}
+}
+
+/// Calls of trace `requests`, with the trace types of each. `vmTrace` is
+/// rejected, as the EVM does not report the steps of a call.
+fn trace_calls(
+	requests: Vec<(CallRequest, Vec<TraceType>)>,
+) -> Result<(Vec<CallRequest>, Vec<Vec<TraceType>>)> {
+	if requests.iter().any(|(_, trace_types)| trace_types.contains(&TraceType::VmTrace)) {
+		return Err(Error::invalid_params(
+			"the vmTrace trace type is not supported, only trace and stateDiff are"
+		));
+	}
+	Ok(requests.into_iter().unzip())
+}
+
+/// EVM account `address` with its code, in the state of the block `id` with
+/// `changes` on top. `None` if the account does not exist.
+fn account_state<B, C>(
+	client: &C,
+	id: &BlockId<B>,
+	changes: &PendingState,
+	address: H160,
+) -> Result<Option<(pallet_evm::Account, Vec<u8>)>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let basic = match changes.account(address) {
+		Some(basic) => basic,
+		None => client.runtime_api().account_basic(id, address)
+			.map_err(|_| internal_err("fetch runtime account basic failed"))?,
+	};
+	let code = match changes.code(address) {
+		Some(code) => code,
+		None => client.runtime_api().account_code_at(id, address)
+			.map_err(|_| internal_err("fetch runtime account code failed"))?,
+	};
+	let exists = basic.nonce != U256::zero() || basic.balance != U256::zero() || !code.is_empty();
+	Ok(if exists { Some((basic, code)) } else { None })
+}
+
+/// Storage slot `index` of `address`, in the state of the block `id` with
+/// `changes` on top. `None` if the slot is empty.
+fn storage_state<B, C>(
+	client: &C,
+	id: &BlockId<B>,
+	changes: &PendingState,
+	address: H160,
+	index: H256,
+) -> Result<Option<H256>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let value = match changes.storage(address, index) {
+		Some(value) => value,
+		None => client.runtime_api()
+			.storage_at(id, address, U256::from_big_endian(index.as_bytes()))
+			.map_err(|_| internal_err("fetch runtime storage failed"))?,
+	};
+	Ok(if value.is_zero() { None } else { Some(value) })
+}
+
+/// Differences between the state of the block `id` with `before` on top and
+/// with `after` on top, over the accounts and slots `after` writes. Accounts
+/// left as they were are omitted, as in Parity.
+fn state_diff_build<B, C>(
+	client: &C,
+	id: &BlockId<B>,
+	before: &PendingState,
+	after: &PendingState,
+) -> Result<StateDiff> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let mut state_diff = StateDiff::new();
+	for (address, indices) in after.touched() {
+		let from = account_state(client, id, before, address)?;
+		let to = account_state(client, id, after, address)?;
+		let mut storage = BTreeMap::new();
+		for index in indices {
+			let diff = Diff::new(
+				storage_state(client, id, before, address, index)?,
+				storage_state(client, id, after, address, index)?,
+			);
+			if !diff.is_same() {
+				storage.insert(index, diff);
+			}
+		}
+		let account_diff = AccountDiff {
+			balance: Diff::new(
+				from.as_ref().map(|(basic, _)| basic.balance),
+				to.as_ref().map(|(basic, _)| basic.balance),
+			),
+			nonce: Diff::new(
+				from.as_ref().map(|(basic, _)| basic.nonce),
+				to.as_ref().map(|(basic, _)| basic.nonce),
+			),
+			code: Diff::new(
+				from.map(|(_, code)| Bytes(code)),
+				to.map(|(_, code)| Bytes(code)),
+			),
+			storage,
+		};
+		let unchanged = account_diff.balance.is_same() && account_diff.nonce.is_same() &&
+			account_diff.code.is_same() && account_diff.storage.is_empty();
+		if !unchanged {
+			state_diff.insert(address, account_diff);
+		}
+	}
+	Ok(state_diff)
+}
+
+/// Storage changes of executing the first `len` of `calls` on the block `id`.
+fn call_many_changes<B, C, BE>(
+	client: &C,
+	backend: &BE,
+	id: &BlockId<B>,
+	calls: &[DryRunCall],
+	block_override: &BlockOverride,
+	len: usize,
+) -> Result<PendingState> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B>,
+{
+	if len == 0 {
+		return Ok(PendingState::default());
+	}
+	let hash = match *id {
+		BlockId::Hash(hash) => hash,
+		BlockId::Number(number) => client.hash(number)
+			.map_err(|_| internal_err("fetch block hash failed"))?
+			.ok_or(internal_err("fetch block hash failed"))?,
+	};
+
+	let api = client.runtime_api();
+	call_many_at(client, &*api, id, calls[..len].to_vec(), block_override.clone())?;
+	let state = backend.state_at(*id)
+		.map_err(|_| internal_err("fetch state failed"))?;
+	let changes = api.into_storage_changes(&state, None, hash)
+		.map_err(|_| internal_err("collect call changes failed"))?;
+	Ok(PendingState::new(changes.main_storage_changes))
+}
+
+/// State differences of each of `calls` executed in sequence on the block
+/// `id`, for the calls `wanted`. Storage changes are only collected for a
+/// whole runtime api call, so the differences of a call are the ones between
+/// the changes of the calls before it, and of these calls and itself.
+fn state_diffs<B, C, BE>(
+	client: &C,
+	backend: &BE,
+	id: &BlockId<B>,
+	calls: &[DryRunCall],
+	block_override: &BlockOverride,
+	wanted: &[bool],
+) -> Result<Vec<Option<StateDiff>>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B>,
+{
+	let mut state_diffs = vec![None; calls.len()];
+	// Changes of the calls before the one diffed, reused from the previous
+	// diff when it is the one of the call right before.
+	let mut before: Option<(usize, PendingState)> = None;
+	for (index, _) in wanted.iter().enumerate().filter(|(_, wanted)| **wanted) {
+		let before_changes = match before.take() {
+			Some((len, changes)) if len == index => changes,
+			_ => call_many_changes(client, backend, id, calls, block_override, index)?,
+		};
+		let after_changes = call_many_changes(client, backend, id, calls, block_override, index + 1)?;
+		state_diffs[index] = Some(state_diff_build(client, id, &before_changes, &after_changes)?);
+		before = Some((index + 1, after_changes));
+	}
+	Ok(state_diffs)
+}
+
+/// Block to execute calls on for `number`, the latest block for `latest` and
+/// `pending`. A hash or number not naming a block of the chain served is
+/// rejected, rather than executed on the latest state.
+fn trace_block_id<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	number: Option<BlockNumber>,
+) -> Result<BlockId<B>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let header = latest_header(client, latest)?;
+	if let None | Some(BlockNumber::Latest) | Some(BlockNumber::Pending) = number {
+		return Ok(BlockId::Hash(header.hash()));
+	}
+
+	let unknown = Error::invalid_params(format!("unknown block {:?}", number));
+	match native_block_id(client, frontier_backend, latest, number)? {
+		Some(BlockId::Number(number)) if number > *header.number() => Err(unknown),
+		Some(id) => Ok(id),
+		None => Err(unknown),
+	}
+}
+
+/// Block to execute `requests` on, with their calls and block override.
+fn dry_run_calls<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	requests: Vec<CallRequest>,
+	number: Option<BlockNumber>,
+	block_override: Option<CallBlockOverride>,
+) -> Result<(BlockId<B>, Vec<DryRunCall>, BlockOverride)> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	limits.check_result_count(requests.len(), limits.max_traces, "traces")?;
+	let block_override = block_override_build(block_override)?;
+
+	let id = trace_block_id(client, frontier_backend, latest, number)?;
+	// Runtimes from before version 2 of the api cannot execute dependent calls,
+	// nor report why a call failed.
+	if !dry_run_outcome_available(client, &id)? {
+		return Err(not_supported_at("traced calls", &id));
+	}
+
+	let mut calls = Vec::with_capacity(requests.len());
+	for request in requests {
+		let gas_price = execute_gas_price(client, &id, &request, block_override.base_fee)?;
+		calls.push(DryRunCall {
+			from: request.from.unwrap_or_default(),
+			to: request.to,
+			data: request.data.map(|d| d.0).unwrap_or_default(),
+			value: request.value.unwrap_or_default(),
+			gas_limit: execute_gas_limit(client, &id, request.gas, limits)?,
+			gas_price,
+			nonce: request.nonce,
+		});
+	}
+	Ok((id, calls, block_override))
+}
+
+/// Outcomes of `calls` executed in sequence with `api` on the block `id`, in
+/// the block context of `block_override`.
+fn call_many_at<B, C>(
+	client: &C,
+	api: &C::Api,
+	id: &BlockId<B>,
+	calls: Vec<DryRunCall>,
+	block_override: BlockOverride,
+) -> Result<Vec<DryRunOutcome>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let span = lookup_span("call_many", id);
+	let _enter = span.enter();
+	let outcomes = if base_fee_override_available(client, id)? {
+		api.call_many(id, calls, block_override)
+	} else if block_override.base_fee.is_some() {
+		return Err(not_supported_at("base fee overrides", id));
+	} else if call_many_block_override_available(client, id)? {
+		#[allow(deprecated)]
+		api.call_many_before_version_4(id, calls, block_override.into())
+	} else if block_override != BlockOverride::default() {
+		return Err(not_supported_at("block overrides of traced calls", id));
+	} else {
+		#[allow(deprecated)]
+		api.call_many_before_version_3(id, calls)
+	}.map_err(|_| internal_err("executing calls failed"))?;
+
+	outcomes.into_iter()
+		.map(|outcome| outcome.ok_or(internal_err("inner executing call failed")))
+		.collect()
+}
+
+/// Outcomes of `requests` executed in sequence on the block `number`, in the
+/// block context of `block_override`.
+fn dry_run_many<B, C>(
+	client: &C,
+	frontier_backend: &dyn fc_api::Backend<B>,
+	latest: LatestBlock,
+	limits: &EthApiLimits,
+	requests: Vec<CallRequest>,
+	number: Option<BlockNumber>,
+	block_override: Option<CallBlockOverride>,
+) -> Result<Vec<(DryRunCall, DryRunOutcome)>> where
+	B: BlockT<Hash=H256>,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B>,
+{
+	let (id, calls, block_override) = dry_run_calls(
+		client, frontier_backend, latest, limits, requests, number, block_override,
+	)?;
+	let outcomes = call_many_at(client, &*client.runtime_api(), &id, calls.clone(), block_override)?;
+	Ok(calls.into_iter().zip(outcomes).collect())
+}
+
+impl<B, C, BE> TraceApi<B, C, BE> where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B> + 'static,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+{
+	/// Executes `requests` on the blocking task pool, building the trace of
+	/// each call with `build`.
+	fn spawn_dry_run_many<T, F>(
+		&self,
+		requests: Vec<CallRequest>,
+		number: Option<BlockNumber>,
+		block_override: Option<CallBlockOverride>,
+		build: F,
+	) -> BoxFuture<Vec<T>> where
+		T: Send + 'static,
+		F: Fn(DryRunCall, DryRunOutcome) -> T + Send + 'static,
+	{
+		let client = self.client.clone();
+		let frontier_backend = self.frontier_backend.clone();
+		let latest = self.latest;
+		let limits = self.limits.clone();
+		self.blocking_pool.spawn(move || {
+			let outcomes = dry_run_many(
+				client.as_ref(),
+				frontier_backend.as_ref(),
+				latest,
+				&limits,
+				requests,
+				number,
+				block_override,
+			)?;
+			Ok(outcomes.into_iter().map(|(call, outcome)| build(call, outcome)).collect())
+		})
+	}
+
+	/// Traces `requests` on the blocking task pool.
+	fn spawn_trace_many(
+		&self,
+		requests: Vec<(CallRequest, Vec<TraceType>)>,
+		number: Option<BlockNumber>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<Vec<TraceResults>> {
+		let (requests, trace_types) = match trace_calls(requests) {
+			Ok(requests) => requests,
+			Err(err) => return Box::new(future::err(err)),
+		};
+		let client = self.client.clone();
+		let backend = self.backend.clone();
+		let frontier_backend = self.frontier_backend.clone();
+		let latest = self.latest;
+		let limits = self.limits.clone();
+		self.blocking_pool.spawn(move || {
+			let client = client.as_ref();
+			let (id, calls, block_override) = dry_run_calls(
+				client, frontier_backend.as_ref(), latest, &limits, requests, number, block_override,
+			)?;
+			let outcomes = call_many_at(
+				client, &*client.runtime_api(), &id, calls.clone(), block_override.clone(),
+			)?;
+			let wanted = trace_types.iter()
+				.map(|trace_types| trace_types.contains(&TraceType::StateDiff))
+				.collect::<Vec<_>>();
+			let state_diffs = state_diffs(
+				client, backend.as_ref(), &id, &calls, &block_override, &wanted,
+			)?;
+
+			Ok(calls.into_iter().zip(outcomes).zip(trace_types).zip(state_diffs)
+				.map(|(((call, outcome), trace_types), state_diff)| {
+					let mut results = trace_results_build(call, outcome);
+					if !trace_types.contains(&TraceType::Trace) {
+						results.trace.clear();
+					}
+					results.state_diff = state_diff;
+					results
+				})
+				.collect())
+		})
+	}
+}
+
+impl<B, C, BE> TraceApiT for TraceApi<B, C, BE> where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeApi<B> + ApiExt<B, StateBackend = BE::State>,
+	BE: Backend<B> + 'static,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+{
+	fn call(
+		&self,
+		request: CallRequest,
+		trace_types: Vec<TraceType>,
+		number: Option<BlockNumber>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<TraceResults> {
+		Box::new(
+			self.spawn_trace_many(vec![(request, trace_types)], number, block_override)
+				.and_then(|mut traces| traces.pop().ok_or(internal_err("executing call failed")))
+		)
+	}
+
+	fn call_many(
+		&self,
+		requests: Vec<(CallRequest, Vec<TraceType>)>,
+		number: Option<BlockNumber>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<Vec<TraceResults>> {
+		self.spawn_trace_many(requests, number, block_override)
+	}
+
+	fn debug_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		config: Option<TraceCallConfig>,
+	) -> BoxFuture<CallFrame> {
+		// Without a tracer, geth traces with the struct logger.
+		let config = config.unwrap_or_default();
+		match config.tracer.as_ref().map(String::as_str) {
+			Some("callTracer") => {},
+			Some(tracer) => return Box::new(future::err(Error::invalid_params(
+				format!("the {} tracer is not supported, only callTracer is", tracer)
+			))),
+			None => return Box::new(future::err(Error::invalid_params(
+				"the struct logger is not supported, the tracer must be callTracer"
+			))),
+		}
+		Box::new(
+			self.spawn_dry_run_many(vec![request], number, config.block_overrides, call_frame_build)
+				.and_then(|mut frames| frames.pop().ok_or(internal_err("executing call failed")))
+		)
+	}
+
+	fn debug_call_many(
+		&self,
+		requests: Vec<CallRequest>,
+		number: Option<BlockNumber>,
+		block_override: Option<CallBlockOverride>,
+	) -> BoxFuture<Vec<CallFrame>> {
+		self.spawn_dry_run_many(requests, number, block_override, call_frame_build)
+	}
+
+	fn account_range(
+		&self,
+		number: Option<BlockNumber>,
+		start: Bytes,
+		max_results: u32,
+	) -> BoxFuture<AccountRange> {
+		let client = self.client.clone();
+		let frontier_backend = self.frontier_backend.clone();
+		let latest = self.latest;
+		self.blocking_pool.spawn(move || {
+			let client = client.as_ref();
+			let id = trace_block_id(client, frontier_backend.as_ref(), latest, number)?;
+			if !extended_api_available(client, &id)? {
+				return Err(not_supported_at("account ranges", &id));
+			}
+
+			let span = lookup_span("account_range", &id);
+			let _enter = span.enter();
+			let range = client.runtime_api()
+				.account_range(&id, start.0, max_results.min(ACCOUNT_RANGE_MAX_RESULTS))
+				.map_err(|_| internal_err("fetch runtime account range failed"))?;
+			Ok(account_range_build(range))
+		})
+	}
+}
@@ -15,13 +15,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sp_core::{U256, Pair, Public, sr25519};
+use sp_core::{H160, U256, Pair, Public, sr25519};
 use evm::{ConvertAccountId, HashTruncateConvertAccountId};
 use frontier_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, EVMConfig, GenesisConfig, GrandpaConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, EVMConfig, EthereumConfig, EvmDeployersConfig, GenesisConfig,
+	FrontierPrecompiles, GrandpaConfig, Signature, SudoConfig, SystemConfig, PRECOMPILE_CODE,
+	WASM_BINARY,
 };
 use sc_service::ChainType;
+use crate::system_contracts;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{BlakeTwo256, IdentifyAccount, Verify};
@@ -54,12 +56,13 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
-pub fn development_config() -> ChainSpec {
+/// Development chain spec, with `evm_accounts` added to the EVM genesis.
+pub fn development_config(evm_accounts: BTreeMap<H160, evm::GenesisAccount>) -> ChainSpec {
 	ChainSpec::from_genesis(
 		"Development",
 		"dev",
 		ChainType::Development,
-		|| {
+		move || {
 			testnet_genesis(
 				vec![authority_keys_from_seed("Alice")],
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -69,6 +72,7 @@ pub fn development_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				evm_accounts.clone(),
 				true,
 			)
 		},
@@ -80,12 +84,13 @@ pub fn development_config() -> ChainSpec {
 	)
 }
 
-pub fn local_testnet_config() -> ChainSpec {
+/// Local testnet chain spec, with `evm_accounts` added to the EVM genesis.
+pub fn local_testnet_config(evm_accounts: BTreeMap<H160, evm::GenesisAccount>) -> ChainSpec {
 	ChainSpec::from_genesis(
 		"Local Testnet",
 		"local_testnet",
 		ChainType::Local,
-		|| {
+		move || {
 			testnet_genesis(
 				vec![
 					authority_keys_from_seed("Alice"),
@@ -106,6 +111,7 @@ pub fn local_testnet_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				evm_accounts.clone(),
 				true,
 			)
 		},
@@ -121,6 +127,7 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	extra_evm_accounts: BTreeMap<H160, evm::GenesisAccount>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	let alice_account_id = get_account_id_from_seed::<sr25519::Public>("Alice");
@@ -136,6 +143,36 @@ fn testnet_genesis(
 			code: WASM_BINARY.to_vec(),
 		},
 	);
+	// Accounts of `--enable-dev-signer`.
+	for address in frontier_rpc::EthSigner::accounts(&frontier_rpc::EthDevSigner::new()) {
+		evm_accounts.insert(
+			address,
+			evm::GenesisAccount {
+				nonce: 0.into(),
+				balance: U256::from(1_000_000_000_000_000_000_000_000u128),
+				storage: BTreeMap::new(),
+				code: vec![],
+			},
+		);
+	}
+
+	// Precompiles respond to `extcodesize` like contracts.
+	for address in FrontierPrecompiles::addresses() {
+		evm_accounts.insert(
+			address,
+			evm::GenesisAccount {
+				nonce: 0.into(),
+				balance: U256::zero(),
+				storage: BTreeMap::new(),
+				code: PRECOMPILE_CODE.to_vec(),
+			},
+		);
+	}
+
+	// Infrastructure contracts that tools expect at well-known addresses.
+	evm_accounts.extend(system_contracts::evm_accounts());
+
+	evm_accounts.extend(extra_evm_accounts);
 
 	GenesisConfig {
 		system: Some(SystemConfig {
@@ -162,5 +199,10 @@ fn testnet_genesis(
 		evm: Some(EVMConfig {
 			accounts: evm_accounts,
 		}),
+		ethereum: Some(EthereumConfig {}),
+		evm_deployers: Some(EvmDeployersConfig {
+			enforced: false,
+			deployers: vec![],
+		}),
 	}
 }
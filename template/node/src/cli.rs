@@ -15,9 +15,344 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sc_cli::{RunCmd, Subcommand};
+use std::{path::PathBuf, str::FromStr, time::Duration};
+use sc_cli::{RunCmd, SharedParams, ImportParams};
 use structopt::StructOpt;
 
+/// How blocks are authored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Aura authoring with GRANDPA finality.
+	Aura,
+	/// Blocks are authored on request, through the `engine_createBlock` RPC.
+	Manual,
+	/// A block is authored as soon as a transaction enters the pool.
+	Instant,
+}
+
+impl Sealing {
+	/// Whether blocks are authored by manual seal rather than by Aura.
+	pub fn is_manual_seal(&self) -> bool {
+		*self != Sealing::Aura
+	}
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"aura" => Ok(Sealing::Aura),
+			"manual" => Ok(Sealing::Manual),
+			"instant" => Ok(Sealing::Instant),
+			other => Err(format!("unknown sealing mode `{}`, expected aura, manual or instant", other)),
+		}
+	}
+}
+
+/// Ethereum rpc parameters.
+#[derive(Debug, Clone, StructOpt)]
+pub struct RpcParams {
+	/// Log Ethereum rpc calls under the `rpc` target, warning about calls
+	/// slower than this many milliseconds.
+	#[structopt(long)]
+	pub rpc_slow_call_threshold: Option<u64>,
+
+	/// Limit each connection to this many `debug_*`, `trace_*`, `eth_getLogs`
+	/// and `frontier_getBlockRange` calls per second.
+	#[structopt(long)]
+	pub rpc_rate_limit: Option<u32>,
+
+	/// Ethereum rpc namespaces to serve, as a comma separated list of `eth`,
+	/// `trace`, `frontier`, `account`, `dev` and `admin`.
+	#[structopt(long, default_value = "eth,trace,frontier,account,dev,admin")]
+	pub ethapi: frontier_rpc::EthApiSet,
+
+	/// Maximum number of logs returned by a single `eth_getLogs` query.
+	#[structopt(long)]
+	pub max_past_logs: Option<usize>,
+
+	/// Maximum number of blocks spanned by a single `eth_getLogs` or
+	/// `frontier_getBlockRange` query.
+	#[structopt(long)]
+	pub max_block_range: Option<u64>,
+
+	/// Maximum number of traces returned by a single `trace_*` or `debug_*`
+	/// query.
+	#[structopt(long)]
+	pub max_traces: Option<usize>,
+
+	/// Maximum number of calls of a single `eth_simulateV1` request.
+	#[structopt(long)]
+	pub max_simulate_calls: Option<usize>,
+
+	/// Maximum size in bytes of the responses of `eth_getBlockByHash`,
+	/// `eth_getBlockByNumber` and `frontier_getBlockRange`.
+	#[structopt(long)]
+	pub max_response_size: Option<usize>,
+
+	/// Gas available to `eth_call`, `eth_estimateGas` and traced calls,
+	/// whatever the block gas limit.
+	#[structopt(long)]
+	pub rpc_gas_cap: Option<u64>,
+
+	/// Gas available to `eth_call`, `eth_estimateGas` and traced calls, as a
+	/// multiple of the block gas limit. Follows the runtime by default.
+	#[structopt(long)]
+	pub execute_gas_limit_multiplier: Option<u64>,
+
+	/// Number of recovered Ethereum transaction senders kept in memory.
+	/// Defaults to the size of `frontier_rpc::EthConfiguration`.
+	#[structopt(long)]
+	pub eth_sender_cache_size: Option<usize>,
+
+	/// Number of recent blocks whose Ethereum data is kept in memory.
+	/// Defaults to the size of `frontier_rpc::EthConfiguration`.
+	#[structopt(long)]
+	pub eth_block_data_cache_size: Option<usize>,
+
+	/// File holding a secret that WebSocket connections must send to
+	/// `frontier_authenticate` before calling `trace_*` and `evm_*` methods.
+	#[structopt(long, parse(from_os_str))]
+	pub rpc_secret_file: Option<PathBuf>,
+
+	/// Accept Ethereum transactions without EIP-155 replay protection in
+	/// `eth_sendRawTransaction`, for tooling that still signs legacy ones.
+	#[structopt(long)]
+	pub rpc_allow_unprotected_txs: bool,
+
+	/// Manage the well-known development accounts, which `eth_accounts` lists
+	/// and `eth_signTypedData_v4` signs with. Only for local chains.
+	#[structopt(long)]
+	pub enable_dev_signer: bool,
+
+	/// Serve `personal_newAccount`, `personal_importRawKey`,
+	/// `personal_sendTransaction` and `personal_sign`, keeping accounts and
+	/// passwords in memory. Only for development nodes; the calls are also
+	/// denied when unsafe rpc methods are.
+	#[structopt(long)]
+	pub unsafe_personal_api: bool,
+
+	/// Maximum number of active `eth_subscribe` subscriptions of a WebSocket
+	/// connection.
+	#[structopt(long, default_value = "32")]
+	pub rpc_max_subscriptions_per_connection: usize,
+
+	/// Number of notifications buffered for a subscription whose client does
+	/// not keep up.
+	#[structopt(long, default_value = "512")]
+	pub rpc_subscription_buffer: usize,
+
+	/// What to do with a subscription whose buffer is full: `drop` further
+	/// notifications, or `disconnect` the subscription.
+	#[structopt(long, default_value = "drop")]
+	pub rpc_subscription_overflow: frontier_rpc::OverflowPolicy,
+
+	/// Number of past blocks per second whose logs the `logs` subscriptions
+	/// with a `fromBlock` replay, all together, before following new blocks.
+	#[structopt(long, default_value = "1000")]
+	pub rpc_subscription_replay_rate: u32,
+
+	/// Number of threads running `eth_getLogs` scans, gas estimations and
+	/// simulations, apart from the threads serving the other rpc calls.
+	#[structopt(long, default_value = "4")]
+	pub rpc_blocking_threads: usize,
+
+	/// Number of expensive rpc calls waiting for a blocking thread, beyond
+	/// which further ones are rejected.
+	#[structopt(long, default_value = "64")]
+	pub rpc_blocking_queue: usize,
+
+	/// Number of threads running `trace_*` and `debug_*` calls. They do not
+	/// use the other blocking threads.
+	#[structopt(long, default_value = "2")]
+	pub rpc_trace_threads: usize,
+
+	/// Number of trace calls waiting for a trace thread, beyond which further
+	/// ones are rejected.
+	#[structopt(long, default_value = "16")]
+	pub rpc_trace_queue: usize,
+
+	/// Seconds an expensive rpc call waits for a free thread before being
+	/// rejected.
+	#[structopt(long, default_value = "30")]
+	pub rpc_blocking_timeout: u64,
+
+	/// Block that the Ethereum rpc serves as `latest`: `best`, `finalized`, or
+	/// a number of blocks behind the best block, so that conservative clients
+	/// never see blocks that may still be reorged away.
+	#[structopt(long, default_value = "best")]
+	pub rpc_latest_block: frontier_rpc::LatestBlock,
+}
+
+impl RpcParams {
+	/// Configuration of the Ethereum rpc.
+	pub fn eth_configuration(&self) -> frontier_rpc::EthConfiguration {
+		let default = frontier_rpc::EthConfiguration::default();
+		frontier_rpc::EthConfiguration {
+			max_past_logs: self.max_past_logs,
+			max_block_range: self.max_block_range,
+			max_traces: self.max_traces,
+			max_simulate_calls: self.max_simulate_calls,
+			max_response_size: self.max_response_size,
+			rpc_gas_cap: self.rpc_gas_cap.map(Into::into),
+			execute_gas_limit_multiplier: self.execute_gas_limit_multiplier,
+			sender_cache_size: self.eth_sender_cache_size.unwrap_or(default.sender_cache_size),
+			block_data_cache_size: self.eth_block_data_cache_size
+				.unwrap_or(default.block_data_cache_size),
+			namespaces: self.ethapi.clone(),
+		}
+	}
+
+	/// Logger of Ethereum rpc calls, if enabled.
+	pub fn call_logger(&self) -> Option<frontier_rpc::CallLogger> {
+		self.rpc_slow_call_threshold
+			.map(|threshold| frontier_rpc::CallLogger::new(Duration::from_millis(threshold)))
+	}
+
+	/// Rate limiter of expensive Ethereum rpc calls, if enabled.
+	pub fn rate_limiter(&self) -> Option<frontier_rpc::RateLimiter> {
+		self.rpc_rate_limit
+			.map(|calls_per_second| frontier_rpc::RateLimiter::new(calls_per_second, calls_per_second))
+	}
+
+	/// Limits of the Ethereum subscriptions.
+	pub fn subscription_limits(&self) -> frontier_rpc::SubscriptionLimits {
+		frontier_rpc::SubscriptionLimits {
+			max_per_connection: self.rpc_max_subscriptions_per_connection,
+			buffer_size: self.rpc_subscription_buffer,
+			overflow: self.rpc_subscription_overflow,
+			replay_rate: self.rpc_subscription_replay_rate,
+		}
+	}
+
+	/// Pool of threads running the expensive Ethereum rpc calls.
+	pub fn blocking_pool(&self) -> Result<frontier_rpc::BlockingTaskPool, String> {
+		frontier_rpc::BlockingTaskPool::new(self.rpc_blocking_threads, self.rpc_blocking_queue)
+			.map(|pool| pool.with_queue_timeout(Duration::from_secs(self.rpc_blocking_timeout)))
+			.map_err(|e| format!("cannot start rpc blocking threads: {}", e))
+	}
+
+	/// Pool of threads running the Ethereum trace calls.
+	pub fn trace_pool(&self) -> Result<frontier_rpc::BlockingTaskPool, String> {
+		frontier_rpc::BlockingTaskPool::new(self.rpc_trace_threads, self.rpc_trace_queue)
+			.map(|pool| pool.with_queue_timeout(Duration::from_secs(self.rpc_blocking_timeout)))
+			.map_err(|e| format!("cannot start rpc trace threads: {}", e))
+	}
+
+	/// Authenticator of sensitive Ethereum rpc calls, if enabled.
+	pub fn authenticator(&self) -> Result<Option<frontier_rpc::Authenticator>, String> {
+		match &self.rpc_secret_file {
+			Some(path) => {
+				let secret = std::fs::read_to_string(path)
+					.map_err(|e| format!("cannot read rpc secret file {}: {}", path.display(), e))?;
+				let secret = secret.trim();
+				if secret.is_empty() {
+					return Err(format!("rpc secret file {} is empty", path.display()))
+				}
+				Ok(Some(frontier_rpc::Authenticator::new(secret.as_bytes().to_vec())))
+			},
+			None => Ok(None),
+		}
+	}
+}
+
+/// Export of the Ethereum data of the best chain.
+#[derive(Debug, StructOpt)]
+pub struct ExportEthereumCmd {
+	/// First block to export.
+	#[structopt(long, default_value = "1")]
+	pub from: u32,
+
+	/// Last block to export, the best block by default.
+	#[structopt(long)]
+	pub to: Option<u32>,
+
+	/// Records to export: `blocks`, `transactions` or `receipts`.
+	#[structopt(long, default_value = "blocks")]
+	pub records: frontier_rpc::ExportRecords,
+
+	/// Output format: `json` for newline delimited JSON, or `csv`.
+	#[structopt(long, default_value = "json")]
+	pub format: frontier_rpc::ExportFormat,
+
+	/// Fields of the records to export, as a comma separated list of rpc field
+	/// names such as `hash,from,to,value`. All fields by default.
+	#[structopt(long, use_delimiter = true)]
+	pub fields: Vec<String>,
+
+	/// Output file, standard output by default.
+	#[structopt(long, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl sc_cli::CliConfiguration for ExportEthereumCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Rebuild of the Ethereum mapping of the node database.
+#[derive(Debug, StructOpt)]
+pub struct RebuildDbCmd {
+	/// Start over from genesis, rather than resuming an interrupted rebuild.
+	#[structopt(long)]
+	pub restart: bool,
+
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl sc_cli::CliConfiguration for RebuildDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Maintenance of the Frontier data of the node database.
+#[derive(Debug, StructOpt)]
+pub enum DbCmd {
+	/// Rebuild the mapping from Ethereum block and transaction hashes to
+	/// Substrate blocks by walking the canonical chain, for a node whose
+	/// mapping got corrupted. An interrupted rebuild resumes where it stopped.
+	Rebuild(RebuildDbCmd),
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+	#[structopt(flatten)]
+	Base(sc_cli::Subcommand),
+
+	/// Export the Ethereum blocks, transactions or receipts of the best chain
+	/// as newline delimited JSON or CSV.
+	ExportEthereum(ExportEthereumCmd),
+
+	/// Maintain the Frontier data of the node database.
+	Db(DbCmd),
+
+	/// Benchmark the dispatchables of the runtime pallets, to generate their
+	/// weights. Needs a node built with `--features runtime-benchmarks`.
+	#[structopt(name = "benchmark")]
+	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+}
+
 #[derive(Debug, StructOpt)]
 pub struct Cli {
 	#[structopt(subcommand)]
@@ -25,4 +360,33 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// How blocks are authored: `aura`, or `manual` and `instant` for a local
+	/// development chain.
+	#[structopt(long, default_value = "aura")]
+	pub sealing: Sealing,
+
+	/// With `--sealing manual`, also author a block as soon as an Ethereum
+	/// transaction enters the pool. Rejected with any other sealing.
+	#[structopt(long)]
+	pub automine: bool,
+
+	#[structopt(flatten)]
+	pub rpc: RpcParams,
+
+	/// Append the Ethereum blocks, transactions and receipts of every imported
+	/// block to this file, as newline delimited JSON.
+	#[structopt(long, parse(from_os_str))]
+	pub export_blocks: Option<PathBuf>,
+
+	/// Keep the Ethereum transaction mappings of this many last blocks only,
+	/// pruning older ones. All are kept by default.
+	#[structopt(long)]
+	pub transaction_retention: Option<u32>,
+
+	/// Add the accounts of the `alloc` section of this geth `genesis.json`,
+	/// with their balance, nonce, code and storage, to the EVM genesis of the
+	/// `dev` and `local` chains.
+	#[structopt(long, parse(from_os_str))]
+	pub geth_genesis: Option<PathBuf>,
 }
@@ -15,10 +15,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{fs::File, io::{self, BufWriter, Write}, sync::Arc};
 use crate::chain_spec;
-use crate::cli::Cli;
+use crate::geth_genesis;
+use crate::cli::{Cli, DbCmd, ExportEthereumCmd, RebuildDbCmd, Sealing, Subcommand};
 use crate::service;
+use frontier_template_runtime::{opaque::Block, RuntimeApi};
 use sc_cli::SubstrateCli;
+use sc_service::Configuration;
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> &'static str {
@@ -50,9 +54,18 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		let evm_accounts = match &self.geth_genesis {
+			Some(path) => {
+				let json = std::fs::read_to_string(path)
+					.map_err(|e| format!("cannot read geth genesis {}: {}", path.display(), e))?;
+				geth_genesis::evm_accounts(&json)?
+			},
+			None => Default::default(),
+		};
+
 		Ok(match id {
-			"dev" => Box::new(chain_spec::development_config()),
-			"" | "local" => Box::new(chain_spec::local_testnet_config()),
+			"dev" => Box::new(chain_spec::development_config(evm_accounts)),
+			"" | "local" => Box::new(chain_spec::local_testnet_config(evm_accounts)),
 			path => Box::new(chain_spec::ChainSpec::from_json_file(
 				std::path::PathBuf::from(path),
 			)?),
@@ -64,18 +77,84 @@ impl SubstrateCli for Cli {
 pub fn run() -> sc_cli::Result<()> {
 	let cli = Cli::from_args();
 
+	let sealing = cli.sealing;
+	let automine = cli.automine;
+	if automine && sealing != Sealing::Manual {
+		return Err("--automine requires --sealing manual".to_string().into());
+	}
+	let rpc_params = cli.rpc.clone();
+	let export_blocks = cli.export_blocks.clone();
+	let transaction_retention = cli.transaction_retention;
+
 	match &cli.subcommand {
-		Some(subcommand) => {
+		Some(Subcommand::Base(subcommand)) => {
 			let runner = cli.create_runner(subcommand)?;
-			runner.run_subcommand(subcommand, |config| Ok(new_full_start!(config).0))
+			runner.run_subcommand(subcommand, |config| Ok(new_full_start!(config, sealing, rpc_params).0))
+		}
+		Some(Subcommand::ExportEthereum(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| export_ethereum(cmd, config))
+		}
+		Some(Subcommand::Db(DbCmd::Rebuild(cmd))) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| rebuild_db(cmd, config))
+		}
+		Some(Subcommand::Benchmark(cmd)) => {
+			if cfg!(feature = "runtime-benchmarks") {
+				let runner = cli.create_runner(cmd)?;
+				runner.sync_run(|config| cmd.run::<Block, service::Executor>(config))
+			} else {
+				Err("Benchmarking was not enabled when building the node. \
+					Enable it with `--features runtime-benchmarks`.".to_string().into())
+			}
 		}
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node(
 				service::new_light,
-				service::new_full,
+				|config| service::new_full(
+					config, sealing, automine, rpc_params, export_blocks, transaction_retention,
+				),
 				frontier_template_runtime::VERSION
 			)
 		}
 	}
 }
+
+/// Export the Ethereum data of the best chain as requested by `cmd`.
+fn export_ethereum(cmd: &ExportEthereumCmd, config: Configuration) -> sc_cli::Result<()> {
+	let client = Arc::new(
+		sc_service::new_full_client::<Block, RuntimeApi, service::Executor>(&config)?
+	);
+	let mut sink: Box<dyn Write> = match &cmd.output {
+		Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+		None => Box::new(BufWriter::new(io::stdout())),
+	};
+
+	let written = frontier_rpc::export_range(
+		client.as_ref(),
+		cmd.from,
+		cmd.to,
+		cmd.records,
+		cmd.format,
+		&cmd.fields,
+		&mut sink,
+	)?;
+	log::info!("Exported {} records", written);
+	Ok(())
+}
+
+/// Rebuild the Ethereum mapping of the database as requested by `cmd`.
+fn rebuild_db(cmd: &RebuildDbCmd, config: Configuration) -> sc_cli::Result<()> {
+	let client = sc_service::new_full_client::<Block, RuntimeApi, service::Executor>(&config)?;
+	if let (false, Some(checkpoint)) = (cmd.restart, fc_db::load_rebuild_checkpoint(&client)?) {
+		log::info!("Resuming the rebuild of the Ethereum mapping at block #{}", checkpoint);
+	}
+
+	let rebuilt = fc_db::rebuild_mapping::<Block, _, _>(&client, cmd.restart, |number, best| {
+		let percent = (number as u64 + 1) * 100 / (best as u64 + 1);
+		log::info!("Rebuilt the Ethereum mapping up to block #{}/#{} ({}%)", number, best, percent);
+	})?;
+	log::info!("Rebuilt the Ethereum mapping of {} blocks", rebuilt);
+	Ok(())
+}
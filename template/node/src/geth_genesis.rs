@@ -0,0 +1,152 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Import of the `alloc` section of a geth `genesis.json`, so that a chain
+//! migrating from geth starts with the same EVM state.
+//!
+//! Numbers are hex with a `0x` prefix or decimal, as geth accepts them. Storage
+//! keys and values may be shorter than 32 bytes and are left padded.
+
+use std::collections::BTreeMap;
+use rustc_hex::FromHex;
+use serde_json::Value;
+use sp_core::{H160, H256, U256};
+
+/// EVM genesis accounts of the `alloc` section of the geth genesis `json`.
+pub fn evm_accounts(json: &str) -> Result<BTreeMap<H160, evm::GenesisAccount>, String> {
+	let genesis: Value = serde_json::from_str(json)
+		.map_err(|e| format!("invalid geth genesis: {}", e))?;
+	let alloc = genesis.get("alloc")
+		.and_then(Value::as_object)
+		.ok_or_else(|| "geth genesis has no alloc section".to_string())?;
+
+	alloc.iter().map(|(address, account)| {
+		let address = hex_bytes(address)
+			.ok()
+			.filter(|bytes| bytes.len() == 20)
+			.map(|bytes| H160::from_slice(&bytes))
+			.ok_or_else(|| format!("invalid alloc address {}", address))?;
+		let account = evm_account(account)
+			.map_err(|e| format!("invalid alloc account {:?}: {}", address, e))?;
+		Ok((address, account))
+	}).collect()
+}
+
+fn evm_account(account: &Value) -> Result<evm::GenesisAccount, String> {
+	let field = |name: &str| account.get(name).filter(|value| !value.is_null());
+
+	let storage = match field("storage") {
+		Some(storage) => storage.as_object()
+			.ok_or_else(|| "storage is not an object".to_string())?
+			.iter()
+			.map(|(key, value)| {
+				let value = value.as_str()
+					.ok_or_else(|| format!("storage value of {} is not a string", key))?;
+				Ok((word(key)?, word(value)?))
+			})
+			.collect::<Result<_, String>>()?,
+		None => BTreeMap::new(),
+	};
+
+	Ok(evm::GenesisAccount {
+		nonce: field("nonce").map(number).transpose()?.unwrap_or_default(),
+		balance: field("balance").map(number).transpose()?.unwrap_or_default(),
+		storage,
+		code: match field("code") {
+			Some(code) => hex_bytes(code.as_str().ok_or_else(|| "code is not a string".to_string())?)?,
+			None => Vec::new(),
+		},
+	})
+}
+
+/// Bytes of a hex string, with or without `0x` prefix. An odd number of digits
+/// is left padded.
+fn hex_bytes(s: &str) -> Result<Vec<u8>, String> {
+	let digits = if s.starts_with("0x") || s.starts_with("0X") { &s[2..] } else { s };
+	let digits = if digits.len() % 2 == 1 { format!("0{}", digits) } else { digits.to_string() };
+	digits.from_hex().map_err(|e| format!("invalid hex {}: {}", s, e))
+}
+
+/// A storage key or value, left padded to 32 bytes.
+fn word(s: &str) -> Result<H256, String> {
+	let bytes = hex_bytes(s)?;
+	if bytes.len() > 32 {
+		return Err(format!("storage word {} is longer than 32 bytes", s))
+	}
+	let mut word = H256::zero();
+	word.as_bytes_mut()[32 - bytes.len()..].copy_from_slice(&bytes);
+	Ok(word)
+}
+
+/// A JSON number, or a string holding a `0x` prefixed hex or a decimal number.
+fn number(value: &Value) -> Result<U256, String> {
+	match value {
+		Value::Number(n) => n.as_u64()
+			.map(U256::from)
+			.ok_or_else(|| format!("invalid number {}", n)),
+		Value::String(s) if s.starts_with("0x") || s.starts_with("0X") => {
+			let bytes = hex_bytes(s)?;
+			if bytes.len() > 32 {
+				return Err(format!("number {} is longer than 32 bytes", s))
+			}
+			Ok(U256::from_big_endian(&bytes))
+		},
+		Value::String(s) => U256::from_dec_str(s)
+			.map_err(|_| format!("invalid number {}", s)),
+		other => Err(format!("invalid number {}", other)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn imports_geth_alloc() {
+		let accounts = evm_accounts(r#"{
+			"config": { "chainId": 42 },
+			"alloc": {
+				"0x1000000000000000000000000000000000000001": {
+					"balance": "1000000000000000000",
+					"nonce": "0x2",
+					"code": "0x6001",
+					"storage": { "0x01": "0xff" }
+				},
+				"2000000000000000000000000000000000000002": { "balance": "0x10" }
+			}
+		}"#).unwrap();
+
+		let address = |s: &str| H160::from_slice(&hex_bytes(s).unwrap());
+
+		let contract = &accounts[&address("1000000000000000000000000000000000000001")];
+		assert_eq!(contract.balance, U256::from(1_000_000_000_000_000_000u64));
+		assert_eq!(contract.nonce, U256::from(2));
+		assert_eq!(contract.code, vec![0x60, 0x01]);
+		assert_eq!(contract.storage[&H256::from_low_u64_be(1)], H256::from_low_u64_be(0xff));
+
+		let holder = &accounts[&address("2000000000000000000000000000000000000002")];
+		assert_eq!(holder.balance, U256::from(0x10));
+		assert!(holder.code.is_empty());
+	}
+
+	#[test]
+	fn rejects_invalid_alloc() {
+		assert!(evm_accounts(r#"{ "alloc": { "0x01": {} } }"#).is_err());
+		assert!(evm_accounts(r#"{ "alloc": { "0x1000000000000000000000000000000000000001": { "balance": "x" } } }"#).is_err());
+		assert!(evm_accounts(r#"{}"#).is_err());
+	}
+}
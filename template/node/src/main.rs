@@ -19,11 +19,13 @@
 #![warn(missing_docs)]
 
 mod chain_spec;
+mod geth_genesis;
 #[macro_use]
 mod service;
 mod cli;
 mod command;
 mod rpc;
+mod system_contracts;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
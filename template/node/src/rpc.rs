@@ -25,9 +25,10 @@ use sp_transaction_pool::TransactionPool;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_consensus::SelectChain;
 use sc_rpc_api::DenyUnsafe;
-use sc_client_api::backend::{StorageProvider, Backend, StateBackend};
+use sc_client_api::{BlockBackend, backend::{StorageProvider, Backend, StateBackend}};
 use sp_runtime::traits::BlakeTwo256;
 use sp_block_builder::BlockBuilder;
+use sc_consensus_manual_seal::rpc::EngineCommand;
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -42,9 +43,13 @@ pub struct LightDeps<C, F, P> {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC> {
+pub struct FullDeps<C, P, SC, BE> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<BE>,
+	/// Frontier storage backend
+	pub frontier_backend: Arc<dyn fc_api::Backend<Block>>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -53,54 +58,303 @@ pub struct FullDeps<C, P, SC> {
 	pub deny_unsafe: DenyUnsafe,
 	/// The Node authority flag
 	pub is_authority: bool,
+	/// Manual seal command sink, when blocks are authored on request
+	pub command_sink: Option<futures::channel::mpsc::Sender<EngineCommand<Hash>>>,
+	/// Block timestamps of the manual seal development chain
+	pub dev_clock: frontier_rpc::DevClock,
+	/// Logger of Ethereum rpc calls, if enabled
+	pub call_logger: Option<frontier_rpc::CallLogger>,
+	/// Rate limiter of expensive Ethereum rpc calls, if enabled
+	pub rate_limiter: Option<frontier_rpc::RateLimiter>,
+	/// Ethereum rpc namespaces, limits and cache sizes
+	pub eth_config: frontier_rpc::EthConfiguration,
+	/// Authenticator of sensitive Ethereum rpc calls, if enabled
+	pub authenticator: Option<frontier_rpc::Authenticator>,
+	/// Whether Ethereum transactions without replay protection are accepted
+	pub allow_unprotected_txs: bool,
+	/// Whether the development accounts are managed by the node
+	pub enable_dev_signer: bool,
+	/// Whether the personal namespace is served
+	pub unsafe_personal_api: bool,
+	/// Active Ethereum subscriptions
+	pub eth_pubsub: frontier_rpc::EthPubSubHub,
+	/// Threads running the expensive Ethereum rpc calls
+	pub blocking_pool: frontier_rpc::BlockingTaskPool,
+	/// Threads running the Ethereum trace calls
+	pub trace_pool: frontier_rpc::BlockingTaskPool,
+	/// Block served as `latest` by the Ethereum rpc.
+	pub latest_block: frontier_rpc::LatestBlock,
+	/// Ethereum data of recent blocks, warmed as they are imported
+	pub block_data_cache: Arc<frontier_rpc::EthBlockDataCache>,
+	/// Network state served by the admin namespace
+	pub network_state: frontier_rpc::NetworkStateHandle,
+	/// Client name and version reported by `admin_nodeInfo`
+	pub client_name: String,
+}
+
+/// Network state of the admin namespace, from a network state of the service.
+pub fn network_state(state: sc_network::network_state::NetworkState) -> frontier_rpc::NetworkState {
+	use sc_network::network_state::PeerEndpoint;
+
+	frontier_rpc::NetworkState {
+		peer_id: state.peer_id,
+		listen_addresses: state.listened_addresses.iter().map(ToString::to_string).collect(),
+		peers: state.connected_peers.into_iter().map(|(peer_id, peer)| {
+			let (local_address, remote_address, inbound) = match peer.endpoint {
+				PeerEndpoint::Dialing(address) => (None, address.to_string(), false),
+				PeerEndpoint::Listening { local_addr, send_back_addr } =>
+					(Some(local_addr.to_string()), send_back_addr.to_string(), true),
+			};
+			frontier_rpc::NetworkPeer {
+				peer_id,
+				version: peer.version_string,
+				local_address,
+				remote_address,
+				inbound,
+			}
+		}).collect(),
+	}
+}
+
+/// Requires authentication for the calls of `delegate` if `authenticator` is
+/// set.
+fn guard<M, D>(
+	authenticator: &Option<frontier_rpc::Authenticator>,
+	delegate: D,
+) -> Vec<(String, jsonrpc_core::RemoteProcedure<M>)> where
+	M: jsonrpc_pubsub::PubSubMetadata,
+	D: IntoIterator<Item=(String, jsonrpc_core::RemoteProcedure<M>)>,
+{
+	match authenticator {
+		Some(authenticator) => authenticator.guard(delegate),
+		None => delegate.into_iter().collect(),
+	}
+}
+
+/// Adds `delegate` to `io`, running its calls in tracing spans, rate limiting
+/// them if `rate_limiter` is set and logging them if `call_logger` is set.
+fn extend_with_middleware<M, D>(
+	io: &mut jsonrpc_core::IoHandler<M>,
+	call_logger: &Option<frontier_rpc::CallLogger>,
+	rate_limiter: &Option<frontier_rpc::RateLimiter>,
+	delegate: D,
+) where
+	M: jsonrpc_pubsub::PubSubMetadata + frontier_rpc::PeerAddress,
+	D: IntoIterator<Item=(String, jsonrpc_core::RemoteProcedure<M>)>,
+{
+	let delegate = frontier_rpc::instrument_spans(delegate);
+	let delegate: Vec<_> = match call_logger {
+		Some(call_logger) => call_logger.instrument(delegate),
+		None => delegate,
+	};
+	match rate_limiter {
+		Some(rate_limiter) => io.extend_with(rate_limiter.limit(delegate)),
+		None => io.extend_with(delegate),
+	}
 }
 
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, M, SC, BE>(
-	deps: FullDeps<C, P, SC>,
+	deps: FullDeps<C, P, SC, BE>,
 ) -> jsonrpc_core::IoHandler<M> where
 	BE: Backend<Block> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
-	C: ProvideRuntimeApi<Block> + StorageProvider<Block, BE>,
+	C: ProvideRuntimeApi<Block> + StorageProvider<Block, BE> + BlockBackend<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: BlockBuilder<Block>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
 	C::Api: frontier_rpc_primitives::EthereumRuntimeApi<Block>,
+	C::Api: frontier_rpc_primitives::AccountMappingRuntimeApi<Block, AccountId>,
+	C::Api: sp_api::ApiExt<Block, StateBackend = BE::State>,
 	<C::Api as sp_api::ApiErrorExt>::Error: fmt::Debug,
-	P: TransactionPool<Block=Block> + 'static,
-	M: jsonrpc_core::Metadata + Default,
+	P: TransactionPool<Block=Block, Hash=Hash> + 'static,
+	M: jsonrpc_pubsub::PubSubMetadata + frontier_rpc::PeerAddress + Default,
 	SC: SelectChain<Block> +'static,
 {
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
-	use frontier_rpc::{EthApi, EthApiServer};
+	use frontier_rpc::{
+		EthApi, EthApiServer, EthApiLimits, TraceApi, TraceApiServer, DevApi, DevApiServer,
+		FrontierApi, FrontierApiServer, AccountApi, AccountApiServer, EthApiNamespace,
+		EthSigner, EthDevSigner, EthSignApi, EthSignApiServer,
+		PersonalApi, PersonalApiServer, PersonalSigner, EthPubSubApi, EthPubSubApiServer,
+		RpcModules, RpcModulesApiServer, AdminApi, AdminApiServer,
+	};
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
+		frontier_backend,
 		pool,
 		select_chain,
 		deny_unsafe,
-		is_authority
+		is_authority,
+		command_sink,
+		dev_clock,
+		call_logger,
+		rate_limiter,
+		eth_config,
+		authenticator,
+		allow_unprotected_txs,
+		enable_dev_signer,
+		unsafe_personal_api,
+		eth_pubsub,
+		blocking_pool,
+		trace_pool,
+		latest_block,
+		block_data_cache,
+		network_state,
+		client_name,
 	} = deps;
 
+	if let Some(authenticator) = &authenticator {
+		io.extend_with(authenticator.delegate());
+	}
+
+	let best = sp_api::BlockId::Hash(client.info().best_hash);
+	if let Ok(false) = frontier_rpc::ethereum_api_available(&*client, &best) {
+		log::warn!(
+			"Best block runtime has no Ethereum api, Ethereum calls fail until it gets one"
+		);
+	}
+
 	io.extend_with(
 		SystemApi::to_delegate(FullSystem::new(client.clone(), pool.clone(), deny_unsafe))
 	);
 	io.extend_with(
 		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
 	);
-	io.extend_with(
-		EthApiServer::to_delegate(EthApi::new(
+	// Namespaces reported by `rpc_modules`.
+	let mut modules = vec!["rpc"];
+	if eth_config.namespaces.contains(EthApiNamespace::Eth) {
+		modules.push("eth");
+		let personal_signer = if unsafe_personal_api {
+			Some(PersonalSigner::new())
+		} else {
+			None
+		};
+		let signers = || {
+			let mut signers: Vec<Box<dyn EthSigner>> = Vec::new();
+			if enable_dev_signer {
+				signers.push(Box::new(EthDevSigner::new()));
+			}
+			if let Some(personal_signer) = &personal_signer {
+				signers.push(Box::new(personal_signer.clone()));
+			}
+			signers
+		};
+		let eth_api = || EthApi::builder(
 			client.clone(),
-			select_chain,
 			pool.clone(),
 			frontier_template_runtime::TransactionConverter,
-			is_authority,
-		))
-	);
+			frontier_backend.clone(),
+			blocking_pool.clone(),
+		)
+			.latest(latest_block)
+			.authority(is_authority)
+			.signers(signers())
+			.block_data_cache(block_data_cache.clone())
+			.backend(backend.clone())
+			.limits(EthApiLimits { allow_unprotected_txs, ..Default::default() })
+			.configuration(&eth_config)
+			.build();
+		extend_with_middleware(
+			&mut io,
+			&call_logger,
+			&rate_limiter,
+			EthApiServer::to_delegate(eth_api()),
+		);
+		io.extend_with(EthPubSubApiServer::to_delegate(EthPubSubApi::new(eth_pubsub)));
+		if enable_dev_signer {
+			extend_with_middleware(
+				&mut io,
+				&call_logger,
+				&rate_limiter,
+				EthSignApiServer::to_delegate(EthSignApi::new(signers(), deny_unsafe)),
+			);
+		}
+		if let Some(personal_signer) = personal_signer.clone() {
+			modules.push("personal");
+			extend_with_middleware(
+				&mut io,
+				&call_logger,
+				&rate_limiter,
+				PersonalApiServer::to_delegate(PersonalApi::new(eth_api(), personal_signer, deny_unsafe)),
+			);
+		}
+	}
+	if eth_config.namespaces.contains(EthApiNamespace::Frontier) {
+		modules.push("frontier");
+		extend_with_middleware(
+			&mut io,
+			&call_logger,
+			&rate_limiter,
+			FrontierApiServer::to_delegate(FrontierApi::new(
+				client.clone(),
+				frontier_backend.clone(),
+				latest_block,
+				eth_config.limits(),
+			)),
+		);
+	}
+	if eth_config.namespaces.contains(EthApiNamespace::Account) {
+		modules.push("frontier");
+		io.extend_with(
+			AccountApiServer::to_delegate(AccountApi::<_, _, AccountId>::new(client.clone()))
+		);
+	}
+	if eth_config.namespaces.contains(EthApiNamespace::Trace) {
+		modules.push("trace");
+		modules.push("debug");
+		extend_with_middleware(
+			&mut io,
+			&call_logger,
+			&rate_limiter,
+			guard(&authenticator, TraceApiServer::to_delegate(TraceApi::new(
+				client.clone(),
+				backend.clone(),
+				frontier_backend.clone(),
+				latest_block,
+				trace_pool.clone(),
+				eth_config.limits(),
+			))),
+		);
+	}
+
+	if eth_config.namespaces.contains(EthApiNamespace::Admin) {
+		modules.push("admin");
+		io.extend_with(guard(&authenticator, AdminApiServer::to_delegate(AdminApi::new(
+			client.clone(),
+			network_state,
+			client_name,
+			deny_unsafe,
+		))));
+	}
+
+	if let Some(command_sink) = command_sink {
+		io.extend_with(
+			// We provide the rpc handler with the sending end of the channel to allow the rpc
+			// send EngineCommands to the background block authorship task.
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink.clone())),
+		);
+		if eth_config.namespaces.contains(EthApiNamespace::Dev) {
+			modules.push("evm");
+			io.extend_with(
+				guard(&authenticator, DevApiServer::to_delegate(DevApi::new(
+					select_chain,
+					backend,
+					command_sink,
+					dev_clock,
+					deny_unsafe,
+				)))
+			);
+		}
+	}
+	io.extend_with(RpcModulesApiServer::to_delegate(RpcModules::new(modules)));
 
 	io
 }
@@ -112,10 +366,11 @@ pub fn create_light<C, P, M, F>(
 	C: sp_blockchain::HeaderBackend<Block>,
 	C: Send + Sync + 'static,
 	F: sc_client_api::light::Fetcher<Block> + 'static,
-	P: TransactionPool + 'static,
+	P: TransactionPool<Block=Block> + 'static,
 	M: jsonrpc_core::Metadata + Default,
 {
 	use substrate_frame_rpc_system::{LightSystem, SystemApi};
+	use frontier_rpc::{LightEthApi, EthApiServer, LatestBlock};
 
 	let LightDeps {
 		client,
@@ -126,9 +381,19 @@ pub fn create_light<C, P, M, F>(
 	let mut io = jsonrpc_core::IoHandler::default();
 	io.extend_with(
 		SystemApi::<Hash, AccountId, Index>::to_delegate(
-			LightSystem::new(client, remote_blockchain, fetcher, pool)
+			LightSystem::new(client.clone(), remote_blockchain.clone(), fetcher.clone(), pool.clone())
 		)
 	);
+	io.extend_with(
+		EthApiServer::to_delegate(LightEthApi::new(
+			client,
+			remote_blockchain,
+			fetcher,
+			pool,
+			frontier_template_runtime::TransactionConverter,
+			LatestBlock::default(),
+		))
+	);
 
 	io
 }
@@ -17,13 +17,20 @@
 
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use std::sync::Arc;
+use std::{fs::OpenOptions, io::BufWriter, path::PathBuf, sync::Arc};
 use std::time::Duration;
 use sc_client_api::ExecutorProvider;
 use sc_consensus::LongestChain;
-use frontier_template_runtime::{self, opaque::Block, RuntimeApi};
+use codec::{Decode, Encode};
+use futures::{future, stream, StreamExt};
+use frontier_template_runtime::{self, opaque::Block, Call, RuntimeApi, UncheckedExtrinsic};
+use sp_runtime::OpaqueExtrinsic;
+use sp_transaction_pool::{TransactionPool, InPoolTransaction};
+use sc_consensus_manual_seal::rpc::EngineCommand;
 use sc_service::{error::{Error as ServiceError}, AbstractService, Configuration, ServiceBuilder};
-use sp_inherents::InherentDataProviders;
+use sp_inherents::{InherentDataProviders, ProvideInherentData, InherentIdentifier, InherentData};
+use sp_timestamp::INHERENT_IDENTIFIER;
+use crate::cli::{RpcParams, Sealing};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
@@ -36,19 +43,71 @@ native_executor_instance!(
 	pub Executor,
 	frontier_template_runtime::api::dispatch,
 	frontier_template_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
 );
 
+/// Block import set up by `new_full_start!`, depending on the sealing mode.
+pub enum ImportSetup<A, L, M> {
+	/// Block import and GRANDPA link used by Aura.
+	Aura(A, L),
+	/// Block import used by manual seal.
+	ManualSeal(M),
+}
+
+/// Timestamp inherent data provider for manual seal, advancing by one slot for
+/// each block so that blocks can be authored faster than the slot duration.
+///
+/// The clock is shared with the development rpc, which can move it forward.
+pub struct MockTimestampInherentDataProvider(pub frontier_rpc::DevClock);
+
+impl ProvideInherentData for MockTimestampInherentDataProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		inherent_data.put_data(INHERENT_IDENTIFIER, &self.0.next_timestamp())
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		sp_timestamp::InherentError::try_from(&INHERENT_IDENTIFIER, error)
+			.map(|e| format!("{:?}", e))
+	}
+}
+
+/// Whether a pool transaction is a call to pallet-ethereum.
+fn is_ethereum_transaction(extrinsic: &OpaqueExtrinsic) -> bool {
+	UncheckedExtrinsic::decode(&mut &*extrinsic.encode())
+		.map(|extrinsic| matches!(extrinsic.function, Call::Ethereum(_)))
+		.unwrap_or(false)
+}
+
 /// Starts a `ServiceBuilder` for a full service.
 ///
 /// Use this macro if you don't actually need the full service, but just the builder in order to
 /// be able to perform chain operations.
 macro_rules! new_full_start {
-	($config:expr) => {{
+	($config:expr, $sealing:expr, $rpc_params:expr) => {{
 		use std::sync::Arc;
 		use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 
+		let sealing: crate::cli::Sealing = $sealing;
+		let rpc_params: crate::cli::RpcParams = $rpc_params;
 		let mut import_setup = None;
 		let inherent_data_providers = sp_inherents::InherentDataProviders::new();
+		let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
+		let dev_clock = frontier_rpc::DevClock::new(frontier_template_runtime::SLOT_DURATION);
+		let (eth_pubsub, eth_pubsub_worker) =
+			frontier_rpc::EthPubSubHub::new(rpc_params.subscription_limits());
+		let block_data_cache = std::sync::Arc::new(frontier_rpc::EthBlockDataCache::new(
+			rpc_params.eth_configuration().block_data_cache_size,
+		));
+		let rpc_block_data_cache = block_data_cache.clone();
+		let network_state = frontier_rpc::NetworkStateHandle::new();
+		let rpc_network_state = network_state.clone();
 
 		let builder = sc_service::ServiceBuilder::new_full::<
 			frontier_template_runtime::opaque::Block, frontier_template_runtime::RuntimeApi, crate::service::Executor
@@ -74,6 +133,29 @@ macro_rules! new_full_start {
 				spawn_task_handle,
 				registry,
 			| {
+				if sealing.is_manual_seal() {
+					inherent_data_providers
+						.register_provider(crate::service::MockTimestampInherentDataProvider(
+							dev_clock.clone(),
+						))
+						.map_err(|e| sc_service::Error::Other(format!("{:?}", e)))?;
+
+					let frontier_block_import = fc_consensus::FrontierBlockImport::new(
+						client.clone(),
+						true,
+					);
+
+					let import_queue = sc_consensus_manual_seal::import_queue(
+						Box::new(frontier_block_import.clone()),
+						spawn_task_handle,
+						registry,
+					);
+
+					import_setup = Some(crate::service::ImportSetup::ManualSeal(frontier_block_import));
+
+					return Ok(import_queue);
+				}
+
 				let select_chain = select_chain.take()
 					.ok_or_else(|| sc_service::Error::SelectChainRequired)?;
 
@@ -83,8 +165,13 @@ macro_rules! new_full_start {
 					select_chain,
 				)?;
 
+				let frontier_block_import = fc_consensus::FrontierBlockImport::new(
+					grandpa_block_import.clone(),
+					true,
+				);
+
 				let aura_block_import = sc_consensus_aura::AuraBlockImport::<_, _, _, AuraPair>::new(
-					grandpa_block_import.clone(), client.clone(),
+					frontier_block_import.clone(), client.clone(),
 				);
 
 				let import_queue = sc_consensus_aura::import_queue::<_, _, _, AuraPair, _>(
@@ -98,46 +185,107 @@ macro_rules! new_full_start {
 					registry,
 				)?;
 
-				import_setup = Some((grandpa_block_import, grandpa_link));
+				import_setup = Some(crate::service::ImportSetup::Aura(frontier_block_import, grandpa_link));
 
 				Ok(import_queue)
 			})?
 			.with_rpc_extensions_builder(|builder| {
 				let client = builder.client().clone();
+				let backend = builder.backend().clone();
+				let frontier_backend: Arc<dyn fc_api::Backend<Block>> =
+					Arc::new(fc_db::Backend::new(client.clone()));
 				let is_authority: bool = builder.config().role.is_authority();
 				let pool = builder.pool().clone();
 				let select_chain = builder.select_chain().cloned()
 					.expect("SelectChain is present for full services or set up failed; qed.");
+				let call_logger = rpc_params.call_logger();
+				let rate_limiter = rpc_params.rate_limiter();
+				let eth_config = rpc_params.eth_configuration();
+				let authenticator = rpc_params.authenticator().map_err(sc_service::Error::Other)?;
+				let blocking_pool = rpc_params.blocking_pool().map_err(sc_service::Error::Other)?;
+				let trace_pool = rpc_params.trace_pool().map_err(sc_service::Error::Other)?;
+				let latest_block = rpc_params.rpc_latest_block;
+				let allow_unprotected_txs = rpc_params.rpc_allow_unprotected_txs;
+				let enable_dev_signer = rpc_params.enable_dev_signer;
+				let unsafe_personal_api = rpc_params.unsafe_personal_api;
+				let client_name = format!(
+					"{}/v{}", builder.config().impl_name, builder.config().impl_version,
+				);
+				let command_sink = if sealing == crate::cli::Sealing::Manual {
+					Some(command_sink)
+				} else {
+					None
+				};
 
 				Ok(move |deny_unsafe| {
 					let deps = crate::rpc::FullDeps {
 						client: client.clone(),
+						backend: backend.clone(),
+						frontier_backend: frontier_backend.clone(),
 						pool: pool.clone(),
 						select_chain: select_chain.clone(),
 						deny_unsafe,
-						is_authority
+						is_authority,
+						command_sink: command_sink.clone(),
+						dev_clock: dev_clock.clone(),
+						call_logger: call_logger.clone(),
+						rate_limiter: rate_limiter.clone(),
+						eth_config: eth_config.clone(),
+						authenticator: authenticator.clone(),
+						allow_unprotected_txs,
+						enable_dev_signer,
+						unsafe_personal_api,
+						eth_pubsub: eth_pubsub.clone(),
+						blocking_pool: blocking_pool.clone(),
+						trace_pool: trace_pool.clone(),
+						latest_block,
+						block_data_cache: rpc_block_data_cache.clone(),
+						network_state: rpc_network_state.clone(),
+						client_name: client_name.clone(),
 					};
 
 					crate::rpc::create_full(deps)
 				})
 			})?;
 
-		(builder, import_setup, inherent_data_providers)
+		(
+			builder,
+			import_setup,
+			inherent_data_providers,
+			commands_stream,
+			eth_pubsub_worker,
+			block_data_cache,
+			network_state,
+		)
 	}}
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceError> {
+pub fn new_full(
+	config: Configuration,
+	sealing: Sealing,
+	automine: bool,
+	rpc_params: RpcParams,
+	export_blocks: Option<PathBuf>,
+	transaction_retention: Option<u32>,
+) -> Result<impl AbstractService, ServiceError> {
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
 	let name = config.network.node_name.clone();
 	let disable_grandpa = config.disable_grandpa;
 
-	let (builder, mut import_setup, inherent_data_providers) = new_full_start!(config);
+	let (
+		builder,
+		mut import_setup,
+		inherent_data_providers,
+		commands_stream,
+		eth_pubsub_worker,
+		block_data_cache,
+		network_state,
+	) = new_full_start!(config, sealing, rpc_params);
 
-	let (block_import, grandpa_link) =
-		import_setup.take()
-			.expect("Link Half and Block Import are present for Full Services or setup failed before. qed");
+	let import_setup = import_setup.take()
+		.expect("Block Import is present for Full Services or setup failed before. qed");
 
 	let service = builder
 		.with_finality_proof_provider(|client, backend| {
@@ -147,6 +295,104 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 		})?
 		.build_full()?;
 
+	let mut frontier_tasks = frontier_rpc::FrontierTasksConfig::default();
+	frontier_tasks.eth_pubsub = Some(
+		eth_pubsub_worker.with_pool(service.client(), service.transaction_pool())
+	);
+	frontier_tasks.transaction_retention = transaction_retention;
+	frontier_tasks.block_data_cache = Some(block_data_cache);
+	if let Some(path) = export_blocks {
+		let file = OpenOptions::new().create(true).append(true).open(&path)
+			.map_err(|e| ServiceError::Other(
+				format!("cannot open block export file {}: {}", path.display(), e)
+			))?;
+		frontier_tasks.export_blocks = Some(Box::new(BufWriter::new(file)));
+	}
+	frontier_rpc::spawn_frontier_tasks(
+		service.client(),
+		frontier_tasks,
+		|name, task| service.spawn_task(name, task),
+	);
+	service.spawn_task(
+		"admin-network-state",
+		service.network_status(Duration::from_secs(5)).for_each(move |(_, state)| {
+			network_state.update(crate::rpc::network_state(state));
+			future::ready(())
+		}),
+	);
+
+	let (block_import, grandpa_link) = match import_setup {
+		ImportSetup::Aura(block_import, grandpa_link) => (block_import, grandpa_link),
+		ImportSetup::ManualSeal(block_import) => {
+			if role.is_authority() {
+				let proposer = sc_basic_authorship::ProposerFactory::new(
+					service.client(),
+					service.transaction_pool(),
+					service.prometheus_registry().as_ref(),
+				);
+
+				let client = service.client();
+				let pool = service.transaction_pool().pool().clone();
+				let select_chain = service.select_chain()
+					.ok_or(ServiceError::SelectChainRequired)?;
+
+				match sealing {
+					Sealing::Manual if automine => {
+						let transaction_pool = service.transaction_pool();
+						let automine_stream = transaction_pool
+							.import_notification_stream()
+							.filter_map(move |hash| future::ready(
+								transaction_pool.ready_transaction(&hash)
+									.filter(|transaction| is_ethereum_transaction(transaction.data()))
+									.map(|_| EngineCommand::SealNewBlock {
+										create_empty: false,
+										finalize: false,
+										parent_hash: None,
+										sender: None,
+									})
+							));
+
+						let authorship_future = sc_consensus_manual_seal::run_manual_seal(
+							Box::new(block_import),
+							proposer,
+							client,
+							pool,
+							stream::select(commands_stream, automine_stream),
+							select_chain,
+							inherent_data_providers,
+						);
+						service.spawn_essential_task("manual-seal", authorship_future);
+					},
+					Sealing::Manual => {
+						let authorship_future = sc_consensus_manual_seal::run_manual_seal(
+							Box::new(block_import),
+							proposer,
+							client,
+							pool,
+							commands_stream,
+							select_chain,
+							inherent_data_providers,
+						);
+						service.spawn_essential_task("manual-seal", authorship_future);
+					},
+					_ => {
+						let authorship_future = sc_consensus_manual_seal::run_instant_seal(
+							Box::new(block_import),
+							proposer,
+							client,
+							pool,
+							select_chain,
+							inherent_data_providers,
+						);
+						service.spawn_essential_task("instant-seal", authorship_future);
+					},
+				}
+			}
+
+			return Ok(service);
+		},
+	};
+
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			service.client(),
@@ -0,0 +1,60 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Infrastructure contracts that Ethereum tools expect at well-known addresses.
+//!
+//! On Ethereum, they are deployed by keyless transactions whose signature only
+//! holds for a fixed chain id or gas price, so they cannot be replayed on
+//! every chain. Their runtime code is set at genesis instead.
+
+use std::collections::BTreeMap;
+use rustc_hex::FromHex;
+use sp_core::{H160, U256};
+
+/// Address of the deterministic deployment proxy, deploying the init code of
+/// its calldata with CREATE2, the first 32 bytes being the salt.
+const CREATE2_DEPLOYER: &str = "4e59b44847b379578588920ca78fbf26c0b4956c";
+
+/// Runtime code of the deterministic deployment proxy.
+const CREATE2_DEPLOYER_CODE: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3";
+
+/// EVM genesis accounts of the bundled infrastructure contracts.
+///
+/// Only the deterministic deployment proxy is bundled. The EIP-1820 registry
+/// (`0x1820a4b7618bde71dce8cdc73aab6c95905fad24`) and Multicall3
+/// (`0xca11bde05977b3631167028862be2a173976ca11`) are solc builds, to be
+/// imported with their published runtime code through the `alloc` section of
+/// `--geth-genesis`.
+pub fn evm_accounts() -> BTreeMap<H160, evm::GenesisAccount> {
+	let mut accounts = BTreeMap::new();
+	accounts.insert(address(CREATE2_DEPLOYER), contract(CREATE2_DEPLOYER_CODE));
+	accounts
+}
+
+fn address(address: &str) -> H160 {
+	let bytes: Vec<u8> = address.from_hex().expect("system contract addresses are valid hex; qed");
+	H160::from_slice(&bytes)
+}
+
+fn contract(code: &str) -> evm::GenesisAccount {
+	evm::GenesisAccount {
+		nonce: U256::one(),
+		balance: U256::zero(),
+		storage: BTreeMap::new(),
+		code: code.from_hex().expect("system contract codes are valid hex; qed"),
+	}
+}
@@ -0,0 +1,93 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dev node driven over JSON-RPC, shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::{
+	io::{Read, Write}, net::{TcpListener, TcpStream},
+	process::{Child, Command, Stdio}, thread, time::{Duration, Instant},
+};
+use serde_json::{json, Value};
+
+/// Dev node killed on drop, so that failing tests do not leak it.
+pub struct Node {
+	process: Child,
+	rpc_port: u16,
+}
+
+impl Node {
+	pub fn start() -> Self {
+		let (rpc_port, ws_port, p2p_port) = (free_port(), free_port(), free_port());
+		let process = Command::new(env!("CARGO_BIN_EXE_frontier-template-node"))
+			.args(&["--dev", "--tmp", "--sealing", "manual", "--no-prometheus", "--no-telemetry"])
+			.args(&["--rpc-port", &rpc_port.to_string()])
+			.args(&["--ws-port", &ws_port.to_string()])
+			.args(&["--port", &p2p_port.to_string()])
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.spawn()
+			.expect("node starts");
+		let node = Node { process, rpc_port };
+
+		let deadline = Instant::now() + Duration::from_secs(60);
+		while node.try_call(&json!({ "jsonrpc": "2.0", "id": 0, "method": "eth_chainId", "params": [] })).is_none() {
+			assert!(Instant::now() < deadline, "node did not serve rpc within a minute");
+			thread::sleep(Duration::from_millis(200));
+		}
+		node
+	}
+
+	pub fn call(&self, request: &Value) -> Value {
+		self.try_call(request).expect("node serves rpc")
+	}
+
+	pub fn try_call(&self, request: &Value) -> Option<Value> {
+		let body = request.to_string();
+		let mut stream = TcpStream::connect(("127.0.0.1", self.rpc_port)).ok()?;
+		write!(
+			stream,
+			"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+			body.len(), body,
+		).ok()?;
+		let mut response = String::new();
+		stream.read_to_string(&mut response).ok()?;
+		let body = &response[response.find("\r\n\r\n")? + 4..];
+		serde_json::from_str(body).ok()
+	}
+
+	/// Result of calling `method` with `params`, failing on an error response.
+	pub fn result(&self, method: &str, params: Value) -> Value {
+		let response = self.call(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }));
+		if let Some(error) = response.get("error") {
+			panic!("{} failed with {}", method, error);
+		}
+		response["result"].clone()
+	}
+}
+
+impl Drop for Node {
+	fn drop(&mut self) {
+		let _ = self.process.kill();
+		let _ = self.process.wait();
+	}
+}
+
+fn free_port() -> u16 {
+	TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
@@ -0,0 +1,189 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end Ethereum RPC of a dev node: raw transactions signed with a
+//! development key are sent, sealed under manual seal, and read back through
+//! the major `eth_*` methods, along with a contract they deploy.
+//!
+//! The suite starts a node, so it only runs on request:
+//!
+//! ```sh
+//! cargo test -p frontier-template-node --test eth_rpc -- --ignored
+//! ```
+
+mod common;
+
+use ethereum_types::{H160, H256, U256};
+use rlp::RlpStream;
+use rustc_hex::{FromHex, ToHex};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+use common::Node;
+
+/// First key of the development signer, endowed at genesis.
+const DEV_KEY: &str = "5fb92d6e98884f76de468fa3f6278f8807c48bebc13595d45af5bdc4da702133";
+
+/// Runtime code returning the 32 bytes word 42.
+const RUNTIME_CODE: &str = "602a60005260206000f3";
+
+/// Init code storing 42 at slot 0, then returning `RUNTIME_CODE`.
+const INIT_CODE: &str = "602a600055600a6011600039600a6000f3602a60005260206000f3";
+
+const GAS_LIMIT: u64 = 500_000;
+
+fn secret_key() -> secp256k1::SecretKey {
+	let key: Vec<u8> = DEV_KEY.from_hex().unwrap();
+	secp256k1::SecretKey::parse_slice(&key).unwrap()
+}
+
+fn address_of(key: &secp256k1::SecretKey) -> H160 {
+	let public = secp256k1::PublicKey::from_secret_key(key).serialize();
+	H160::from_slice(&Keccak256::digest(&public[1..])[12..])
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", bytes.to_hex::<String>())
+}
+
+fn quantity(value: &Value) -> U256 {
+	U256::from_str_radix(&value.as_str().expect("quantities are strings")[2..], 16).unwrap()
+}
+
+/// EIP-155 signed transaction, RLP encoded.
+fn sign_transaction(
+	key: &secp256k1::SecretKey,
+	chain_id: u64,
+	nonce: U256,
+	gas_price: U256,
+	to: Option<H160>,
+	value: U256,
+	input: &[u8],
+) -> Vec<u8> {
+	let append_fields = |stream: &mut RlpStream| {
+		stream.append(&nonce);
+		stream.append(&gas_price);
+		stream.append(&U256::from(GAS_LIMIT));
+		match to {
+			Some(to) => stream.append(&to),
+			None => stream.append_empty_data(),
+		};
+		stream.append(&value);
+		stream.append(&input.to_vec());
+	};
+
+	let mut payload = RlpStream::new_list(9);
+	append_fields(&mut payload);
+	payload.append(&chain_id);
+	payload.append(&0u8);
+	payload.append(&0u8);
+	let hash = Keccak256::digest(&payload.out());
+
+	let message = secp256k1::Message::parse_slice(&hash).unwrap();
+	let (signature, recovery_id) = secp256k1::sign(&message, key);
+	let signature = signature.serialize();
+
+	let mut transaction = RlpStream::new_list(9);
+	append_fields(&mut transaction);
+	transaction.append(&(recovery_id.serialize() as u64 + chain_id * 2 + 35));
+	transaction.append(&U256::from_big_endian(&signature[..32]));
+	transaction.append(&U256::from_big_endian(&signature[32..]));
+	transaction.out()
+}
+
+/// Sends `transaction`, seals a block with it and returns its receipt.
+fn send_and_seal(node: &Node, transaction: &[u8]) -> Value {
+	let hash = node.result("eth_sendRawTransaction", json!([hex(transaction)]));
+	let expected = hex(&Keccak256::digest(transaction));
+	assert_eq!(hash, json!(expected));
+	node.result("evm_mine", json!([]));
+
+	let receipt = node.result("eth_getTransactionReceipt", json!([hash]));
+	assert_eq!(receipt["status"], json!("0x1"), "transaction failed: {}", receipt);
+	receipt
+}
+
+#[test]
+#[ignore]
+fn eth_rpc() {
+	let node = Node::start();
+	// Genesis holds no Ethereum block.
+	node.result("evm_mine", json!([]));
+
+	let key = secret_key();
+	let sender = address_of(&key);
+	let recipient = H160::repeat_byte(0x11);
+	let chain_id = quantity(&node.result("eth_chainId", json!([]))).as_u64();
+	let gas_price = quantity(&node.result("eth_gasPrice", json!([])));
+	let start = quantity(&node.result("eth_blockNumber", json!([])));
+
+	// Value transfer.
+	let nonce = quantity(&node.result("eth_getTransactionCount", json!([sender, "latest"])));
+	let transfer = sign_transaction(
+		&key, chain_id, nonce, gas_price, Some(recipient), U256::from(1_000), &[],
+	);
+	let receipt = send_and_seal(&node, &transfer);
+	assert_eq!(quantity(&receipt["gasUsed"]), U256::from(21_000));
+	assert_eq!(receipt["from"], json!(sender));
+	assert_eq!(receipt["to"], json!(recipient));
+
+	assert_eq!(quantity(&node.result("eth_blockNumber", json!([]))), start + 1);
+	assert_eq!(
+		quantity(&node.result("eth_getBalance", json!([recipient, "latest"]))),
+		U256::from(1_000),
+	);
+	assert_eq!(
+		quantity(&node.result("eth_getTransactionCount", json!([sender, "latest"]))),
+		nonce + 1,
+	);
+
+	let transaction = node.result("eth_getTransactionByHash", json!([receipt["transactionHash"]]));
+	assert_eq!(transaction["blockHash"], receipt["blockHash"]);
+	assert_eq!(transaction["from"], json!(sender));
+
+	let block = node.result("eth_getBlockByNumber", json!(["latest", true]));
+	assert_eq!(block["hash"], receipt["blockHash"]);
+	assert_eq!(block["transactions"][0]["hash"], receipt["transactionHash"]);
+	let by_hash = node.result("eth_getBlockByHash", json!([block["hash"], false]));
+	assert_eq!(by_hash["number"], block["number"]);
+
+	// Contract deployment.
+	let init_code: Vec<u8> = INIT_CODE.from_hex().unwrap();
+	let deployment = sign_transaction(
+		&key, chain_id, nonce + 1, gas_price, None, U256::zero(), &init_code,
+	);
+	let receipt = send_and_seal(&node, &deployment);
+	let contract = receipt["contractAddress"].clone();
+	assert!(contract.is_string(), "deployment has no contract address: {}", receipt);
+
+	assert_eq!(
+		node.result("eth_getCode", json!([contract, "latest"])),
+		json!(format!("0x{}", RUNTIME_CODE)),
+	);
+	let forty_two = json!(format!("{:?}", H256::from_low_u64_be(42)));
+	assert_eq!(node.result("eth_getStorageAt", json!([contract, "0x0", "latest"])), forty_two);
+	assert_eq!(node.result("eth_call", json!([{ "to": contract }, "latest"])), forty_two);
+	let estimate = node.result("eth_estimateGas", json!([{ "from": sender, "to": contract }]));
+	assert!(quantity(&estimate) > U256::from(21_000));
+
+	// The contract emits no logs.
+	let filter = json!({ "fromBlock": format!("{:#x}", start), "toBlock": "latest" });
+	assert_eq!(node.result("eth_getLogs", json!([filter])), json!([]));
+
+	// Calls against a contract that does not exist return nothing.
+	assert_eq!(node.result("eth_call", json!([{ "to": recipient }, "latest"])), json!("0x"));
+}
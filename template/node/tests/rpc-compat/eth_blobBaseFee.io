@@ -0,0 +1,3 @@
+// Base fee per blob gas, the minimum on chains without blobs.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_blobBaseFee","params":[]}
+<< {"jsonrpc":"2.0","id":1,"result":"0x1"}
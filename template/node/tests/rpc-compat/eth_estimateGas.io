@@ -0,0 +1,3 @@
+// Plain transfer.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_estimateGas","params":[{"from":"0x6be02d1d3665660d22ff9624b7be0551ee1ac91b","to":"0x00000000000000000000000000000000000000aa"}]}
+<< {"jsonrpc":"2.0","id":1,"result":"0x5208"}
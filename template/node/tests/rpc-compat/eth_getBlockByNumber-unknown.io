@@ -0,0 +1,3 @@
+// Blocks past the best one are null, not errors.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":["0xffffff",false]}
+<< {"jsonrpc":"2.0","id":1,"result":null}
@@ -0,0 +1,3 @@
+// Code of an account without any.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getCode","params":["0x00000000000000000000000000000000000000aa","latest"]}
+<< {"jsonrpc":"2.0","id":1,"result":"0x"}
@@ -0,0 +1,2 @@
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getHeaderByHash","params":["0x8c2d9bbc1a4b6ef9fb9ec5f4d6c14d1a1e1eb3e14e6a4c8e0a5f3a2a31e0b7c1"]}
+<< {"jsonrpc":"2.0","id":1,"result":null}
@@ -0,0 +1,3 @@
+// A block hash excludes a block range.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getLogs","params":[{"blockHash":"0x8c2d9bbc1a4b6ef9fb9ec5f4d6c14d1a1e1eb3e14e6a4c8e0a5f3a2a31e0b7c1","fromBlock":"0x0"}]}
+<< {"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"cannot specify both BlockHash and FromBlock/ToBlock, choose one or the other"}}
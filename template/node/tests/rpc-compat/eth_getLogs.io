@@ -0,0 +1,3 @@
+// Logs of a range without any.
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getLogs","params":[{"fromBlock":"latest","toBlock":"latest"}]}
+<< {"jsonrpc":"2.0","id":1,"result":[]}
@@ -0,0 +1,2 @@
+>> {"jsonrpc":"2.0","id":1,"method":"eth_getTransactionCount","params":["0x6be02d1d3665660d22ff9624b7be0551ee1ac91b","latest"]}
+<< {"jsonrpc":"2.0","id":1,"result":"0x0"}
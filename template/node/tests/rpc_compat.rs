@@ -0,0 +1,180 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conformance of the Ethereum RPC of a dev node, in the style of the
+//! execution-apis rpc-compat tests.
+//!
+//! Every `rpc-compat/*.io` file holds `>>` requests, each followed by the `<<`
+//! response of geth to the same request, as in the execution-apis fixtures,
+//! so that the node is checked against the reference client rather than
+//! against itself. Requests without a response only have to succeed.
+//! Responses are compared by shape, as hashes, balances and timestamps differ
+//! between the chains:
+//!
+//! * errors must have the same code, messages are not compared;
+//! * objects must have every key of the geth response, and may have more;
+//! * arrays are compared element by element, up to the shorter of the two;
+//! * hex values of 20 bytes or more (addresses, hashes, blooms) must have the
+//!   same length, other quantities must be quantities, and other data must be
+//!   data;
+//! * `null`, booleans and numbers must match in kind.
+//!
+//! Known deviations from geth are listed in
+//! `// missing: <key> ..` lines, naming keys the node does not serve yet, as
+//! absent or `null`.
+//!
+//! The suite starts a node, so it only runs on request:
+//!
+//! ```sh
+//! cargo test -p frontier-template-node --test rpc_compat -- --ignored
+//! ```
+
+mod common;
+
+use std::{fs, path::Path};
+use serde_json::{json, Value};
+
+use common::Node;
+
+fn is_quantity(value: &str) -> bool {
+	value.starts_with("0x") && value.len() > 2
+		&& value[2..].chars().all(|c| c.is_ascii_hexdigit())
+		&& (value == "0x0" || !value.starts_with("0x0"))
+}
+
+fn is_data(value: &str) -> bool {
+	value.starts_with("0x") && value.len() % 2 == 0
+		&& value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Differences of shape between `actual` and the `expected` geth value, at `path`.
+fn compare(path: &str, expected: &Value, actual: &Value, missing: &[String], errors: &mut Vec<String>) {
+	match (expected, actual) {
+		(Value::Object(expected), Value::Object(actual)) => {
+			for (key, expected) in expected {
+				match actual.get(key) {
+					Some(Value::Null) | None if missing.contains(key) => (),
+					Some(actual) => compare(&format!("{}.{}", path, key), expected, actual, missing, errors),
+					None => errors.push(format!("{}.{} is missing", path, key)),
+				}
+			}
+		},
+		(Value::Array(expected), Value::Array(actual)) => {
+			for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+				compare(&format!("{}[{}]", path, i), expected, actual, missing, errors);
+			}
+		},
+		(Value::String(expected), Value::String(actual)) => {
+			let shaped = if is_data(expected) && expected.len() >= 2 + 40 {
+				actual.len() == expected.len() && is_data(actual)
+			} else if is_quantity(expected) {
+				is_quantity(actual)
+			} else if is_data(expected) {
+				is_data(actual)
+			} else {
+				true
+			};
+			if !shaped {
+				errors.push(format!("{} is {}, expected the shape of {}", path, actual, expected));
+			}
+		},
+		(Value::Null, Value::Null) | (Value::Bool(_), Value::Bool(_)) | (Value::Number(_), Value::Number(_)) => (),
+		(expected, actual) => errors.push(format!("{} is {}, expected the shape of {}", path, actual, expected)),
+	}
+}
+
+/// Differences between the response of the node and the geth one.
+fn check(expected: &Value, actual: &Value, missing: &[String]) -> Vec<String> {
+	let mut errors = Vec::new();
+	match (expected.get("error"), actual.get("error")) {
+		(Some(expected), Some(actual)) => if expected["code"] != actual["code"] {
+			errors.push(format!("error code is {}, expected {}", actual["code"], expected["code"]));
+		},
+		(Some(expected), None) => errors.push(format!("succeeded, expected error {}", expected)),
+		(None, Some(actual)) => errors.push(format!("failed with {}, expected a result", actual)),
+		(None, None) => compare("result", &expected["result"], &actual["result"], missing, &mut errors),
+	}
+	errors
+}
+
+#[test]
+#[ignore]
+fn rpc_compat() {
+	let node = Node::start();
+	// Genesis holds no Ethereum block.
+	node.call(&json!({ "jsonrpc": "2.0", "id": 0, "method": "evm_mine", "params": [] }));
+
+	let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/rpc-compat");
+	let mut files = fs::read_dir(&dir).unwrap()
+		.map(|entry| entry.unwrap().path())
+		.filter(|path| path.extension().map_or(false, |extension| extension == "io"))
+		.collect::<Vec<_>>();
+	files.sort();
+
+	let mut failures = Vec::new();
+	for file in files {
+		let name = file.file_stem().unwrap().to_string_lossy().into_owned();
+		let mut missing = Vec::new();
+		let mut pending: Option<Value> = None;
+		for line in fs::read_to_string(&file).unwrap().lines() {
+			if line.starts_with("<< ") {
+				let request = pending.take().expect("responses follow requests");
+				let actual = node.call(&request);
+				let expected = serde_json::from_str::<Value>(&line[3..]).unwrap();
+				for error in check(&expected, &actual, &missing) {
+					failures.push(format!("{}: {}", name, error));
+				}
+				continue;
+			}
+
+			if let Some(request) = pending.take() {
+				failures.extend(unanswered(&node, &name, &request));
+			}
+			if line.starts_with("// missing:") {
+				missing.extend(line["// missing:".len()..].split_whitespace().map(String::from));
+			} else if line.starts_with(">> ") {
+				pending = Some(serde_json::from_str::<Value>(&line[3..]).unwrap());
+			}
+		}
+		if let Some(request) = pending.take() {
+			failures.extend(unanswered(&node, &name, &request));
+		}
+	}
+
+	assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Sends a `request` without a geth response, failing if it is an error.
+fn unanswered(node: &Node, name: &str, request: &Value) -> Option<String> {
+	node.call(request).get("error").map(|error| format!("{}: failed with {}", name, error))
+}
+
+#[test]
+fn shapes() {
+	let missing = vec!["nonce".to_string()];
+	let expected = json!({ "result": { "hash": format!("0x{}", "ab".repeat(32)), "number": "0x10", "nonce": "0x0000000000000000" } });
+
+	let actual = json!({ "result": { "hash": format!("0x{}", "00".repeat(32)), "number": "0x1", "author": "0x" } });
+	assert!(check(&expected, &actual, &missing).is_empty());
+
+	let actual = json!({ "result": { "hash": "0x00", "number": "0x01" } });
+	assert_eq!(check(&expected, &actual, &missing).len(), 2);
+
+	let expected = json!({ "error": { "code": -32602, "message": "missing value for required argument 0" } });
+	assert!(check(&expected, &json!({ "error": { "code": -32602, "message": "Invalid params" } }), &[]).is_empty());
+	assert_eq!(check(&expected, &json!({ "result": null }), &[]).len(), 1);
+}
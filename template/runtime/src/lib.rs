@@ -16,6 +16,11 @@
 // limitations under the License.
 
 //! The Substrate Node Template runtime. This can be compiled with `#[no_std]`, ready for Wasm.
+//!
+//! It is the reference configuration of Frontier: `pallet-evm` with its fee
+//! from `pallet-base-fee` and the `FrontierPrecompiles` set, `pallet-ethereum`
+//! with its block gas limit derived from the block weight, and the runtime apis
+//! the Ethereum rpc needs.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 // `construct_runtime!` does a lot of recursion and requires us to increase the limit to 256.
@@ -32,12 +37,13 @@ use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata, U256, H160, H256};
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, NumberFor, Saturating, Verify,
+	AccountIdConversion, BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, NumberFor,
+	Saturating, Verify,
 };
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, ModuleId, MultiSignature,
+	ApplyExtrinsicResult, ConsensusEngineId, ModuleId, MultiSignature,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -45,12 +51,15 @@ use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 use evm::{FeeCalculator, HashTruncateConvertAccountId, ConvertAccountId};
+use pallet_evm_precompile_ecrecover::ECRecover;
+use pallet_evm_precompile_randomness::Randomness;
+use pallet_evm_precompile_registry::PrecompileRegistry;
 // A few exports that help ease life for downstream crates.
 pub use balances::Call as BalancesCall;
 pub use evm::Account as EVMAccount;
 pub use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{KeyOwnerProofSystem, Randomness, FindAuthor},
+	traits::{KeyOwnerProofSystem, Randomness, FindAuthor, Filter},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
@@ -159,9 +168,28 @@ parameter_types! {
 	pub const Version: RuntimeVersion = VERSION;
 }
 
+/// Rejects the `pallet-evm` calls and EVM balance transfers while Ethereum
+/// transactions are paused, so that pausing suspends every way of running the
+/// EVM. The `pallet-evm` deployments are also held to the deployment
+/// allow-list, like CREATE transactions.
+pub struct BaseFilter;
+
+impl Filter<Call> for BaseFilter {
+	fn filter(call: &Call) -> bool {
+		match call {
+			Call::EVM(evm::Call::create(source, ..)) |
+			Call::EVM(evm::Call::create2(source, ..)) =>
+				!Ethereum::paused() && EvmDeployers::filter(source),
+			Call::EVM(_) => !Ethereum::paused(),
+			Call::EvmAccounts(pallet_evm_accounts::Call::transfer(..)) => !Ethereum::paused(),
+			_ => true,
+		}
+	}
+}
+
 impl system::Trait for Runtime {
 	/// Base call filter.
-	type BaseCallFilter = ();
+	type BaseCallFilter = BaseFilter;
 	/// The identifier used to distinguish between accounts.
 	type AccountId = AccountId;
 	/// The aggregated dispatch type that is available for extrinsics.
@@ -279,14 +307,49 @@ impl sudo::Trait for Runtime {
 	type Call = Call;
 }
 
-/// Fixed gas price of `1`.
-pub struct FixedGasPrice;
+parameter_types! {
+	pub DefaultBaseFeePerGas: U256 = U256::one();
+	pub DefaultElasticity: Permill = Permill::from_parts(125_000);
+}
 
-impl FeeCalculator for FixedGasPrice {
-	fn min_gas_price() -> U256 {
-		// Gas price is always one token per gas.
-		1.into()
-	}
+impl pallet_base_fee::Trait for Runtime {
+	type Event = Event;
+	type DefaultBaseFeePerGas = DefaultBaseFeePerGas;
+	type DefaultElasticity = DefaultElasticity;
+}
+
+impl pallet_evm_accounts::Trait for Runtime {
+	type Event = Event;
+	type WeightInfo = pallet_evm_accounts::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const HotfixMaxAccountsPerCall: u32 = 100;
+	pub const HotfixMaxSlotsPerCall: u32 = 1_000;
+	/// Reclaiming storage is not rewarded on the template chain.
+	pub const HotfixCleanupRewardPerSlot: Balance = 0;
+	pub HotfixCleanupRewardPot: AccountId = ModuleId(*b"py/evmhf").into_account();
+}
+
+impl pallet_evm_deployers::Trait for Runtime {
+	type Event = Event;
+	type ManagerOrigin = system::EnsureRoot<AccountId>;
+}
+
+impl pallet_evm_hotfix::Trait for Runtime {
+	type Event = Event;
+	type HotfixOrigin = system::EnsureRoot<AccountId>;
+	type MaxAccountsPerCall = HotfixMaxAccountsPerCall;
+	type MaxSlotsPerCall = HotfixMaxSlotsPerCall;
+	type CleanupRewardPerSlot = HotfixCleanupRewardPerSlot;
+	type CleanupRewardPot = HotfixCleanupRewardPot;
+}
+
+impl pallet_evm_migrations::Trait for Runtime {
+	type Event = Event;
+	// The vendored pallet-evm has the initial storage layout. Upgrades of
+	// pallet-evm list the migrations of their layout changes here.
+	type Migrations = ();
 }
 
 parameter_types! {
@@ -295,15 +358,128 @@ parameter_types! {
 
 impl evm::Trait for Runtime {
 	type ModuleId = EVMModuleId;
-	type FeeCalculator = FixedGasPrice;
-	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type FeeCalculator = BaseFee;
+	type ConvertAccountId = pallet_evm_accounts::EvmAddressMapping<
+		Runtime,
+		HashTruncateConvertAccountId<BlakeTwo256>,
+	>;
 	type Currency = Balances;
 	type Event = Event;
-	type Precompiles = ();
+	type Precompiles = FrontierPrecompiles;
+}
+
+/// Address of the ecrecover precompile.
+pub const ECRECOVER_PRECOMPILE: u64 = 0x1;
+/// Address of the randomness precompile.
+pub const RANDOMNESS_PRECOMPILE: u64 = 0x800;
+/// Address of the precompile registry.
+pub const REGISTRY_PRECOMPILE: u64 = 0x801;
+
+/// Code deployed at each precompile address, so that contracts checking
+/// `extcodesize` see precompiles as contracts.
+pub use pallet_evm_precompile_registry::PRECOMPILE_CODE;
+
+/// Precompiles available to EVM contracts.
+pub struct FrontierPrecompiles;
+
+impl FrontierPrecompiles {
+	/// Addresses of the precompiles.
+	pub fn addresses() -> Vec<H160> {
+		[ECRECOVER_PRECOMPILE, RANDOMNESS_PRECOMPILE, REGISTRY_PRECOMPILE].iter()
+			.map(|address| H160::from_low_u64_be(*address))
+			.collect()
+	}
+}
+
+parameter_types! {
+	pub PrecompileAddresses: Vec<H160> = FrontierPrecompiles::addresses();
+}
+
+impl evm::Precompiles for FrontierPrecompiles {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+	) -> Option<Result<(evm::ExitSucceed, Vec<u8>, usize), evm::ExitError>> {
+		if address == H160::from_low_u64_be(ECRECOVER_PRECOMPILE) {
+			Some(ECRecover::execute(input, target_gas))
+		} else if address == H160::from_low_u64_be(RANDOMNESS_PRECOMPILE) {
+			// A solo chain has no proof size budget, so storage reads are only
+			// charged the base cost.
+			Some(Randomness::<Runtime, RandomnessCollectiveFlip, ()>::execute(input, target_gas))
+		} else if address == H160::from_low_u64_be(REGISTRY_PRECOMPILE) {
+			Some(PrecompileRegistry::<PrecompileAddresses>::execute(input, target_gas))
+		} else {
+			None
+		}
+	}
+}
+
+parameter_types! {
+	/// Weight charged for one unit of EVM gas. `pallet-evm-gas-benchmark`
+	/// recommends a value for the hardware of the validators.
+	pub const WeightPerGas: Weight = 20_000;
+	/// Legacy transactions are accepted on chain; nodes decide whether their
+	/// rpc takes them with `--rpc-allow-unprotected-txs`.
+	pub const AllowUnprotectedTxs: bool = true;
+	/// Ethereum transactions may take up to the length available to normal
+	/// extrinsics in a block, like signed extrinsics under `CheckWeight`.
+	pub MaxEthereumTransactionSize: u32 = AvailableBlockRatio::get() * MaximumBlockLength::get();
+	/// Calls executed off-chain, such as gas estimations, may use up to this
+	/// many blocks worth of gas.
+	pub const ExecuteGasLimitMultiplier: u64 = 1;
+	/// Identifier of the runtime shown by explorers as the extra data of
+	/// Ethereum blocks.
+	pub EthereumExtraData: H256 = ethereum::extra_data(VERSION.spec_name.as_bytes());
+}
+
+/// Gas limit of Ethereum blocks, following the normal extrinsics weight budget.
+pub type BlockGasLimit = ethereum::BlockGasLimitFromWeight<Runtime, WeightPerGas>;
+
+/// Account of the Aura author of the block.
+pub struct FindAuraAccount;
+
+impl FindAuthor<AccountId> for FindAuraAccount {
+	fn find_author<'a, I>(digests: I) -> Option<AccountId> where
+		I: 'a + IntoIterator<Item=(ConsensusEngineId, &'a [u8])>
+	{
+		let index = Aura::find_author(digests)?;
+		// Aura authorities are the sr25519 keys of their accounts.
+		let authority_id = Aura::authorities().get(index as usize)?.clone();
+		let mut account_id = [0u8; 32];
+		account_id.copy_from_slice(authority_id.as_ref());
+		Some(AccountId::from(account_id))
+	}
+}
+
+/// Address of the Aura author of the block, mapped like any other account.
+pub type EthereumFindAuthor = ethereum::FindAuthorAddress<Runtime, FindAuraAccount>;
+
+/// Puts the base fee of the block overrides of dry runs in pallet-base-fee,
+/// which gas prices are checked against.
+pub struct BaseFeeOverride;
+
+impl ethereum::SetBaseFee for BaseFeeOverride {
+	fn set_base_fee(base_fee: U256) {
+		pallet_base_fee::BaseFeePerGas::put(base_fee);
+	}
 }
 
 impl ethereum::Trait for Runtime {
 	type Event = Event;
+	type BlockGasLimit = BlockGasLimit;
+	type WeightInfo = ethereum::weights::SubstrateWeight<Runtime>;
+	type WeightPerGas = WeightPerGas;
+	type StateRoot = ethereum::IntermediateStateRoot;
+	type DeploymentFilter = EvmDeployers;
+	type AllowUnprotectedTxs = AllowUnprotectedTxs;
+	type FindAuthor = EthereumFindAuthor;
+	type PauseOrigin = system::EnsureRoot<AccountId>;
+	type MaxTransactionSize = MaxEthereumTransactionSize;
+	type ExtraData = EthereumExtraData;
+	type MixHash = ();
+	type HeaderNonce = ();
+	type BaseFeeOverride = BaseFeeOverride;
 }
 
 construct_runtime!(
@@ -320,11 +496,19 @@ construct_runtime!(
 		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: transaction_payment::{Module, Storage},
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
-		Ethereum: ethereum::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		Ethereum: ethereum::{Module, Call, Config, Storage, Event<T>, ValidateUnsigned},
 		EVM: evm::{Module, Config, Call, Storage, Event<T>},
+		BaseFee: pallet_base_fee::{Module, Call, Storage, Event},
+		EvmAccounts: pallet_evm_accounts::{Module, Call, Storage, Event<T>},
+		EvmHotfix: pallet_evm_hotfix::{Module, Call, Event},
+		EvmMigrations: pallet_evm_migrations::{Module, Storage, Event},
+		EvmDeployers: pallet_evm_deployers::{Module, Call, Storage, Config, Event},
 	}
 );
 
+/// Wraps Ethereum transactions in unsigned `ethereum::Call::transact`
+/// extrinsics. Their signature is checked by `pallet-ethereum` in
+/// `ValidateUnsigned`, so they bypass the signed extensions.
 pub struct TransactionConverter;
 
 impl frontier_rpc_primitives::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
@@ -456,7 +640,20 @@ impl_runtime_apis! {
 		}
 
 		fn gas_price() -> U256 {
-			FixedGasPrice::min_gas_price()
+			<Runtime as evm::Trait>::FeeCalculator::min_gas_price()
+		}
+
+		fn block_gas_limit() -> U256 {
+			BlockGasLimit::get()
+		}
+
+		fn validation_constants() -> ethereum::ValidationConstants {
+			ethereum::ValidationConstants {
+				block_gas_limit: BlockGasLimit::get(),
+				execute_gas_limit_multiplier: ExecuteGasLimitMultiplier::get(),
+				max_transaction_size: MaxEthereumTransactionSize::get(),
+				allow_unprotected_txs: AllowUnprotectedTxs::get(),
+			}
 		}
 
 		fn account_code_at(address: H160) -> Vec<u8> {
@@ -466,12 +663,7 @@ impl_runtime_apis! {
 		fn author() -> H160 {
 			let digest = <system::Module<Runtime>>::digest();
 			let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
-			if let Some(index) = <aura::Module<Runtime>>::find_author(pre_runtime_digests) {
-				let authority_id = &<aura::Module<Runtime>>::authorities()[index as usize];
-				<evm::HashTruncateConvertAccountId<BlakeTwo256>>::convert_account_id(&authority_id)
-			} else {
-				H160::zero()
-			}
+			EthereumFindAuthor::find_author(pre_runtime_digests).unwrap_or_default()
 		}
 
 		fn storage_at(address: H160, index: U256) -> H256 {
@@ -480,6 +672,14 @@ impl_runtime_apis! {
 			evm::Module::<Runtime>::account_storages(address, H256::from_slice(&tmp[..]))
 		}
 
+		fn account_storage_root(address: H160) -> H256 {
+			<ethereum::Module<Runtime>>::account_storage_root(address)
+		}
+
+		fn account_range(start: Vec<u8>, max_results: u32) -> ethereum::AccountRange {
+			<ethereum::Module<Runtime>>::account_range(start, max_results)
+		}
+
 		fn call(
 			from: H160,
 			to: H160,
@@ -488,17 +688,56 @@ impl_runtime_apis! {
 			gas_limit: U256,
 			gas_price: U256,
 			nonce: Option<U256>,
-		) -> Option<(Vec<u8>, U256)> {
+			state_overrides: Vec<(H160, ethereum::AccountOverride)>,
+			block_override: ethereum::BlockOverride,
+			pending: ethereum::PendingChanges,
+		) -> Option<ethereum::DryRunOutcome> {
+			// The changes of the pending transactions are written first, and
+			// discarded along with the runtime API call.
+			<ethereum::Module<Runtime>>::apply_pending_changes(pending);
+			for (address, account_override) in state_overrides {
+				<ethereum::Module<Runtime>>::apply_account_override(address, account_override);
+			}
+			<ethereum::Module<Runtime>>::apply_block_override(block_override);
+
 			evm::Module::<Runtime>::execute_call(
 				from,
 				to,
 				data,
 				value,
-				gas_limit.low_u32(),
+				ethereum::saturated_gas_limit(gas_limit),
 				gas_price,
 				nonce,
 				false,
-			).ok().map(|(_, ret, gas)| (ret, gas))
+			).ok().map(|(reason, output, used_gas)| ethereum::DryRunOutcome {
+				succeeded: reason.is_succeed(),
+				error: ethereum::execution_error(&reason),
+				output,
+				used_gas,
+				created: None,
+			})
+		}
+
+		fn apply_pending(pending: Vec<(H160, EthereumTransaction)>) {
+			<ethereum::Module<Runtime>>::apply_pending(pending);
+		}
+
+		fn call_many(
+			calls: Vec<ethereum::DryRunCall>,
+			block_override: ethereum::BlockOverride,
+		) -> Vec<Option<ethereum::DryRunOutcome>> {
+			// State is applied so later calls see the effects of earlier ones; the
+			// changes are discarded along with the runtime API call.
+			<ethereum::Module<Runtime>>::apply_block_override(block_override);
+			calls.into_iter().map(<ethereum::Module<Runtime>>::dry_run).collect()
+		}
+
+		fn simulate(
+			blocks: Vec<ethereum::SimulatedBlock>,
+		) -> Vec<Vec<Option<ethereum::SimulatedOutcome>>> {
+			// Like `call_many`, the changes are discarded along with the runtime
+			// API call.
+			<ethereum::Module<Runtime>>::simulate(blocks)
 		}
 
 		fn block_by_number(number: u32) -> (
@@ -521,6 +760,19 @@ impl_runtime_apis! {
 			None
 		}
 
+		fn block_receipts_by_number(number: u32) -> Option<Vec<EthereumReceipt>> {
+			<ethereum::Module<Runtime>>::block_receipts_by_number(number)
+		}
+
+		fn block_logs_bloom(number: u32) -> Option<ethereum_types::Bloom> {
+			<ethereum::Module<Runtime>>::block_header_by_number(number)
+				.map(|header| header.logs_bloom)
+		}
+
+		fn contract_creation(address: H160) -> Option<H256> {
+			<ethereum::Module<Runtime>>::contract_creation(address)
+		}
+
 		fn block_transaction_count_by_hash(hash: H256) -> Option<U256> {
 			if let Some(block) = <ethereum::Module<Runtime>>::block_by_hash(hash) {
 				return Some(U256::from(block.transactions.len()))
@@ -553,6 +805,16 @@ impl_runtime_apis! {
 			<ethereum::Module<Runtime>>::transaction_by_hash(hash)
 		}
 
+		fn transaction_receipt(hash: H256, index: u32) -> Option<(
+			EthereumTransaction,
+			EthereumBlock,
+			ethereum::TransactionStatus,
+			EthereumReceipt,
+			U256
+		)> {
+			<ethereum::Module<Runtime>>::transaction_receipt(hash, index)
+		}
+
 		fn transaction_by_block_hash_and_index(hash: H256, index: u32) -> Option<(
 			EthereumTransaction,
 			EthereumBlock,
@@ -569,6 +831,32 @@ impl_runtime_apis! {
 				index
 			)
 		}
+		fn ethereum_extrinsic_indices(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<u32> {
+			xts.iter()
+				.enumerate()
+				.filter(|(_, xt)| matches!(xt.function, Call::Ethereum(ethereum::Call::transact(_))))
+				.map(|(index, _)| index as u32)
+				.collect()
+		}
+
+		fn extrinsic_filter(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<EthereumTransaction> {
+			xts.into_iter()
+				.filter_map(|xt| match xt.function {
+					Call::Ethereum(ethereum::Call::transact(transaction)) => Some(transaction),
+					_ => None,
+				})
+				.collect()
+		}
+	}
+
+	impl frontier_rpc_primitives::AccountMappingRuntimeApi<Block, AccountId> for Runtime {
+		fn evm_address(account_id: AccountId) -> H160 {
+			<Runtime as evm::Trait>::ConvertAccountId::convert_account_id(&account_id)
+		}
+
+		fn account_id(address: H160) -> Option<AccountId> {
+			pallet_evm_accounts::Module::<Runtime>::account_id(address)
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
@@ -621,4 +909,27 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			pallet: Vec<u8>,
+			benchmark: Vec<u8>,
+			lowest_range_values: Vec<u32>,
+			highest_range_values: Vec<u32>,
+			steps: Vec<u32>,
+			repeat: u32,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark};
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat);
+
+			add_benchmark!(params, batches, b"pallet-ethereum", Ethereum);
+			add_benchmark!(params, batches, b"pallet-evm-accounts", EvmAccounts);
+
+			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+			Ok(batches)
+		}
+	}
 }